@@ -0,0 +1,77 @@
+use crate::{
+    RouterFeeSchedule, PREFUNDER, SANCTUM_ROUTER_PROGRAM, SOL_BRIDGE_OUT,
+    STAKE_MIN_DELEGATION_LAMPORTS, WSOL_BRIDGE_IN, WSOL_FEE_TOKEN_ACCOUNT,
+};
+
+/// Program IDs, fixed accounts and router fees of a sanctum router deployment.
+///
+/// Defaults to [`RouterConfig::MAINNET`].
+/// Use a different value to work with devnet, localnet or forked deployments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RouterConfig {
+    pub router_program: [u8; 32],
+    pub prefunder: [u8; 32],
+    pub wsol_bridge_in: [u8; 32],
+    pub sol_bridge_out: [u8; 32],
+    pub wsol_fee_token_account: [u8; 32],
+    pub reserve: ReserveConfig,
+    pub fees: RouterFeeSchedule,
+
+    /// The stake program's minimum delegation, in lamports.
+    ///
+    /// Set this to [`crate::STAKE_RAISED_MIN_DELEGATION_LAMPORTS`] once the
+    /// feature that raises it is active on the cluster.
+    pub stake_min_delegation: u64,
+}
+
+/// Program ID and fixed accounts of the sanctum reserve deployment
+/// used for DepositStake to SOL and prefunding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReserveConfig {
+    pub program: [u8; 32],
+    pub pool: [u8; 32],
+    pub pool_sol_reserves: [u8; 32],
+    pub fee: [u8; 32],
+    pub protocol_fee: [u8; 32],
+    pub protocol_fee_vault: [u8; 32],
+}
+
+impl RouterConfig {
+    pub const MAINNET: Self = Self {
+        router_program: SANCTUM_ROUTER_PROGRAM,
+        prefunder: PREFUNDER,
+        wsol_bridge_in: WSOL_BRIDGE_IN,
+        sol_bridge_out: SOL_BRIDGE_OUT,
+        wsol_fee_token_account: WSOL_FEE_TOKEN_ACCOUNT,
+        reserve: ReserveConfig::MAINNET,
+        fees: RouterFeeSchedule::MAINNET,
+        stake_min_delegation: STAKE_MIN_DELEGATION_LAMPORTS,
+    };
+}
+
+impl Default for RouterConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::MAINNET
+    }
+}
+
+impl ReserveConfig {
+    pub const MAINNET: Self = Self {
+        program: sanctum_reserve_core::UNSTAKE_PROGRAM,
+        pool: sanctum_reserve_core::POOL,
+        pool_sol_reserves: sanctum_reserve_core::POOL_SOL_RESERVES,
+        fee: sanctum_reserve_core::FEE,
+        protocol_fee: sanctum_reserve_core::PROTOCOL_FEE,
+        protocol_fee_vault: sanctum_reserve_core::PROTOCOL_FEE_VAULT,
+    };
+}
+
+impl Default for ReserveConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::MAINNET
+    }
+}
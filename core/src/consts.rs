@@ -24,6 +24,14 @@ pub const TOKEN_PROGRAM: [u8; 32] =
 pub const ASSOCIATED_TOKEN_PROGRAM: [u8; 32] =
     bs58::decode_pubkey("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
 
+/// Known deploys of the SPL stake pool program:
+/// the original SPL deploy, sanctum's single-validator deploy and sanctum's multi-validator deploy
+pub const SPL_STAKE_POOL_PROGRAMS: [[u8; 32]; 3] = [
+    bs58::decode_pubkey("SPoo1Ku8WFXoNDMHPsrGSTSG1Y47rzgn41SLUNakuHy"),
+    bs58::decode_pubkey("SP12tWFxD9oJsVWNavTTBZvMbA6gkAmxtVgxdqvyvhY"),
+    bs58::decode_pubkey("SPMBzsVUuoHA4Jm6KunbsotaahvVikZs1JyTW6iJvbn"),
+];
+
 pub const SANCTUM_ROUTER_PROGRAM: [u8; 32] =
     bs58::decode_pubkey("stkitrT1Uoy18Dk1fTrgPw8W6MVzoCfYoAFT4MLsmhq");
 
@@ -45,12 +53,19 @@ pub const NATIVE_MINT: [u8; 32] =
 
 pub const PREFUNDER: [u8; 32] = bs58::decode_pubkey("ALpzvhALRr35nH8mw9SXk2WvmwEYjfw1dvmpFG9Kosu6");
 
-// TODO: STAKE_ACCOUNT_RENT_EXEMPT_LAMPORTS will change with:
-// - dynamic rent
-// - SOL minimum delegation feature
 /// The flash loan amount given out by the router program to make the slumdog stake and withdrawn stake rent-exempt.
 /// This amount is repaid by instant unstaking the slumdog stake
+///
+/// This is the value under [`crate::RentParams::DEFAULT`].
+/// Quoting uses [`crate::RentParams::prefund_flash_loan_lamports`] of the live rent params instead.
 pub const PREFUND_FLASH_LOAN_LAMPORTS: u64 = 2 * STAKE_ACCOUNT_RENT_EXEMPT_LAMPORTS;
 
-// hardcode for simplicity. Need to refactor when rent becomes variable.
+/// This is the value under [`crate::RentParams::DEFAULT`].
+/// Quoting uses [`crate::RentParams::zero_data_acc_rent_exempt_lamports`] of the live rent params instead.
 pub const ZERO_DATA_ACC_RENT_EXEMPT_LAMPORTS: u64 = 890_880;
+
+/// The stake program's minimum delegation while the feature that raises it to 1 SOL is inactive
+pub const STAKE_MIN_DELEGATION_LAMPORTS: u64 = 1;
+
+/// The stake program's minimum delegation once the feature that raises it is active
+pub const STAKE_RAISED_MIN_DELEGATION_LAMPORTS: u64 = 1_000_000_000;
@@ -0,0 +1,116 @@
+use core::{error::Error, fmt::Display};
+
+use bs58_fixed::Bs58String;
+use sanctum_marinade_liquid_staking_core::MarinadeError;
+use sanctum_reserve_core::ReserveError;
+use sanctum_spl_stake_pool_core::SplStakePoolError;
+use solido_legacy_core::LidoError;
+
+use crate::{PoolUpdateType, WithdrawStakeQuoteErr};
+
+type Bs58PkString = Bs58String<44>;
+
+/// Top-level error of the stateful router logic,
+/// shared by the native and wasm sdks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SanctumRouterError {
+    /// Required account (pubkey) not found in the account source
+    /// or not yet fetched by a previous update
+    AccountMissing([u8; 32]),
+
+    InvalidPda,
+
+    InvalidData,
+
+    /// Router for mint not initialized
+    RouterMissing([u8; 32]),
+
+    UnsupportedUpdate {
+        ty: PoolUpdateType,
+        mint: [u8; 32],
+    },
+
+    Spl(SplStakePoolError),
+
+    Marinade(MarinadeError),
+
+    Lido(LidoError),
+
+    Reserve(ReserveError),
+
+    /// A stake account would be delegated less than the stake program's minimum delegation
+    BelowMinimumDelegation,
+
+    /// A router instruction would have more than [`crate::ROUTER_IX_MAX_ACCOUNTS`] accounts
+    TooManyAccounts,
+}
+
+impl Display for SanctumRouterError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::AccountMissing(pk) => {
+                write!(
+                    f,
+                    "{} missing from provided accounts",
+                    Bs58PkString::encode(pk)
+                )
+            }
+            Self::InvalidPda => f.write_str("InvalidPda"),
+            Self::InvalidData => f.write_str("InvalidData"),
+            Self::RouterMissing(mint) => {
+                write!(f, "router missing for mint {}", Bs58PkString::encode(mint))
+            }
+            Self::UnsupportedUpdate { ty, mint } => write!(
+                f,
+                "{ty:?} not supported by pool of mint {}",
+                Bs58PkString::encode(mint)
+            ),
+            Self::Spl(e) => write!(f, "SplStakePoolError::{e}"),
+            Self::Marinade(e) => write!(f, "MarinadeError::{e}"),
+            Self::Lido(e) => write!(f, "LidoError::{e}"),
+            Self::Reserve(e) => write!(f, "ReserveError::{e}"),
+            Self::BelowMinimumDelegation => f.write_str("BelowMinimumDelegation"),
+            Self::TooManyAccounts => f.write_str("TooManyAccounts"),
+        }
+    }
+}
+
+impl Error for SanctumRouterError {}
+
+impl From<SplStakePoolError> for SanctumRouterError {
+    #[inline]
+    fn from(e: SplStakePoolError) -> Self {
+        Self::Spl(e)
+    }
+}
+
+impl From<MarinadeError> for SanctumRouterError {
+    #[inline]
+    fn from(e: MarinadeError) -> Self {
+        Self::Marinade(e)
+    }
+}
+
+impl From<LidoError> for SanctumRouterError {
+    #[inline]
+    fn from(e: LidoError) -> Self {
+        Self::Lido(e)
+    }
+}
+
+impl From<ReserveError> for SanctumRouterError {
+    #[inline]
+    fn from(e: ReserveError) -> Self {
+        Self::Reserve(e)
+    }
+}
+
+impl<E: Into<SanctumRouterError>> From<WithdrawStakeQuoteErr<E>> for SanctumRouterError {
+    #[inline]
+    fn from(e: WithdrawStakeQuoteErr<E>) -> Self {
+        match e {
+            WithdrawStakeQuoteErr::Pool(e) => e.into(),
+            WithdrawStakeQuoteErr::BelowMinimumDelegation => Self::BelowMinimumDelegation,
+        }
+    }
+}
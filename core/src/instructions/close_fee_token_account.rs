@@ -0,0 +1,79 @@
+use generic_array_struct::generic_array_struct;
+
+use super::INSTRUCTION_IDX_CLOSE_FEE_TOKEN_ACCOUNT;
+
+#[generic_array_struct(builder pub)]
+#[repr(transparent)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct CloseFeeTokenAccountIxAccs<T> {
+    /// The router program's admin
+    pub admin: T,
+
+    /// The fee token account PDA to close. Must have 0 balance.
+    ///
+    /// `seeds = ['fee', mint.pubkey]`
+    pub fee_token_account: T,
+
+    /// Receives the rent lamports of the closed `fee_token_account`
+    pub close_to: T,
+
+    pub mint: T,
+    pub token_program: T,
+}
+
+pub type CloseFeeTokenAccountIxKeysOwned = CloseFeeTokenAccountIxAccs<[u8; 32]>;
+pub type CloseFeeTokenAccountIxKeys<'a> = CloseFeeTokenAccountIxAccs<&'a [u8; 32]>;
+pub type CloseFeeTokenAccountIxAccsFlag = CloseFeeTokenAccountIxAccs<bool>;
+
+pub const CLOSE_FEE_TOKEN_ACCOUNT_IX_IS_WRITER: CloseFeeTokenAccountIxAccsFlag =
+    CloseFeeTokenAccountIxAccs([false; CLOSE_FEE_TOKEN_ACCOUNT_IX_ACCS_LEN])
+        .const_with_fee_token_account(true)
+        .const_with_close_to(true);
+
+pub const CLOSE_FEE_TOKEN_ACCOUNT_IX_IS_SIGNER: CloseFeeTokenAccountIxAccsFlag =
+    CloseFeeTokenAccountIxAccs([false; CLOSE_FEE_TOKEN_ACCOUNT_IX_ACCS_LEN]).const_with_admin(true);
+
+impl<T> CloseFeeTokenAccountIxAccs<T> {
+    #[inline]
+    pub const fn new(arr: [T; CLOSE_FEE_TOKEN_ACCOUNT_IX_ACCS_LEN]) -> Self {
+        Self(arr)
+    }
+}
+
+impl<T> AsRef<[T]> for CloseFeeTokenAccountIxAccs<T> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl CloseFeeTokenAccountIxKeysOwned {
+    #[inline]
+    pub fn as_borrowed(&self) -> CloseFeeTokenAccountIxKeys<'_> {
+        CloseFeeTokenAccountIxKeys::new(self.0.each_ref())
+    }
+}
+
+impl CloseFeeTokenAccountIxKeys<'_> {
+    #[inline]
+    pub fn into_owned(self) -> CloseFeeTokenAccountIxKeysOwned {
+        CloseFeeTokenAccountIxKeysOwned::new(self.0.map(|pk| *pk))
+    }
+}
+
+#[repr(transparent)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CloseFeeTokenAccountIxData([u8; 1]);
+
+impl CloseFeeTokenAccountIxData {
+    #[inline]
+    pub const fn new() -> Self {
+        Self([INSTRUCTION_IDX_CLOSE_FEE_TOKEN_ACCOUNT])
+    }
+
+    #[inline]
+    pub const fn to_buf(&self) -> [u8; 1] {
+        self.0
+    }
+}
@@ -0,0 +1,90 @@
+use generic_array_struct::generic_array_struct;
+
+use crate::SYSTEM_PROGRAM;
+
+use super::INSTRUCTION_IDX_CREATE_FEE_TOKEN_ACCOUNT;
+
+#[generic_array_struct(builder pub)]
+#[repr(transparent)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct CreateFeeTokenAccountIxAccs<T> {
+    /// Pays for the rent-exemption of `fee_token_account`
+    pub payer: T,
+
+    /// The fee token account PDA to create.
+    ///
+    /// `seeds = ['fee', mint.pubkey]`
+    pub fee_token_account: T,
+
+    pub mint: T,
+    pub token_program: T,
+    pub system_program: T,
+}
+
+pub type CreateFeeTokenAccountIxKeysOwned = CreateFeeTokenAccountIxAccs<[u8; 32]>;
+pub type CreateFeeTokenAccountIxKeys<'a> = CreateFeeTokenAccountIxAccs<&'a [u8; 32]>;
+pub type CreateFeeTokenAccountIxAccsFlag = CreateFeeTokenAccountIxAccs<bool>;
+
+pub const CREATE_FEE_TOKEN_ACCOUNT_IX_IS_WRITER: CreateFeeTokenAccountIxAccsFlag =
+    CreateFeeTokenAccountIxAccs([false; CREATE_FEE_TOKEN_ACCOUNT_IX_ACCS_LEN])
+        .const_with_payer(true)
+        .const_with_fee_token_account(true);
+
+pub const CREATE_FEE_TOKEN_ACCOUNT_IX_IS_SIGNER: CreateFeeTokenAccountIxAccsFlag =
+    CreateFeeTokenAccountIxAccs([false; CREATE_FEE_TOKEN_ACCOUNT_IX_ACCS_LEN])
+        .const_with_payer(true);
+
+impl<T> CreateFeeTokenAccountIxAccs<T> {
+    #[inline]
+    pub const fn new(arr: [T; CREATE_FEE_TOKEN_ACCOUNT_IX_ACCS_LEN]) -> Self {
+        Self(arr)
+    }
+}
+
+impl<T> AsRef<[T]> for CreateFeeTokenAccountIxAccs<T> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl CreateFeeTokenAccountIxKeysOwned {
+    #[inline]
+    pub fn as_borrowed(&self) -> CreateFeeTokenAccountIxKeys<'_> {
+        CreateFeeTokenAccountIxKeys::new(self.0.each_ref())
+    }
+
+    #[inline]
+    pub fn with_consts(self) -> Self {
+        self.as_borrowed().with_consts().into_owned()
+    }
+}
+
+impl CreateFeeTokenAccountIxKeys<'_> {
+    #[inline]
+    pub fn into_owned(self) -> CreateFeeTokenAccountIxKeysOwned {
+        CreateFeeTokenAccountIxKeysOwned::new(self.0.map(|pk| *pk))
+    }
+
+    #[inline]
+    pub const fn with_consts(self) -> Self {
+        self.const_with_system_program(&SYSTEM_PROGRAM)
+    }
+}
+
+#[repr(transparent)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CreateFeeTokenAccountIxData([u8; 1]);
+
+impl CreateFeeTokenAccountIxData {
+    #[inline]
+    pub const fn new() -> Self {
+        Self([INSTRUCTION_IDX_CREATE_FEE_TOKEN_ACCOUNT])
+    }
+
+    #[inline]
+    pub const fn to_buf(&self) -> [u8; 1] {
+        self.0
+    }
+}
@@ -0,0 +1,457 @@
+use core::{error::Error, fmt::Display};
+
+use crate::{
+    CloseFeeTokenAccountIxKeysOwned, CreateFeeTokenAccountIxKeysOwned, DepositStakeIxKeysOwned,
+    LidoWithdrawStakeIxSuffixKeysOwned, MarinadeDepositSolIxSuffixKeysOwned,
+    MarinadeDepositStakeIxSuffixKeysOwned, MarinadeWithdrawSolIxSuffixKeysOwned,
+    MarinadeWithdrawStakeIxSuffixKeysOwned, PrefundSwapViaStakePrefixKeysOwned,
+    PrefundWithdrawStakePrefixKeysOwned, ReserveDepositStakeIxSuffixKeysOwned, RouterConfig,
+    SplDepositSolIxSuffixKeysOwned, SplDepositStakeIxSuffixKeysOwned,
+    SplWithdrawSolIxSuffixKeysOwned, SplWithdrawStakeIxSuffixKeysOwned,
+    StakeWrappedSolPrefixKeysOwned, SwapViaStakePrefixKeysOwned, WithdrawFeesIxKeysOwned,
+    WithdrawWrappedSolPrefixKeysOwned, CLOSE_FEE_TOKEN_ACCOUNT_IX_ACCS_LEN,
+    CREATE_FEE_TOKEN_ACCOUNT_IX_ACCS_LEN, DEPOSIT_STAKE_IX_ACCS_LEN,
+    LIDO_WITHDRAW_STAKE_IX_SUFFIX_ACCS_LEN, MARINADE_DEPOSIT_SOL_IX_SUFFIX_ACCS_LEN,
+    MARINADE_DEPOSIT_STAKE_IX_SUFFIX_ACCS_LEN, MARINADE_WITHDRAW_SOL_IX_SUFFIX_ACCS_LEN,
+    MARINADE_WITHDRAW_STAKE_IX_SUFFIX_ACCS_LEN, PREFUND_SWAP_VIA_STAKE_PREFIX_ACCS_LEN,
+    PREFUND_WITHDRAW_STAKE_PREFIX_ACCS_LEN, RESERVE_DEPOSIT_STAKE_IX_SUFFIX_ACCS_LEN,
+    SPL_DEPOSIT_SOL_IX_SUFFIX_ACCS_LEN, SPL_DEPOSIT_STAKE_IX_SUFFIX_ACCS_LEN,
+    SPL_STAKE_POOL_PROGRAMS, SPL_WITHDRAW_SOL_IX_SUFFIX_ACCS_LEN,
+    SPL_WITHDRAW_STAKE_IX_SUFFIX_ACCS_LEN, STAKE_WRAPPED_SOL_PREFIX_ACCS_LEN,
+    SWAP_VIA_STAKE_PREFIX_ACCS_LEN, WITHDRAW_FEES_IX_ACCS_LEN,
+    WITHDRAW_WRAPPED_SOL_PREFIX_ACCS_LEN,
+};
+
+use super::{
+    INSTRUCTION_IDX_CLOSE_FEE_TOKEN_ACCOUNT, INSTRUCTION_IDX_CREATE_FEE_TOKEN_ACCOUNT,
+    INSTRUCTION_IDX_DEPOSIT_STAKE, INSTRUCTION_IDX_PREFUND_SWAP_VIA_STAKE,
+    INSTRUCTION_IDX_PREFUND_WITHDRAW_STAKE, INSTRUCTION_IDX_STAKE_WRAPPED_SOL,
+    INSTRUCTION_IDX_SWAP_VIA_STAKE, INSTRUCTION_IDX_WITHDRAW_FEES,
+    INSTRUCTION_IDX_WITHDRAW_WRAPPED_SOL,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RouterIxDecodeErr {
+    /// Instruction data is empty or its discriminant is `>= INSTRUCTION_COUNT`
+    UnknownInstruction,
+
+    /// Instruction data is shorter than what its discriminant requires
+    DataTooShort,
+
+    /// Account list is shorter than what the instruction + its suffixes require
+    NotEnoughAccounts,
+
+    /// A suffix's first account is not the program of any known pool
+    UnknownSuffixProgram,
+}
+
+impl Display for RouterIxDecodeErr {
+    // Display=Debug, since this is just a simple discriminated str enum
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl Error for RouterIxDecodeErr {}
+
+/// A decoded sanctum router instruction's data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RouterInstruction {
+    StakeWrappedSol { amount: u64 },
+    SwapViaStake { amount: u64, bridge_stake_seed: u32 },
+    CreateFeeTokenAccount,
+    CloseFeeTokenAccount,
+    WithdrawFees,
+    DepositStake,
+    PrefundWithdrawStake { amount: u64, bridge_stake_seed: u32 },
+    PrefundSwapViaStake { amount: u64, bridge_stake_seed: u32 },
+    WithdrawWrappedSol { amount: u64 },
+}
+
+impl RouterInstruction {
+    /// Inverse of the various `*IxData::new()`s.
+    ///
+    /// Trailing bytes after the instruction's args are ignored.
+    #[inline]
+    pub fn try_from_buf(buf: &[u8]) -> Result<Self, RouterIxDecodeErr> {
+        let (discm, args) = buf
+            .split_first()
+            .ok_or(RouterIxDecodeErr::UnknownInstruction)?;
+        Ok(match *discm {
+            INSTRUCTION_IDX_STAKE_WRAPPED_SOL => Self::StakeWrappedSol {
+                amount: read_amount(args)?,
+            },
+            INSTRUCTION_IDX_SWAP_VIA_STAKE => {
+                let (amount, bridge_stake_seed) = read_amount_and_seed(args)?;
+                Self::SwapViaStake {
+                    amount,
+                    bridge_stake_seed,
+                }
+            }
+            INSTRUCTION_IDX_CREATE_FEE_TOKEN_ACCOUNT => Self::CreateFeeTokenAccount,
+            INSTRUCTION_IDX_CLOSE_FEE_TOKEN_ACCOUNT => Self::CloseFeeTokenAccount,
+            INSTRUCTION_IDX_WITHDRAW_FEES => Self::WithdrawFees,
+            INSTRUCTION_IDX_DEPOSIT_STAKE => Self::DepositStake,
+            INSTRUCTION_IDX_PREFUND_WITHDRAW_STAKE => {
+                let (amount, bridge_stake_seed) = read_amount_and_seed(args)?;
+                Self::PrefundWithdrawStake {
+                    amount,
+                    bridge_stake_seed,
+                }
+            }
+            INSTRUCTION_IDX_PREFUND_SWAP_VIA_STAKE => {
+                let (amount, bridge_stake_seed) = read_amount_and_seed(args)?;
+                Self::PrefundSwapViaStake {
+                    amount,
+                    bridge_stake_seed,
+                }
+            }
+            INSTRUCTION_IDX_WITHDRAW_WRAPPED_SOL => Self::WithdrawWrappedSol {
+                amount: read_amount(args)?,
+            },
+            _ => return Err(RouterIxDecodeErr::UnknownInstruction),
+        })
+    }
+
+    /// Input amount of the instruction, `None` if the instruction does not have one
+    #[inline]
+    pub const fn amount(&self) -> Option<u64> {
+        match self {
+            Self::StakeWrappedSol { amount }
+            | Self::SwapViaStake { amount, .. }
+            | Self::PrefundWithdrawStake { amount, .. }
+            | Self::PrefundSwapViaStake { amount, .. }
+            | Self::WithdrawWrappedSol { amount } => Some(*amount),
+            Self::CreateFeeTokenAccount
+            | Self::CloseFeeTokenAccount
+            | Self::WithdrawFees
+            | Self::DepositStake => None,
+        }
+    }
+
+    /// `None` if the instruction does not use a bridge stake account
+    #[inline]
+    pub const fn bridge_stake_seed(&self) -> Option<u32> {
+        match self {
+            Self::SwapViaStake {
+                bridge_stake_seed, ..
+            }
+            | Self::PrefundWithdrawStake {
+                bridge_stake_seed, ..
+            }
+            | Self::PrefundSwapViaStake {
+                bridge_stake_seed, ..
+            } => Some(*bridge_stake_seed),
+            _ => None,
+        }
+    }
+
+    /// Splits an instruction's account list into its named prefix accounts
+    /// and its pool-specific suffix accounts.
+    ///
+    /// Returns the decoded accounts and any remaining accounts that were not
+    /// part of the instruction's known layout.
+    ///
+    /// `config` is the router deployment the instruction is for,
+    /// used to classify reserve suffixes by `config.reserve.program`.
+    #[inline]
+    pub fn try_split_accounts<'a>(
+        &self,
+        accounts: &'a [[u8; 32]],
+        config: &RouterConfig,
+    ) -> Result<(RouterIxAccounts, &'a [[u8; 32]]), RouterIxDecodeErr> {
+        Ok(match self {
+            Self::StakeWrappedSol { .. } => {
+                let (prefix, rem) = split::<STAKE_WRAPPED_SOL_PREFIX_ACCS_LEN>(accounts)?;
+                let (suffix, rem) = DepositSolIxSuffixKeysOwned::try_split(rem)?;
+                (
+                    RouterIxAccounts::StakeWrappedSol {
+                        prefix: StakeWrappedSolPrefixKeysOwned::new(prefix),
+                        suffix,
+                    },
+                    rem,
+                )
+            }
+            Self::SwapViaStake { .. } => {
+                let (prefix, rem) = split::<SWAP_VIA_STAKE_PREFIX_ACCS_LEN>(accounts)?;
+                let (withdraw, rem) = WithdrawStakeIxSuffixKeysOwned::try_split(rem)?;
+                let (deposit, rem) = DepositStakeIxSuffixKeysOwned::try_split(rem, config)?;
+                (
+                    RouterIxAccounts::SwapViaStake {
+                        prefix: SwapViaStakePrefixKeysOwned::new(prefix),
+                        withdraw,
+                        deposit,
+                    },
+                    rem,
+                )
+            }
+            Self::CreateFeeTokenAccount => {
+                let (accs, rem) = split::<CREATE_FEE_TOKEN_ACCOUNT_IX_ACCS_LEN>(accounts)?;
+                (
+                    RouterIxAccounts::CreateFeeTokenAccount(CreateFeeTokenAccountIxKeysOwned::new(
+                        accs,
+                    )),
+                    rem,
+                )
+            }
+            Self::CloseFeeTokenAccount => {
+                let (accs, rem) = split::<CLOSE_FEE_TOKEN_ACCOUNT_IX_ACCS_LEN>(accounts)?;
+                (
+                    RouterIxAccounts::CloseFeeTokenAccount(CloseFeeTokenAccountIxKeysOwned::new(
+                        accs,
+                    )),
+                    rem,
+                )
+            }
+            Self::WithdrawFees => {
+                let (accs, rem) = split::<WITHDRAW_FEES_IX_ACCS_LEN>(accounts)?;
+                (
+                    RouterIxAccounts::WithdrawFees(WithdrawFeesIxKeysOwned::new(accs)),
+                    rem,
+                )
+            }
+            Self::DepositStake => {
+                let (prefix, rem) = split::<DEPOSIT_STAKE_IX_ACCS_LEN>(accounts)?;
+                let (suffix, rem) = DepositStakeIxSuffixKeysOwned::try_split(rem, config)?;
+                (
+                    RouterIxAccounts::DepositStake {
+                        prefix: DepositStakeIxKeysOwned::new(prefix),
+                        suffix,
+                    },
+                    rem,
+                )
+            }
+            Self::PrefundWithdrawStake { .. } => {
+                let (prefix, rem) = split::<PREFUND_WITHDRAW_STAKE_PREFIX_ACCS_LEN>(accounts)?;
+                let (suffix, rem) = WithdrawStakeIxSuffixKeysOwned::try_split(rem)?;
+                (
+                    RouterIxAccounts::PrefundWithdrawStake {
+                        prefix: PrefundWithdrawStakePrefixKeysOwned::new(prefix),
+                        suffix,
+                    },
+                    rem,
+                )
+            }
+            Self::PrefundSwapViaStake { .. } => {
+                let (prefix, rem) = split::<PREFUND_SWAP_VIA_STAKE_PREFIX_ACCS_LEN>(accounts)?;
+                let (withdraw, rem) = WithdrawStakeIxSuffixKeysOwned::try_split(rem)?;
+                let (deposit, rem) = DepositStakeIxSuffixKeysOwned::try_split(rem, config)?;
+                (
+                    RouterIxAccounts::PrefundSwapViaStake {
+                        prefix: PrefundSwapViaStakePrefixKeysOwned::new(prefix),
+                        withdraw,
+                        deposit,
+                    },
+                    rem,
+                )
+            }
+            Self::WithdrawWrappedSol { .. } => {
+                let (prefix, rem) = split::<WITHDRAW_WRAPPED_SOL_PREFIX_ACCS_LEN>(accounts)?;
+                let (suffix, rem) = WithdrawSolIxSuffixKeysOwned::try_split(rem)?;
+                (
+                    RouterIxAccounts::WithdrawWrappedSol {
+                        prefix: WithdrawWrappedSolPrefixKeysOwned::new(prefix),
+                        suffix,
+                    },
+                    rem,
+                )
+            }
+        })
+    }
+}
+
+/// A decoded sanctum router instruction's accounts
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RouterIxAccounts {
+    StakeWrappedSol {
+        prefix: StakeWrappedSolPrefixKeysOwned,
+        suffix: DepositSolIxSuffixKeysOwned,
+    },
+    SwapViaStake {
+        prefix: SwapViaStakePrefixKeysOwned,
+        withdraw: WithdrawStakeIxSuffixKeysOwned,
+        deposit: DepositStakeIxSuffixKeysOwned,
+    },
+    CreateFeeTokenAccount(CreateFeeTokenAccountIxKeysOwned),
+    CloseFeeTokenAccount(CloseFeeTokenAccountIxKeysOwned),
+    WithdrawFees(WithdrawFeesIxKeysOwned),
+    DepositStake {
+        prefix: DepositStakeIxKeysOwned,
+        suffix: DepositStakeIxSuffixKeysOwned,
+    },
+    PrefundWithdrawStake {
+        prefix: PrefundWithdrawStakePrefixKeysOwned,
+        suffix: WithdrawStakeIxSuffixKeysOwned,
+    },
+    PrefundSwapViaStake {
+        prefix: PrefundSwapViaStakePrefixKeysOwned,
+        withdraw: WithdrawStakeIxSuffixKeysOwned,
+        deposit: DepositStakeIxSuffixKeysOwned,
+    },
+    WithdrawWrappedSol {
+        prefix: WithdrawWrappedSolPrefixKeysOwned,
+        suffix: WithdrawSolIxSuffixKeysOwned,
+    },
+}
+
+// Suffixes are classified by their first account, which is always the pool's program.
+// The SPL stake pool program has multiple deploys, all of which are in `SPL_STAKE_POOL_PROGRAMS`.
+// Any other program is rejected with `UnknownSuffixProgram`.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DepositSolIxSuffixKeysOwned {
+    Spl(SplDepositSolIxSuffixKeysOwned),
+    Marinade(MarinadeDepositSolIxSuffixKeysOwned),
+}
+
+impl DepositSolIxSuffixKeysOwned {
+    #[inline]
+    pub fn try_split(accounts: &[[u8; 32]]) -> Result<(Self, &[[u8; 32]]), RouterIxDecodeErr> {
+        match accounts.first() {
+            Some(&sanctum_marinade_liquid_staking_core::MARINADE_STAKING_PROGRAM) => {
+                split::<MARINADE_DEPOSIT_SOL_IX_SUFFIX_ACCS_LEN>(accounts).map(|(a, rem)| {
+                    (
+                        Self::Marinade(MarinadeDepositSolIxSuffixKeysOwned::new(a)),
+                        rem,
+                    )
+                })
+            }
+            Some(program) if SPL_STAKE_POOL_PROGRAMS.contains(program) => {
+                split::<SPL_DEPOSIT_SOL_IX_SUFFIX_ACCS_LEN>(accounts)
+                    .map(|(a, rem)| (Self::Spl(SplDepositSolIxSuffixKeysOwned::new(a)), rem))
+            }
+            Some(_) => Err(RouterIxDecodeErr::UnknownSuffixProgram),
+            None => Err(RouterIxDecodeErr::NotEnoughAccounts),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WithdrawSolIxSuffixKeysOwned {
+    Spl(SplWithdrawSolIxSuffixKeysOwned),
+    Marinade(MarinadeWithdrawSolIxSuffixKeysOwned),
+}
+
+impl WithdrawSolIxSuffixKeysOwned {
+    #[inline]
+    pub fn try_split(accounts: &[[u8; 32]]) -> Result<(Self, &[[u8; 32]]), RouterIxDecodeErr> {
+        match accounts.first() {
+            Some(&sanctum_marinade_liquid_staking_core::MARINADE_STAKING_PROGRAM) => {
+                split::<MARINADE_WITHDRAW_SOL_IX_SUFFIX_ACCS_LEN>(accounts).map(|(a, rem)| {
+                    (
+                        Self::Marinade(MarinadeWithdrawSolIxSuffixKeysOwned::new(a)),
+                        rem,
+                    )
+                })
+            }
+            Some(program) if SPL_STAKE_POOL_PROGRAMS.contains(program) => {
+                split::<SPL_WITHDRAW_SOL_IX_SUFFIX_ACCS_LEN>(accounts)
+                    .map(|(a, rem)| (Self::Spl(SplWithdrawSolIxSuffixKeysOwned::new(a)), rem))
+            }
+            Some(_) => Err(RouterIxDecodeErr::UnknownSuffixProgram),
+            None => Err(RouterIxDecodeErr::NotEnoughAccounts),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DepositStakeIxSuffixKeysOwned {
+    Spl(SplDepositStakeIxSuffixKeysOwned),
+    Marinade(MarinadeDepositStakeIxSuffixKeysOwned),
+    Reserve(ReserveDepositStakeIxSuffixKeysOwned),
+}
+
+impl DepositStakeIxSuffixKeysOwned {
+    /// Reserve suffixes are classified by `config.reserve.program`
+    #[inline]
+    pub fn try_split<'a>(
+        accounts: &'a [[u8; 32]],
+        config: &RouterConfig,
+    ) -> Result<(Self, &'a [[u8; 32]]), RouterIxDecodeErr> {
+        match accounts.first() {
+            Some(&sanctum_marinade_liquid_staking_core::MARINADE_STAKING_PROGRAM) => {
+                split::<MARINADE_DEPOSIT_STAKE_IX_SUFFIX_ACCS_LEN>(accounts).map(|(a, rem)| {
+                    (
+                        Self::Marinade(MarinadeDepositStakeIxSuffixKeysOwned::new(a)),
+                        rem,
+                    )
+                })
+            }
+            Some(program) if *program == config.reserve.program => {
+                split::<RESERVE_DEPOSIT_STAKE_IX_SUFFIX_ACCS_LEN>(accounts).map(|(a, rem)| {
+                    (
+                        Self::Reserve(ReserveDepositStakeIxSuffixKeysOwned::new(a)),
+                        rem,
+                    )
+                })
+            }
+            Some(program) if SPL_STAKE_POOL_PROGRAMS.contains(program) => {
+                split::<SPL_DEPOSIT_STAKE_IX_SUFFIX_ACCS_LEN>(accounts)
+                    .map(|(a, rem)| (Self::Spl(SplDepositStakeIxSuffixKeysOwned::new(a)), rem))
+            }
+            Some(_) => Err(RouterIxDecodeErr::UnknownSuffixProgram),
+            None => Err(RouterIxDecodeErr::NotEnoughAccounts),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WithdrawStakeIxSuffixKeysOwned {
+    Spl(SplWithdrawStakeIxSuffixKeysOwned),
+    Lido(LidoWithdrawStakeIxSuffixKeysOwned),
+    Marinade(MarinadeWithdrawStakeIxSuffixKeysOwned),
+}
+
+impl WithdrawStakeIxSuffixKeysOwned {
+    #[inline]
+    pub fn try_split(accounts: &[[u8; 32]]) -> Result<(Self, &[[u8; 32]]), RouterIxDecodeErr> {
+        match accounts.first() {
+            Some(&solido_legacy_core::PROGRAM_ID) => {
+                split::<LIDO_WITHDRAW_STAKE_IX_SUFFIX_ACCS_LEN>(accounts)
+                    .map(|(a, rem)| (Self::Lido(LidoWithdrawStakeIxSuffixKeysOwned::new(a)), rem))
+            }
+            Some(&sanctum_marinade_liquid_staking_core::MARINADE_STAKING_PROGRAM) => {
+                split::<MARINADE_WITHDRAW_STAKE_IX_SUFFIX_ACCS_LEN>(accounts).map(|(a, rem)| {
+                    (
+                        Self::Marinade(MarinadeWithdrawStakeIxSuffixKeysOwned::new(a)),
+                        rem,
+                    )
+                })
+            }
+            Some(program) if SPL_STAKE_POOL_PROGRAMS.contains(program) => {
+                split::<SPL_WITHDRAW_STAKE_IX_SUFFIX_ACCS_LEN>(accounts)
+                    .map(|(a, rem)| (Self::Spl(SplWithdrawStakeIxSuffixKeysOwned::new(a)), rem))
+            }
+            Some(_) => Err(RouterIxDecodeErr::UnknownSuffixProgram),
+            None => Err(RouterIxDecodeErr::NotEnoughAccounts),
+        }
+    }
+}
+
+#[inline]
+fn split<const N: usize>(
+    accounts: &[[u8; 32]],
+) -> Result<([[u8; 32]; N], &[[u8; 32]]), RouterIxDecodeErr> {
+    accounts
+        .split_first_chunk::<N>()
+        .map(|(a, rem)| (*a, rem))
+        .ok_or(RouterIxDecodeErr::NotEnoughAccounts)
+}
+
+#[inline]
+fn read_amount(args: &[u8]) -> Result<u64, RouterIxDecodeErr> {
+    args.first_chunk()
+        .map(|a| u64::from_le_bytes(*a))
+        .ok_or(RouterIxDecodeErr::DataTooShort)
+}
+
+#[inline]
+fn read_amount_and_seed(args: &[u8]) -> Result<(u64, u32), RouterIxDecodeErr> {
+    let amount = read_amount(args)?;
+    let seed = args
+        .get(8..)
+        .and_then(|s| s.first_chunk())
+        .map(|a| u32::from_le_bytes(*a))
+        .ok_or(RouterIxDecodeErr::DataTooShort)?;
+    Ok((amount, seed))
+}
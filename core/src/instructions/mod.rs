@@ -1,13 +1,25 @@
+mod close_fee_token_account;
+mod create_fee_token_account;
+mod decode;
 mod deposit_stake;
 mod prefund_swap_via_stake;
 mod prefund_withdraw_stake;
+mod router_ix;
 mod stake_wrapped_sol;
+mod swap_via_stake;
+mod withdraw_fees;
 mod withdraw_wrapped_sol;
 
+pub use close_fee_token_account::*;
+pub use create_fee_token_account::*;
+pub use decode::*;
 pub use deposit_stake::*;
 pub use prefund_swap_via_stake::*;
 pub use prefund_withdraw_stake::*;
+pub use router_ix::*;
 pub use stake_wrapped_sol::*;
+pub use swap_via_stake::*;
+pub use withdraw_fees::*;
 pub use withdraw_wrapped_sol::*;
 
 use crate::internal_utils::seqconsts;
@@ -0,0 +1,580 @@
+use crate::{
+    CloseFeeTokenAccountIxData, CloseFeeTokenAccountIxKeysOwned, CreateFeeTokenAccountIxData,
+    CreateFeeTokenAccountIxKeysOwned, DepositSolSufAccs, DepositStakeIxData,
+    DepositStakeIxKeysOwned, DepositStakeSufAccs, PrefundSwapViaStakeIxData,
+    PrefundSwapViaStakePrefixKeysOwned, PrefundWithdrawStakeIxData,
+    PrefundWithdrawStakePrefixKeysOwned, StakeWrappedSolIxData, StakeWrappedSolPrefixKeysOwned,
+    SwapViaStakeIxData, SwapViaStakePrefixKeysOwned, WithdrawFeesIxData, WithdrawFeesIxKeysOwned,
+    WithdrawSolSufAccs, WithdrawStakeSufAccs, WithdrawWrappedSolIxData,
+    WithdrawWrappedSolPrefixKeysOwned, CLOSE_FEE_TOKEN_ACCOUNT_IX_ACCS_LEN,
+    CLOSE_FEE_TOKEN_ACCOUNT_IX_IS_SIGNER, CLOSE_FEE_TOKEN_ACCOUNT_IX_IS_WRITER,
+    CREATE_FEE_TOKEN_ACCOUNT_IX_ACCS_LEN, CREATE_FEE_TOKEN_ACCOUNT_IX_IS_SIGNER,
+    CREATE_FEE_TOKEN_ACCOUNT_IX_IS_WRITER, DEPOSIT_STAKE_IX_ACCS_LEN, DEPOSIT_STAKE_IX_IS_SIGNER,
+    DEPOSIT_STAKE_IX_IS_WRITER_NON_WSOL_OUT, DEPOSIT_STAKE_IX_IS_WRITER_WSOL_OUT,
+    MARINADE_WITHDRAW_STAKE_IX_SUFFIX_ACCS_LEN, NATIVE_MINT,
+    PREFUND_SWAP_VIA_STAKE_PREFIX_ACCS_LEN, PREFUND_SWAP_VIA_STAKE_PREFIX_IS_SIGNER,
+    PREFUND_SWAP_VIA_STAKE_PREFIX_IS_WRITER_NON_WSOL_OUT,
+    PREFUND_SWAP_VIA_STAKE_PREFIX_IS_WRITER_WSOL_OUT, PREFUND_WITHDRAW_STAKE_PREFIX_ACCS_LEN,
+    PREFUND_WITHDRAW_STAKE_PREFIX_IS_SIGNER, PREFUND_WITHDRAW_STAKE_PREFIX_IS_WRITER,
+    SANCTUM_ROUTER_PROGRAM, SPL_DEPOSIT_STAKE_IX_SUFFIX_ACCS_LEN,
+    STAKE_WRAPPED_SOL_PREFIX_ACCS_LEN, STAKE_WRAPPED_SOL_PREFIX_IS_SIGNER,
+    STAKE_WRAPPED_SOL_PREFIX_IS_WRITER, SWAP_VIA_STAKE_PREFIX_ACCS_LEN,
+    SWAP_VIA_STAKE_PREFIX_IS_SIGNER, SWAP_VIA_STAKE_PREFIX_IS_WRITER_NON_WSOL_OUT,
+    SWAP_VIA_STAKE_PREFIX_IS_WRITER_WSOL_OUT, WITHDRAW_FEES_IX_ACCS_LEN,
+    WITHDRAW_FEES_IX_IS_SIGNER, WITHDRAW_FEES_IX_IS_WRITER, WITHDRAW_WRAPPED_SOL_PREFIX_ACCS_LEN,
+    WITHDRAW_WRAPPED_SOL_PREFIX_IS_SIGNER, WITHDRAW_WRAPPED_SOL_PREFIX_IS_WRITER,
+};
+
+/// Max number of accounts of any sanctum router instruction.
+///
+/// Largest is currently PrefundSwapViaStake: 19 prefix + 12 Marinade WithdrawStake suffix
+/// + 12 SPL DepositStake suffix = 43
+pub const ROUTER_IX_MAX_ACCOUNTS: usize = 48;
+
+const _: () = assert!(
+    ROUTER_IX_MAX_ACCOUNTS
+        >= PREFUND_SWAP_VIA_STAKE_PREFIX_ACCS_LEN
+            + MARINADE_WITHDRAW_STAKE_IX_SUFFIX_ACCS_LEN
+            + SPL_DEPOSIT_STAKE_IX_SUFFIX_ACCS_LEN
+);
+
+/// Max length of any sanctum router instruction's data
+pub const ROUTER_IX_MAX_DATA_LEN: usize = 13;
+
+// Fixed-size prefixes and data of every instruction must fit,
+// so that `RouterIx::from_fixed()` is infallible
+const _: () = {
+    let accs_lens = [
+        STAKE_WRAPPED_SOL_PREFIX_ACCS_LEN,
+        SWAP_VIA_STAKE_PREFIX_ACCS_LEN,
+        CREATE_FEE_TOKEN_ACCOUNT_IX_ACCS_LEN,
+        CLOSE_FEE_TOKEN_ACCOUNT_IX_ACCS_LEN,
+        WITHDRAW_FEES_IX_ACCS_LEN,
+        DEPOSIT_STAKE_IX_ACCS_LEN,
+        PREFUND_WITHDRAW_STAKE_PREFIX_ACCS_LEN,
+        PREFUND_SWAP_VIA_STAKE_PREFIX_ACCS_LEN,
+        WITHDRAW_WRAPPED_SOL_PREFIX_ACCS_LEN,
+    ];
+    let data_lens = [
+        core::mem::size_of::<StakeWrappedSolIxData>(),
+        core::mem::size_of::<SwapViaStakeIxData>(),
+        core::mem::size_of::<CreateFeeTokenAccountIxData>(),
+        core::mem::size_of::<CloseFeeTokenAccountIxData>(),
+        core::mem::size_of::<WithdrawFeesIxData>(),
+        core::mem::size_of::<DepositStakeIxData>(),
+        core::mem::size_of::<PrefundWithdrawStakeIxData>(),
+        core::mem::size_of::<PrefundSwapViaStakeIxData>(),
+        core::mem::size_of::<WithdrawWrappedSolIxData>(),
+    ];
+    let mut i = 0;
+    while i < accs_lens.len() {
+        assert!(accs_lens[i] <= ROUTER_IX_MAX_ACCOUNTS);
+        assert!(data_lens[i] <= ROUTER_IX_MAX_DATA_LEN);
+        i += 1;
+    }
+};
+
+/// A fully assembled sanctum router instruction,
+/// stored inline in fixed-size buffers so that no allocation is required.
+///
+/// Methods that append variable-length accounts or data return `None`
+/// instead of exceeding [`ROUTER_IX_MAX_ACCOUNTS`] or [`ROUTER_IX_MAX_DATA_LEN`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RouterIx {
+    program_address: [u8; 32],
+    keys: [[u8; 32]; ROUTER_IX_MAX_ACCOUNTS],
+    is_signer: [bool; ROUTER_IX_MAX_ACCOUNTS],
+    is_writable: [bool; ROUTER_IX_MAX_ACCOUNTS],
+    accounts_len: usize,
+    data: [u8; ROUTER_IX_MAX_DATA_LEN],
+    data_len: usize,
+}
+
+/// Accessors
+impl RouterIx {
+    /// [`SANCTUM_ROUTER_PROGRAM`] unless changed with [`Self::with_program_address`]
+    #[inline]
+    pub const fn program_address(&self) -> &[u8; 32] {
+        &self.program_address
+    }
+
+    #[inline]
+    pub fn keys(&self) -> &[[u8; 32]] {
+        &self.keys[..self.accounts_len]
+    }
+
+    #[inline]
+    pub fn is_signer(&self) -> &[bool] {
+        &self.is_signer[..self.accounts_len]
+    }
+
+    #[inline]
+    pub fn is_writable(&self) -> &[bool] {
+        &self.is_writable[..self.accounts_len]
+    }
+
+    #[inline]
+    pub fn data(&self) -> &[u8] {
+        &self.data[..self.data_len]
+    }
+
+    /// Iterator of `(pubkey, is_signer, is_writable)`
+    #[inline]
+    pub fn accounts(&self) -> impl ExactSizeIterator<Item = (&[u8; 32], bool, bool)> + '_ {
+        self.keys()
+            .iter()
+            .zip(self.is_signer())
+            .zip(self.is_writable())
+            .map(|((k, s), w)| (k, *s, *w))
+    }
+}
+
+/// Low-level builder methods
+impl RouterIx {
+    /// Creates a new instruction for [`SANCTUM_ROUTER_PROGRAM`] with no accounts.
+    ///
+    /// Returns `None` if `data` is longer than [`ROUTER_IX_MAX_DATA_LEN`]
+    #[inline]
+    pub fn new(data: &[u8]) -> Option<Self> {
+        if data.len() > ROUTER_IX_MAX_DATA_LEN {
+            return None;
+        }
+        let mut res = Self::empty();
+        res.data[..data.len()].copy_from_slice(data);
+        res.data_len = data.len();
+        Some(res)
+    }
+
+    #[inline]
+    const fn empty() -> Self {
+        Self {
+            program_address: SANCTUM_ROUTER_PROGRAM,
+            keys: [[0u8; 32]; ROUTER_IX_MAX_ACCOUNTS],
+            is_signer: [false; ROUTER_IX_MAX_ACCOUNTS],
+            is_writable: [false; ROUTER_IX_MAX_ACCOUNTS],
+            accounts_len: 0,
+            data: [0u8; ROUTER_IX_MAX_DATA_LEN],
+            data_len: 0,
+        }
+    }
+
+    /// Creates a new instruction from an instruction's fixed-size data and accounts.
+    ///
+    /// Only called with this crate's instruction types,
+    /// all of which are checked to fit at compile time above.
+    #[inline]
+    fn from_fixed<const D: usize, const N: usize>(
+        data: &[u8; D],
+        keys: &[[u8; 32]; N],
+        is_signer: &[bool; N],
+        is_writable: &[bool; N],
+    ) -> Self {
+        let mut res = Self::empty();
+        res.data[..D].copy_from_slice(data);
+        res.data_len = D;
+        res.keys[..N].copy_from_slice(keys);
+        res.is_signer[..N].copy_from_slice(is_signer);
+        res.is_writable[..N].copy_from_slice(is_writable);
+        res.accounts_len = N;
+        res
+    }
+
+    /// Sets the program the instruction is for,
+    /// e.g. `RouterConfig::router_program` for non-mainnet deployments
+    #[inline]
+    pub const fn with_program_address(mut self, program_address: [u8; 32]) -> Self {
+        self.program_address = program_address;
+        self
+    }
+
+    /// Appends `keys` with their corresponding signer and writable flags.
+    ///
+    /// Returns `None` if `keys`, `is_signer` and `is_writable` are of different lengths
+    /// or if the total number of accounts would exceed [`ROUTER_IX_MAX_ACCOUNTS`]
+    #[inline]
+    pub fn with_accounts(
+        mut self,
+        keys: &[[u8; 32]],
+        is_signer: &[bool],
+        is_writable: &[bool],
+    ) -> Option<Self> {
+        if keys.len() != is_signer.len() || keys.len() != is_writable.len() {
+            return None;
+        }
+        let start = self.accounts_len;
+        let end = start
+            .checked_add(keys.len())
+            .filter(|end| *end <= ROUTER_IX_MAX_ACCOUNTS)?;
+        self.keys[start..end].copy_from_slice(keys);
+        self.is_signer[start..end].copy_from_slice(is_signer);
+        self.is_writable[start..end].copy_from_slice(is_writable);
+        self.accounts_len = end;
+        Some(self)
+    }
+
+    #[inline]
+    pub fn with_deposit_sol_suffix(self, suffix: impl DepositSolSufAccs) -> Option<Self> {
+        self.with_accounts(
+            suffix.suffix_accounts().as_ref(),
+            suffix.suffix_is_signer().as_ref(),
+            suffix.suffix_is_writable().as_ref(),
+        )
+    }
+
+    #[inline]
+    pub fn with_withdraw_sol_suffix(self, suffix: impl WithdrawSolSufAccs) -> Option<Self> {
+        self.with_accounts(
+            suffix.suffix_accounts().as_ref(),
+            suffix.suffix_is_signer().as_ref(),
+            suffix.suffix_is_writable().as_ref(),
+        )
+    }
+
+    #[inline]
+    pub fn with_deposit_stake_suffix(self, suffix: impl DepositStakeSufAccs) -> Option<Self> {
+        self.with_accounts(
+            suffix.suffix_accounts().as_ref(),
+            suffix.suffix_is_signer().as_ref(),
+            suffix.suffix_is_writable().as_ref(),
+        )
+    }
+
+    #[inline]
+    pub fn with_withdraw_stake_suffix(self, suffix: impl WithdrawStakeSufAccs) -> Option<Self> {
+        self.with_accounts(
+            suffix.suffix_accounts().as_ref(),
+            suffix.suffix_is_signer().as_ref(),
+            suffix.suffix_is_writable().as_ref(),
+        )
+    }
+}
+
+/// Prefix-only constructors, for when the suffix accounts are only
+/// known after the prefix has been built.
+/// Follow up with the appropriate `with_*_suffix()` method(s).
+impl RouterIx {
+    #[inline]
+    pub fn stake_wrapped_sol_prefix(
+        prefix: &StakeWrappedSolPrefixKeysOwned,
+        data: StakeWrappedSolIxData,
+    ) -> Self {
+        Self::from_fixed(
+            &data.to_buf(),
+            &prefix.0,
+            &STAKE_WRAPPED_SOL_PREFIX_IS_SIGNER.0,
+            &STAKE_WRAPPED_SOL_PREFIX_IS_WRITER.0,
+        )
+    }
+
+    #[inline]
+    pub fn withdraw_wrapped_sol_prefix(
+        prefix: &WithdrawWrappedSolPrefixKeysOwned,
+        data: WithdrawWrappedSolIxData,
+    ) -> Self {
+        Self::from_fixed(
+            &data.to_buf(),
+            &prefix.0,
+            &WITHDRAW_WRAPPED_SOL_PREFIX_IS_SIGNER.0,
+            &WITHDRAW_WRAPPED_SOL_PREFIX_IS_WRITER.0,
+        )
+    }
+
+    /// Writable flags are selected based on whether `prefix.out_mint` is wSOL
+    #[inline]
+    pub fn deposit_stake_prefix(
+        prefix: &DepositStakeIxKeysOwned,
+        data: DepositStakeIxData,
+    ) -> Self {
+        let is_writer = if *prefix.out_mint() == NATIVE_MINT {
+            DEPOSIT_STAKE_IX_IS_WRITER_WSOL_OUT
+        } else {
+            DEPOSIT_STAKE_IX_IS_WRITER_NON_WSOL_OUT
+        };
+        Self::from_fixed(
+            &data.to_buf(),
+            &prefix.0,
+            &DEPOSIT_STAKE_IX_IS_SIGNER.0,
+            &is_writer.0,
+        )
+    }
+
+    #[inline]
+    pub fn prefund_withdraw_stake_prefix(
+        prefix: &PrefundWithdrawStakePrefixKeysOwned,
+        data: PrefundWithdrawStakeIxData,
+    ) -> Self {
+        Self::from_fixed(
+            &data.to_buf(),
+            &prefix.0,
+            &PREFUND_WITHDRAW_STAKE_PREFIX_IS_SIGNER.0,
+            &PREFUND_WITHDRAW_STAKE_PREFIX_IS_WRITER.0,
+        )
+    }
+
+    /// Writable flags are selected based on whether `prefix.out_mint` is wSOL
+    #[inline]
+    pub fn prefund_swap_via_stake_prefix(
+        prefix: &PrefundSwapViaStakePrefixKeysOwned,
+        data: PrefundSwapViaStakeIxData,
+    ) -> Self {
+        let is_writer = if *prefix.out_mint() == NATIVE_MINT {
+            PREFUND_SWAP_VIA_STAKE_PREFIX_IS_WRITER_WSOL_OUT
+        } else {
+            PREFUND_SWAP_VIA_STAKE_PREFIX_IS_WRITER_NON_WSOL_OUT
+        };
+        Self::from_fixed(
+            &data.to_buf(),
+            &prefix.0,
+            &PREFUND_SWAP_VIA_STAKE_PREFIX_IS_SIGNER.0,
+            &is_writer.0,
+        )
+    }
+
+    /// Writable flags are selected based on whether `prefix.out_mint` is wSOL
+    #[inline]
+    pub fn swap_via_stake_prefix(
+        prefix: &SwapViaStakePrefixKeysOwned,
+        data: SwapViaStakeIxData,
+    ) -> Self {
+        let is_writer = if *prefix.out_mint() == NATIVE_MINT {
+            SWAP_VIA_STAKE_PREFIX_IS_WRITER_WSOL_OUT
+        } else {
+            SWAP_VIA_STAKE_PREFIX_IS_WRITER_NON_WSOL_OUT
+        };
+        Self::from_fixed(
+            &data.to_buf(),
+            &prefix.0,
+            &SWAP_VIA_STAKE_PREFIX_IS_SIGNER.0,
+            &is_writer.0,
+        )
+    }
+}
+
+/// Full instruction constructors.
+///
+/// Constructors that take suffixes return `None` if the suffixes do not fit,
+/// see [`RouterIx::with_accounts`]
+impl RouterIx {
+    #[inline]
+    pub fn stake_wrapped_sol(
+        prefix: &StakeWrappedSolPrefixKeysOwned,
+        data: StakeWrappedSolIxData,
+        suffix: impl DepositSolSufAccs,
+    ) -> Option<Self> {
+        Self::stake_wrapped_sol_prefix(prefix, data).with_deposit_sol_suffix(suffix)
+    }
+
+    #[inline]
+    pub fn withdraw_wrapped_sol(
+        prefix: &WithdrawWrappedSolPrefixKeysOwned,
+        data: WithdrawWrappedSolIxData,
+        suffix: impl WithdrawSolSufAccs,
+    ) -> Option<Self> {
+        Self::withdraw_wrapped_sol_prefix(prefix, data).with_withdraw_sol_suffix(suffix)
+    }
+
+    #[inline]
+    pub fn deposit_stake(
+        prefix: &DepositStakeIxKeysOwned,
+        data: DepositStakeIxData,
+        suffix: impl DepositStakeSufAccs,
+    ) -> Option<Self> {
+        Self::deposit_stake_prefix(prefix, data).with_deposit_stake_suffix(suffix)
+    }
+
+    #[inline]
+    pub fn prefund_withdraw_stake(
+        prefix: &PrefundWithdrawStakePrefixKeysOwned,
+        data: PrefundWithdrawStakeIxData,
+        suffix: impl WithdrawStakeSufAccs,
+    ) -> Option<Self> {
+        Self::prefund_withdraw_stake_prefix(prefix, data).with_withdraw_stake_suffix(suffix)
+    }
+
+    #[inline]
+    pub fn prefund_swap_via_stake(
+        prefix: &PrefundSwapViaStakePrefixKeysOwned,
+        data: PrefundSwapViaStakeIxData,
+        withdraw_suffix: impl WithdrawStakeSufAccs,
+        deposit_suffix: impl DepositStakeSufAccs,
+    ) -> Option<Self> {
+        Self::prefund_swap_via_stake_prefix(prefix, data)
+            .with_withdraw_stake_suffix(withdraw_suffix)?
+            .with_deposit_stake_suffix(deposit_suffix)
+    }
+
+    #[inline]
+    pub fn swap_via_stake(
+        prefix: &SwapViaStakePrefixKeysOwned,
+        data: SwapViaStakeIxData,
+        withdraw_suffix: impl WithdrawStakeSufAccs,
+        deposit_suffix: impl DepositStakeSufAccs,
+    ) -> Option<Self> {
+        Self::swap_via_stake_prefix(prefix, data)
+            .with_withdraw_stake_suffix(withdraw_suffix)?
+            .with_deposit_stake_suffix(deposit_suffix)
+    }
+
+    #[inline]
+    pub fn create_fee_token_account(
+        accounts: &CreateFeeTokenAccountIxKeysOwned,
+        data: CreateFeeTokenAccountIxData,
+    ) -> Self {
+        Self::from_fixed(
+            &data.to_buf(),
+            &accounts.0,
+            &CREATE_FEE_TOKEN_ACCOUNT_IX_IS_SIGNER.0,
+            &CREATE_FEE_TOKEN_ACCOUNT_IX_IS_WRITER.0,
+        )
+    }
+
+    #[inline]
+    pub fn close_fee_token_account(
+        accounts: &CloseFeeTokenAccountIxKeysOwned,
+        data: CloseFeeTokenAccountIxData,
+    ) -> Self {
+        Self::from_fixed(
+            &data.to_buf(),
+            &accounts.0,
+            &CLOSE_FEE_TOKEN_ACCOUNT_IX_IS_SIGNER.0,
+            &CLOSE_FEE_TOKEN_ACCOUNT_IX_IS_WRITER.0,
+        )
+    }
+
+    #[inline]
+    pub fn withdraw_fees(accounts: &WithdrawFeesIxKeysOwned, data: WithdrawFeesIxData) -> Self {
+        Self::from_fixed(
+            &data.to_buf(),
+            &accounts.0,
+            &WITHDRAW_FEES_IX_IS_SIGNER.0,
+            &WITHDRAW_FEES_IX_IS_WRITER.0,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        MARINADE_WITHDRAW_STAKE_IX_SUFFIX_IS_SIGNER, MARINADE_WITHDRAW_STAKE_IX_SUFFIX_IS_WRITER,
+        SPL_DEPOSIT_STAKE_IX_SUFFIX_IS_SIGNER, SPL_DEPOSIT_STAKE_IX_SUFFIX_IS_WRITER,
+    };
+
+    use super::*;
+
+    const LARGEST_ACCS_LEN: usize = PREFUND_SWAP_VIA_STAKE_PREFIX_ACCS_LEN
+        + MARINADE_WITHDRAW_STAKE_IX_SUFFIX_ACCS_LEN
+        + SPL_DEPOSIT_STAKE_IX_SUFFIX_ACCS_LEN;
+
+    /// PrefundSwapViaStake with a Marinade WithdrawStake suffix
+    /// followed by a SPL DepositStake suffix, the largest instruction possible
+    fn largest_ix() -> RouterIx {
+        let withdraw_keys = [[1u8; 32]; MARINADE_WITHDRAW_STAKE_IX_SUFFIX_ACCS_LEN];
+        let deposit_keys = [[2u8; 32]; SPL_DEPOSIT_STAKE_IX_SUFFIX_ACCS_LEN];
+        RouterIx::prefund_swap_via_stake_prefix(
+            &PrefundSwapViaStakePrefixKeysOwned::default(),
+            PrefundSwapViaStakeIxData::new(1_000_000_000, 1),
+        )
+        .with_accounts(
+            &withdraw_keys,
+            &MARINADE_WITHDRAW_STAKE_IX_SUFFIX_IS_SIGNER.0,
+            &MARINADE_WITHDRAW_STAKE_IX_SUFFIX_IS_WRITER.0,
+        )
+        .unwrap()
+        .with_accounts(
+            &deposit_keys,
+            &SPL_DEPOSIT_STAKE_IX_SUFFIX_IS_SIGNER.0,
+            &SPL_DEPOSIT_STAKE_IX_SUFFIX_IS_WRITER.0,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn largest_suffix_combination_fits() {
+        const WITHDRAW_START: usize = PREFUND_SWAP_VIA_STAKE_PREFIX_ACCS_LEN;
+        const DEPOSIT_START: usize = WITHDRAW_START + MARINADE_WITHDRAW_STAKE_IX_SUFFIX_ACCS_LEN;
+
+        let ix = largest_ix();
+        assert_eq!(LARGEST_ACCS_LEN, 43);
+        assert_eq!(ix.keys().len(), LARGEST_ACCS_LEN);
+        assert_eq!(ix.accounts().len(), LARGEST_ACCS_LEN);
+        assert_eq!(
+            ix.data(),
+            PrefundSwapViaStakeIxData::new(1_000_000_000, 1).to_buf()
+        );
+
+        assert_eq!(
+            ix.is_signer()[..WITHDRAW_START],
+            PREFUND_SWAP_VIA_STAKE_PREFIX_IS_SIGNER.0
+        );
+        assert_eq!(
+            ix.is_writable()[..WITHDRAW_START],
+            PREFUND_SWAP_VIA_STAKE_PREFIX_IS_WRITER_NON_WSOL_OUT.0
+        );
+        assert_eq!(
+            ix.is_signer()[WITHDRAW_START..DEPOSIT_START],
+            MARINADE_WITHDRAW_STAKE_IX_SUFFIX_IS_SIGNER.0
+        );
+        assert_eq!(
+            ix.is_writable()[WITHDRAW_START..DEPOSIT_START],
+            MARINADE_WITHDRAW_STAKE_IX_SUFFIX_IS_WRITER.0
+        );
+        assert_eq!(
+            ix.is_signer()[DEPOSIT_START..],
+            SPL_DEPOSIT_STAKE_IX_SUFFIX_IS_SIGNER.0
+        );
+        assert_eq!(
+            ix.is_writable()[DEPOSIT_START..],
+            SPL_DEPOSIT_STAKE_IX_SUFFIX_IS_WRITER.0
+        );
+        assert!(ix.keys()[WITHDRAW_START..DEPOSIT_START]
+            .iter()
+            .all(|k| *k == [1u8; 32]));
+        assert!(ix.keys()[DEPOSIT_START..].iter().all(|k| *k == [2u8; 32]));
+    }
+
+    #[test]
+    fn with_accounts_up_to_max() {
+        const REMAINING: usize = ROUTER_IX_MAX_ACCOUNTS - LARGEST_ACCS_LEN;
+
+        let ix = largest_ix()
+            .with_accounts(
+                &[[3u8; 32]; REMAINING],
+                &[false; REMAINING],
+                &[false; REMAINING],
+            )
+            .unwrap();
+        assert_eq!(ix.keys().len(), ROUTER_IX_MAX_ACCOUNTS);
+
+        assert_eq!(ix.with_accounts(&[[4u8; 32]], &[false], &[false]), None);
+    }
+
+    #[test]
+    fn with_accounts_overflow_returns_none() {
+        const EXTRA: usize = ROUTER_IX_MAX_ACCOUNTS - LARGEST_ACCS_LEN + 1;
+
+        assert_eq!(
+            largest_ix().with_accounts(&[[3u8; 32]; EXTRA], &[false; EXTRA], &[false; EXTRA]),
+            None
+        );
+    }
+
+    #[test]
+    fn with_accounts_len_mismatch_returns_none() {
+        let ix = RouterIx::new(&[]).unwrap();
+        assert_eq!(
+            ix.clone()
+                .with_accounts(&[[1u8; 32]; 2], &[false; 1], &[false; 2]),
+            None
+        );
+        assert_eq!(
+            ix.with_accounts(&[[1u8; 32]; 2], &[false; 2], &[false; 3]),
+            None
+        );
+    }
+
+    #[test]
+    fn new_data_too_long_returns_none() {
+        assert!(RouterIx::new(&[0u8; ROUTER_IX_MAX_DATA_LEN]).is_some());
+        assert_eq!(RouterIx::new(&[0u8; ROUTER_IX_MAX_DATA_LEN + 1]), None);
+    }
+}
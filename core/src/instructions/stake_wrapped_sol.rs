@@ -1,6 +1,6 @@
 use generic_array_struct::generic_array_struct;
 
-use crate::{SOL_BRIDGE_OUT, SYSTEM_PROGRAM, WSOL_BRIDGE_IN};
+use crate::{RouterConfig, SYSTEM_PROGRAM};
 
 use super::INSTRUCTION_IDX_STAKE_WRAPPED_SOL;
 
@@ -48,23 +48,37 @@ impl StakeWrappedSolPrefixKeysOwned {
         StakeWrappedSolPrefixKeys::new(self.0.each_ref())
     }
 
+    #[inline]
+    pub fn with_config_consts(self, config: &RouterConfig) -> Self {
+        self.as_borrowed().with_config_consts(config).into_owned()
+    }
+
+    #[deprecated(note = "use `with_config_consts()` instead")]
     #[inline]
     pub fn with_consts(self) -> Self {
-        self.as_borrowed().with_consts().into_owned()
+        self.with_config_consts(&RouterConfig::MAINNET)
     }
 }
 
-impl StakeWrappedSolPrefixKeys<'_> {
+impl<'a> StakeWrappedSolPrefixKeys<'a> {
     #[inline]
     pub fn into_owned(self) -> StakeWrappedSolPrefixKeysOwned {
         StakeWrappedSolPrefixKeysOwned::new(self.0.map(|pk| *pk))
     }
 
+    /// Sets the accounts that are fixed for a given router deployment
     #[inline]
-    pub const fn with_consts(self) -> Self {
+    pub const fn with_config_consts(self, config: &'a RouterConfig) -> Self {
         self.const_with_system_program(&SYSTEM_PROGRAM)
-            .const_with_wsol_bridge_in(&WSOL_BRIDGE_IN)
-            .const_with_sol_bridge_out(&SOL_BRIDGE_OUT)
+            .const_with_wsol_bridge_in(&config.wsol_bridge_in)
+            .const_with_sol_bridge_out(&config.sol_bridge_out)
+    }
+
+    /// Sets the accounts that are fixed for the mainnet deployment
+    #[deprecated(note = "use `with_config_consts()` instead")]
+    #[inline]
+    pub const fn with_consts(self) -> Self {
+        self.with_config_consts(&RouterConfig::MAINNET)
     }
 }
 
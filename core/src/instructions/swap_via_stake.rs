@@ -0,0 +1,83 @@
+use generic_array_struct::generic_array_struct;
+
+use super::INSTRUCTION_IDX_SWAP_VIA_STAKE;
+
+#[generic_array_struct(builder pub)]
+#[repr(transparent)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct SwapViaStakePrefixAccs<T> {
+    /// Needs to be mutable and a system account to pay for
+    /// the rent-exemption of `bridge_stake`
+    pub user: T,
+
+    pub inp_token: T,
+    pub out_token: T,
+
+    /// The bridge stake account PDA that is withdrawn to and then deposited.
+    /// Make sure the seed is not already in use.
+    ///
+    /// `seeds = ['bridge_stake', user.pubkey, bridge_stake_seed]`
+    pub bridge_stake: T,
+
+    pub out_fee_token: T,
+    pub inp_mint: T,
+    pub out_mint: T,
+}
+
+pub type SwapViaStakePrefixKeysOwned = SwapViaStakePrefixAccs<[u8; 32]>;
+pub type SwapViaStakePrefixKeys<'a> = SwapViaStakePrefixAccs<&'a [u8; 32]>;
+pub type SwapViaStakePrefixAccsFlag = SwapViaStakePrefixAccs<bool>;
+
+pub const SWAP_VIA_STAKE_PREFIX_IS_WRITER_NON_WSOL_OUT: SwapViaStakePrefixAccsFlag =
+    SwapViaStakePrefixAccs([true; SWAP_VIA_STAKE_PREFIX_ACCS_LEN]);
+
+/// If output mint is wsol, it must be set to readonly
+pub const SWAP_VIA_STAKE_PREFIX_IS_WRITER_WSOL_OUT: SwapViaStakePrefixAccsFlag =
+    SWAP_VIA_STAKE_PREFIX_IS_WRITER_NON_WSOL_OUT.const_with_out_mint(false);
+
+pub const SWAP_VIA_STAKE_PREFIX_IS_SIGNER: SwapViaStakePrefixAccsFlag =
+    SwapViaStakePrefixAccs([false; SWAP_VIA_STAKE_PREFIX_ACCS_LEN]).const_with_user(true);
+
+impl<T> SwapViaStakePrefixAccs<T> {
+    #[inline]
+    pub const fn new(arr: [T; SWAP_VIA_STAKE_PREFIX_ACCS_LEN]) -> Self {
+        Self(arr)
+    }
+}
+
+impl SwapViaStakePrefixKeysOwned {
+    #[inline]
+    pub fn as_borrowed(&self) -> SwapViaStakePrefixKeys<'_> {
+        SwapViaStakePrefixKeys::new(self.0.each_ref())
+    }
+}
+
+impl SwapViaStakePrefixKeys<'_> {
+    #[inline]
+    pub fn into_owned(self) -> SwapViaStakePrefixKeysOwned {
+        SwapViaStakePrefixKeysOwned::new(self.0.map(|pk| *pk))
+    }
+}
+
+#[repr(transparent)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SwapViaStakeIxData([u8; 13]);
+
+impl SwapViaStakeIxData {
+    #[inline]
+    pub fn new(amount: u64, bridge_stake_seed: u32) -> Self {
+        let mut buf = [0u8; 13];
+
+        buf[0] = INSTRUCTION_IDX_SWAP_VIA_STAKE;
+        buf[1..9].copy_from_slice(&amount.to_le_bytes());
+        buf[9..13].copy_from_slice(&bridge_stake_seed.to_le_bytes());
+
+        Self(buf)
+    }
+
+    #[inline]
+    pub const fn to_buf(&self) -> [u8; 13] {
+        self.0
+    }
+}
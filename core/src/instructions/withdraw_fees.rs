@@ -0,0 +1,79 @@
+use generic_array_struct::generic_array_struct;
+
+use super::INSTRUCTION_IDX_WITHDRAW_FEES;
+
+#[generic_array_struct(builder pub)]
+#[repr(transparent)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct WithdrawFeesIxAccs<T> {
+    /// The router program's admin
+    pub admin: T,
+
+    /// The fee token account PDA to withdraw all accumulated fees from.
+    ///
+    /// `seeds = ['fee', mint.pubkey]`
+    pub fee_token_account: T,
+
+    /// Token account of `mint` to receive the withdrawn fees
+    pub withdraw_to: T,
+
+    pub mint: T,
+    pub token_program: T,
+}
+
+pub type WithdrawFeesIxKeysOwned = WithdrawFeesIxAccs<[u8; 32]>;
+pub type WithdrawFeesIxKeys<'a> = WithdrawFeesIxAccs<&'a [u8; 32]>;
+pub type WithdrawFeesIxAccsFlag = WithdrawFeesIxAccs<bool>;
+
+pub const WITHDRAW_FEES_IX_IS_WRITER: WithdrawFeesIxAccsFlag =
+    WithdrawFeesIxAccs([false; WITHDRAW_FEES_IX_ACCS_LEN])
+        .const_with_fee_token_account(true)
+        .const_with_withdraw_to(true);
+
+pub const WITHDRAW_FEES_IX_IS_SIGNER: WithdrawFeesIxAccsFlag =
+    WithdrawFeesIxAccs([false; WITHDRAW_FEES_IX_ACCS_LEN]).const_with_admin(true);
+
+impl<T> WithdrawFeesIxAccs<T> {
+    #[inline]
+    pub const fn new(arr: [T; WITHDRAW_FEES_IX_ACCS_LEN]) -> Self {
+        Self(arr)
+    }
+}
+
+impl<T> AsRef<[T]> for WithdrawFeesIxAccs<T> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl WithdrawFeesIxKeysOwned {
+    #[inline]
+    pub fn as_borrowed(&self) -> WithdrawFeesIxKeys<'_> {
+        WithdrawFeesIxKeys::new(self.0.each_ref())
+    }
+}
+
+impl WithdrawFeesIxKeys<'_> {
+    #[inline]
+    pub fn into_owned(self) -> WithdrawFeesIxKeysOwned {
+        WithdrawFeesIxKeysOwned::new(self.0.map(|pk| *pk))
+    }
+}
+
+#[repr(transparent)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WithdrawFeesIxData([u8; 1]);
+
+impl WithdrawFeesIxData {
+    #[inline]
+    pub const fn new() -> Self {
+        Self([INSTRUCTION_IDX_WITHDRAW_FEES])
+    }
+
+    #[inline]
+    pub const fn to_buf(&self) -> [u8; 1] {
+        self.0
+    }
+}
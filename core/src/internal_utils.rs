@@ -30,3 +30,19 @@ macro_rules! seqconsts {
     ($($tail:tt)*) => { seqconsts!(@cnt 0; $($tail)*); };
 }
 pub(crate) use seqconsts;
+
+/// `amt * num / denom`, rounded down.
+///
+/// Returns `None` if `denom` is 0 or the result overflows u64
+#[inline]
+pub(crate) const fn mul_div_floor(amt: u64, num: u64, denom: u64) -> Option<u64> {
+    if denom == 0 {
+        return None;
+    }
+    let res = (amt as u128) * (num as u128) / (denom as u128);
+    if res > u64::MAX as u128 {
+        None
+    } else {
+        Some(res as u64)
+    }
+}
@@ -6,17 +6,31 @@
 
 #![cfg_attr(all(not(test), not(feature = "std")), no_std)]
 
+mod config;
 mod consts;
+mod err;
 mod instructions;
 mod internal_utils;
 mod pda;
 mod quote;
+mod rent;
 mod routers;
 mod traits;
+mod update;
 
+#[cfg(feature = "solana")]
+mod solana;
+
+pub use config::*;
 pub use consts::*;
+pub use err::*;
 pub use instructions::*;
 pub use pda::*;
 pub use quote::*;
+pub use rent::*;
 pub use routers::*;
 pub use traits::*;
+pub use update::*;
+
+#[cfg(feature = "solana")]
+pub use solana::*;
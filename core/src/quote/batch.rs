@@ -0,0 +1,15 @@
+/// Quotes each of `amts` in order with the same `quoter`.
+///
+/// Use this to build a quote curve, e.g. for price impact charts,
+/// without having to reselect and reconstruct the quoter for every amount.
+///
+/// `quote` is typically a quoter trait method, e.g.
+/// `quote_batch(&quoter, amts, DepositSolQuoter::quote_deposit_sol)`.
+#[inline]
+pub fn quote_batch<Q, T, E>(
+    quoter: Q,
+    amts: impl IntoIterator<Item = u64>,
+    quote: impl Fn(&Q, u64) -> Result<T, E>,
+) -> impl Iterator<Item = Result<T, E>> {
+    amts.into_iter().map(move |amt| quote(&quoter, amt))
+}
@@ -0,0 +1,154 @@
+use core::cmp::Ordering;
+
+use solido_legacy_core::STSOL_MINT_ADDR;
+
+use crate::NATIVE_MINT;
+
+/// The single-instruction routes the router program can use to swap
+/// between a token pair.
+///
+/// Ordering of the variants is used as the tie-break in [`quote_best`],
+/// simpler routes come first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "wasm",
+    derive(tsify_next::Tsify),
+    tsify(into_wasm_abi, from_wasm_abi)
+)]
+pub enum RouteKind {
+    DepositSol,
+    WithdrawSol,
+    SwapViaStake,
+    PrefundSwapViaStake,
+}
+
+impl RouteKind {
+    pub const ALL: [Self; 4] = [
+        Self::DepositSol,
+        Self::WithdrawSol,
+        Self::SwapViaStake,
+        Self::PrefundSwapViaStake,
+    ];
+
+    /// Returns whether this route supports swapping from `inp_mint` to `out_mint`.
+    ///
+    /// This only considers what the underlying pool programs support;
+    /// the route may still fail to quote, e.g. if the pool's router has not been initialized.
+    pub fn supports(&self, inp_mint: &[u8; 32], out_mint: &[u8; 32]) -> bool {
+        match self {
+            // lido no longer accepts deposits
+            Self::DepositSol => {
+                *inp_mint == NATIVE_MINT && !matches!(*out_mint, NATIVE_MINT | STSOL_MINT_ADDR)
+            }
+            // lido does not support WithdrawSol.
+            // mSOL's WithdrawSol is marinade's liquid unstake
+            Self::WithdrawSol => {
+                *out_mint == NATIVE_MINT && !matches!(*inp_mint, NATIVE_MINT | STSOL_MINT_ADDR)
+            }
+            // lido does not support DepositStake.
+            // Output to wSOL deposits into the reserve pool
+            Self::SwapViaStake | Self::PrefundSwapViaStake => {
+                *inp_mint != NATIVE_MINT && *out_mint != STSOL_MINT_ADDR && inp_mint != out_mint
+            }
+        }
+    }
+
+    /// Returns all routes that support swapping from `inp_mint` to `out_mint`
+    #[inline]
+    pub fn candidates(inp_mint: [u8; 32], out_mint: [u8; 32]) -> impl Iterator<Item = Self> {
+        Self::ALL
+            .into_iter()
+            .filter(move |r| r.supports(&inp_mint, &out_mint))
+    }
+}
+
+/// Summary of a route's quote, in a form comparable across routes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+#[cfg_attr(
+    feature = "wasm",
+    derive(tsify_next::Tsify),
+    tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)
+)]
+pub struct RouteQuote {
+    pub route: RouteKind,
+
+    /// Input tokens that will leave the user's wallet
+    pub inp: u64,
+
+    /// The amount of tokens received, after all fees including router fees
+    pub out: u64,
+
+    /// Router fee charged, in terms of output tokens
+    pub router_fee: u64,
+
+    /// Lamports the user pays for the rent-exemption of the bridge stake account,
+    /// on top of `inp` input tokens. Only nonzero for [`RouteKind::SwapViaStake`].
+    ///
+    /// This is deposited along with the withdrawn stake, so `out` includes its value
+    pub bridge_rent: u64,
+
+    /// `out` minus the output tokens `bridge_rent` is worth,
+    /// i.e. the output tokens received for `inp` alone.
+    ///
+    /// Quotes are ranked by this instead of `out`
+    pub net_out: u64,
+}
+
+impl RouteQuote {
+    /// Orders quotes from best to worst:
+    /// most net output tokens first, ties broken by [`RouteKind`]'s ordering
+    #[inline]
+    pub fn cmp_best(&self, other: &Self) -> Ordering {
+        other
+            .net_out
+            .cmp(&self.net_out)
+            .then_with(|| self.route.cmp(&other.route))
+    }
+}
+
+/// The quotes of all routes that successfully quoted, ranked from best to worst
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct RankedRouteQuotes([Option<RouteQuote>; RouteKind::ALL.len()]);
+
+impl RankedRouteQuotes {
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &RouteQuote> {
+        self.0.iter().map_while(Option::as_ref)
+    }
+
+    #[inline]
+    pub fn best(&self) -> Option<&RouteQuote> {
+        self.0[0].as_ref()
+    }
+}
+
+/// Quotes every route that supports swapping `amt` of `inp_mint` to `out_mint`
+/// with `quote_route` and ranks the results with [`RouteQuote::cmp_best`].
+///
+/// Routes that fail to quote are excluded from the returned quotes.
+pub fn quote_best<E>(
+    inp_mint: &[u8; 32],
+    out_mint: &[u8; 32],
+    amt: u64,
+    mut quote_route: impl FnMut(RouteKind, u64) -> Result<RouteQuote, E>,
+) -> RankedRouteQuotes {
+    let mut res = RankedRouteQuotes::default();
+    RouteKind::candidates(*inp_mint, *out_mint)
+        .filter_map(|route| quote_route(route, amt).ok())
+        .zip(res.0.iter_mut())
+        .for_each(|(q, slot)| *slot = Some(q));
+    // None sorts last
+    res.0.sort_unstable_by(|a, b| match (a, b) {
+        (Some(a), Some(b)) => a.cmp_best(b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+    res
+}
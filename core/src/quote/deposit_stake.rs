@@ -1,4 +1,4 @@
-use crate::{ActiveStakeParams, WithRouterFee, DEPOSIT_STAKE_GLOBAL_FEE_BPS};
+use crate::{ActiveStakeParams, RouterFee, RouterFeeSchedule, WithRouterFee};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct DepositStakeQuote {
@@ -13,18 +13,15 @@ pub struct DepositStakeQuote {
 }
 
 impl DepositStakeQuote {
-    pub fn with_router_fee(self) -> WithRouterFee<Self> {
+    /// Charges `router_fee` on `self.out`.
+    ///
+    /// DepositStake, SwapViaStake and PrefundSwapViaStake use their
+    /// respective [`crate::RouterFeeSchedule`] entries,
+    /// or [`RouterFee::ZERO`] if the output mint is wSOL.
+    #[inline]
+    pub const fn with_router_fee_of(self, router_fee: &RouterFee) -> WithRouterFee<Self> {
         let Self { inp, out, fee } = self;
-
-        if out == 0 {
-            return WithRouterFee::zero(self);
-        }
-
-        // cast-safety: DEPOSIT_STAKE_GLOBAL_FEE_BPS is < 10_000, no overflow should happen
-        let router_fee =
-            ((out as u128) * (DEPOSIT_STAKE_GLOBAL_FEE_BPS as u128) / 10_000u128) as u64;
-        let router_fee = router_fee.max(1);
-
+        let router_fee = router_fee.fee(out);
         WithRouterFee {
             quote: Self {
                 inp,
@@ -34,4 +31,13 @@ impl DepositStakeQuote {
             router_fee,
         }
     }
+
+    /// Applies for DepositStake
+    #[deprecated(
+        note = "use `with_router_fee_of()` with `RouterFeeSchedule::deposit_stake` instead"
+    )]
+    #[inline]
+    pub const fn with_router_fee(self) -> WithRouterFee<Self> {
+        self.with_router_fee_of(&RouterFeeSchedule::MAINNET.deposit_stake)
+    }
 }
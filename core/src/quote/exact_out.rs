@@ -0,0 +1,90 @@
+use core::{error::Error, fmt::Display};
+
+use crate::StakeQuoteError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ExactOutQuoteErr<E> {
+    /// No input amount up to `u64::MAX` results in the desired output amount
+    OutTooLarge,
+
+    /// Quoting the required input amount failed,
+    /// e.g. because it exceeds the pool's liquidity
+    Pool(E),
+}
+
+impl<E: core::fmt::Debug> Display for ExactOutQuoteErr<E> {
+    // Display=Debug, since this is just a simple discriminated str enum
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl<E: core::fmt::Debug> Error for ExactOutQuoteErr<E> {}
+
+impl<E: StakeQuoteError> StakeQuoteError for ExactOutQuoteErr<E> {
+    #[inline]
+    fn is_vote_specific(&self) -> bool {
+        match self {
+            Self::OutTooLarge => false,
+            Self::Pool(e) => e.is_vote_specific(),
+        }
+    }
+}
+
+/// Finds the quote for the smallest nonzero input amount that results in
+/// at least `out` output, where `quote_out` extracts the output amount from
+/// a quote returned by `quote`.
+///
+/// Output amounts are rounded, so the returned quote's output may be slightly more than `out`.
+///
+/// This is a search over `quote`, so it works for any pool, but assumes that
+/// output is monotonically non-decreasing with input.
+/// Errors from `quote` for inputs smaller than the required input are skipped, since these are
+/// usually "deposit too small" errors, while errors for larger inputs are returned.
+pub fn quote_exact_out<Q, E>(
+    out: u64,
+    quote: impl Fn(u64) -> Result<Q, E>,
+    quote_out: impl Fn(&Q) -> u64,
+) -> Result<Q, ExactOutQuoteErr<E>> {
+    // Find an upper bound by doubling, starting from `out`.
+    // Invariant: all inputs <= lo are insufficient
+    let mut lo = 0;
+    let mut hi = out.max(1);
+    let mut seen_insufficient = false;
+    let mut last_err = None;
+    let mut res = loop {
+        match quote(hi) {
+            Ok(q) if quote_out(&q) >= out => break q,
+            Ok(_) => seen_insufficient = true,
+            Err(e) => {
+                // an error after an insufficient quote means
+                // the required input is too large for the pool
+                if seen_insufficient {
+                    return Err(ExactOutQuoteErr::Pool(e));
+                }
+                last_err = Some(e);
+            }
+        }
+        if hi == u64::MAX {
+            return Err(match last_err {
+                Some(e) if !seen_insufficient => ExactOutQuoteErr::Pool(e),
+                _ => ExactOutQuoteErr::OutTooLarge,
+            });
+        }
+        lo = hi;
+        hi = hi.saturating_mul(2);
+    };
+
+    // binary search for the smallest sufficient input in (lo, hi]
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        match quote(mid) {
+            Ok(q) if quote_out(&q) >= out => {
+                hi = mid;
+                res = q;
+            }
+            _ => lo = mid,
+        }
+    }
+    Ok(res)
+}
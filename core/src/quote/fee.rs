@@ -0,0 +1,248 @@
+use crate::{
+    DepositStakeQuote, Prefund, SolValueCalc, TokenQuote, WithRouterFee,
+    WithdrawSolDepositSolQuote, WithdrawStakeQuote,
+};
+
+/// Max number of nonzero fee components any single quote has.
+///
+/// WithdrawSol + DepositSol has the most: withdraw pool fee, WithdrawSol router fee,
+/// deposit pool fee, referral fee, DepositSol router fee.
+/// PrefundSwapViaStake has 4: withdraw pool fee, deposit pool fee, router fee, prefund fee.
+pub const MAX_FEE_COMPONENTS: usize = 5;
+
+const _: () = assert!(MAX_FEE_COMPONENTS == fee_count(WithdrawSolDepositSolQuote::fees));
+
+/// Length of the fee array returned by `fees`, for compile-time checks
+const fn fee_count<A, B, const N: usize>(_fees: fn(A, B) -> [Fee; N]) -> usize {
+    N
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "wasm",
+    derive(tsify_next::Tsify),
+    tsify(into_wasm_abi, from_wasm_abi)
+)]
+pub enum FeeKind {
+    /// Fee charged by the stake pool, paid to the pool's manager
+    PoolManager,
+
+    /// Part of the stake pool's fee paid to the referrer instead of the manager
+    Referral,
+
+    /// Sanctum router global fee
+    Router,
+
+    /// Instant unstake fee charged by the reserve pool to repay the prefund flash loan
+    Prefund,
+
+    /// Instant unstake fee charged by the reserve pool for deposits into it
+    ReserveUnstake,
+}
+
+/// The tokens a fee is charged in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "wasm",
+    derive(tsify_next::Tsify),
+    tsify(into_wasm_abi, from_wasm_abi)
+)]
+pub enum FeeUnit {
+    /// The quote's input tokens
+    Inp,
+
+    /// The quote's output tokens
+    Out,
+
+    /// SOL
+    Lamports,
+}
+
+/// A fee in its native unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fee {
+    pub kind: FeeKind,
+    pub unit: FeeUnit,
+    pub amt: u64,
+}
+
+impl Fee {
+    #[inline]
+    pub const fn new(kind: FeeKind, unit: FeeUnit, amt: u64) -> Self {
+        Self { kind, unit, amt }
+    }
+}
+
+/// A fee in its native unit and its lamport-equivalent value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+#[cfg_attr(
+    feature = "wasm",
+    derive(tsify_next::Tsify),
+    tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)
+)]
+pub struct FeeComponent {
+    pub kind: FeeKind,
+
+    pub unit: FeeUnit,
+
+    /// In terms of `unit`
+    pub amt: u64,
+
+    /// `amt` converted to SOL at the pool's current exchange rate
+    pub lamports: u64,
+}
+
+/// The nonzero fee components of a quote
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct FeeBreakdown([Option<FeeComponent>; MAX_FEE_COMPONENTS]);
+
+impl FeeBreakdown {
+    /// Converts each of `fees` to lamports with `inp` for [`FeeUnit::Inp`]
+    /// and `out` for [`FeeUnit::Out`] fees. Zero fees are omitted.
+    ///
+    /// Returns `None` if a fee fails to convert or
+    /// there are more than [`MAX_FEE_COMPONENTS`] nonzero fees.
+    pub fn new<I: SolValueCalc + ?Sized, O: SolValueCalc + ?Sized>(
+        fees: impl IntoIterator<Item = Fee>,
+        inp: &I,
+        out: &O,
+    ) -> Option<Self> {
+        let mut res = Self::default();
+        let mut slots = res.0.iter_mut();
+        for Fee { kind, unit, amt } in fees.into_iter().filter(|f| f.amt > 0) {
+            let lamports = match unit {
+                FeeUnit::Inp => inp.sol_value(amt)?,
+                FeeUnit::Out => out.sol_value(amt)?,
+                FeeUnit::Lamports => amt,
+            };
+            *slots.next()? = Some(FeeComponent {
+                kind,
+                unit,
+                amt,
+                lamports,
+            });
+        }
+        Some(res)
+    }
+
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &FeeComponent> {
+        self.0.iter().map_while(Option::as_ref)
+    }
+
+    /// Sum of all components' lamport-equivalent values
+    #[inline]
+    pub fn total_lamports(&self) -> u64 {
+        self.iter().fold(0, |sum, c| sum.saturating_add(c.lamports))
+    }
+}
+
+impl WithRouterFee<TokenQuote> {
+    /// `referral_fee` is the part of `self.quote.fee` paid to the referrer
+    #[inline]
+    pub const fn deposit_sol_fees(&self, referral_fee: u64) -> [Fee; 3] {
+        [
+            Fee::new(
+                FeeKind::PoolManager,
+                FeeUnit::Out,
+                self.quote.fee.saturating_sub(referral_fee),
+            ),
+            Fee::new(FeeKind::Referral, FeeUnit::Out, referral_fee),
+            Fee::new(FeeKind::Router, FeeUnit::Out, self.router_fee),
+        ]
+    }
+
+    /// The stake pool charges its WithdrawSol fee in pool tokens, i.e. input tokens
+    #[inline]
+    pub const fn withdraw_sol_fees(&self) -> [Fee; 2] {
+        [
+            Fee::new(FeeKind::PoolManager, FeeUnit::Inp, self.quote.fee),
+            Fee::new(FeeKind::Router, FeeUnit::Out, self.router_fee),
+        ]
+    }
+}
+
+impl WithRouterFee<DepositStakeQuote> {
+    /// `pool` is the kind of `self.quote.fee`:
+    /// [`FeeKind::ReserveUnstake`] for deposits into the reserve pool,
+    /// [`FeeKind::PoolManager`] otherwise.
+    ///
+    /// Stake pool referral fees are not included since the router
+    /// sets the referrer to the output token account, so they go to the user.
+    #[inline]
+    pub const fn fees(&self, pool: FeeKind) -> [Fee; 2] {
+        [
+            Fee::new(pool, FeeUnit::Out, self.quote.fee),
+            Fee::new(FeeKind::Router, FeeUnit::Out, self.router_fee),
+        ]
+    }
+}
+
+impl Prefund<WithdrawStakeQuote> {
+    #[inline]
+    pub const fn fees(&self) -> [Fee; 2] {
+        [
+            Fee::new(FeeKind::PoolManager, FeeUnit::Inp, self.quote.fee),
+            Fee::new(FeeKind::Prefund, FeeUnit::Lamports, self.prefund_fee),
+        ]
+    }
+}
+
+impl WithdrawStakeQuote {
+    #[inline]
+    pub const fn fee(&self) -> Fee {
+        Fee::new(FeeKind::PoolManager, FeeUnit::Inp, self.fee)
+    }
+}
+
+/// Fees of a SwapViaStake route.
+///
+/// See [`WithRouterFee::<DepositStakeQuote>::fees`] for `pool`
+#[inline]
+pub const fn swap_via_stake_fees(
+    wsq: &WithdrawStakeQuote,
+    dsq: &WithRouterFee<DepositStakeQuote>,
+    pool: FeeKind,
+) -> [Fee; 3] {
+    let [d, r] = dsq.fees(pool);
+    [wsq.fee(), d, r]
+}
+
+/// Fees of a PrefundSwapViaStake route.
+///
+/// See [`WithRouterFee::<DepositStakeQuote>::fees`] for `pool`
+#[inline]
+pub const fn prefund_swap_via_stake_fees(
+    wsq: &Prefund<WithdrawStakeQuote>,
+    dsq: &WithRouterFee<DepositStakeQuote>,
+    pool: FeeKind,
+) -> [Fee; 4] {
+    let [w, p] = wsq.fees();
+    let [d, r] = dsq.fees(pool);
+    [w, d, r, p]
+}
+
+impl WithdrawSolDepositSolQuote {
+    /// The WithdrawSol leg's router fee is in [`FeeUnit::Lamports`].
+    ///
+    /// `referral_fee` is the part of `self.deposit_sol.quote.fee` paid to the referrer
+    #[inline]
+    pub const fn fees(&self, referral_fee: u64) -> [Fee; 5] {
+        let [wm, wr] = self.withdraw_sol.withdraw_sol_fees();
+        let [dm, dref, dr] = self.deposit_sol.deposit_sol_fees(referral_fee);
+        [
+            wm,
+            Fee::new(wr.kind, FeeUnit::Lamports, wr.amt),
+            dm,
+            dref,
+            dr,
+        ]
+    }
+}
@@ -1,15 +1,29 @@
+mod batch;
+mod best;
 mod deposit_stake;
+mod exact_out;
+mod fee;
 mod prefund;
+mod router_fee;
+mod split;
 mod stake;
 mod swap_via_stake;
 mod token;
+mod withdraw_sol_deposit_sol;
 mod withdraw_stake;
 
+pub use batch::*;
+pub use best::*;
 pub use deposit_stake::*;
+pub use exact_out::*;
+pub use fee::*;
 pub use prefund::*;
+pub use router_fee::*;
+pub use split::*;
 pub use stake::*;
 pub use swap_via_stake::*;
 pub use token::*;
+pub use withdraw_sol_deposit_sol::*;
 pub use withdraw_stake::*;
 
 /// A quote with the sanctum router global fee charged on top.
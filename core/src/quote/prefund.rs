@@ -1,6 +1,6 @@
-use sanctum_reserve_core::{FeeEnum, PoolBalance};
+use sanctum_reserve_core::{FeeEnum, PoolBalance, ReserveError};
 
-use crate::{PREFUND_FLASH_LOAN_LAMPORTS, ZERO_DATA_ACC_RENT_EXEMPT_LAMPORTS};
+use crate::{PrefundWithdrawStakeQuoteErr, RentParams};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(
@@ -27,12 +27,57 @@ pub struct Prefund<Q> {
 pub fn slumdog_target_lamports(
     reserves_balance: &PoolBalance,
     reserves_fee: &FeeEnum,
+    rent: &RentParams,
 ) -> Option<u64> {
-    reserves_fee.reverse_from_rem(reserves_balance, PREFUND_FLASH_LOAN_LAMPORTS)
+    reserves_fee.reverse_from_rem(reserves_balance, rent.prefund_flash_loan_lamports())
 }
 
 #[inline]
-pub const fn reserves_has_enough_for_slumdog(reserves_balance: &PoolBalance) -> bool {
+pub fn reserves_has_enough_for_slumdog(reserves_balance: &PoolBalance, rent: &RentParams) -> bool {
     reserves_balance.sol_reserves_lamports
-        >= PREFUND_FLASH_LOAN_LAMPORTS + ZERO_DATA_ACC_RENT_EXEMPT_LAMPORTS
+        >= rent
+            .prefund_flash_loan_lamports()
+            .saturating_add(rent.zero_data_acc_rent_exempt_lamports())
+}
+
+/// Reserve pool and cluster state that prefunded WithdrawStake quotes depend on
+#[derive(Debug, Clone, Copy)]
+pub struct PrefundParams<'a> {
+    pub reserves_balance: PoolBalance,
+    pub reserves_fee: &'a FeeEnum,
+    pub rent: RentParams,
+
+    /// The stake program's minimum delegation, in lamports
+    pub min_delegation: u64,
+}
+
+impl PrefundParams<'_> {
+    #[inline]
+    pub fn slumdog_target_lamports(&self) -> Option<u64> {
+        slumdog_target_lamports(&self.reserves_balance, self.reserves_fee, &self.rent)
+    }
+
+    #[inline]
+    pub fn reserves_has_enough_for_slumdog(&self) -> bool {
+        reserves_has_enough_for_slumdog(&self.reserves_balance, &self.rent)
+    }
+
+    /// Computes the active stake that is split from the withdrawn stake account
+    /// into the slumdog stake account, which is the prefund fee.
+    ///
+    /// Errors if the slumdog stake account would be delegated less than
+    /// `self.min_delegation`.
+    #[inline]
+    pub fn slumdog_split_lamports<E>(&self) -> Result<u64, PrefundWithdrawStakeQuoteErr<E>> {
+        let split = self
+            .slumdog_target_lamports()
+            .ok_or(PrefundWithdrawStakeQuoteErr::Reserve(
+                ReserveError::InternalError,
+            ))?
+            .saturating_sub(self.rent.stake_account_rent_exempt_lamports());
+        if split < self.min_delegation {
+            return Err(PrefundWithdrawStakeQuoteErr::BelowMinimumDelegation);
+        }
+        Ok(split)
+    }
 }
@@ -0,0 +1,94 @@
+use crate::{DEPOSIT_STAKE_GLOBAL_FEE_BPS, WITHDRAW_WRAPPED_SOL_GLOBAL_FEE_BPS};
+
+/// The sanctum router global fee charged by a single instruction type,
+/// in terms of the instruction's output tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+#[cfg_attr(
+    feature = "wasm",
+    derive(tsify_next::Tsify),
+    tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)
+)]
+pub struct RouterFee {
+    /// Fee rate in basis points of output tokens, rounded down
+    pub bps: u64,
+
+    /// Fee charged if the rounded-down fee is smaller than this.
+    /// Only applies to nonzero outputs.
+    ///
+    /// The on-chain program charges at least 1 token for every
+    /// instruction that has a nonzero fee rate.
+    pub min_fee: u64,
+}
+
+impl RouterFee {
+    pub const ZERO: Self = Self { bps: 0, min_fee: 0 };
+
+    /// `bps` with the on-chain program's minimum fee of 1 token
+    #[inline]
+    pub const fn with_min_one(bps: u64) -> Self {
+        Self { bps, min_fee: 1 }
+    }
+
+    /// Returns the router fee charged on `out` output tokens:
+    /// 0 if `out` is 0, else `max(floor(out * bps / 10_000), min_fee)`,
+    /// capped at `out`.
+    #[inline]
+    pub const fn fee(&self, out: u64) -> u64 {
+        if out == 0 {
+            return 0;
+        }
+        let fee = (out as u128) * (self.bps as u128) / 10_000;
+        let fee = if fee < self.min_fee as u128 {
+            self.min_fee as u128
+        } else {
+            fee
+        };
+        // cast-safety: capped at out, which is a u64
+        if fee > out as u128 {
+            out
+        } else {
+            fee as u64
+        }
+    }
+}
+
+/// Router fees of each instruction type that charges one.
+///
+/// StakeWrappedSol, PrefundWithdrawStake and deposits of stake into the reserve pool
+/// (DepositStake to wSOL) do not charge router fees.
+///
+/// Defaults to [`RouterFeeSchedule::MAINNET`].
+/// Override this if the on-chain fees change before this library is updated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct RouterFeeSchedule {
+    pub withdraw_wrapped_sol: RouterFee,
+    pub deposit_stake: RouterFee,
+    pub swap_via_stake: RouterFee,
+    pub prefund_swap_via_stake: RouterFee,
+}
+
+impl RouterFeeSchedule {
+    pub const MAINNET: Self = Self {
+        withdraw_wrapped_sol: RouterFee::with_min_one(WITHDRAW_WRAPPED_SOL_GLOBAL_FEE_BPS),
+        deposit_stake: RouterFee::with_min_one(DEPOSIT_STAKE_GLOBAL_FEE_BPS),
+        swap_via_stake: RouterFee::with_min_one(DEPOSIT_STAKE_GLOBAL_FEE_BPS),
+        prefund_swap_via_stake: RouterFee::with_min_one(DEPOSIT_STAKE_GLOBAL_FEE_BPS),
+    };
+}
+
+impl Default for RouterFeeSchedule {
+    #[inline]
+    fn default() -> Self {
+        Self::MAINNET
+    }
+}
@@ -0,0 +1,215 @@
+use core::{error::Error, fmt::Display};
+
+/// Default number of equal parts [`split_input`] divides the total input into
+pub const DEFAULT_SPLIT_PARTS: u64 = 16;
+
+/// Amount of a split route's total input allocated to a single leg,
+/// and the output of that leg for that input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SplitAlloc {
+    pub inp: u64,
+    pub out: u64,
+
+    /// Cached quote of this leg taking the next chunk, see [`split_input`]
+    next: NextChunk,
+
+    /// Whether an error of this leg was already passed to `on_err`
+    err_reported: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+enum NextChunk {
+    #[default]
+    Unquoted,
+
+    /// Additional output of this leg from taking the next chunk
+    Gain(u64),
+
+    /// This leg cannot take the next chunk
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SplitErr {
+    /// `legs` and `allocs` have different lengths
+    LenMismatch,
+
+    /// `parts` is 0
+    ZeroParts,
+
+    /// None of the legs can take more input,
+    /// `unallocated` input could not be allocated
+    NoCapacity { unallocated: u64 },
+}
+
+impl Display for SplitErr {
+    // Display=Debug, since this is just a simple discriminated str enum
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl Error for SplitErr {}
+
+/// Divides `total` input across `legs` to maximize total output,
+/// writing each leg's allocation into the corresponding entry of `allocs`.
+/// Returns the total output.
+///
+/// Legs execute in order and share `state`: each leg is quoted against the state left behind by
+/// the legs before it that were allocated input, and updates it to after it has executed.
+/// This accounts for legs that draw on the same liquidity, such as PrefundSwapViaStake legs that
+/// all repay their prefund flash loans to, and possibly deposit into, the reserve pool.
+///
+/// Each leg returns its output for a given input amount, or an error if it cannot take
+/// that amount, e.g. because it exceeds the validator's active stake or the pool's liquidity.
+/// `on_err` is called with the leg's index for the first such error of each leg.
+/// Use this to find out why [`SplitErr::NoCapacity`] was returned.
+///
+/// `total` is divided into `parts` near-equal chunks. Each chunk is greedily allocated to
+/// the leg with the most additional output from taking it, ties broken in favour of earlier legs.
+/// This is optimal up to the chunk size when each leg's output has diminishing returns,
+/// but legs with fixed costs, such as the PrefundSwapViaStake prefund fee,
+/// are only opened once a single chunk makes up for that cost.
+///
+/// Each leg's additional output from taking the next chunk is cached and only requoted
+/// for the leg that was allocated the last chunk, so a leg's cached quote does not reflect
+/// changes to `state` made by chunks allocated to legs before it since it was last quoted.
+/// Every allocation is still checked by executing all allocated legs in order,
+/// and the returned total output is exact. This takes `O(parts * legs.len())` leg calls.
+///
+/// `allocs` is reset before allocating.
+pub fn split_input<S: Clone, E, L: Fn(&mut S, u64) -> Result<u64, E>>(
+    total: u64,
+    parts: u64,
+    state: &S,
+    legs: &[L],
+    allocs: &mut [SplitAlloc],
+    mut on_err: impl FnMut(usize, E),
+) -> Result<u64, SplitErr> {
+    if legs.len() != allocs.len() {
+        return Err(SplitErr::LenMismatch);
+    }
+    if parts == 0 {
+        return Err(SplitErr::ZeroParts);
+    }
+    allocs.fill(SplitAlloc::default());
+
+    let base = total / parts;
+    let rem = total % parts;
+    let mut unallocated = total;
+    let mut total_out = 0;
+    let mut quoted_chunk = 0;
+    // first `rem` chunks are 1 larger
+    for chunk in (0..parts).map(|i| base + u64::from(i < rem)) {
+        if chunk == 0 {
+            continue;
+        }
+        // cached quotes are for a different chunk size
+        if chunk != quoted_chunk {
+            quote_next_chunks(state, legs, allocs, chunk, &mut on_err);
+            quoted_chunk = chunk;
+        }
+        let i = loop {
+            // strictly greater to keep the earliest leg on ties
+            let best =
+                allocs
+                    .iter()
+                    .enumerate()
+                    .fold(None, |best: Option<(usize, u64)>, (i, a)| match a.next {
+                        NextChunk::Gain(gain) if best.is_none_or(|(_, b)| gain > b) => {
+                            Some((i, gain))
+                        }
+                        _ => best,
+                    });
+            let (i, _) = best.ok_or(SplitErr::NoCapacity { unallocated })?;
+            match run_legs(state, legs, allocs, (i, chunk), None::<&mut fn(usize, E)>) {
+                Ok(_) => break i,
+                // cached quote was stale, e.g. legs before it used up shared liquidity
+                Err((j, e)) => {
+                    allocs[i].next = NextChunk::Failed;
+                    report_err(&mut allocs[j], j, e, &mut on_err);
+                }
+            }
+        };
+        // legs are deterministic, so this always succeeds like it did above
+        total_out = run_legs(state, legs, allocs, (i, chunk), Some(&mut on_err))
+            .map_err(|_| SplitErr::NoCapacity { unallocated })?;
+        unallocated -= chunk;
+    }
+
+    Ok(total_out)
+}
+
+/// Quotes every leg taking `chunk` more input than allocated,
+/// against the state left behind by the allocated legs before it
+fn quote_next_chunks<S: Clone, E, L: Fn(&mut S, u64) -> Result<u64, E>>(
+    state: &S,
+    legs: &[L],
+    allocs: &mut [SplitAlloc],
+    chunk: u64,
+    on_err: &mut impl FnMut(usize, E),
+) {
+    let mut state = state.clone();
+    for (i, (leg, alloc)) in legs.iter().zip(allocs.iter_mut()).enumerate() {
+        // unchecked-arith: sum of all allocations never exceeds split total
+        alloc.next = match leg(&mut state.clone(), alloc.inp + chunk) {
+            Ok(out) => NextChunk::Gain(out.saturating_sub(alloc.out)),
+            Err(e) => {
+                report_err(alloc, i, e, on_err);
+                NextChunk::Failed
+            }
+        };
+        if alloc.inp > 0 {
+            // allocated legs executed successfully in the same order when allocated
+            let _ = leg(&mut state, alloc.inp);
+        }
+    }
+}
+
+fn report_err<E>(alloc: &mut SplitAlloc, i: usize, e: E, on_err: &mut impl FnMut(usize, E)) {
+    if !alloc.err_reported {
+        alloc.err_reported = true;
+        on_err(i, e);
+    }
+}
+
+/// Executes all legs that have been allocated input in order against a copy of `state`,
+/// with leg `extra_leg` taking `extra_inp` more input than allocated.
+///
+/// Returns the total output, or the index and error of the first leg that failed.
+/// If `commit` is provided, the allocations are updated to the executed inputs and outputs,
+/// and `extra_leg` is requoted for taking another `extra_inp`, reporting its error to `commit`.
+fn run_legs<S: Clone, E, L: Fn(&mut S, u64) -> Result<u64, E>>(
+    state: &S,
+    legs: &[L],
+    allocs: &mut [SplitAlloc],
+    (extra_leg, extra_inp): (usize, u64),
+    mut commit: Option<&mut impl FnMut(usize, E)>,
+) -> Result<u64, (usize, E)> {
+    let mut state = state.clone();
+    let mut total_out: u64 = 0;
+    for (i, (leg, alloc)) in legs.iter().zip(allocs.iter_mut()).enumerate() {
+        // unchecked-arith: sum of all allocations never exceeds split total
+        let inp = alloc.inp + if i == extra_leg { extra_inp } else { 0 };
+        if inp == 0 {
+            continue;
+        }
+        let next_state = (commit.is_some() && i == extra_leg).then(|| state.clone());
+        let out = leg(&mut state, inp).map_err(|e| (i, e))?;
+        if let Some(on_err) = commit.as_deref_mut() {
+            alloc.inp = inp;
+            alloc.out = out;
+            if let Some(mut next_state) = next_state {
+                alloc.next = match leg(&mut next_state, inp + extra_inp) {
+                    Ok(next_out) => NextChunk::Gain(next_out.saturating_sub(out)),
+                    Err(e) => {
+                        report_err(alloc, i, e, on_err);
+                        NextChunk::Failed
+                    }
+                };
+            }
+        }
+        total_out = total_out.saturating_add(out);
+    }
+    Ok(total_out)
+}
@@ -1,9 +1,10 @@
 use core::{error::Error, fmt::Display};
 
-use sanctum_reserve_core::{FeeEnum, PoolBalance, ReserveError};
+use sanctum_reserve_core::ReserveError;
 
 use crate::{
-    DepositStakeQuote, DepositStakeQuoter, Prefund, PrefundWithdrawStakeQuoteErr, StakeQuoteError,
+    quote_exact_out, DepositStakeQuote, DepositStakeQuoter, ExactOutQuoteErr, Prefund,
+    PrefundParams, PrefundWithdrawStakeQuoteErr, RouterFee, StakeQuoteError, WithRouterFee,
     WithdrawStakeQuote, WithdrawStakeQuoter,
 };
 
@@ -15,31 +16,231 @@ pub fn quote_prefund_swap_via_stake<W: WithdrawStakeQuoter, D: DepositStakeQuote
     w_itr: impl IntoIterator<Item = W>,
     d: D,
     inp_tokens: u64,
-    reserves_balance: &PoolBalance,
-    reserves_fee: &FeeEnum,
+    prefund: &PrefundParams,
 ) -> QuotePrefundSwapViaStakeResult<W::Error, D::Error> {
+    quote_prefund_swap_via_stake_diagnosed(w_itr, d, inp_tokens, prefund, |_| ())
+}
+
+/// A bridge stake account candidate that was skipped
+/// due to a vote-specific error
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BridgeRejection<W, D> {
+    /// Vote account of the rejected candidate.
+    ///
+    /// `None` if the withdraw stake leg errored and the quoter
+    /// does not have a [`WithdrawStakeQuoter::fixed_vote`]
+    pub vote: Option<[u8; 32]>,
+
+    /// Either [`PrefundSwapViaStakeQuoteErr::WithdrawStake`]
+    /// or [`PrefundSwapViaStakeQuoteErr::DepositStake`]
+    pub err: PrefundSwapViaStakeQuoteErr<W, D>,
+}
+
+/// [`quote_prefund_swap_via_stake`], but calls `on_reject` for each withdraw stake quoter
+/// of `w_itr` that was skipped due to a vote-specific error, in `w_itr`'s order.
+///
+/// Use this to find out why a quote resulted in [`PrefundSwapViaStakeQuoteErr::NoMatch`].
+#[inline]
+pub fn quote_prefund_swap_via_stake_diagnosed<W: WithdrawStakeQuoter, D: DepositStakeQuoter>(
+    w_itr: impl IntoIterator<Item = W>,
+    d: D,
+    inp_tokens: u64,
+    prefund: &PrefundParams,
+    mut on_reject: impl FnMut(BridgeRejection<W::Error, D::Error>),
+) -> QuotePrefundSwapViaStakeResult<W::Error, D::Error> {
+    for w in w_itr {
+        match quote_candidate(&w, &d, inp_tokens, prefund)? {
+            Ok(q) => return Ok(q),
+            Err(r) => on_reject(r),
+        }
+    }
+    Err(PrefundSwapViaStakeQuoteErr::NoMatch)
+}
+
+/// [`quote_prefund_swap_via_stake_diagnosed`], but keeps evaluating the rest of `w_itr`
+/// after the first valid quote, so that `on_reject` is called for every rejected candidate.
+///
+/// Returns the same quote as [`quote_prefund_swap_via_stake`]. Evaluation stops early if a
+/// later candidate errors with an error that is not vote-specific.
+#[inline]
+pub fn quote_prefund_swap_via_stake_diagnosed_all<W: WithdrawStakeQuoter, D: DepositStakeQuoter>(
+    w_itr: impl IntoIterator<Item = W>,
+    d: D,
+    inp_tokens: u64,
+    prefund: &PrefundParams,
+    mut on_reject: impl FnMut(BridgeRejection<W::Error, D::Error>),
+) -> QuotePrefundSwapViaStakeResult<W::Error, D::Error> {
+    let mut res = None;
+    for w in w_itr {
+        match quote_candidate(&w, &d, inp_tokens, prefund) {
+            Ok(Ok(q)) => {
+                res.get_or_insert(q);
+            }
+            Ok(Err(r)) => on_reject(r),
+            Err(e) => return res.ok_or(e),
+        }
+    }
+    res.ok_or(PrefundSwapViaStakeQuoteErr::NoMatch)
+}
+
+/// `Err` if the candidate was rejected due to a vote-specific error
+type CandidateQuote<W, D> =
+    Result<(Prefund<WithdrawStakeQuote>, DepositStakeQuote), BridgeRejection<W, D>>;
+
+/// Quotes a single bridge candidate
+fn quote_candidate<W: WithdrawStakeQuoter, D: DepositStakeQuoter>(
+    w: &W,
+    d: &D,
+    inp_tokens: u64,
+    prefund: &PrefundParams,
+) -> Result<CandidateQuote<W::Error, D::Error>, PrefundSwapViaStakeQuoteErr<W::Error, D::Error>> {
+    let wsq = match w.quote_prefund_withdraw_stake(inp_tokens, None, prefund) {
+        Ok(q) => q,
+        Err(e) if e.is_vote_specific() => {
+            return Ok(Err(BridgeRejection {
+                vote: w.fixed_vote().copied(),
+                err: e.into(),
+            }))
+        }
+        Err(e) => return Err(e.into()),
+    };
+    match d.quote_deposit_stake(wsq.quote.out) {
+        Ok(dsq) => Ok(Ok((wsq, dsq))),
+        Err(e) if e.is_vote_specific() => Ok(Err(BridgeRejection {
+            vote: Some(wsq.quote.out.vote),
+            err: PrefundSwapViaStakeQuoteErr::DepositStake(e),
+        })),
+        Err(e) => Err(PrefundSwapViaStakeQuoteErr::DepositStake(e)),
+    }
+}
+
+/// Default bound on the number of withdraw stake quoters evaluated by
+/// [`quote_prefund_swap_via_stake_best_out`]
+pub const DEFAULT_MAX_BRIDGE_CANDIDATES: usize = 256;
+
+/// Like [`quote_prefund_swap_via_stake`], but instead of returning the first
+/// withdraw stake quoter of `w_itr` that gives a valid quote, evaluates
+/// all of them and returns the quote with the most output tokens.
+///
+/// Ties are broken in favour of the earliest quoter in `w_itr`,
+/// so that results are deterministic given the same validator list.
+///
+/// At most `max_candidates` quoters are evaluated, in `w_itr`'s order,
+/// to bound work for pools with large validator lists.
+/// Vote-specific errors skip the candidate, while other errors are returned immediately.
+#[inline]
+pub fn quote_prefund_swap_via_stake_best_out<W: WithdrawStakeQuoter, D: DepositStakeQuoter>(
+    w_itr: impl IntoIterator<Item = W>,
+    d: D,
+    inp_tokens: u64,
+    prefund: &PrefundParams,
+    max_candidates: usize,
+) -> QuotePrefundSwapViaStakeResult<W::Error, D::Error> {
+    let mut best: Option<(Prefund<WithdrawStakeQuote>, DepositStakeQuote)> = None;
+    for w in w_itr.into_iter().take(max_candidates) {
+        let wsq = match map_res(w.quote_prefund_withdraw_stake(inp_tokens, None, prefund)) {
+            None => continue,
+            Some(r) => r?,
+        };
+        let dsq = match map_res(d.quote_deposit_stake(wsq.quote.out)) {
+            None => continue,
+            Some(r) => r.map_err(PrefundSwapViaStakeQuoteErr::DepositStake)?,
+        };
+        let is_better = match &best {
+            // strictly greater to keep the earliest candidate on ties
+            Some((_, best_dsq)) => dsq.out > best_dsq.out,
+            None => true,
+        };
+        if is_better {
+            best = Some((wsq, dsq));
+        }
+    }
+    best.ok_or(PrefundSwapViaStakeQuoteErr::NoMatch)
+}
+
+pub type QuotePrefundSwapViaStakeExactOutResult<W, D> = Result<
+    (
+        Prefund<WithdrawStakeQuote>,
+        WithRouterFee<DepositStakeQuote>,
+    ),
+    ExactOutQuoteErr<PrefundSwapViaStakeQuoteErr<W, D>>,
+>;
+
+/// Exact-out version of [`quote_prefund_swap_via_stake`].
+///
+/// Returns the quote for the minimum `inp_tokens` that results in at least
+/// `out_tokens` output tokens after all fees of the route:
+/// withdraw stake fee, prefund fee, deposit stake fee and router fee.
+///
+/// `router_fee` should be [`crate::RouterFeeSchedule::prefund_swap_via_stake`]
+/// or [`RouterFee::ZERO`] depending on the output mint.
+///
+/// Like [`quote_prefund_swap_via_stake`], the first withdraw stake quoter of `w_itr`
+/// that gives a valid quote is used. The chosen bridge vote account is `dsq.quote.inp.vote`.
+#[inline]
+pub fn quote_prefund_swap_via_stake_exact_out<W: WithdrawStakeQuoter, D: DepositStakeQuoter>(
+    w_itr: impl IntoIterator<Item = W>,
+    d: D,
+    out_tokens: u64,
+    prefund: &PrefundParams,
+    router_fee: &RouterFee,
+) -> QuotePrefundSwapViaStakeExactOutResult<W::Error, D::Error> {
     w_itr
         .into_iter()
         .filter_map(|w| {
-            let wsq = match map_res(w.quote_prefund_withdraw_stake(
-                inp_tokens,
-                None,
-                reserves_balance,
-                reserves_fee,
-            ))? {
+            let res = quote_exact_out(
+                out_tokens,
+                |inp_tokens| -> Result<_, PrefundSwapViaStakeQuoteErr<W::Error, D::Error>> {
+                    let wsq = w.quote_prefund_withdraw_stake(inp_tokens, None, prefund)?;
+                    let dsq = d
+                        .quote_deposit_stake(wsq.quote.out)
+                        .map_err(PrefundSwapViaStakeQuoteErr::DepositStake)?;
+                    Ok((wsq, dsq.with_router_fee_of(router_fee)))
+                },
+                |(_wsq, dsq)| dsq.quote.out,
+            );
+            // vote-specific errors continue iteration with the next quoter
+            map_res(res)
+        })
+        .next()
+        .map_or_else(
+            || Err(ExactOutQuoteErr::Pool(PrefundSwapViaStakeQuoteErr::NoMatch)),
+            |r| r,
+        )
+}
+
+pub type QuoteSwapViaStakeResult<W, D> =
+    Result<(WithdrawStakeQuote, DepositStakeQuote), SwapViaStakeQuoteErr<W, D>>;
+
+/// The non-prefunded version of [`quote_prefund_swap_via_stake`].
+///
+/// The user pays `bridge_rent` lamports for the rent-exemption of the bridge stake account
+/// on top of `inp_tokens`, which is then deposited along with the withdrawn stake.
+/// `bridge_rent` should be [`crate::RentParams::stake_account_rent_exempt_lamports`].
+#[inline]
+pub fn quote_swap_via_stake<W: WithdrawStakeQuoter, D: DepositStakeQuoter>(
+    w_itr: impl IntoIterator<Item = W>,
+    d: D,
+    inp_tokens: u64,
+    bridge_rent: u64,
+) -> QuoteSwapViaStakeResult<W::Error, D::Error> {
+    w_itr
+        .into_iter()
+        .filter_map(|w| {
+            let wsq = match map_res(w.quote_withdraw_stake(inp_tokens, None))? {
                 // stop iteration with err
-                Err(e) => return Some(Err(e.into())),
-                Ok(q) => q,
+                Err(e) => return Some(Err(SwapViaStakeQuoteErr::WithdrawStake(e))),
+                Ok(q) => q.with_user_funded_rent(bridge_rent),
             };
-            let dsq = match map_res(d.quote_deposit_stake(wsq.quote.out))? {
+            let dsq = match map_res(d.quote_deposit_stake(wsq.out))? {
                 // stop iteration with err
-                Err(e) => return Some(Err(PrefundSwapViaStakeQuoteErr::DepositStake(e))),
+                Err(e) => return Some(Err(SwapViaStakeQuoteErr::DepositStake(e))),
                 Ok(q) => q,
             };
             Some(Ok((wsq, dsq)))
         })
         .next()
-        .map_or_else(|| Err(PrefundSwapViaStakeQuoteErr::NoMatch), |r| r)
+        .map_or_else(|| Err(SwapViaStakeQuoteErr::NoMatch), |r| r)
 }
 
 /// Converts `Result<T, E>` to `Option<Result<T, E>>`
@@ -60,6 +261,9 @@ pub enum PrefundSwapViaStakeQuoteErr<W, D> {
     Reserve(ReserveError),
     WithdrawStake(W),
     DepositStake(D),
+
+    /// See [`PrefundWithdrawStakeQuoteErr::BelowMinimumDelegation`]
+    BelowMinimumDelegation,
 }
 
 impl<W, D> From<PrefundWithdrawStakeQuoteErr<W>> for PrefundSwapViaStakeQuoteErr<W, D> {
@@ -69,6 +273,7 @@ impl<W, D> From<PrefundWithdrawStakeQuoteErr<W>> for PrefundSwapViaStakeQuoteErr
         match e {
             PrefundWithdrawStakeQuoteErr::Pool(e) => Self::WithdrawStake(e),
             PrefundWithdrawStakeQuoteErr::Reserve(e) => Self::Reserve(e),
+            PrefundWithdrawStakeQuoteErr::BelowMinimumDelegation => Self::BelowMinimumDelegation,
         }
     }
 }
@@ -81,3 +286,19 @@ impl<W: core::fmt::Debug, D: core::fmt::Debug> Display for PrefundSwapViaStakeQu
 }
 
 impl<W: core::fmt::Debug, D: core::fmt::Debug> Error for PrefundSwapViaStakeQuoteErr<W, D> {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SwapViaStakeQuoteErr<W, D> {
+    NoMatch,
+    WithdrawStake(W),
+    DepositStake(D),
+}
+
+impl<W: core::fmt::Debug, D: core::fmt::Debug> Display for SwapViaStakeQuoteErr<W, D> {
+    // Display=Debug, since this is just a simple discriminated str enum
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl<W: core::fmt::Debug, D: core::fmt::Debug> Error for SwapViaStakeQuoteErr<W, D> {}
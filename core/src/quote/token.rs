@@ -1,4 +1,4 @@
-use crate::{WithRouterFee, WITHDRAW_WRAPPED_SOL_GLOBAL_FEE_BPS};
+use crate::{RouterFee, RouterFeeSchedule, WithRouterFee};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(
@@ -23,19 +23,13 @@ pub struct TokenQuote {
 }
 
 impl TokenQuote {
-    /// Applies for WithdrawSol
-    pub fn withdraw_sol_with_router_fee(self) -> WithRouterFee<Self> {
+    /// Charges `router_fee` on `self.out`.
+    ///
+    /// WithdrawWrappedSol uses [`crate::RouterFeeSchedule::withdraw_wrapped_sol`]
+    #[inline]
+    pub const fn with_router_fee(self, router_fee: &RouterFee) -> WithRouterFee<Self> {
         let Self { inp, out, fee } = self;
-
-        if out == 0 {
-            return WithRouterFee::zero(self);
-        }
-
-        // cast-safety: GLOBAL_FEE_BPS is < 10_000, no overflow should happen
-        let router_fee = ((self.out as u128) * (WITHDRAW_WRAPPED_SOL_GLOBAL_FEE_BPS as u128)
-            / 10_000u128) as u64;
-        let router_fee = router_fee.max(1);
-
+        let router_fee = router_fee.fee(out);
         WithRouterFee {
             quote: Self {
                 inp,
@@ -45,6 +39,15 @@ impl TokenQuote {
             router_fee,
         }
     }
+
+    /// Applies for WithdrawSol
+    #[deprecated(
+        note = "use `with_router_fee()` with `RouterFeeSchedule::withdraw_wrapped_sol` instead"
+    )]
+    #[inline]
+    pub const fn withdraw_sol_with_router_fee(self) -> WithRouterFee<Self> {
+        self.with_router_fee(&RouterFeeSchedule::MAINNET.withdraw_wrapped_sol)
+    }
 }
 
 impl From<sanctum_spl_stake_pool_core::DepositSolQuote> for TokenQuote {
@@ -0,0 +1,90 @@
+use core::{error::Error, fmt::Display};
+
+use crate::{DepositSolQuoter, RouterFee, TokenQuote, WithRouterFee, WithdrawSolQuoter};
+
+/// Quote for the two-instruction route of WithdrawWrappedSol from the input LST's pool
+/// into an intermediate wSOL token account, followed by StakeWrappedSol of that wSOL
+/// into the output LST's pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+#[cfg_attr(
+    feature = "wasm",
+    derive(tsify_next::Tsify),
+    tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)
+)]
+pub struct WithdrawSolDepositSolQuote {
+    /// The WithdrawWrappedSol leg.
+    ///
+    /// `withdraw_sol.quote.out` is the wSOL amount that is deposited by the StakeWrappedSol leg
+    pub withdraw_sol: WithRouterFee<TokenQuote>,
+
+    /// The StakeWrappedSol leg
+    pub deposit_sol: WithRouterFee<TokenQuote>,
+}
+
+impl WithdrawSolDepositSolQuote {
+    /// Input LST tokens that will leave the user's wallet
+    #[inline]
+    pub const fn inp(&self) -> u64 {
+        self.withdraw_sol.quote.inp
+    }
+
+    /// Intermediate wSOL lamports, after the WithdrawWrappedSol router fee
+    #[inline]
+    pub const fn bridge_lamports(&self) -> u64 {
+        self.withdraw_sol.quote.out
+    }
+
+    /// Output LST tokens received, after all fees
+    #[inline]
+    pub const fn out(&self) -> u64 {
+        self.deposit_sol.quote.out
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WithdrawSolDepositSolQuoteErr<W, D> {
+    WithdrawSol(W),
+    DepositSol(D),
+}
+
+impl<W: core::fmt::Debug, D: core::fmt::Debug> Display for WithdrawSolDepositSolQuoteErr<W, D> {
+    // Display=Debug, since this is just a simple discriminated str enum
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl<W: core::fmt::Debug, D: core::fmt::Debug> Error for WithdrawSolDepositSolQuoteErr<W, D> {}
+
+pub type QuoteWithdrawSolDepositSolResult<W, D> =
+    Result<WithdrawSolDepositSolQuote, WithdrawSolDepositSolQuoteErr<W, D>>;
+
+/// Chains a WithdrawSol quote of `inp_tokens` from `w` with a DepositSol quote
+/// of its output lamports, after the WithdrawWrappedSol router fee
+/// `withdraw_wrapped_sol_fee`, into `d`.
+///
+/// StakeWrappedSol does not charge router fees.
+#[inline]
+pub fn quote_withdraw_sol_deposit_sol<W: WithdrawSolQuoter, D: DepositSolQuoter>(
+    w: W,
+    d: D,
+    inp_tokens: u64,
+    withdraw_wrapped_sol_fee: &RouterFee,
+) -> QuoteWithdrawSolDepositSolResult<W::Error, D::Error> {
+    let withdraw_sol = w
+        .quote_withdraw_sol(inp_tokens)
+        .map_err(WithdrawSolDepositSolQuoteErr::WithdrawSol)?
+        .with_router_fee(withdraw_wrapped_sol_fee);
+    let deposit_sol = d
+        .quote_deposit_sol(withdraw_sol.quote.out)
+        .map_err(WithdrawSolDepositSolQuoteErr::DepositSol)?;
+    Ok(WithdrawSolDepositSolQuote {
+        withdraw_sol,
+        deposit_sol: WithRouterFee::zero(deposit_sol),
+    })
+}
@@ -1,4 +1,4 @@
-use crate::ActiveStakeParams;
+use crate::{ActiveStakeParams, StakeAccountLamports};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct WithdrawStakeQuote {
@@ -10,4 +10,45 @@ pub struct WithdrawStakeQuote {
 
     /// In terms of input tokens, charged by the stake pool
     pub fee: u64,
+
+    /// The pool's stake account that `out` is split from, for pools where
+    /// it is not determined by `out.vote` alone, e.g. marinade,
+    /// which can have multiple stake accounts delegated to the same validator.
+    ///
+    /// `None` for all other pools
+    pub stake_to_split: Option<[u8; 32]>,
+}
+
+impl WithdrawStakeQuote {
+    /// For non-prefunded instructions (e.g. SwapViaStake), the user pays
+    /// for the rent-exemption of the destination stake account,
+    /// which is added to its unstaked lamports.
+    ///
+    /// `rent_lamports` should be [`crate::RentParams::stake_account_rent_exempt_lamports`]
+    #[inline]
+    pub const fn with_user_funded_rent(self, rent_lamports: u64) -> Self {
+        let Self {
+            inp,
+            out:
+                ActiveStakeParams {
+                    vote,
+                    lamports: StakeAccountLamports { staked, unstaked },
+                },
+            fee,
+            stake_to_split,
+        } = self;
+        Self {
+            inp,
+            out: ActiveStakeParams {
+                vote,
+                lamports: StakeAccountLamports {
+                    staked,
+                    // unchecked-arith: SOL supply is nowhere near u64::MAX
+                    unstaked: unstaked + rent_lamports,
+                },
+            },
+            fee,
+            stake_to_split,
+        }
+    }
 }
@@ -0,0 +1,142 @@
+/// Bytes of account metadata charged rent on top of account data
+const ACCOUNT_STORAGE_OVERHEAD: u64 = 128;
+
+const STAKE_ACCOUNT_LEN: u64 = 200;
+
+const RENT_SYSVAR_LEN: usize = 17;
+
+/// Rent parameters of the cluster, as stored in the Rent sysvar.
+///
+/// Defaults to [`RentParams::DEFAULT`], the values that have been in effect
+/// on mainnet since genesis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct RentParams {
+    pub lamports_per_byte_year: u64,
+
+    /// Years of rent an account must hold to be rent-exempt
+    pub exemption_threshold: f64,
+
+    pub burn_percent: u8,
+}
+
+impl RentParams {
+    pub const DEFAULT: Self = Self {
+        lamports_per_byte_year: 3480,
+        exemption_threshold: 2.0,
+        burn_percent: 50,
+    };
+
+    /// Deserializes the data of the Rent sysvar account.
+    ///
+    /// Returns `None` if `data` is too short, `exemption_threshold` is
+    /// negative or not finite, or `burn_percent` is over 100.
+    #[inline]
+    pub fn from_sysvar_data(data: &[u8]) -> Option<Self> {
+        let data: &[u8; RENT_SYSVAR_LEN] = data.first_chunk()?;
+        let (lamports_per_byte_year, rest) = data.split_first_chunk::<8>()?;
+        let (exemption_threshold, rest) = rest.split_first_chunk::<8>()?;
+        let res = Self {
+            lamports_per_byte_year: u64::from_le_bytes(*lamports_per_byte_year),
+            exemption_threshold: f64::from_le_bytes(*exemption_threshold),
+            burn_percent: *rest.first()?,
+        };
+        (res.exemption_threshold.is_finite()
+            && res.exemption_threshold >= 0.0
+            && res.burn_percent <= 100)
+            .then_some(res)
+    }
+
+    /// Minimum lamports an account with `data_len` bytes of data
+    /// must hold to be rent-exempt
+    #[inline]
+    pub fn min_balance(&self, data_len: u64) -> u64 {
+        let bytes = ACCOUNT_STORAGE_OVERHEAD.saturating_add(data_len);
+        // same float math as the runtime's
+        (bytes.saturating_mul(self.lamports_per_byte_year) as f64 * self.exemption_threshold) as u64
+    }
+
+    #[inline]
+    pub fn stake_account_rent_exempt_lamports(&self) -> u64 {
+        self.min_balance(STAKE_ACCOUNT_LEN)
+    }
+
+    #[inline]
+    pub fn zero_data_acc_rent_exempt_lamports(&self) -> u64 {
+        self.min_balance(0)
+    }
+
+    /// The flash loan amount given out by the router program to make
+    /// the slumdog stake and withdrawn stake rent-exempt.
+    ///
+    /// See [`crate::PREFUND_FLASH_LOAN_LAMPORTS`]
+    #[inline]
+    pub fn prefund_flash_loan_lamports(&self) -> u64 {
+        self.stake_account_rent_exempt_lamports().saturating_mul(2)
+    }
+}
+
+impl Default for RentParams {
+    #[inline]
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Data of the mainnet Rent sysvar account
+    fn mainnet_sysvar_data() -> [u8; RENT_SYSVAR_LEN] {
+        let mut data = [0u8; RENT_SYSVAR_LEN];
+        data[..8].copy_from_slice(&3480u64.to_le_bytes());
+        data[8..16].copy_from_slice(&2.0f64.to_le_bytes());
+        data[16] = 50;
+        data
+    }
+
+    #[test]
+    fn from_mainnet_sysvar_data() {
+        let rent = RentParams::from_sysvar_data(&mainnet_sysvar_data()).unwrap();
+        assert_eq!(rent, RentParams::DEFAULT);
+        assert_eq!(rent.min_balance(200), 2_282_880);
+        assert_eq!(rent.stake_account_rent_exempt_lamports(), 2_282_880);
+        assert_eq!(rent.zero_data_acc_rent_exempt_lamports(), 890_880);
+    }
+
+    #[test]
+    fn from_sysvar_data_ignores_trailing_bytes() {
+        let mut data = [0u8; RENT_SYSVAR_LEN + 1];
+        data[..RENT_SYSVAR_LEN].copy_from_slice(&mainnet_sysvar_data());
+        assert_eq!(
+            RentParams::from_sysvar_data(&data),
+            Some(RentParams::DEFAULT)
+        );
+    }
+
+    #[test]
+    fn from_truncated_sysvar_data() {
+        let data = mainnet_sysvar_data();
+        for len in 0..RENT_SYSVAR_LEN {
+            assert_eq!(RentParams::from_sysvar_data(&data[..len]), None);
+        }
+    }
+
+    #[test]
+    fn from_invalid_sysvar_data() {
+        for threshold in [f64::NAN, f64::INFINITY, -1.0] {
+            let mut data = mainnet_sysvar_data();
+            data[8..16].copy_from_slice(&threshold.to_le_bytes());
+            assert_eq!(RentParams::from_sysvar_data(&data), None);
+        }
+
+        let mut data = mainnet_sysvar_data();
+        data[16] = 101;
+        assert_eq!(RentParams::from_sysvar_data(&data), None);
+    }
+}
@@ -1,3 +1,5 @@
+mod sol_value;
 mod withdraw_stake;
 
+pub use sol_value::*;
 pub use withdraw_stake::*;
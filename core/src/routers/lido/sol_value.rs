@@ -0,0 +1,29 @@
+use solido_legacy_core::ExchangeRate;
+
+use crate::{internal_utils::mul_div_floor, SolValueCalc};
+
+/// Uses the exchange rate as of the epoch it was last computed in
+#[derive(Debug, Clone, Copy)]
+pub struct LidoSolValueCalc<'a> {
+    pub exchange_rate: &'a ExchangeRate,
+}
+
+impl SolValueCalc for LidoSolValueCalc<'_> {
+    #[inline]
+    fn sol_value(&self, tokens: u64) -> Option<u64> {
+        mul_div_floor(
+            tokens,
+            self.exchange_rate.sol_balance,
+            self.exchange_rate.st_sol_supply,
+        )
+    }
+
+    #[inline]
+    fn tokens_for_sol(&self, lamports: u64) -> Option<u64> {
+        mul_div_floor(
+            lamports,
+            self.exchange_rate.st_sol_supply,
+            self.exchange_rate.sol_balance,
+        )
+    }
+}
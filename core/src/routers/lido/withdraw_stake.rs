@@ -2,20 +2,29 @@ use generic_array_struct::generic_array_struct;
 use solido_legacy_core::{max_withdraw_lamports, LidoError, Validator};
 
 use crate::{
-    ActiveStakeParams, StakeAccountLamports, WithdrawStakeQuote, WithdrawStakeQuoter,
-    WithdrawStakeSufAccs, STAKE_PROGRAM, SYSTEM_PROGRAM, SYSVAR_CLOCK, TOKEN_PROGRAM,
+    ActiveStakeParams, StakeAccountLamports, WithdrawStakeQuote, WithdrawStakeQuoteErr,
+    WithdrawStakeQuoter, WithdrawStakeSufAccs, STAKE_MIN_DELEGATION_LAMPORTS, STAKE_PROGRAM,
+    SYSTEM_PROGRAM, SYSVAR_CLOCK, TOKEN_PROGRAM,
 };
 
+/// Errors with [`WithdrawStakeQuoteErr::BelowMinimumDelegation`] if the
+/// withdrawn stake account would be delegated less than `min_delegation`.
 #[derive(Debug, Clone)]
 pub struct LidoWithdrawStakeQuoter<'a> {
     pub exchange_rate: &'a solido_legacy_core::ExchangeRate,
     pub largest_stake_vote: &'a [u8; 32],
     pub curr_epoch: u64,
     pub largest_stake_effective_stake_balance: u64,
+
+    /// The stake program's minimum delegation, in lamports
+    pub min_delegation: u64,
 }
 
 impl<'a> LidoWithdrawStakeQuoter<'a> {
-    /// Returns `None` if `validator_list` is empty
+    /// Returns `None` if `validator_list` is empty.
+    ///
+    /// `min_delegation` defaults to [`STAKE_MIN_DELEGATION_LAMPORTS`],
+    /// see [`Self::with_min_delegation`]
     #[inline]
     pub fn new(
         state: &'a solido_legacy_core::Lido,
@@ -30,12 +39,44 @@ impl<'a> LidoWithdrawStakeQuoter<'a> {
             largest_stake_vote: largest_stake.vote_account_address(),
             curr_epoch,
             largest_stake_effective_stake_balance: largest_stake.effective_stake_balance(),
+            min_delegation: STAKE_MIN_DELEGATION_LAMPORTS,
         })
     }
 }
 
+impl LidoWithdrawStakeQuoter<'_> {
+    /// Returns this quoter with `min_delegation` set to the stake program's
+    /// current minimum delegation, usually
+    /// [`crate::RouterConfig::stake_min_delegation`]
+    #[inline]
+    pub const fn with_min_delegation(self, min_delegation: u64) -> Self {
+        Self {
+            min_delegation,
+            ..self
+        }
+    }
+
+    /// Returns this quoter with `largest_stake_effective_stake_balance` updated to
+    /// after `quote` has executed.
+    ///
+    /// The exchange rate is only recomputed once per epoch so it is unchanged.
+    ///
+    /// This does not switch to another validator if the current one is no longer the largest;
+    /// use `LidoRouterOwned::apply_withdraw_stake()` in the sdk followed by
+    /// `withdraw_stake_quoter()` for that.
+    #[inline]
+    pub fn apply_withdraw_stake(self, quote: &WithdrawStakeQuote) -> Self {
+        Self {
+            largest_stake_effective_stake_balance: self
+                .largest_stake_effective_stake_balance
+                .saturating_sub(quote.out.lamports.staked),
+            ..self
+        }
+    }
+}
+
 impl WithdrawStakeQuoter for LidoWithdrawStakeQuoter<'_> {
-    type Error = LidoError;
+    type Error = WithdrawStakeQuoteErr<LidoError>;
 
     #[inline]
     fn quote_withdraw_stake(
@@ -45,11 +86,11 @@ impl WithdrawStakeQuoter for LidoWithdrawStakeQuoter<'_> {
     ) -> Result<WithdrawStakeQuote, Self::Error> {
         if let Some(v) = vote {
             if v != self.largest_stake_vote {
-                return Err(LidoError::ValidatorWithMoreStakeExists);
+                return Err(LidoError::ValidatorWithMoreStakeExists.into());
             }
         }
         if self.curr_epoch > self.exchange_rate.computed_in_epoch {
-            return Err(LidoError::ExchangeRateNotUpdatedInThisEpoch);
+            return Err(LidoError::ExchangeRateNotUpdatedInThisEpoch.into());
         }
         let lamports_staked = self
             .exchange_rate
@@ -60,7 +101,10 @@ impl WithdrawStakeQuoter for LidoWithdrawStakeQuoter<'_> {
                 .ok_or(LidoError::CalculationFailure)?;
         if lamports_staked > max_withdraw_lamports {
             // StakeWithdrawalTooLarge
-            return Err(LidoError::InvalidAmount);
+            return Err(LidoError::InvalidAmount.into());
+        }
+        if lamports_staked < self.min_delegation {
+            return Err(WithdrawStakeQuoteErr::BelowMinimumDelegation);
         }
         Ok(WithdrawStakeQuote {
             inp: tokens,
@@ -72,8 +116,14 @@ impl WithdrawStakeQuoter for LidoWithdrawStakeQuoter<'_> {
                 },
             },
             fee: 0,
+            stake_to_split: None,
         })
     }
+
+    #[inline]
+    fn fixed_vote(&self) -> Option<&[u8; 32]> {
+        Some(self.largest_stake_vote)
+    }
 }
 
 #[derive(Debug, Clone)]
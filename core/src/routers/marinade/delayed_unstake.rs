@@ -0,0 +1,367 @@
+//! Delayed unstake (OrderUnstake followed by Claim).
+//!
+//! Unlike the other marinade routers, these are direct marinade program
+//! instructions instead of router program instruction suffixes.
+
+use core::{error::Error, fmt::Display};
+
+use generic_array_struct::generic_array_struct;
+use sanctum_marinade_liquid_staking_core::{
+    MarinadeError, State as MarinadeState, MSOL_MINT_ADDR, RESERVE_PUBKEY, STATE_PUBKEY,
+};
+
+use crate::{
+    internal_utils::mul_div_floor, MarinadeSolValueCalc, SolValueCalc, SYSTEM_PROGRAM,
+    SYSVAR_CLOCK, SYSVAR_RENT, TOKEN_PROGRAM,
+};
+
+/// Size of a ticket account, including its anchor discriminator
+pub const MARINADE_TICKET_ACCOUNT_LEN: usize = 88;
+
+const TICKET_ACCOUNT_DISCM: [u8; 8] = [133, 77, 18, 98, 211, 1, 231, 3];
+
+const ORDER_UNSTAKE_IX_DISCM: [u8; 8] = [97, 167, 144, 107, 117, 190, 128, 36];
+
+const CLAIM_IX_DISCM: [u8; 8] = [62, 198, 214, 193, 213, 159, 108, 210];
+
+/// Denominator of `state.delayed_unstake_fee`, which is in
+/// hundredths of a basis point
+const BP_CENTS_DENOM: u64 = 1_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarinadeDelayedUnstakeErr {
+    Marinade(MarinadeError),
+
+    /// The SOL value of the mSOL to unstake is below `state.min_withdraw`
+    WithdrawAmountIsTooLow,
+
+    /// The ticket's claimable epoch has not been reached yet
+    TicketNotDue,
+}
+
+impl Display for MarinadeDelayedUnstakeErr {
+    // Display=Debug, since this is just a simple discriminated str enum
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl Error for MarinadeDelayedUnstakeErr {}
+
+impl From<MarinadeError> for MarinadeDelayedUnstakeErr {
+    #[inline]
+    fn from(e: MarinadeError) -> Self {
+        Self::Marinade(e)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MarinadeOrderUnstakeQuote {
+    /// mSOL burnt
+    pub inp: u64,
+
+    /// Lamports claimable from the ticket, after fees
+    pub lamports: u64,
+
+    /// Delayed unstake fee, in lamports
+    pub fee: u64,
+
+    /// Epoch from which the ticket can be claimed
+    pub claimable_epoch: u64,
+}
+
+/// OrderUnstake of mSOL for a ticket claimable for SOL from the next epoch onwards.
+///
+/// Uses the state's `msol_price` as of its last update, same as [`MarinadeSolValueCalc`]
+#[derive(Debug, Clone, Copy)]
+pub struct MarinadeOrderUnstakeQuoter<'a> {
+    pub state: &'a MarinadeState,
+    pub curr_epoch: u64,
+}
+
+impl MarinadeOrderUnstakeQuoter<'_> {
+    #[inline]
+    pub fn quote_order_unstake(
+        &self,
+        msol: u64,
+    ) -> Result<MarinadeOrderUnstakeQuote, MarinadeDelayedUnstakeErr> {
+        if self.state.paused {
+            return Err(MarinadeError::ProgramIsPaused.into());
+        }
+        let lamports_bef_fee = MarinadeSolValueCalc { state: self.state }
+            .sol_value(msol)
+            .ok_or(MarinadeError::CalculationFailure)?;
+        if lamports_bef_fee < self.state.min_withdraw {
+            return Err(MarinadeDelayedUnstakeErr::WithdrawAmountIsTooLow);
+        }
+        // the fee is burnt with the rest of the mSOL instead of being transferred,
+        // so it is charged in lamports
+        let fee = mul_div_floor(
+            lamports_bef_fee,
+            self.state.delayed_unstake_fee.bp_cents.into(),
+            BP_CENTS_DENOM,
+        )
+        .ok_or(MarinadeError::CalculationFailure)?;
+        let lamports = lamports_bef_fee
+            .checked_sub(fee)
+            .ok_or(MarinadeError::CalculationFailure)?;
+        Ok(MarinadeOrderUnstakeQuote {
+            inp: msol,
+            lamports,
+            fee,
+            claimable_epoch: self.curr_epoch.saturating_add(1),
+        })
+    }
+}
+
+/// A delayed unstake ticket, created by OrderUnstake and closed by Claim
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MarinadeTicketAccount {
+    /// The marinade state this ticket was created from
+    pub state: [u8; 32],
+
+    /// System account the ticket's lamports are claimed to
+    pub beneficiary: [u8; 32],
+
+    pub lamports: u64,
+
+    pub created_epoch: u64,
+}
+
+impl MarinadeTicketAccount {
+    /// Returns `None` if `data` is not that of a ticket account
+    #[inline]
+    pub fn from_acc_data(data: &[u8]) -> Option<Self> {
+        let data: &[u8; MARINADE_TICKET_ACCOUNT_LEN] = data.first_chunk()?;
+        let (discm, rest) = data.split_first_chunk::<8>()?;
+        if *discm != TICKET_ACCOUNT_DISCM {
+            return None;
+        }
+        let (state, rest) = rest.split_first_chunk::<32>()?;
+        let (beneficiary, rest) = rest.split_first_chunk::<32>()?;
+        let (lamports, rest) = rest.split_first_chunk::<8>()?;
+        let (created_epoch, _) = rest.split_first_chunk::<8>()?;
+        Some(Self {
+            state: *state,
+            beneficiary: *beneficiary,
+            lamports: u64::from_le_bytes(*lamports),
+            created_epoch: u64::from_le_bytes(*created_epoch),
+        })
+    }
+
+    /// Epoch from which this ticket can be claimed
+    #[inline]
+    pub const fn claimable_epoch(&self) -> u64 {
+        self.created_epoch.saturating_add(1)
+    }
+
+    /// Only checks the epoch.
+    ///
+    /// On-chain, the claim additionally fails if it is made too soon after
+    /// the start of the claimable epoch or if the reserve does not have enough SOL yet,
+    /// both of which resolve themselves with time.
+    #[inline]
+    pub const fn is_claimable(&self, curr_epoch: u64) -> bool {
+        curr_epoch >= self.claimable_epoch()
+    }
+
+    #[inline]
+    pub const fn check_claimable(&self, curr_epoch: u64) -> Result<(), MarinadeDelayedUnstakeErr> {
+        if self.is_claimable(curr_epoch) {
+            Ok(())
+        } else {
+            Err(MarinadeDelayedUnstakeErr::TicketNotDue)
+        }
+    }
+}
+
+// OrderUnstake
+
+#[generic_array_struct(builder pub)]
+#[repr(transparent)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MarinadeOrderUnstakeIxAccs<T> {
+    pub state: T,
+    pub msol_mint: T,
+
+    /// mSOL token account to burn mSOL from.
+    /// Its owner becomes the ticket's beneficiary.
+    pub burn_msol_from: T,
+
+    /// Owner or delegate of `burn_msol_from`
+    pub burn_msol_authority: T,
+
+    /// Uninitialized account of [`MARINADE_TICKET_ACCOUNT_LEN`] bytes owned by
+    /// the marinade program, created earlier in the same transaction
+    pub new_ticket_account: T,
+
+    pub clock: T,
+    pub rent: T,
+    pub token_program: T,
+}
+pub type MarinadeOrderUnstakeIxKeysOwned = MarinadeOrderUnstakeIxAccs<[u8; 32]>;
+pub type MarinadeOrderUnstakeIxKeys<'a> = MarinadeOrderUnstakeIxAccs<&'a [u8; 32]>;
+pub type MarinadeOrderUnstakeIxAccsFlag = MarinadeOrderUnstakeIxAccs<bool>;
+
+pub const MARINADE_ORDER_UNSTAKE_IX_IS_WRITER: MarinadeOrderUnstakeIxAccsFlag =
+    MarinadeOrderUnstakeIxAccs([false; MARINADE_ORDER_UNSTAKE_IX_ACCS_LEN])
+        .const_with_state(true)
+        .const_with_msol_mint(true)
+        .const_with_burn_msol_from(true)
+        .const_with_new_ticket_account(true);
+
+pub const MARINADE_ORDER_UNSTAKE_IX_IS_SIGNER: MarinadeOrderUnstakeIxAccsFlag =
+    MarinadeOrderUnstakeIxAccs([false; MARINADE_ORDER_UNSTAKE_IX_ACCS_LEN])
+        .const_with_burn_msol_authority(true);
+
+impl<T> MarinadeOrderUnstakeIxAccs<T> {
+    #[inline]
+    pub const fn new(arr: [T; MARINADE_ORDER_UNSTAKE_IX_ACCS_LEN]) -> Self {
+        Self(arr)
+    }
+}
+
+impl<T> AsRef<[T]> for MarinadeOrderUnstakeIxAccs<T> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl MarinadeOrderUnstakeIxKeysOwned {
+    #[inline]
+    pub fn as_borrowed(&self) -> MarinadeOrderUnstakeIxKeys<'_> {
+        MarinadeOrderUnstakeIxKeys::new(self.0.each_ref())
+    }
+
+    #[inline]
+    pub fn with_consts(self) -> Self {
+        self.as_borrowed().with_consts().into_owned()
+    }
+}
+
+impl MarinadeOrderUnstakeIxKeys<'_> {
+    #[inline]
+    pub fn into_owned(self) -> MarinadeOrderUnstakeIxKeysOwned {
+        MarinadeOrderUnstakeIxKeysOwned::new(self.0.map(|pk| *pk))
+    }
+
+    #[inline]
+    pub const fn with_consts(self) -> Self {
+        self.const_with_state(&STATE_PUBKEY)
+            .const_with_msol_mint(&MSOL_MINT_ADDR)
+            .const_with_clock(&SYSVAR_CLOCK)
+            .const_with_rent(&SYSVAR_RENT)
+            .const_with_token_program(&TOKEN_PROGRAM)
+    }
+}
+
+#[repr(transparent)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MarinadeOrderUnstakeIxData([u8; 16]);
+
+impl MarinadeOrderUnstakeIxData {
+    #[inline]
+    pub fn new(msol_amount: u64) -> Self {
+        let mut buf = [0u8; 16];
+
+        buf[0..8].copy_from_slice(&ORDER_UNSTAKE_IX_DISCM);
+        buf[8..16].copy_from_slice(&msol_amount.to_le_bytes());
+
+        Self(buf)
+    }
+
+    #[inline]
+    pub const fn to_buf(&self) -> [u8; 16] {
+        self.0
+    }
+}
+
+// Claim
+
+#[generic_array_struct(builder pub)]
+#[repr(transparent)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MarinadeClaimIxAccs<T> {
+    pub state: T,
+    pub reserve: T,
+    pub ticket_account: T,
+
+    /// The ticket's beneficiary
+    pub transfer_sol_to: T,
+
+    pub clock: T,
+    pub system_program: T,
+}
+pub type MarinadeClaimIxKeysOwned = MarinadeClaimIxAccs<[u8; 32]>;
+pub type MarinadeClaimIxKeys<'a> = MarinadeClaimIxAccs<&'a [u8; 32]>;
+pub type MarinadeClaimIxAccsFlag = MarinadeClaimIxAccs<bool>;
+
+pub const MARINADE_CLAIM_IX_IS_WRITER: MarinadeClaimIxAccsFlag =
+    MarinadeClaimIxAccs([false; MARINADE_CLAIM_IX_ACCS_LEN])
+        .const_with_state(true)
+        .const_with_reserve(true)
+        .const_with_ticket_account(true)
+        .const_with_transfer_sol_to(true);
+
+pub const MARINADE_CLAIM_IX_IS_SIGNER: MarinadeClaimIxAccsFlag =
+    MarinadeClaimIxAccs([false; MARINADE_CLAIM_IX_ACCS_LEN]);
+
+impl<T> MarinadeClaimIxAccs<T> {
+    #[inline]
+    pub const fn new(arr: [T; MARINADE_CLAIM_IX_ACCS_LEN]) -> Self {
+        Self(arr)
+    }
+}
+
+impl<T> AsRef<[T]> for MarinadeClaimIxAccs<T> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl MarinadeClaimIxKeysOwned {
+    #[inline]
+    pub fn as_borrowed(&self) -> MarinadeClaimIxKeys<'_> {
+        MarinadeClaimIxKeys::new(self.0.each_ref())
+    }
+
+    #[inline]
+    pub fn with_consts(self) -> Self {
+        self.as_borrowed().with_consts().into_owned()
+    }
+}
+
+impl MarinadeClaimIxKeys<'_> {
+    #[inline]
+    pub fn into_owned(self) -> MarinadeClaimIxKeysOwned {
+        MarinadeClaimIxKeysOwned::new(self.0.map(|pk| *pk))
+    }
+
+    #[inline]
+    pub const fn with_consts(self) -> Self {
+        self.const_with_state(&STATE_PUBKEY)
+            .const_with_reserve(&RESERVE_PUBKEY)
+            .const_with_clock(&SYSVAR_CLOCK)
+            .const_with_system_program(&SYSTEM_PROGRAM)
+    }
+}
+
+#[repr(transparent)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MarinadeClaimIxData([u8; 8]);
+
+impl MarinadeClaimIxData {
+    #[inline]
+    pub const fn new() -> Self {
+        Self(CLAIM_IX_DISCM)
+    }
+
+    #[inline]
+    pub const fn to_buf(&self) -> [u8; 8] {
+        self.0
+    }
+}
@@ -27,6 +27,21 @@ impl DepositSolQuoter for MarinadeDepositSolQuoter<'_> {
     }
 }
 
+impl MarinadeDepositSolQuoter<'_> {
+    /// Returns this quoter with `msol_leg_balance` updated to after `quote` has executed.
+    ///
+    /// Marinade fills deposits from the liquidity pool's mSOL leg first
+    /// and only mints the remainder, so the leg decreases by up to `quote.out`.
+    /// The remaining state changes from minting leave the mSOL price unchanged.
+    #[inline]
+    pub const fn apply_deposit_sol(self, quote: &TokenQuote) -> Self {
+        Self {
+            state: self.state,
+            msol_leg_balance: self.msol_leg_balance.saturating_sub(quote.out),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct MarinadeDepositSolSufAccs<'a> {
     pub liq_pool_msol_leg: &'a [u8; 32],
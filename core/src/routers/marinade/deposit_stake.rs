@@ -54,6 +54,19 @@ impl DepositStakeQuoter for MarinadeDepositStakeQuoter<'_> {
     }
 }
 
+impl MarinadeDepositStakeQuoter<'_> {
+    /// Returns this quoter with `msol_leg_balance` updated to after `quote` has executed.
+    ///
+    /// See [`crate::MarinadeDepositSolQuoter::apply_deposit_sol`]
+    #[inline]
+    pub const fn apply_deposit_stake(self, quote: &DepositStakeQuote) -> Self {
+        Self {
+            msol_leg_balance: self.msol_leg_balance.saturating_sub(quote.out),
+            ..self
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct MarinadeDepositStakeSufAccs<'a> {
     pub state: &'a MarinadeState,
@@ -1,5 +1,13 @@
+mod delayed_unstake;
 mod deposit_sol;
 mod deposit_stake;
+mod sol_value;
+mod withdraw_sol;
+mod withdraw_stake;
 
+pub use delayed_unstake::*;
 pub use deposit_sol::*;
 pub use deposit_stake::*;
+pub use sol_value::*;
+pub use withdraw_sol::*;
+pub use withdraw_stake::*;
@@ -0,0 +1,24 @@
+use sanctum_marinade_liquid_staking_core::State as MarinadeState;
+
+use crate::{internal_utils::mul_div_floor, SolValueCalc};
+
+/// Denominator of [`MarinadeState::msol_price`]
+pub const MARINADE_MSOL_PRICE_DENOM: u64 = 1 << 32;
+
+/// Uses the state's `msol_price` as of its last update
+#[derive(Debug, Clone, Copy)]
+pub struct MarinadeSolValueCalc<'a> {
+    pub state: &'a MarinadeState,
+}
+
+impl SolValueCalc for MarinadeSolValueCalc<'_> {
+    #[inline]
+    fn sol_value(&self, tokens: u64) -> Option<u64> {
+        mul_div_floor(tokens, self.state.msol_price, MARINADE_MSOL_PRICE_DENOM)
+    }
+
+    #[inline]
+    fn tokens_for_sol(&self, lamports: u64) -> Option<u64> {
+        mul_div_floor(lamports, MARINADE_MSOL_PRICE_DENOM, self.state.msol_price)
+    }
+}
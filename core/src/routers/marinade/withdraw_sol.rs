@@ -0,0 +1,238 @@
+use core::{error::Error, fmt::Display};
+
+use generic_array_struct::generic_array_struct;
+use sanctum_marinade_liquid_staking_core::{MarinadeError, State as MarinadeState};
+
+use crate::{
+    internal_utils::mul_div_floor, MarinadeSolValueCalc, SolValueCalc, TokenQuote,
+    WithdrawSolQuoter, WithdrawSolSufAccs, SYSTEM_PROGRAM,
+};
+
+/// Denominator of the liquidity pool's fee basis points
+const BPS_DENOM: u64 = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarinadeWithdrawSolQuoteErr {
+    Marinade(MarinadeError),
+
+    /// The liquidity pool's SOL leg does not have enough SOL for the liquid unstake
+    InsufficientLiquidity,
+}
+
+impl Display for MarinadeWithdrawSolQuoteErr {
+    // Display=Debug, since this is just a simple discriminated str enum
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl Error for MarinadeWithdrawSolQuoteErr {}
+
+impl From<MarinadeError> for MarinadeWithdrawSolQuoteErr {
+    #[inline]
+    fn from(e: MarinadeError) -> Self {
+        Self::Marinade(e)
+    }
+}
+
+/// Liquid unstake of mSOL for SOL from the liquidity pool's SOL leg.
+///
+/// Uses the state's `msol_price` as of its last update, same as [`MarinadeSolValueCalc`]
+#[derive(Debug, Clone, Copy)]
+pub struct MarinadeWithdrawSolQuoter<'a> {
+    pub state: &'a MarinadeState,
+
+    /// Lamport balance of [`sanctum_marinade_liquid_staking_core::LIQ_POOL_SOL_LEG_PUBKEY`],
+    /// including its rent-exempt minimum
+    pub sol_leg_lamports: u64,
+}
+
+impl MarinadeWithdrawSolQuoter<'_> {
+    /// SOL in the SOL leg that is available for liquid unstakes
+    #[inline]
+    pub const fn available_lamports(&self) -> u64 {
+        self.sol_leg_lamports
+            .saturating_sub(self.state.rent_exempt_for_token_acc)
+    }
+
+    /// Liquid unstake fee, in basis points, given the lamports that
+    /// remain available in the SOL leg after the unstake.
+    ///
+    /// Decreases linearly from `lp_max_fee` if no lamports remain
+    /// to `lp_min_fee` if at least `lp_liquidity_target` lamports remain.
+    #[inline]
+    pub fn fee_bps(&self, lamports_after: u64) -> u64 {
+        let liq_pool = &self.state.liq_pool;
+        let max = u64::from(liq_pool.lp_max_fee.basis_points);
+        let min = u64::from(liq_pool.lp_min_fee.basis_points);
+        if lamports_after >= liq_pool.lp_liquidity_target {
+            return min;
+        }
+        // unwrap-safety: lamports_after < lp_liquidity_target so denom != 0
+        // and result <= max - min
+        max - mul_div_floor(
+            max.saturating_sub(min),
+            lamports_after,
+            liq_pool.lp_liquidity_target,
+        )
+        .unwrap()
+    }
+
+    /// The part of a liquid unstake's `fee` that goes to the treasury
+    /// instead of the liquidity pool's mSOL leg
+    #[inline]
+    pub fn treasury_cut(&self, fee: u64) -> u64 {
+        mul_div_floor(
+            fee,
+            self.state.liq_pool.treasury_cut.basis_points.into(),
+            BPS_DENOM,
+        )
+        .unwrap_or(0)
+    }
+
+    /// Returns this quoter with `sol_leg_lamports` updated to after `quote` has executed.
+    ///
+    /// Liquid unstaking does not change the mSOL price.
+    #[inline]
+    pub const fn apply_withdraw_sol(self, quote: &TokenQuote) -> Self {
+        Self {
+            state: self.state,
+            sol_leg_lamports: self.sol_leg_lamports.saturating_sub(quote.out),
+        }
+    }
+}
+
+impl WithdrawSolQuoter for MarinadeWithdrawSolQuoter<'_> {
+    type Error = MarinadeWithdrawSolQuoteErr;
+
+    #[inline]
+    fn quote_withdraw_sol(&self, tokens: u64) -> Result<TokenQuote, Self::Error> {
+        if self.state.paused {
+            return Err(MarinadeError::ProgramIsPaused.into());
+        }
+        let calc = MarinadeSolValueCalc { state: self.state };
+        let available = self.available_lamports();
+        let remove = calc
+            .sol_value(tokens)
+            .ok_or(MarinadeError::CalculationFailure)?;
+        let fee_bps = if remove >= available {
+            u64::from(self.state.liq_pool.lp_max_fee.basis_points)
+        } else {
+            self.fee_bps(available - remove)
+        };
+        let fee =
+            mul_div_floor(tokens, fee_bps, BPS_DENOM).ok_or(MarinadeError::CalculationFailure)?;
+        let out = calc
+            .sol_value(tokens - fee)
+            .ok_or(MarinadeError::CalculationFailure)?;
+        if out > available {
+            return Err(MarinadeWithdrawSolQuoteErr::InsufficientLiquidity);
+        }
+        Ok(TokenQuote {
+            inp: tokens,
+            out,
+            fee,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct MarinadeWithdrawSolSufAccs<'a> {
+    pub liq_pool_msol_leg: &'a [u8; 32],
+    pub treasury_msol: &'a [u8; 32],
+}
+
+impl WithdrawSolSufAccs for MarinadeWithdrawSolSufAccs<'_> {
+    type Accs = MarinadeWithdrawSolIxSuffixKeysOwned;
+    type AccFlags = MarinadeWithdrawSolIxSuffixAccsFlag;
+
+    #[inline]
+    fn suffix_accounts(&self) -> Self::Accs {
+        MarinadeWithdrawSolIxSuffixAccsBuilder::start()
+            .with_marinade_program(sanctum_marinade_liquid_staking_core::MARINADE_STAKING_PROGRAM)
+            .with_state(sanctum_marinade_liquid_staking_core::STATE_PUBKEY)
+            .with_liq_pool_sol_leg(sanctum_marinade_liquid_staking_core::LIQ_POOL_SOL_LEG_PUBKEY)
+            .with_liq_pool_msol_leg(*self.liq_pool_msol_leg)
+            .with_treasury_msol(*self.treasury_msol)
+            .with_system_program(SYSTEM_PROGRAM)
+            .build()
+    }
+
+    #[inline]
+    fn suffix_is_signer(&self) -> Self::AccFlags {
+        MARINADE_WITHDRAW_SOL_IX_SUFFIX_IS_SIGNER
+    }
+
+    #[inline]
+    fn suffix_is_writable(&self) -> Self::AccFlags {
+        MARINADE_WITHDRAW_SOL_IX_SUFFIX_IS_WRITER
+    }
+}
+
+impl<'a> MarinadeWithdrawSolSufAccs<'a> {
+    #[inline]
+    pub const fn from_state(state: &'a MarinadeState) -> Self {
+        Self {
+            liq_pool_msol_leg: &state.liq_pool.msol_leg,
+            treasury_msol: &state.treasury_msol_account,
+        }
+    }
+}
+
+#[generic_array_struct(builder pub)]
+#[repr(transparent)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "wasm",
+    derive(tsify_next::Tsify),
+    tsify(into_wasm_abi, from_wasm_abi)
+)]
+pub struct MarinadeWithdrawSolIxSuffixAccs<T> {
+    pub marinade_program: T,
+    pub state: T,
+    pub liq_pool_sol_leg: T,
+    pub liq_pool_msol_leg: T,
+    pub treasury_msol: T,
+    pub system_program: T,
+}
+pub type MarinadeWithdrawSolIxSuffixKeysOwned = MarinadeWithdrawSolIxSuffixAccs<[u8; 32]>;
+pub type MarinadeWithdrawSolIxSuffixKeys<'a> = MarinadeWithdrawSolIxSuffixAccs<&'a [u8; 32]>;
+pub type MarinadeWithdrawSolIxSuffixAccsFlag = MarinadeWithdrawSolIxSuffixAccs<bool>;
+
+pub const MARINADE_WITHDRAW_SOL_IX_SUFFIX_IS_WRITER: MarinadeWithdrawSolIxSuffixAccsFlag =
+    MarinadeWithdrawSolIxSuffixAccs([false; MARINADE_WITHDRAW_SOL_IX_SUFFIX_ACCS_LEN])
+        .const_with_state(true)
+        .const_with_liq_pool_sol_leg(true)
+        .const_with_liq_pool_msol_leg(true)
+        .const_with_treasury_msol(true);
+
+pub const MARINADE_WITHDRAW_SOL_IX_SUFFIX_IS_SIGNER: MarinadeWithdrawSolIxSuffixAccsFlag =
+    MarinadeWithdrawSolIxSuffixAccs([false; MARINADE_WITHDRAW_SOL_IX_SUFFIX_ACCS_LEN]);
+
+impl<T> MarinadeWithdrawSolIxSuffixAccs<T> {
+    #[inline]
+    pub const fn new(arr: [T; MARINADE_WITHDRAW_SOL_IX_SUFFIX_ACCS_LEN]) -> Self {
+        Self(arr)
+    }
+}
+
+impl<T> AsRef<[T]> for MarinadeWithdrawSolIxSuffixAccs<T> {
+    fn as_ref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl MarinadeWithdrawSolIxSuffixKeysOwned {
+    #[inline]
+    pub fn as_borrowed(&self) -> MarinadeWithdrawSolIxSuffixKeys<'_> {
+        MarinadeWithdrawSolIxSuffixKeys::new(self.0.each_ref())
+    }
+}
+
+impl MarinadeWithdrawSolIxSuffixKeys<'_> {
+    #[inline]
+    pub fn into_owned(&self) -> MarinadeWithdrawSolIxSuffixKeysOwned {
+        MarinadeWithdrawSolIxSuffixKeysOwned::new(self.0.map(|pk| *pk))
+    }
+}
@@ -0,0 +1,260 @@
+use const_crypto::bs58;
+use generic_array_struct::generic_array_struct;
+use sanctum_marinade_liquid_staking_core::{
+    MarinadeError, State as MarinadeState, MARINADE_STAKING_PROGRAM, STATE_PUBKEY,
+};
+
+use crate::{
+    internal_utils::mul_div_floor, ActiveStakeParams, MarinadeSolValueCalc, SolValueCalc,
+    StakeAccountLamports, WithdrawStakeQuote, WithdrawStakeQuoteErr, WithdrawStakeQuoter,
+    WithdrawStakeSufAccs, STAKE_MIN_DELEGATION_LAMPORTS, STAKE_PROGRAM, SYSTEM_PROGRAM,
+    SYSVAR_CLOCK, TOKEN_PROGRAM,
+};
+
+/// Marinade's stake list, `state.stake_system.stake_list.account`
+pub const MARINADE_STAKE_LIST_PUBKEY: [u8; 32] =
+    bs58::decode_pubkey("Anv3XE7e5saNdm16MU6bniYS59Mpv7DzQXHAhxJUmAKW");
+
+/// Denominator of `state.withdraw_stake_account_fee`, which is in
+/// hundredths of a basis point
+const BP_CENTS_DENOM: u64 = 1_000_000;
+
+/// A stake account on Marinade's stake list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MarinadeStakeAccount {
+    pub addr: [u8; 32],
+
+    /// Vote account this stake account is delegated to
+    pub vote: [u8; 32],
+
+    /// `last_update_delegated_lamports` of this stake account's stake record
+    /// on the stake list
+    pub delegated_lamports: u64,
+
+    /// From this stake account's stake record on the stake list
+    pub is_emergency_unstaking: bool,
+}
+
+/// WithdrawStake by splitting from one of the stake accounts on Marinade's stake list.
+///
+/// Errors with [`WithdrawStakeQuoteErr::BelowMinimumDelegation`] if the
+/// withdrawn stake account would be delegated less than `min_delegation`.
+#[derive(Debug, Clone, Copy)]
+pub struct MarinadeWithdrawStakeQuoter<'a> {
+    pub state: &'a MarinadeState,
+
+    /// The stake account to split the withdrawn stake from
+    pub stake_account: &'a MarinadeStakeAccount,
+
+    /// The stake program's minimum delegation, in lamports
+    pub min_delegation: u64,
+}
+
+impl<'a> MarinadeWithdrawStakeQuoter<'a> {
+    /// `min_delegation` defaults to [`STAKE_MIN_DELEGATION_LAMPORTS`],
+    /// see [`Self::with_min_delegation`]
+    #[inline]
+    pub const fn new(state: &'a MarinadeState, stake_account: &'a MarinadeStakeAccount) -> Self {
+        Self {
+            state,
+            stake_account,
+            min_delegation: STAKE_MIN_DELEGATION_LAMPORTS,
+        }
+    }
+
+    /// Returns this quoter with `min_delegation` replaced,
+    /// usually with [`crate::RouterConfig::stake_min_delegation`]
+    #[inline]
+    pub const fn with_min_delegation(self, min_delegation: u64) -> Self {
+        Self {
+            min_delegation,
+            ..self
+        }
+    }
+}
+
+impl WithdrawStakeQuoter for MarinadeWithdrawStakeQuoter<'_> {
+    type Error = WithdrawStakeQuoteErr<MarinadeError>;
+
+    #[inline]
+    fn quote_withdraw_stake(
+        &self,
+        tokens: u64,
+        vote: Option<&[u8; 32]>,
+    ) -> Result<WithdrawStakeQuote, Self::Error> {
+        let MarinadeStakeAccount {
+            addr,
+            vote: stake_vote,
+            delegated_lamports,
+            is_emergency_unstaking,
+            ..
+        } = self.stake_account;
+        if let Some(v) = vote {
+            if v != stake_vote {
+                return Err(MarinadeError::WrongValidatorAccountOrIndex.into());
+            }
+        }
+        if self.state.paused {
+            return Err(MarinadeError::ProgramIsPaused.into());
+        }
+        if !self.state.withdraw_stake_account_enabled {
+            return Err(MarinadeError::WithdrawStakeAccountIsNotEnabled.into());
+        }
+        if *is_emergency_unstaking {
+            return Err(MarinadeError::StakeAccountIsEmergencyUnstaking.into());
+        }
+        let fee = mul_div_floor(
+            tokens,
+            self.state.withdraw_stake_account_fee.bp_cents.into(),
+            BP_CENTS_DENOM,
+        )
+        .ok_or(MarinadeError::CalculationFailure)?;
+        let split_lamports = tokens
+            .checked_sub(fee)
+            .and_then(|tokens| MarinadeSolValueCalc { state: self.state }.sol_value(tokens))
+            .ok_or(MarinadeError::CalculationFailure)?;
+        let min_stake = self.state.stake_system.min_stake;
+        if split_lamports < min_stake {
+            return Err(MarinadeError::WithdrawStakeLamportsIsTooLow.into());
+        }
+        let remainder = delegated_lamports
+            .checked_sub(split_lamports)
+            .ok_or(MarinadeError::SelectedStakeAccountHasNotEnoughFunds)?;
+        if remainder < min_stake {
+            return Err(MarinadeError::StakeAccountRemainderTooLow.into());
+        }
+        if split_lamports < self.min_delegation {
+            return Err(WithdrawStakeQuoteErr::BelowMinimumDelegation);
+        }
+        Ok(WithdrawStakeQuote {
+            inp: tokens,
+            out: ActiveStakeParams {
+                vote: *stake_vote,
+                lamports: StakeAccountLamports {
+                    staked: split_lamports,
+                    unstaked: 0,
+                },
+            },
+            fee,
+            stake_to_split: Some(*addr),
+        })
+    }
+
+    #[inline]
+    fn fixed_vote(&self) -> Option<&[u8; 32]> {
+        Some(&self.stake_account.vote)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MarinadeWithdrawStakeSufAccs<'a> {
+    pub state: &'a MarinadeState,
+
+    /// Stake account on the stake list to split the withdrawn stake from
+    pub stake_to_split: [u8; 32],
+
+    /// The stake withdraw authority PDA
+    pub stake_withdraw_authority: [u8; 32],
+
+    /// The stake deposit authority PDA
+    pub stake_deposit_authority: [u8; 32],
+}
+
+impl WithdrawStakeSufAccs for MarinadeWithdrawStakeSufAccs<'_> {
+    type Accs = MarinadeWithdrawStakeIxSuffixKeysOwned;
+    type AccFlags = MarinadeWithdrawStakeIxSuffixAccsFlag;
+
+    #[inline]
+    fn suffix_accounts(&self) -> Self::Accs {
+        MarinadeWithdrawStakeIxSuffixAccsBuilder::start()
+            .with_marinade_program(MARINADE_STAKING_PROGRAM)
+            .with_marinade_state(STATE_PUBKEY)
+            .with_treasury_msol(self.state.treasury_msol_account)
+            .with_validator_list(self.state.validator_system.validator_list.account)
+            .with_stake_list(self.state.stake_system.stake_list.account)
+            .with_stake_withdraw_authority(self.stake_withdraw_authority)
+            .with_stake_deposit_authority(self.stake_deposit_authority)
+            .with_stake_to_split(self.stake_to_split)
+            .with_clock(SYSVAR_CLOCK)
+            .with_token_program(TOKEN_PROGRAM)
+            .with_stake_program(STAKE_PROGRAM)
+            .with_system_program(SYSTEM_PROGRAM)
+            .build()
+    }
+
+    #[inline]
+    fn suffix_is_signer(&self) -> Self::AccFlags {
+        MARINADE_WITHDRAW_STAKE_IX_SUFFIX_IS_SIGNER
+    }
+
+    #[inline]
+    fn suffix_is_writable(&self) -> Self::AccFlags {
+        MARINADE_WITHDRAW_STAKE_IX_SUFFIX_IS_WRITER
+    }
+}
+
+#[generic_array_struct(builder pub)]
+#[repr(transparent)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "wasm",
+    derive(tsify_next::Tsify),
+    tsify(into_wasm_abi, from_wasm_abi)
+)]
+pub struct MarinadeWithdrawStakeIxSuffixAccs<T> {
+    pub marinade_program: T,
+    pub marinade_state: T,
+    pub treasury_msol: T,
+    pub validator_list: T,
+    pub stake_list: T,
+    pub stake_withdraw_authority: T,
+    pub stake_deposit_authority: T,
+    pub stake_to_split: T,
+    pub clock: T,
+    pub token_program: T,
+    pub stake_program: T,
+    pub system_program: T,
+}
+pub type MarinadeWithdrawStakeIxSuffixKeysOwned = MarinadeWithdrawStakeIxSuffixAccs<[u8; 32]>;
+pub type MarinadeWithdrawStakeIxSuffixKeys<'a> = MarinadeWithdrawStakeIxSuffixAccs<&'a [u8; 32]>;
+pub type MarinadeWithdrawStakeIxSuffixAccsFlag = MarinadeWithdrawStakeIxSuffixAccs<bool>;
+
+pub const MARINADE_WITHDRAW_STAKE_IX_SUFFIX_IS_WRITER: MarinadeWithdrawStakeIxSuffixAccsFlag =
+    MarinadeWithdrawStakeIxSuffixAccs([false; MARINADE_WITHDRAW_STAKE_IX_SUFFIX_ACCS_LEN])
+        .const_with_marinade_state(true)
+        .const_with_treasury_msol(true)
+        .const_with_validator_list(true)
+        .const_with_stake_list(true)
+        .const_with_stake_to_split(true);
+
+pub const MARINADE_WITHDRAW_STAKE_IX_SUFFIX_IS_SIGNER: MarinadeWithdrawStakeIxSuffixAccsFlag =
+    MarinadeWithdrawStakeIxSuffixAccs([false; MARINADE_WITHDRAW_STAKE_IX_SUFFIX_ACCS_LEN]);
+
+impl<T> MarinadeWithdrawStakeIxSuffixAccs<T> {
+    #[inline]
+    pub const fn new(arr: [T; MARINADE_WITHDRAW_STAKE_IX_SUFFIX_ACCS_LEN]) -> Self {
+        Self(arr)
+    }
+}
+
+impl<T> AsRef<[T]> for MarinadeWithdrawStakeIxSuffixAccs<T> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl MarinadeWithdrawStakeIxSuffixKeysOwned {
+    #[inline]
+    pub fn as_borrowed(&self) -> MarinadeWithdrawStakeIxSuffixKeys<'_> {
+        MarinadeWithdrawStakeIxSuffixKeys::new(self.0.each_ref())
+    }
+}
+
+impl MarinadeWithdrawStakeIxSuffixKeys<'_> {
+    #[inline]
+    pub fn into_owned(self) -> MarinadeWithdrawStakeIxSuffixKeysOwned {
+        MarinadeWithdrawStakeIxSuffixKeysOwned::new(self.0.map(|pk| *pk))
+    }
+}
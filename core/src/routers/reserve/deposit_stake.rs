@@ -5,7 +5,8 @@ use sanctum_reserve_core::{
 
 use crate::{
     slumdog_target_lamports, ActiveStakeParams, DepositStakeQuote, DepositStakeQuoter,
-    DepositStakeSufAccs, STAKE_PROGRAM, SYSTEM_PROGRAM, SYSVAR_CLOCK, TOKEN_PROGRAM,
+    DepositStakeSufAccs, RentParams, ReserveConfig, STAKE_PROGRAM, SYSTEM_PROGRAM, SYSVAR_CLOCK,
+    TOKEN_PROGRAM,
 };
 
 #[derive(Debug, Clone, Copy)]
@@ -56,18 +57,31 @@ impl ReserveDepositStakeQuoter<'_> {
     }
 
     #[inline]
-    fn pool_balance(&self) -> PoolBalance {
+    pub fn pool_balance(&self) -> PoolBalance {
         PoolBalance {
             pool_incoming_stake: self.pool_incoming_stake,
             sol_reserves_lamports: self.pool_sol_reserves,
         }
     }
 
+    /// Returns this quoter with pool state updated to after the
+    /// prefund flash loan has been repaid by instant unstaking the slumdog stake
     #[inline]
-    pub fn after_prefund(self) -> Result<Self, ReserveError> {
-        let stake = slumdog_target_lamports(&self.pool_balance(), self.fee_account)
+    pub fn after_prefund(self, rent: &RentParams) -> Result<Self, ReserveError> {
+        let stake = slumdog_target_lamports(&self.pool_balance(), self.fee_account, rent)
             .ok_or(ReserveError::InternalError)?;
-        let quote = self.quote_deposit_stake_inner(stake)?;
+        self.apply_unstake(stake)
+    }
+
+    /// Returns this quoter with pool state updated to after `quote` has executed
+    #[inline]
+    pub fn apply_deposit_stake(self, quote: &DepositStakeQuote) -> Result<Self, ReserveError> {
+        self.apply_unstake(quote.inp.lamports.total())
+    }
+
+    #[inline]
+    fn apply_unstake(self, deposit_stake_total_lamports: u64) -> Result<Self, ReserveError> {
+        let quote = self.quote_deposit_stake_inner(deposit_stake_total_lamports)?;
         let Self {
             fee_account,
             protocol_fee_account,
@@ -88,6 +102,7 @@ impl ReserveDepositStakeQuoter<'_> {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct ReserveDepositStakeSufAccs {
     pub stake_acc_record_addr: [u8; 32],
+    pub reserve: ReserveConfig,
 }
 
 impl DepositStakeSufAccs for ReserveDepositStakeSufAccs {
@@ -96,12 +111,12 @@ impl DepositStakeSufAccs for ReserveDepositStakeSufAccs {
 
     fn suffix_accounts(&self) -> Self::Accs {
         ReserveDepositStakeIxSuffixAccsBuilder::start()
-            .with_reserve_program(sanctum_reserve_core::UNSTAKE_PROGRAM)
-            .with_protocol_fee(sanctum_reserve_core::PROTOCOL_FEE)
-            .with_pool_sol_reserves(sanctum_reserve_core::POOL_SOL_RESERVES)
-            .with_reserve_fee(sanctum_reserve_core::FEE)
-            .with_reserve_pool(sanctum_reserve_core::POOL)
-            .with_protocol_fee_dest(sanctum_reserve_core::PROTOCOL_FEE_VAULT)
+            .with_reserve_program(self.reserve.program)
+            .with_protocol_fee(self.reserve.protocol_fee)
+            .with_pool_sol_reserves(self.reserve.pool_sol_reserves)
+            .with_reserve_fee(self.reserve.fee)
+            .with_reserve_pool(self.reserve.pool)
+            .with_protocol_fee_dest(self.reserve.protocol_fee_vault)
             .with_stake_acc_record(self.stake_acc_record_addr)
             .with_clock(SYSVAR_CLOCK)
             .with_system_program(SYSTEM_PROGRAM)
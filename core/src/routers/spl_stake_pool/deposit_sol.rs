@@ -1,7 +1,9 @@
 use generic_array_struct::generic_array_struct;
 use sanctum_spl_stake_pool_core::{DepositSolQuoteArgs, SplStakePoolError, StakePool};
 
-use crate::{DepositSolQuoter, DepositSolSufAccs, SplSolSufAccs, TokenQuote};
+use crate::{
+    internal_utils::mul_div_floor, DepositSolQuoter, DepositSolSufAccs, SplSolSufAccs, TokenQuote,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct SplDepositSolQuoter<'a> {
@@ -29,6 +31,16 @@ impl DepositSolQuoter for SplDepositSolQuoter<'_> {
     }
 }
 
+impl SplDepositSolQuoter<'_> {
+    /// Returns the part of a DepositSol quote's `fee` that is paid to the referrer
+    /// instead of the manager
+    #[inline]
+    pub fn referral_fee(&self, fee: u64) -> u64 {
+        // referral fee is a percentage of the total deposit fee
+        mul_div_floor(fee, self.stake_pool.sol_referral_fee.into(), 100).unwrap_or(0)
+    }
+}
+
 impl DepositSolSufAccs for SplSolSufAccs<'_> {
     type Accs = SplDepositSolIxSuffixKeysOwned;
     type AccFlags = SplDepositSolIxSuffixAccsFlag;
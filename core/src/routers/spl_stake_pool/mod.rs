@@ -2,11 +2,13 @@ use sanctum_spl_stake_pool_core::StakePool;
 
 mod deposit_sol;
 mod deposit_stake;
+mod sol_value;
 mod withdraw_sol;
 mod withdraw_stake;
 
 pub use deposit_sol::*;
 pub use deposit_stake::*;
+pub use sol_value::*;
 pub use withdraw_sol::*;
 pub use withdraw_stake::*;
 
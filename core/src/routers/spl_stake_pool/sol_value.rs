@@ -0,0 +1,36 @@
+use sanctum_spl_stake_pool_core::StakePool;
+
+use crate::{internal_utils::mul_div_floor, SolValueCalc};
+
+/// Uses the stake pool's `total_lamports / pool_token_supply` as of its last update
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SplSolValueCalc<'a> {
+    pub stake_pool: &'a StakePool,
+}
+
+impl SolValueCalc for SplSolValueCalc<'_> {
+    #[inline]
+    fn sol_value(&self, tokens: u64) -> Option<u64> {
+        // empty pool is 1:1, same as the stake pool program
+        if self.stake_pool.pool_token_supply == 0 {
+            return Some(tokens);
+        }
+        mul_div_floor(
+            tokens,
+            self.stake_pool.total_lamports,
+            self.stake_pool.pool_token_supply,
+        )
+    }
+
+    #[inline]
+    fn tokens_for_sol(&self, lamports: u64) -> Option<u64> {
+        if self.stake_pool.pool_token_supply == 0 {
+            return Some(lamports);
+        }
+        mul_div_floor(
+            lamports,
+            self.stake_pool.pool_token_supply,
+            self.stake_pool.total_lamports,
+        )
+    }
+}
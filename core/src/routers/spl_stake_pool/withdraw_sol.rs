@@ -30,6 +30,20 @@ impl WithdrawSolQuoter for SplWithdrawSolQuoter<'_> {
     }
 }
 
+impl SplWithdrawSolQuoter<'_> {
+    /// Returns this quoter with `reserve_stake_lamports` updated to after `quote` has executed.
+    ///
+    /// `stake_pool` is borrowed and therefore not updated, use
+    /// `SplStakePoolRouterOwned::apply_withdraw_sol()` in the sdk to update it.
+    #[inline]
+    pub const fn apply_withdraw_sol(self, quote: &TokenQuote) -> Self {
+        Self {
+            reserve_stake_lamports: self.reserve_stake_lamports.saturating_sub(quote.out),
+            ..self
+        }
+    }
+}
+
 impl WithdrawSolSufAccs for SplSolSufAccs<'_> {
     type Accs = SplWithdrawSolIxSuffixKeysOwned;
     type AccFlags = SplWithdrawSolIxSuffixAccsFlag;
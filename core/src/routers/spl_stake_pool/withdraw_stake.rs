@@ -9,8 +9,9 @@ use sanctum_spl_stake_pool_core::{
 };
 
 use crate::{
-    ActiveStakeParams, StakeAccountLamports, WithdrawStakeQuote, WithdrawStakeQuoter,
-    WithdrawStakeSufAccs, STAKE_PROGRAM, SYSTEM_PROGRAM, SYSVAR_CLOCK, TOKEN_PROGRAM,
+    ActiveStakeParams, StakeAccountLamports, WithdrawStakeQuote, WithdrawStakeQuoteErr,
+    WithdrawStakeQuoter, WithdrawStakeSufAccs, STAKE_MIN_DELEGATION_LAMPORTS, STAKE_PROGRAM,
+    SYSTEM_PROGRAM, SYSVAR_CLOCK, TOKEN_PROGRAM,
 };
 
 #[derive(Debug, Clone, Copy)]
@@ -85,15 +86,21 @@ impl WithdrawStakeQuoter for SplWithdrawStakeQuoter<'_> {
 //
 /// [`SplWithdrawStakeQuoter`], but only for one of the validators in the pool
 /// instead of all of them.
+///
+/// Also errors with [`WithdrawStakeQuoteErr::BelowMinimumDelegation`] if the
+/// withdrawn stake account would be delegated less than `min_delegation`.
 #[derive(Debug, Clone, Copy)]
 pub struct SplWithdrawStakeValQuoter<'a> {
     pub stake_pool: &'a StakePool,
     pub curr_epoch: u64,
     pub validator: &'a ValidatorStakeInfo,
+
+    /// The stake program's minimum delegation, in lamports
+    pub min_delegation: u64,
 }
 
 impl WithdrawStakeQuoter for SplWithdrawStakeValQuoter<'_> {
-    type Error = SplStakePoolError;
+    type Error = WithdrawStakeQuoteErr<SplStakePoolError>;
 
     #[inline]
     fn quote_withdraw_stake(
@@ -103,7 +110,7 @@ impl WithdrawStakeQuoter for SplWithdrawStakeValQuoter<'_> {
     ) -> Result<WithdrawStakeQuote, Self::Error> {
         if let Some(v) = vote {
             if v != self.validator.vote_account_address() {
-                return Err(SplStakePoolError::IncorrectWithdrawVoteAddress);
+                return Err(SplStakePoolError::IncorrectWithdrawVoteAddress.into());
             }
         }
         let quote = self.stake_pool.quote_withdraw_stake(
@@ -112,7 +119,16 @@ impl WithdrawStakeQuoter for SplWithdrawStakeValQuoter<'_> {
                 current_epoch: self.curr_epoch,
             },
         )?;
-        conv_quote(quote, self.validator)
+        let quote = conv_quote(quote, self.validator)?;
+        if quote.out.lamports.staked < self.min_delegation {
+            return Err(WithdrawStakeQuoteErr::BelowMinimumDelegation);
+        }
+        Ok(quote)
+    }
+
+    #[inline]
+    fn fixed_vote(&self) -> Option<&[u8; 32]> {
+        Some(self.validator.vote_account_address())
     }
 }
 
@@ -129,6 +145,9 @@ impl<'a> SplWithdrawStakeValQuoter<'a> {
     /// - otherwise, a iterator yielding a single entry of the preferred withdraw validator is returned
     ///
     /// Returns Err if preferred withdraw validator is set but not on list.
+    ///
+    /// `min_delegation` of each quoter defaults to [`STAKE_MIN_DELEGATION_LAMPORTS`],
+    /// see [`Self::with_min_delegation`]
     #[inline]
     pub fn all<'parent: 'a>(
         stake_pool: &'parent StakePool,
@@ -162,11 +181,24 @@ impl<'a> SplWithdrawStakeValQuoter<'a> {
             validator,
             stake_pool,
             curr_epoch,
+            min_delegation: STAKE_MIN_DELEGATION_LAMPORTS,
         };
         Ok(s1.iter().map(ctor).chain(s2.iter().map(ctor)))
     }
 }
 
+impl SplWithdrawStakeValQuoter<'_> {
+    /// Returns this quoter with `min_delegation` replaced,
+    /// usually with [`crate::RouterConfig::stake_min_delegation`]
+    #[inline]
+    pub const fn with_min_delegation(self, min_delegation: u64) -> Self {
+        Self {
+            min_delegation,
+            ..self
+        }
+    }
+}
+
 fn conv_quote(
     sanctum_spl_stake_pool_core::WithdrawStakeQuote {
         tokens_in,
@@ -189,6 +221,7 @@ fn conv_quote(
             },
         },
         fee: fee_amount,
+        stake_to_split: None,
     })
 }
 
@@ -0,0 +1,442 @@
+//! Conversions to [`solana_instruction`] types
+
+use solana_instruction::{AccountMeta, Instruction};
+use solana_pubkey::Pubkey;
+
+use crate::{
+    CloseFeeTokenAccountIxData, CloseFeeTokenAccountIxKeysOwned, CreateFeeTokenAccountIxData,
+    CreateFeeTokenAccountIxKeysOwned, DepositSolSufAccs, DepositStakeIxData,
+    DepositStakeIxKeysOwned, DepositStakeSufAccs, LidoWithdrawStakeIxSuffixKeysOwned,
+    MarinadeClaimIxData, MarinadeClaimIxKeysOwned, MarinadeDepositSolIxSuffixKeysOwned,
+    MarinadeDepositStakeIxSuffixKeysOwned, MarinadeOrderUnstakeIxData,
+    MarinadeOrderUnstakeIxKeysOwned, MarinadeWithdrawSolIxSuffixKeysOwned,
+    MarinadeWithdrawStakeIxSuffixKeysOwned, PrefundSwapViaStakeIxData,
+    PrefundSwapViaStakePrefixKeysOwned, PrefundWithdrawStakeIxData,
+    PrefundWithdrawStakePrefixKeysOwned, ReserveDepositStakeIxSuffixKeysOwned, RouterIx,
+    SplDepositSolIxSuffixKeysOwned, SplDepositStakeIxSuffixKeysOwned,
+    SplWithdrawSolIxSuffixKeysOwned, SplWithdrawStakeIxSuffixKeysOwned, StakeWrappedSolIxData,
+    StakeWrappedSolPrefixKeysOwned, SwapViaStakeIxData, SwapViaStakePrefixKeysOwned,
+    WithdrawFeesIxData, WithdrawFeesIxKeysOwned, WithdrawSolSufAccs, WithdrawStakeSufAccs,
+    WithdrawWrappedSolIxData, WithdrawWrappedSolPrefixKeysOwned,
+    CLOSE_FEE_TOKEN_ACCOUNT_IX_IS_SIGNER, CLOSE_FEE_TOKEN_ACCOUNT_IX_IS_WRITER,
+    CREATE_FEE_TOKEN_ACCOUNT_IX_IS_SIGNER, CREATE_FEE_TOKEN_ACCOUNT_IX_IS_WRITER,
+    DEPOSIT_STAKE_IX_IS_SIGNER, DEPOSIT_STAKE_IX_IS_WRITER_NON_WSOL_OUT,
+    DEPOSIT_STAKE_IX_IS_WRITER_WSOL_OUT, LIDO_WITHDRAW_STAKE_IX_SUFFIX_IS_SIGNER,
+    LIDO_WITHDRAW_STAKE_IX_SUFFIX_IS_WRITER, MARINADE_CLAIM_IX_IS_SIGNER,
+    MARINADE_CLAIM_IX_IS_WRITER, MARINADE_DEPOSIT_SOL_IX_SUFFIX_IS_SIGNER,
+    MARINADE_DEPOSIT_SOL_IX_SUFFIX_IS_WRITER, MARINADE_DEPOSIT_STAKE_IX_SUFFIX_IS_SIGNER,
+    MARINADE_DEPOSIT_STAKE_IX_SUFFIX_IS_WRITER, MARINADE_ORDER_UNSTAKE_IX_IS_SIGNER,
+    MARINADE_ORDER_UNSTAKE_IX_IS_WRITER, MARINADE_WITHDRAW_SOL_IX_SUFFIX_IS_SIGNER,
+    MARINADE_WITHDRAW_SOL_IX_SUFFIX_IS_WRITER, MARINADE_WITHDRAW_STAKE_IX_SUFFIX_IS_SIGNER,
+    MARINADE_WITHDRAW_STAKE_IX_SUFFIX_IS_WRITER, NATIVE_MINT,
+    PREFUND_SWAP_VIA_STAKE_PREFIX_IS_SIGNER, PREFUND_SWAP_VIA_STAKE_PREFIX_IS_WRITER_NON_WSOL_OUT,
+    PREFUND_SWAP_VIA_STAKE_PREFIX_IS_WRITER_WSOL_OUT, PREFUND_WITHDRAW_STAKE_PREFIX_IS_SIGNER,
+    PREFUND_WITHDRAW_STAKE_PREFIX_IS_WRITER, RESERVE_DEPOSIT_STAKE_IX_SUFFIX_IS_SIGNER,
+    RESERVE_DEPOSIT_STAKE_IX_SUFFIX_IS_WRITER, SANCTUM_ROUTER_PROGRAM,
+    SPL_DEPOSIT_SOL_IX_SUFFIX_IS_SIGNER, SPL_DEPOSIT_SOL_IX_SUFFIX_IS_WRITER,
+    SPL_DEPOSIT_STAKE_IX_SUFFIX_IS_SIGNER, SPL_DEPOSIT_STAKE_IX_SUFFIX_IS_WRITER,
+    SPL_WITHDRAW_SOL_IX_SUFFIX_IS_SIGNER, SPL_WITHDRAW_SOL_IX_SUFFIX_IS_WRITER,
+    SPL_WITHDRAW_STAKE_IX_SUFFIX_IS_SIGNER, SPL_WITHDRAW_STAKE_IX_SUFFIX_IS_WRITER,
+    STAKE_WRAPPED_SOL_PREFIX_IS_SIGNER, STAKE_WRAPPED_SOL_PREFIX_IS_WRITER,
+    SWAP_VIA_STAKE_PREFIX_IS_SIGNER, SWAP_VIA_STAKE_PREFIX_IS_WRITER_NON_WSOL_OUT,
+    SWAP_VIA_STAKE_PREFIX_IS_WRITER_WSOL_OUT, WITHDRAW_FEES_IX_IS_SIGNER,
+    WITHDRAW_FEES_IX_IS_WRITER, WITHDRAW_WRAPPED_SOL_PREFIX_IS_SIGNER,
+    WITHDRAW_WRAPPED_SOL_PREFIX_IS_WRITER,
+};
+
+/// # Panics
+///
+/// If `keys`, `is_signer` and `is_writable` are of different lengths
+#[inline]
+pub fn account_metas(
+    keys: &[[u8; 32]],
+    is_signer: &[bool],
+    is_writable: &[bool],
+) -> Vec<AccountMeta> {
+    assert!(keys.len() == is_signer.len() && keys.len() == is_writable.len());
+    keys.iter()
+        .zip(is_signer)
+        .zip(is_writable)
+        .map(|((k, s), w)| AccountMeta {
+            pubkey: Pubkey::new_from_array(*k),
+            is_signer: *s,
+            is_writable: *w,
+        })
+        .collect()
+}
+
+#[inline]
+pub fn deposit_sol_suffix_account_metas(suffix: impl DepositSolSufAccs) -> Vec<AccountMeta> {
+    account_metas(
+        suffix.suffix_accounts().as_ref(),
+        suffix.suffix_is_signer().as_ref(),
+        suffix.suffix_is_writable().as_ref(),
+    )
+}
+
+#[inline]
+pub fn withdraw_sol_suffix_account_metas(suffix: impl WithdrawSolSufAccs) -> Vec<AccountMeta> {
+    account_metas(
+        suffix.suffix_accounts().as_ref(),
+        suffix.suffix_is_signer().as_ref(),
+        suffix.suffix_is_writable().as_ref(),
+    )
+}
+
+#[inline]
+pub fn deposit_stake_suffix_account_metas(suffix: impl DepositStakeSufAccs) -> Vec<AccountMeta> {
+    account_metas(
+        suffix.suffix_accounts().as_ref(),
+        suffix.suffix_is_signer().as_ref(),
+        suffix.suffix_is_writable().as_ref(),
+    )
+}
+
+#[inline]
+pub fn withdraw_stake_suffix_account_metas(suffix: impl WithdrawStakeSufAccs) -> Vec<AccountMeta> {
+    account_metas(
+        suffix.suffix_accounts().as_ref(),
+        suffix.suffix_is_signer().as_ref(),
+        suffix.suffix_is_writable().as_ref(),
+    )
+}
+
+macro_rules! impl_to_account_metas {
+    ($($KeysOwned:ty => ($is_signer:expr, $is_writer:expr)),* $(,)?) => {
+        $(
+            impl $KeysOwned {
+                #[inline]
+                pub fn to_account_metas(&self) -> Vec<AccountMeta> {
+                    account_metas(&self.0, &$is_signer.0, &$is_writer.0)
+                }
+            }
+        )*
+    };
+}
+
+impl_to_account_metas!(
+    // prefixes
+    StakeWrappedSolPrefixKeysOwned => (
+        STAKE_WRAPPED_SOL_PREFIX_IS_SIGNER,
+        STAKE_WRAPPED_SOL_PREFIX_IS_WRITER
+    ),
+    WithdrawWrappedSolPrefixKeysOwned => (
+        WITHDRAW_WRAPPED_SOL_PREFIX_IS_SIGNER,
+        WITHDRAW_WRAPPED_SOL_PREFIX_IS_WRITER
+    ),
+    PrefundWithdrawStakePrefixKeysOwned => (
+        PREFUND_WITHDRAW_STAKE_PREFIX_IS_SIGNER,
+        PREFUND_WITHDRAW_STAKE_PREFIX_IS_WRITER
+    ),
+
+    // admin instructions
+    CreateFeeTokenAccountIxKeysOwned => (
+        CREATE_FEE_TOKEN_ACCOUNT_IX_IS_SIGNER,
+        CREATE_FEE_TOKEN_ACCOUNT_IX_IS_WRITER
+    ),
+    CloseFeeTokenAccountIxKeysOwned => (
+        CLOSE_FEE_TOKEN_ACCOUNT_IX_IS_SIGNER,
+        CLOSE_FEE_TOKEN_ACCOUNT_IX_IS_WRITER
+    ),
+    WithdrawFeesIxKeysOwned => (WITHDRAW_FEES_IX_IS_SIGNER, WITHDRAW_FEES_IX_IS_WRITER),
+
+    // marinade delayed unstake instructions
+    MarinadeOrderUnstakeIxKeysOwned => (
+        MARINADE_ORDER_UNSTAKE_IX_IS_SIGNER,
+        MARINADE_ORDER_UNSTAKE_IX_IS_WRITER
+    ),
+    MarinadeClaimIxKeysOwned => (MARINADE_CLAIM_IX_IS_SIGNER, MARINADE_CLAIM_IX_IS_WRITER),
+
+    // suffixes
+    SplDepositSolIxSuffixKeysOwned => (
+        SPL_DEPOSIT_SOL_IX_SUFFIX_IS_SIGNER,
+        SPL_DEPOSIT_SOL_IX_SUFFIX_IS_WRITER
+    ),
+    SplWithdrawSolIxSuffixKeysOwned => (
+        SPL_WITHDRAW_SOL_IX_SUFFIX_IS_SIGNER,
+        SPL_WITHDRAW_SOL_IX_SUFFIX_IS_WRITER
+    ),
+    SplDepositStakeIxSuffixKeysOwned => (
+        SPL_DEPOSIT_STAKE_IX_SUFFIX_IS_SIGNER,
+        SPL_DEPOSIT_STAKE_IX_SUFFIX_IS_WRITER
+    ),
+    SplWithdrawStakeIxSuffixKeysOwned => (
+        SPL_WITHDRAW_STAKE_IX_SUFFIX_IS_SIGNER,
+        SPL_WITHDRAW_STAKE_IX_SUFFIX_IS_WRITER
+    ),
+    MarinadeDepositSolIxSuffixKeysOwned => (
+        MARINADE_DEPOSIT_SOL_IX_SUFFIX_IS_SIGNER,
+        MARINADE_DEPOSIT_SOL_IX_SUFFIX_IS_WRITER
+    ),
+    MarinadeDepositStakeIxSuffixKeysOwned => (
+        MARINADE_DEPOSIT_STAKE_IX_SUFFIX_IS_SIGNER,
+        MARINADE_DEPOSIT_STAKE_IX_SUFFIX_IS_WRITER
+    ),
+    MarinadeWithdrawSolIxSuffixKeysOwned => (
+        MARINADE_WITHDRAW_SOL_IX_SUFFIX_IS_SIGNER,
+        MARINADE_WITHDRAW_SOL_IX_SUFFIX_IS_WRITER
+    ),
+    MarinadeWithdrawStakeIxSuffixKeysOwned => (
+        MARINADE_WITHDRAW_STAKE_IX_SUFFIX_IS_SIGNER,
+        MARINADE_WITHDRAW_STAKE_IX_SUFFIX_IS_WRITER
+    ),
+    LidoWithdrawStakeIxSuffixKeysOwned => (
+        LIDO_WITHDRAW_STAKE_IX_SUFFIX_IS_SIGNER,
+        LIDO_WITHDRAW_STAKE_IX_SUFFIX_IS_WRITER
+    ),
+    ReserveDepositStakeIxSuffixKeysOwned => (
+        RESERVE_DEPOSIT_STAKE_IX_SUFFIX_IS_SIGNER,
+        RESERVE_DEPOSIT_STAKE_IX_SUFFIX_IS_WRITER
+    ),
+);
+
+impl DepositStakeIxKeysOwned {
+    /// Writable flags are selected based on whether `self.out_mint` is wSOL
+    #[inline]
+    pub fn to_account_metas(&self) -> Vec<AccountMeta> {
+        let is_writer = if *self.out_mint() == NATIVE_MINT {
+            DEPOSIT_STAKE_IX_IS_WRITER_WSOL_OUT
+        } else {
+            DEPOSIT_STAKE_IX_IS_WRITER_NON_WSOL_OUT
+        };
+        account_metas(&self.0, &DEPOSIT_STAKE_IX_IS_SIGNER.0, &is_writer.0)
+    }
+}
+
+impl PrefundSwapViaStakePrefixKeysOwned {
+    /// Writable flags are selected based on whether `self.out_mint` is wSOL
+    #[inline]
+    pub fn to_account_metas(&self) -> Vec<AccountMeta> {
+        let is_writer = if *self.out_mint() == NATIVE_MINT {
+            PREFUND_SWAP_VIA_STAKE_PREFIX_IS_WRITER_WSOL_OUT
+        } else {
+            PREFUND_SWAP_VIA_STAKE_PREFIX_IS_WRITER_NON_WSOL_OUT
+        };
+        account_metas(
+            &self.0,
+            &PREFUND_SWAP_VIA_STAKE_PREFIX_IS_SIGNER.0,
+            &is_writer.0,
+        )
+    }
+}
+
+impl SwapViaStakePrefixKeysOwned {
+    /// Writable flags are selected based on whether `self.out_mint` is wSOL
+    #[inline]
+    pub fn to_account_metas(&self) -> Vec<AccountMeta> {
+        let is_writer = if *self.out_mint() == NATIVE_MINT {
+            SWAP_VIA_STAKE_PREFIX_IS_WRITER_WSOL_OUT
+        } else {
+            SWAP_VIA_STAKE_PREFIX_IS_WRITER_NON_WSOL_OUT
+        };
+        account_metas(&self.0, &SWAP_VIA_STAKE_PREFIX_IS_SIGNER.0, &is_writer.0)
+    }
+}
+
+macro_rules! impl_to_instruction {
+    ($($IxData:ty),* $(,)?) => {
+        $(
+            impl $IxData {
+                /// `accounts` should be the full list of the instruction's accounts,
+                /// i.e. prefix followed by suffix(es) if any
+                #[inline]
+                pub fn to_instruction(&self, accounts: Vec<AccountMeta>) -> Instruction {
+                    Instruction {
+                        program_id: Pubkey::new_from_array(SANCTUM_ROUTER_PROGRAM),
+                        accounts,
+                        data: self.to_buf().into(),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_to_instruction!(
+    StakeWrappedSolIxData,
+    WithdrawWrappedSolIxData,
+    DepositStakeIxData,
+    PrefundWithdrawStakeIxData,
+    PrefundSwapViaStakeIxData,
+    SwapViaStakeIxData,
+    CreateFeeTokenAccountIxData,
+    CloseFeeTokenAccountIxData,
+    WithdrawFeesIxData,
+);
+
+macro_rules! impl_marinade_to_instruction {
+    ($($IxData:ty => $KeysOwned:ty),* $(,)?) => {
+        $(
+            impl $IxData {
+                /// Marinade program instruction, not a router program instruction
+                #[inline]
+                pub fn to_instruction(&self, keys: &$KeysOwned) -> Instruction {
+                    Instruction {
+                        program_id: Pubkey::new_from_array(
+                            sanctum_marinade_liquid_staking_core::MARINADE_STAKING_PROGRAM,
+                        ),
+                        accounts: keys.to_account_metas(),
+                        data: self.to_buf().into(),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_marinade_to_instruction!(
+    MarinadeOrderUnstakeIxData => MarinadeOrderUnstakeIxKeysOwned,
+    MarinadeClaimIxData => MarinadeClaimIxKeysOwned,
+);
+
+impl RouterIx {
+    #[inline]
+    pub fn to_account_metas(&self) -> Vec<AccountMeta> {
+        account_metas(self.keys(), self.is_signer(), self.is_writable())
+    }
+
+    #[inline]
+    pub fn to_instruction(&self) -> Instruction {
+        Instruction {
+            program_id: Pubkey::new_from_array(*self.program_address()),
+            accounts: self.to_account_metas(),
+            data: self.data().into(),
+        }
+    }
+}
+
+impl From<&RouterIx> for Instruction {
+    #[inline]
+    fn from(ix: &RouterIx) -> Self {
+        ix.to_instruction()
+    }
+}
+
+impl From<RouterIx> for Instruction {
+    #[inline]
+    fn from(ix: RouterIx) -> Self {
+        ix.to_instruction()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        DepositSolIxSuffixKeysOwned, RouterConfig, RouterInstruction, RouterIxAccounts,
+        SPL_DEPOSIT_SOL_IX_SUFFIX_ACCS_LEN, SPL_STAKE_POOL_PROGRAMS,
+    };
+
+    use super::*;
+
+    /// `[start, start + 1, ...]`, so that every key is distinct
+    fn distinct_keys<const N: usize>(start: u8) -> [[u8; 32]; N] {
+        core::array::from_fn(|i| [start + i as u8; 32])
+    }
+
+    fn unzip_metas(metas: &[AccountMeta]) -> (Vec<[u8; 32]>, Vec<bool>, Vec<bool>) {
+        let keys = metas.iter().map(|m| m.pubkey.to_bytes()).collect();
+        let is_signer = metas.iter().map(|m| m.is_signer).collect();
+        let is_writable = metas.iter().map(|m| m.is_writable).collect();
+        (keys, is_signer, is_writable)
+    }
+
+    #[test]
+    fn account_metas_round_trip() {
+        let keys: [[u8; 32]; 4] = distinct_keys(1);
+        let is_signer = [true, false, true, false];
+        let is_writable = [true, true, false, false];
+
+        let metas = account_metas(&keys, &is_signer, &is_writable);
+        let (rt_keys, rt_is_signer, rt_is_writable) = unzip_metas(&metas);
+        assert_eq!(rt_keys, keys);
+        assert_eq!(rt_is_signer, is_signer);
+        assert_eq!(rt_is_writable, is_writable);
+    }
+
+    #[test]
+    #[should_panic]
+    fn account_metas_len_mismatch_panics() {
+        account_metas(&distinct_keys::<2>(1), &[false], &[false, false]);
+    }
+
+    #[test]
+    fn router_ix_to_instruction_round_trips() {
+        const AMOUNT: u64 = 1_000_000_000;
+
+        let prefix = StakeWrappedSolPrefixKeysOwned::new(distinct_keys(1));
+        let mut suffix_keys = distinct_keys::<SPL_DEPOSIT_SOL_IX_SUFFIX_ACCS_LEN>(100);
+        // suffixes are decoded by their first account, the pool's program
+        suffix_keys[0] = SPL_STAKE_POOL_PROGRAMS[0];
+        let suffix = SplDepositSolIxSuffixKeysOwned::new(suffix_keys);
+        let data = StakeWrappedSolIxData::new(AMOUNT);
+
+        let ix = RouterIx::stake_wrapped_sol_prefix(&prefix, data)
+            .with_accounts(
+                &suffix.0,
+                &SPL_DEPOSIT_SOL_IX_SUFFIX_IS_SIGNER.0,
+                &SPL_DEPOSIT_SOL_IX_SUFFIX_IS_WRITER.0,
+            )
+            .unwrap();
+        let instruction = Instruction::from(&ix);
+
+        assert_eq!(instruction.program_id.to_bytes(), SANCTUM_ROUTER_PROGRAM);
+        assert_eq!(instruction.data, ix.data());
+        let (keys, is_signer, is_writable) = unzip_metas(&instruction.accounts);
+        assert_eq!(keys, ix.keys());
+        assert_eq!(is_signer, ix.is_signer());
+        assert_eq!(is_writable, ix.is_writable());
+
+        // same as building the instruction from the individual types' conversions
+        let mut accounts = prefix.to_account_metas();
+        accounts.extend(suffix.to_account_metas());
+        assert_eq!(instruction, data.to_instruction(accounts));
+
+        // decodes back into what it was built from
+        assert_eq!(
+            RouterInstruction::try_from_buf(&instruction.data).unwrap(),
+            RouterInstruction::StakeWrappedSol { amount: AMOUNT }
+        );
+        let (decoded, remaining) = RouterInstruction::StakeWrappedSol { amount: AMOUNT }
+            .try_split_accounts(&keys, &RouterConfig::MAINNET)
+            .unwrap();
+        assert_eq!(
+            decoded,
+            RouterIxAccounts::StakeWrappedSol {
+                prefix,
+                suffix: DepositSolIxSuffixKeysOwned::Spl(suffix),
+            }
+        );
+        assert!(remaining.is_empty());
+    }
+
+    #[test]
+    fn router_ix_program_address_round_trips() {
+        let program = [9u8; 32];
+        let keys = WithdrawFeesIxKeysOwned::new(distinct_keys(1));
+        let ix = RouterIx::withdraw_fees(&keys, WithdrawFeesIxData::new());
+
+        assert_eq!(
+            Instruction::from(ix.clone()),
+            WithdrawFeesIxData::new().to_instruction(keys.to_account_metas())
+        );
+        assert_eq!(
+            Instruction::from(ix.with_program_address(program))
+                .program_id
+                .to_bytes(),
+            program
+        );
+    }
+
+    #[test]
+    fn wsol_out_prefix_account_metas_match_router_ix() {
+        let mut keys = PrefundSwapViaStakePrefixKeysOwned::default();
+        for out_mint in [NATIVE_MINT, [1u8; 32]] {
+            keys = keys.with_out_mint(out_mint);
+            let ix = RouterIx::prefund_swap_via_stake_prefix(
+                &keys,
+                PrefundSwapViaStakeIxData::new(1, 0),
+            );
+            assert_eq!(keys.to_account_metas(), ix.to_account_metas());
+        }
+    }
+}
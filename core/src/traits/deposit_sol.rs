@@ -1,11 +1,26 @@
 use core::{error::Error, ops::Deref};
 
-use crate::quote::TokenQuote;
+use crate::{quote_exact_out, ExactOutQuoteErr, TokenQuote};
 
 pub trait DepositSolQuoter {
     type Error: Error;
 
     fn quote_deposit_sol(&self, lamports: u64) -> Result<TokenQuote, Self::Error>;
+
+    /// Quotes the minimum lamports required to receive at least `tokens` output tokens.
+    ///
+    /// See [`quote_exact_out`] for details.
+    #[inline]
+    fn quote_deposit_sol_exact_out(
+        &self,
+        tokens: u64,
+    ) -> Result<TokenQuote, ExactOutQuoteErr<Self::Error>> {
+        quote_exact_out(
+            tokens,
+            |lamports| self.quote_deposit_sol(lamports),
+            |q| q.out,
+        )
+    }
 }
 
 /// Blanket for refs
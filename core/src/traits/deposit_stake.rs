@@ -1,6 +1,9 @@
 use core::{error::Error, ops::Deref};
 
-use crate::{ActiveStakeParams, DepositStakeQuote, StakeQuoteError};
+use crate::{
+    quote_exact_out, ActiveStakeParams, DepositStakeQuote, ExactOutQuoteErr, StakeAccountLamports,
+    StakeQuoteError,
+};
 
 pub trait DepositStakeQuoter {
     type Error: Error + StakeQuoteError;
@@ -10,6 +13,29 @@ pub trait DepositStakeQuoter {
         &self,
         stake: ActiveStakeParams,
     ) -> Result<DepositStakeQuote, Self::Error>;
+
+    /// Quotes the minimum actively staked lamports a stake account delegated to `vote`
+    /// with `unstaked` unstaked lamports must have to receive at least `tokens` output tokens.
+    ///
+    /// See [`quote_exact_out`] for details.
+    #[inline]
+    fn quote_deposit_stake_exact_out(
+        &self,
+        vote: &[u8; 32],
+        unstaked: u64,
+        tokens: u64,
+    ) -> Result<DepositStakeQuote, ExactOutQuoteErr<Self::Error>> {
+        quote_exact_out(
+            tokens,
+            |staked| {
+                self.quote_deposit_stake(ActiveStakeParams {
+                    vote: *vote,
+                    lamports: StakeAccountLamports { staked, unstaked },
+                })
+            },
+            |q| q.out,
+        )
+    }
 }
 
 /// Blanket for refs
@@ -1,11 +1,13 @@
 mod deposit_sol;
 mod deposit_stake;
+mod sol_value;
 mod stake_quote_err;
 mod withdraw_sol;
 mod withdraw_stake;
 
 pub use deposit_sol::*;
 pub use deposit_stake::*;
+pub use sol_value::*;
 pub use stake_quote_err::*;
 pub use withdraw_sol::*;
 pub use withdraw_stake::*;
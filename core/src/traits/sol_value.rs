@@ -0,0 +1,45 @@
+use core::ops::Deref;
+
+/// Fee-free conversion between a LST and SOL at the pool's current exchange rate.
+///
+/// Returns `None` on arithmetic overflow or if the pool's exchange rate is invalid.
+pub trait SolValueCalc {
+    /// Lamports that `tokens` LST tokens are worth, rounded down
+    fn sol_value(&self, tokens: u64) -> Option<u64>;
+
+    /// LST tokens that `lamports` SOL is worth, rounded down
+    fn tokens_for_sol(&self, lamports: u64) -> Option<u64>;
+}
+
+/// Blanket for refs
+/// NB: this means we can only implement this trait for internal types
+impl<R, T: SolValueCalc> SolValueCalc for R
+where
+    R: Deref<Target = T>,
+{
+    #[inline]
+    fn sol_value(&self, tokens: u64) -> Option<u64> {
+        self.deref().sol_value(tokens)
+    }
+
+    #[inline]
+    fn tokens_for_sol(&self, lamports: u64) -> Option<u64> {
+        self.deref().tokens_for_sol(lamports)
+    }
+}
+
+/// wSOL is always 1:1 with SOL
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct WsolSolValueCalc;
+
+impl SolValueCalc for WsolSolValueCalc {
+    #[inline]
+    fn sol_value(&self, tokens: u64) -> Option<u64> {
+        Some(tokens)
+    }
+
+    #[inline]
+    fn tokens_for_sol(&self, lamports: u64) -> Option<u64> {
+        Some(lamports)
+    }
+}
@@ -3,7 +3,7 @@ use sanctum_reserve_core::ReserveError;
 use sanctum_spl_stake_pool_core::SplStakePoolError;
 use solido_legacy_core::LidoError;
 
-use crate::PrefundWithdrawStakeQuoteErr;
+use crate::{PrefundSwapViaStakeQuoteErr, PrefundWithdrawStakeQuoteErr, WithdrawStakeQuoteErr};
 
 pub trait StakeQuoteError {
     /// Indicates if this error is specific to the individual
@@ -39,8 +39,10 @@ impl StakeQuoteError for MarinadeError {
         matches!(
             self,
             Self::WrongValidatorAccountOrIndex
-            // WithdrawStake (not yet enabled)
+            // WithdrawStake
             | Self::SelectedStakeAccountHasNotEnoughFunds
+            | Self::StakeAccountRemainderTooLow
+            | Self::StakeAccountIsEmergencyUnstaking
         )
     }
 }
@@ -73,6 +75,31 @@ impl<E: StakeQuoteError> StakeQuoteError for PrefundWithdrawStakeQuoteErr<E> {
         match self {
             Self::Pool(p) => p.is_vote_specific(),
             Self::Reserve(p) => p.is_vote_specific(),
+            // depends on withdrawal amount, not the vote account
+            Self::BelowMinimumDelegation => false,
+        }
+    }
+}
+
+impl<W: StakeQuoteError, D: StakeQuoteError> StakeQuoteError for PrefundSwapViaStakeQuoteErr<W, D> {
+    #[inline]
+    fn is_vote_specific(&self) -> bool {
+        match self {
+            Self::NoMatch => false,
+            Self::Reserve(e) => e.is_vote_specific(),
+            Self::WithdrawStake(e) => e.is_vote_specific(),
+            Self::DepositStake(e) => e.is_vote_specific(),
+            Self::BelowMinimumDelegation => false,
+        }
+    }
+}
+
+impl<E: StakeQuoteError> StakeQuoteError for WithdrawStakeQuoteErr<E> {
+    #[inline]
+    fn is_vote_specific(&self) -> bool {
+        match self {
+            Self::Pool(e) => e.is_vote_specific(),
+            Self::BelowMinimumDelegation => false,
         }
     }
 }
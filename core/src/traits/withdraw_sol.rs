@@ -1,11 +1,26 @@
 use core::{error::Error, ops::Deref};
 
-use crate::quote::TokenQuote;
+use crate::{quote_exact_out, ExactOutQuoteErr, TokenQuote};
 
 pub trait WithdrawSolQuoter {
     type Error: Error;
 
     fn quote_withdraw_sol(&self, tokens: u64) -> Result<TokenQuote, Self::Error>;
+
+    /// Quotes the minimum LST tokens required to receive at least `lamports` SOL.
+    ///
+    /// See [`quote_exact_out`] for details.
+    #[inline]
+    fn quote_withdraw_sol_exact_out(
+        &self,
+        lamports: u64,
+    ) -> Result<TokenQuote, ExactOutQuoteErr<Self::Error>> {
+        quote_exact_out(
+            lamports,
+            |tokens| self.quote_withdraw_sol(tokens),
+            |q| q.out,
+        )
+    }
 }
 
 /// Blanket for refs
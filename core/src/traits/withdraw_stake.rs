@@ -1,10 +1,9 @@
 use core::{error::Error, fmt::Display, ops::Deref};
 
-use sanctum_reserve_core::{FeeEnum, PoolBalance, ReserveError};
-use sanctum_spl_stake_pool_core::STAKE_ACCOUNT_RENT_EXEMPT_LAMPORTS;
+use sanctum_reserve_core::ReserveError;
 
 use crate::{
-    reserves_has_enough_for_slumdog, slumdog_target_lamports, ActiveStakeParams, Prefund,
+    quote_exact_out, ActiveStakeParams, ExactOutQuoteErr, Prefund, PrefundParams,
     StakeAccountLamports, StakeQuoteError, WithdrawStakeQuote,
 };
 
@@ -12,6 +11,10 @@ use crate::{
 pub enum PrefundWithdrawStakeQuoteErr<E> {
     Reserve(ReserveError),
     Pool(E),
+
+    /// The slumdog stake account or the withdrawn stake account
+    /// would be delegated less than the stake program's minimum delegation
+    BelowMinimumDelegation,
 }
 
 impl<E: core::fmt::Debug> Display for PrefundWithdrawStakeQuoteErr<E> {
@@ -23,6 +26,33 @@ impl<E: core::fmt::Debug> Display for PrefundWithdrawStakeQuoteErr<E> {
 
 impl<E: core::fmt::Debug> Error for PrefundWithdrawStakeQuoteErr<E> {}
 
+/// Error of withdraw stake quoters that check the withdrawn stake account
+/// against the stake program's minimum delegation on top of the pool's own checks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum WithdrawStakeQuoteErr<E> {
+    Pool(E),
+
+    /// The withdrawn stake account would be delegated less than
+    /// the stake program's minimum delegation
+    BelowMinimumDelegation,
+}
+
+impl<E: core::fmt::Debug> Display for WithdrawStakeQuoteErr<E> {
+    // Display=Debug, since this is just a simple discriminated str enum
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl<E: core::fmt::Debug> Error for WithdrawStakeQuoteErr<E> {}
+
+impl<E> From<E> for WithdrawStakeQuoteErr<E> {
+    #[inline]
+    fn from(e: E) -> Self {
+        Self::Pool(e)
+    }
+}
+
 pub trait WithdrawStakeQuoter {
     type Error: Error + StakeQuoteError;
 
@@ -36,53 +66,85 @@ pub trait WithdrawStakeQuoter {
         vote: Option<&[u8; 32]>,
     ) -> Result<WithdrawStakeQuote, Self::Error>;
 
+    /// The vote account of the stake this quoter withdraws,
+    /// if it is the same regardless of `tokens`.
+    ///
+    /// Used to identify the rejected candidate when a quote
+    /// errors before the withdrawn stake account is known.
+    #[inline]
+    fn fixed_vote(&self) -> Option<&[u8; 32]> {
+        None
+    }
+
+    /// Quotes the minimum LST tokens required to withdraw a stake account
+    /// with at least `lamports` total lamports.
+    ///
+    /// See [`quote_exact_out`] for details.
+    #[inline]
+    fn quote_withdraw_stake_exact_out(
+        &self,
+        lamports: u64,
+        vote: Option<&[u8; 32]>,
+    ) -> Result<WithdrawStakeQuote, ExactOutQuoteErr<Self::Error>> {
+        quote_exact_out(
+            lamports,
+            |tokens| self.quote_withdraw_stake(tokens, vote),
+            |q| q.out.lamports.total(),
+        )
+    }
+
     /// The default impl here assumes the program does not fund rent-exemption for the
     /// destination stake account that is split to during withdrawal.
     /// (get_withdraw_stake_quote()'s returned quote.out.unstaked = 0)
+    ///
+    /// Errors with [`PrefundWithdrawStakeQuoteErr::BelowMinimumDelegation`] if either
+    /// the slumdog stake account or the withdrawn stake account would be delegated
+    /// less than `prefund.min_delegation`.
     fn quote_prefund_withdraw_stake(
         &self,
         tokens: u64,
         vote: Option<&[u8; 32]>,
-        reserves_balance: &PoolBalance,
-        reserves_fee: &FeeEnum,
+        prefund: &PrefundParams,
     ) -> Result<Prefund<WithdrawStakeQuote>, PrefundWithdrawStakeQuoteErr<Self::Error>> {
         let WithdrawStakeQuote {
             inp,
             out: ActiveStakeParams { vote, lamports },
             fee,
+            stake_to_split,
         } = self
             .quote_withdraw_stake(tokens, vote)
             .map_err(PrefundWithdrawStakeQuoteErr::Pool)?;
-        if !reserves_has_enough_for_slumdog(reserves_balance) {
+        if !prefund.reserves_has_enough_for_slumdog() {
             return Err(PrefundWithdrawStakeQuoteErr::Reserve(
                 ReserveError::NotEnoughLiquidity,
             ));
         }
         // amount of active stake that will be split
         // from the withdrawn stake account to slumdog
-        let prefund_fee = slumdog_target_lamports(reserves_balance, reserves_fee)
-            .ok_or(PrefundWithdrawStakeQuoteErr::Reserve(
-                ReserveError::InternalError,
-            ))?
-            .saturating_sub(STAKE_ACCOUNT_RENT_EXEMPT_LAMPORTS);
+        let prefund_fee = prefund.slumdog_split_lamports()?;
+        // errors if original quote does not give enough
+        // sol to repay prefund flash loan.
+        // TODO: even though this is a math error, it might be more
+        // helpful for consumers to return something like "WithdrawalTooSmall"
+        // instead but that will require adding it to sanctum-reserve-core
+        let staked = lamports.total().checked_sub(prefund_fee).ok_or(
+            PrefundWithdrawStakeQuoteErr::Reserve(ReserveError::InternalError),
+        )?;
+        if staked < prefund.min_delegation {
+            return Err(PrefundWithdrawStakeQuoteErr::BelowMinimumDelegation);
+        }
         Ok(Prefund {
             quote: WithdrawStakeQuote {
                 inp,
                 out: ActiveStakeParams {
                     vote,
                     lamports: StakeAccountLamports {
-                        // return None if original quote does not give enough
-                        // sol to repay prefund flash loan.
-                        // TODO: even though this is a math error, it might be more
-                        // helpful for consumers to return something like "WithdrawalTooSmall"
-                        // instead but that will require adding it to sanctum-reserve-core
-                        staked: lamports.total().checked_sub(prefund_fee).ok_or(
-                            PrefundWithdrawStakeQuoteErr::Reserve(ReserveError::InternalError),
-                        )?,
-                        unstaked: STAKE_ACCOUNT_RENT_EXEMPT_LAMPORTS,
+                        staked,
+                        unstaked: prefund.rent.stake_account_rent_exempt_lamports(),
                     },
                 },
                 fee,
+                stake_to_split,
             },
             prefund_fee,
         })
@@ -105,6 +167,11 @@ where
     ) -> Result<WithdrawStakeQuote, Self::Error> {
         self.deref().quote_withdraw_stake(tokens, vote)
     }
+
+    #[inline]
+    fn fixed_vote(&self) -> Option<&[u8; 32]> {
+        self.deref().fixed_vote()
+    }
 }
 
 pub trait WithdrawStakeSufAccs {
@@ -0,0 +1,13 @@
+/// The kind of swap a pool's router is being updated for,
+/// which determines the accounts it needs fetched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PoolUpdateType {
+    DepositSol,
+    DepositStake,
+    WithdrawSol,
+    WithdrawStake,
+
+    /// Marinade OrderUnstake and Claim,
+    /// which additionally need the current epoch
+    DelayedUnstake,
+}
@@ -0,0 +1,129 @@
+use std::iter::once;
+
+use jupiter_amm_interface::{AccountMap, KeyedAccount, Quote, QuoteParams, SwapMode};
+use rust_decimal::Decimal;
+use sanctum_marinade_liquid_staking_core::{MSOL_MINT_ADDR, STATE_PUBKEY};
+use sanctum_router::{
+    AccountRef, AccountSource, InitData, InitMint, SanctumRouterError, SplInitData,
+};
+use sanctum_router_core::{RouterConfig, RouterIx, NATIVE_MINT};
+use sanctum_spl_stake_pool_core::StakePool;
+use solana_sdk::{instruction::AccountMeta, pubkey::Pubkey};
+use solido_legacy_core::{LIDO_STATE_ADDR, STSOL_MINT_ADDR};
+
+pub const LABEL: &str = "Sanctum Router";
+
+/// Newtype to use jupiter's [`AccountMap`] as an [`AccountSource`]
+#[derive(Debug, Clone, Copy)]
+pub struct JupAccountMap<'a>(pub &'a AccountMap);
+
+impl AccountSource for JupAccountMap<'_> {
+    #[inline]
+    fn account(&self, pubkey: &[u8; 32]) -> Option<AccountRef<'_>> {
+        self.0
+            .get(&Pubkey::new_from_array(*pubkey))
+            .map(|acc| AccountRef {
+                owner: pubkey_bytes(&acc.owner),
+                data: &acc.data,
+                lamports: acc.lamports,
+            })
+    }
+}
+
+#[inline]
+fn pubkey_bytes(pk: &Pubkey) -> &[u8; 32] {
+    // unwrap-safety: Pubkey is always 32 bytes
+    pk.as_ref().try_into().unwrap()
+}
+
+/// Determines the LST mint and the data required to init its router from
+/// the pool's main account:
+/// - marinade: state
+/// - lido: state
+/// - reserve: `config.reserve.pool`, mint is wSOL
+/// - spl: stake pool
+pub fn init_mint_from_keyed_account(
+    KeyedAccount { key, account, .. }: &KeyedAccount,
+    config: &RouterConfig,
+) -> anyhow::Result<InitMint> {
+    let key = key.to_bytes();
+    if key == config.reserve.pool {
+        return Ok(InitMint {
+            mint: NATIVE_MINT,
+            init: None,
+        });
+    }
+    Ok(match key {
+        STATE_PUBKEY => InitMint {
+            mint: MSOL_MINT_ADDR,
+            init: None,
+        },
+        LIDO_STATE_ADDR => InitMint {
+            mint: STSOL_MINT_ADDR,
+            init: None,
+        },
+        stake_pool_addr => {
+            let stake_pool = StakePool::borsh_de(account.data.as_slice())
+                .map_err(|_e| SanctumRouterError::InvalidData)?;
+            InitMint {
+                mint: stake_pool.pool_mint,
+                init: Some(InitData::Spl(SplInitData {
+                    stake_pool_addr,
+                    stake_pool_program_addr: account.owner.to_bytes(),
+                    validator_list_addr: stake_pool.validator_list,
+                    reserve_stake_addr: stake_pool.reserve_stake,
+                })),
+            }
+        }
+    })
+}
+
+/// Errors if `quote_params` is not an ExactIn quote from `inp_mint` to `out_mint`
+pub fn check_quote_params(
+    QuoteParams {
+        input_mint,
+        output_mint,
+        swap_mode,
+        ..
+    }: &QuoteParams,
+    inp_mint: &[u8; 32],
+    out_mint: &[u8; 32],
+) -> anyhow::Result<()> {
+    if matches!(swap_mode, SwapMode::ExactOut) {
+        anyhow::bail!("ExactOut not supported");
+    }
+    if input_mint.to_bytes() != *inp_mint || output_mint.to_bytes() != *out_mint {
+        anyhow::bail!("Unsupported mint pair {input_mint} -> {output_mint}");
+    }
+    Ok(())
+}
+
+/// `fee` and `router_fee` are in terms of output tokens
+pub fn jup_quote(inp: u64, out: u64, fee: u64, router_fee: u64, out_mint: &[u8; 32]) -> Quote {
+    let fee_amount = fee + router_fee;
+    let bef_fees = out + fee_amount;
+    let fee_pct = if bef_fees == 0 {
+        Decimal::ZERO
+    } else {
+        Decimal::from(fee_amount) / Decimal::from(bef_fees)
+    };
+    Quote {
+        in_amount: inp,
+        out_amount: out,
+        fee_amount,
+        fee_mint: Pubkey::new_from_array(*out_mint),
+        fee_pct,
+        ..Default::default()
+    }
+}
+
+/// Jupiter expects the router program as the first account,
+/// followed by the instruction's accounts
+pub fn router_ix_account_metas(ix: &RouterIx) -> Vec<AccountMeta> {
+    once(AccountMeta::new_readonly(
+        Pubkey::new_from_array(*ix.program_address()),
+        false,
+    ))
+    .chain(ix.to_account_metas())
+    .collect()
+}
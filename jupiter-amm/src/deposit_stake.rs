@@ -0,0 +1,238 @@
+use anyhow::anyhow;
+use jupiter_amm_interface::{
+    AccountMap, Amm, AmmContext, KeyedAccount, Quote, QuoteParams, Swap, SwapAndAccountMetas,
+    SwapMode, SwapParams,
+};
+use sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR;
+use sanctum_router::{find_fee_token_account_pda, SanctumRouter, SanctumRouterError, SwapMints};
+use sanctum_router_core::{
+    ActiveStakeParams, DepositStakeIxAccsBuilder, DepositStakeIxData, DepositStakeQuote,
+    DepositStakeQuoter, RouterConfig, RouterIx, StakeAccountLamports, WithRouterFee, NATIVE_MINT,
+};
+use sanctum_spl_stake_pool_core::STAKE_ACCOUNT_RENT_EXEMPT_LAMPORTS;
+use solana_sdk::pubkey::Pubkey;
+use solido_legacy_core::STSOL_MINT_ADDR;
+
+use crate::{
+    init_mint_from_keyed_account, jup_quote, router_ix_account_metas, JupAccountMap, LABEL,
+};
+
+/// Active stake account -> LST via the pool's DepositStake.
+///
+/// Since the input is a stake account and not a token,
+/// the input "mint" is the vote account the stake account is delegated to:
+/// - [`QuoteParams::input_mint`] / [`SwapParams::source_mint`] is the vote account
+/// - [`QuoteParams::amount`] is the total lamports of the stake account,
+///   of which [`STAKE_ACCOUNT_RENT_EXEMPT_LAMPORTS`] is assumed to be unstaked
+/// - [`SwapParams::source_token_account`] is the stake account to deposit
+/// - [`SwapParams::token_transfer_authority`] is the stake account's withdraw authority
+#[derive(Debug, Clone)]
+pub struct DepositStakeAmm {
+    router: SanctumRouter,
+
+    /// The pool's main account this was created from
+    key: [u8; 32],
+
+    out_mint: [u8; 32],
+}
+
+impl DepositStakeAmm {
+    /// `keyed_account` is the marinade state, reserve pool or a spl stake pool
+    ///
+    /// [`Amm::from_keyed_account`] for a router deployment other than
+    /// [`RouterConfig::MAINNET`]
+    pub fn from_keyed_account_with_config(
+        keyed_account: &KeyedAccount,
+        config: RouterConfig,
+    ) -> anyhow::Result<Self> {
+        let init_mint = init_mint_from_keyed_account(keyed_account, &config)?;
+        if init_mint.mint == STSOL_MINT_ADDR {
+            return Err(anyhow!(
+                "{} does not support DepositStake",
+                keyed_account.key
+            ));
+        }
+        let mut router = SanctumRouter::new(config);
+        router.init([init_mint])?;
+        Ok(Self {
+            router,
+            key: keyed_account.key.to_bytes(),
+            out_mint: init_mint.mint,
+        })
+    }
+
+    #[inline]
+    pub const fn out_mint(&self) -> &[u8; 32] {
+        &self.out_mint
+    }
+
+    #[inline]
+    const fn swap_mints(&self) -> [SwapMints; 1] {
+        [SwapMints::DepositStake { out: self.out_mint }]
+    }
+
+    pub fn quote_deposit_stake(
+        &self,
+        stake: ActiveStakeParams,
+    ) -> Result<WithRouterFee<DepositStakeQuote>, SanctumRouterError> {
+        Ok(match self.out_mint {
+            NATIVE_MINT => WithRouterFee::zero(
+                self.router
+                    .reserve_router
+                    .deposit_stake_quoter()?
+                    .quote_deposit_stake(stake)?,
+            ),
+            MSOL_MINT_ADDR => self
+                .router
+                .marinade_router
+                .deposit_stake_quoter()?
+                .quote_deposit_stake(stake)?
+                .with_router_fee_of(&self.router.config.fees.deposit_stake),
+            mint => self
+                .router
+                .try_find_spl_by_mint(&mint)?
+                .deposit_stake_quoter(self.router.try_curr_epoch()?)?
+                .quote_deposit_stake(stake)?
+                .with_router_fee_of(&self.router.config.fees.deposit_stake),
+        })
+    }
+
+    pub fn deposit_stake_ix(
+        &self,
+        swap_params: &SwapParams,
+    ) -> Result<RouterIx, SanctumRouterError> {
+        let vote = swap_params.source_mint.to_bytes();
+        let stake_account = swap_params.source_token_account.to_bytes();
+        let prefix = DepositStakeIxAccsBuilder::start()
+            .with_user(swap_params.token_transfer_authority.to_bytes())
+            .with_out_token(swap_params.destination_token_account.to_bytes())
+            .with_out_fee_token(
+                find_fee_token_account_pda(&self.router.config, &self.out_mint)
+                    .ok_or(SanctumRouterError::InvalidPda)?
+                    .0,
+            )
+            .with_out_mint(self.out_mint)
+            .with_inp_stake(stake_account)
+            .build();
+        let data = DepositStakeIxData::new();
+        let ix = match self.out_mint {
+            NATIVE_MINT => RouterIx::deposit_stake(
+                &prefix,
+                data,
+                self.router
+                    .reserve_router
+                    .deposit_stake_suf_accs(&self.router.config, &stake_account)?,
+            ),
+            MSOL_MINT_ADDR => RouterIx::deposit_stake(
+                &prefix,
+                data,
+                self.router.marinade_router.deposit_stake_suf_accs(&vote)?,
+            ),
+            mint => RouterIx::deposit_stake(
+                &prefix,
+                data,
+                self.router
+                    .try_find_spl_by_mint(&mint)?
+                    .deposit_stake_suf_accs(&vote)?,
+            ),
+        }
+        .ok_or(SanctumRouterError::TooManyAccounts)?;
+        Ok(ix.with_program_address(self.router.config.router_program))
+    }
+}
+
+impl Amm for DepositStakeAmm {
+    /// `keyed_account` is the marinade state, reserve pool or a spl stake pool
+    fn from_keyed_account(
+        keyed_account: &KeyedAccount,
+        _amm_context: &AmmContext,
+    ) -> anyhow::Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::from_keyed_account_with_config(keyed_account, RouterConfig::MAINNET)
+    }
+
+    fn label(&self) -> String {
+        LABEL.to_owned()
+    }
+
+    fn program_id(&self) -> Pubkey {
+        Pubkey::new_from_array(self.router.config.router_program)
+    }
+
+    fn key(&self) -> Pubkey {
+        Pubkey::new_from_array(self.key)
+    }
+
+    /// Input is a stake account, so only the output mint is returned
+    fn get_reserve_mints(&self) -> Vec<Pubkey> {
+        vec![Pubkey::new_from_array(self.out_mint)]
+    }
+
+    fn get_accounts_to_update(&self) -> Vec<Pubkey> {
+        self.router
+            .accounts_to_update(self.swap_mints())
+            .unwrap_or_default()
+            .into_iter()
+            .map(Pubkey::new_from_array)
+            .collect()
+    }
+
+    fn update(&mut self, account_map: &AccountMap) -> anyhow::Result<()> {
+        let swap_mints = self.swap_mints();
+        Ok(self
+            .router
+            .update(swap_mints, &JupAccountMap(account_map))?)
+    }
+
+    fn quote(
+        &self,
+        QuoteParams {
+            amount,
+            input_mint,
+            output_mint,
+            swap_mode,
+            ..
+        }: &QuoteParams,
+    ) -> anyhow::Result<Quote> {
+        if matches!(swap_mode, SwapMode::ExactOut) {
+            return Err(anyhow!("ExactOut not supported"));
+        }
+        if output_mint.to_bytes() != self.out_mint {
+            return Err(anyhow!("Unsupported output mint {output_mint}"));
+        }
+        let staked = amount
+            .checked_sub(STAKE_ACCOUNT_RENT_EXEMPT_LAMPORTS)
+            .ok_or_else(|| anyhow!("{amount} lamports is below stake account rent-exemption"))?;
+        let WithRouterFee {
+            quote: DepositStakeQuote { out, fee, .. },
+            router_fee,
+        } = self.quote_deposit_stake(ActiveStakeParams {
+            vote: input_mint.to_bytes(),
+            lamports: StakeAccountLamports {
+                staked,
+                unstaked: STAKE_ACCOUNT_RENT_EXEMPT_LAMPORTS,
+            },
+        })?;
+        Ok(jup_quote(*amount, out, fee, router_fee, &self.out_mint))
+    }
+
+    fn get_swap_and_account_metas(
+        &self,
+        swap_params: &SwapParams,
+    ) -> anyhow::Result<SwapAndAccountMetas> {
+        Ok(SwapAndAccountMetas {
+            swap: Swap::StakeDexDepositStake,
+            account_metas: router_ix_account_metas(&self.deposit_stake_ix(swap_params)?),
+        })
+    }
+
+    fn clone_amm(&self) -> Box<dyn Amm + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn unidirectional(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,17 @@
+// Implementation notes:
+//
+// - Each route kind is a separate `Amm` that owns its own `SanctumRouter`
+//   initialized only for the mints involved, so that `get_accounts_to_update()`
+//   only returns the accounts required for that route.
+
+mod common;
+mod deposit_stake;
+mod prefund_swap_via_stake;
+mod stake_wrapped_sol;
+mod withdraw_wrapped_sol;
+
+pub use common::*;
+pub use deposit_stake::*;
+pub use prefund_swap_via_stake::*;
+pub use stake_wrapped_sol::*;
+pub use withdraw_wrapped_sol::*;
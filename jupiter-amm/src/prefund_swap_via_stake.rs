@@ -0,0 +1,310 @@
+use anyhow::anyhow;
+use jupiter_amm_interface::{
+    AccountMap, Amm, AmmContext, KeyedAccount, Quote, QuoteParams, Swap, SwapAndAccountMetas,
+    SwapParams,
+};
+use sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR;
+use sanctum_router::{
+    create_slumdog_stake_addr, find_bridge_stake_acc_pda, find_fee_token_account_pda,
+    find_reserve_stake_account_record_pda, SanctumRouter, SanctumRouterError, SwapMints,
+};
+use sanctum_router_core::{
+    quote_prefund_swap_via_stake, DepositStakeQuote, Prefund, PrefundSwapViaStakeIxData,
+    PrefundSwapViaStakePrefixAccsBuilder, RouterConfig, RouterIx, SplWithdrawStakeValQuoter,
+    WithRouterFee, WithdrawStakeQuote, NATIVE_MINT, STAKE_PROGRAM, SYSTEM_PROGRAM, SYSVAR_CLOCK,
+};
+use solana_sdk::pubkey::Pubkey;
+use solido_legacy_core::STSOL_MINT_ADDR;
+
+use crate::{
+    check_quote_params, init_mint_from_keyed_account, jup_quote, router_ix_account_metas,
+    JupAccountMap, LABEL,
+};
+
+/// LST -> LST (or wSOL via the reserve pool) via PrefundSwapViaStake:
+/// WithdrawStake from the input pool, DepositStake into the output pool
+#[derive(Debug, Clone)]
+pub struct PrefundSwapViaStakeAmm {
+    router: SanctumRouter,
+
+    /// The input pool's main account this was created from
+    key: [u8; 32],
+
+    inp_mint: [u8; 32],
+
+    out_mint: [u8; 32],
+}
+
+impl PrefundSwapViaStakeAmm {
+    /// - `inp` is the lido state or a spl stake pool
+    /// - `out` is the marinade state, reserve pool or a spl stake pool
+    pub fn from_keyed_accounts(inp: &KeyedAccount, out: &KeyedAccount) -> anyhow::Result<Self> {
+        Self::from_keyed_accounts_with_config(inp, out, RouterConfig::MAINNET)
+    }
+
+    /// [`Self::from_keyed_accounts`] for a router deployment other than
+    /// [`RouterConfig::MAINNET`]
+    pub fn from_keyed_accounts_with_config(
+        inp: &KeyedAccount,
+        out: &KeyedAccount,
+        config: RouterConfig,
+    ) -> anyhow::Result<Self> {
+        let inp_init = init_mint_from_keyed_account(inp, &config)?;
+        if matches!(inp_init.mint, NATIVE_MINT | MSOL_MINT_ADDR) {
+            return Err(anyhow!("{} does not support WithdrawStake", inp.key));
+        }
+        let out_init = init_mint_from_keyed_account(out, &config)?;
+        if out_init.mint == STSOL_MINT_ADDR {
+            return Err(anyhow!("{} does not support DepositStake", out.key));
+        }
+        if inp_init.mint == out_init.mint {
+            return Err(anyhow!("inp and out pools are the same"));
+        }
+        let mut router = SanctumRouter::new(config);
+        router.init([inp_init, out_init])?;
+        Ok(Self {
+            router,
+            key: inp.key.to_bytes(),
+            inp_mint: inp_init.mint,
+            out_mint: out_init.mint,
+        })
+    }
+
+    #[inline]
+    pub const fn inp_mint(&self) -> &[u8; 32] {
+        &self.inp_mint
+    }
+
+    #[inline]
+    pub const fn out_mint(&self) -> &[u8; 32] {
+        &self.out_mint
+    }
+
+    #[inline]
+    const fn swap_mints(&self) -> [SwapMints; 1] {
+        [SwapMints::PrefundSwapViaStake {
+            inp: self.inp_mint,
+            out: self.out_mint,
+        }]
+    }
+
+    // TODO: same N^2 concern as the wasm sdk's quote_prefund_swap_via_stake_inner()
+    pub fn quote_prefund_swap_via_stake(
+        &self,
+        tokens: u64,
+    ) -> anyhow::Result<(Prefund<WithdrawStakeQuote>, DepositStakeQuote)> {
+        let this = &self.router;
+        let prefund = this.prefund_params()?;
+
+        macro_rules! match_deposit_stake {
+            ($w_itr:expr) => {
+                match self.out_mint {
+                    NATIVE_MINT => {
+                        let d = this
+                            .reserve_router
+                            .deposit_stake_quoter()?
+                            .after_prefund(&prefund.rent)
+                            .map_err(SanctumRouterError::from)?;
+                        quote_prefund_swap_via_stake($w_itr, d, tokens, &prefund)?
+                    }
+                    MSOL_MINT_ADDR => {
+                        let d = this.marinade_router.deposit_stake_quoter()?;
+                        quote_prefund_swap_via_stake($w_itr, d, tokens, &prefund)?
+                    }
+                    out => {
+                        let d = this
+                            .try_find_spl_by_mint(&out)?
+                            .deposit_stake_quoter(this.try_curr_epoch()?)?;
+                        quote_prefund_swap_via_stake($w_itr, d, tokens, &prefund)?
+                    }
+                }
+            };
+        }
+
+        Ok(match self.inp_mint {
+            STSOL_MINT_ADDR => {
+                let w_itr = std::iter::once(
+                    this.lido_router
+                        .withdraw_stake_quoter(this.try_curr_epoch()?)?
+                        .with_min_delegation(this.config.stake_min_delegation),
+                );
+                match_deposit_stake!(w_itr)
+            }
+            inp => {
+                let router = this.try_find_spl_by_mint(&inp)?;
+                let w_itr = SplWithdrawStakeValQuoter::all(
+                    router.try_stake_pool()?,
+                    router.try_validator_list()?,
+                    this.try_curr_epoch()?,
+                )
+                .map_err(SanctumRouterError::from)?
+                .map(|w| w.with_min_delegation(this.config.stake_min_delegation));
+                match_deposit_stake!(w_itr)
+            }
+        })
+    }
+
+    pub fn prefund_swap_via_stake_ix(
+        &self,
+        swap_params: &SwapParams,
+        bridge_stake_seed: u32,
+    ) -> anyhow::Result<RouterIx> {
+        let config = &self.router.config;
+        let user = swap_params.token_transfer_authority.to_bytes();
+        let (bridge_stake, _bump) = find_bridge_stake_acc_pda(config, &user, bridge_stake_seed)
+            .ok_or(SanctumRouterError::InvalidPda)?;
+        let slumdog_stake = create_slumdog_stake_addr(&bridge_stake);
+        let (slumdog_stake_acc_record, _bump) =
+            find_reserve_stake_account_record_pda(config, &slumdog_stake)
+                .ok_or(SanctumRouterError::InvalidPda)?;
+        let prefix = PrefundSwapViaStakePrefixAccsBuilder::start()
+            .with_user(user)
+            .with_inp_token(swap_params.source_token_account.to_bytes())
+            .with_out_token(swap_params.destination_token_account.to_bytes())
+            .with_out_fee_token(
+                find_fee_token_account_pda(config, &self.out_mint)
+                    .ok_or(SanctumRouterError::InvalidPda)?
+                    .0,
+            )
+            .with_inp_mint(self.inp_mint)
+            .with_out_mint(self.out_mint)
+            .with_prefunder(config.prefunder)
+            .with_bridge_stake(bridge_stake)
+            .with_slumdog_stake(slumdog_stake)
+            .with_slumdog_stake_acc_record(slumdog_stake_acc_record)
+            .with_unstake_program(config.reserve.program)
+            .with_unstake_pool(config.reserve.pool)
+            .with_unstake_fee(config.reserve.fee)
+            .with_unstake_pool_sol_reserves(config.reserve.pool_sol_reserves)
+            .with_unstake_protocol_fee(config.reserve.protocol_fee)
+            .with_unstake_protocol_fee_dest(config.reserve.protocol_fee_vault)
+            .with_clock(SYSVAR_CLOCK)
+            .with_stake_program(STAKE_PROGRAM)
+            .with_system_program(SYSTEM_PROGRAM)
+            .build();
+
+        let (_wsq, dsq) = self.quote_prefund_swap_via_stake(swap_params.in_amount)?;
+        let vote = dsq.inp.vote;
+
+        let ix = RouterIx::prefund_swap_via_stake_prefix(
+            &prefix,
+            PrefundSwapViaStakeIxData::new(swap_params.in_amount, bridge_stake_seed),
+        )
+        .with_program_address(config.router_program);
+        let ix = match self.inp_mint {
+            STSOL_MINT_ADDR => {
+                ix.with_withdraw_stake_suffix(self.router.lido_router.withdraw_stake_suf_accs()?)
+            }
+            inp => ix.with_withdraw_stake_suffix(
+                self.router
+                    .try_find_spl_by_mint(&inp)?
+                    .withdraw_stake_suf_accs(&vote)?,
+            ),
+        }
+        .ok_or(SanctumRouterError::TooManyAccounts)?;
+        let ix = match self.out_mint {
+            NATIVE_MINT => ix.with_deposit_stake_suffix(
+                self.router
+                    .reserve_router
+                    .deposit_stake_suf_accs(config, &bridge_stake)?,
+            ),
+            MSOL_MINT_ADDR => ix.with_deposit_stake_suffix(
+                self.router.marinade_router.deposit_stake_suf_accs(&vote)?,
+            ),
+            out => ix.with_deposit_stake_suffix(
+                self.router
+                    .try_find_spl_by_mint(&out)?
+                    .deposit_stake_suf_accs(&vote)?,
+            ),
+        }
+        .ok_or(SanctumRouterError::TooManyAccounts)?;
+        Ok(ix)
+    }
+}
+
+impl Amm for PrefundSwapViaStakeAmm {
+    /// Always errors since this route requires 2 pools,
+    /// use [`PrefundSwapViaStakeAmm::from_keyed_accounts`] instead
+    fn from_keyed_account(
+        keyed_account: &KeyedAccount,
+        _amm_context: &AmmContext,
+    ) -> anyhow::Result<Self>
+    where
+        Self: Sized,
+    {
+        Err(anyhow!(
+            "PrefundSwapViaStakeAmm requires both inp and out pools, use from_keyed_accounts(). Got {}",
+            keyed_account.key
+        ))
+    }
+
+    fn label(&self) -> String {
+        LABEL.to_owned()
+    }
+
+    fn program_id(&self) -> Pubkey {
+        Pubkey::new_from_array(self.router.config.router_program)
+    }
+
+    fn key(&self) -> Pubkey {
+        Pubkey::new_from_array(self.key)
+    }
+
+    fn get_reserve_mints(&self) -> Vec<Pubkey> {
+        [self.inp_mint, self.out_mint]
+            .map(Pubkey::new_from_array)
+            .into()
+    }
+
+    fn get_accounts_to_update(&self) -> Vec<Pubkey> {
+        self.router
+            .accounts_to_update(self.swap_mints())
+            .unwrap_or_default()
+            .into_iter()
+            .map(Pubkey::new_from_array)
+            .collect()
+    }
+
+    fn update(&mut self, account_map: &AccountMap) -> anyhow::Result<()> {
+        let swap_mints = self.swap_mints();
+        Ok(self
+            .router
+            .update(swap_mints, &JupAccountMap(account_map))?)
+    }
+
+    fn quote(&self, quote_params: &QuoteParams) -> anyhow::Result<Quote> {
+        check_quote_params(quote_params, &self.inp_mint, &self.out_mint)?;
+        let (Prefund { quote: wsq, .. }, dsq) =
+            self.quote_prefund_swap_via_stake(quote_params.amount)?;
+        let WithRouterFee {
+            quote: DepositStakeQuote { out, fee, .. },
+            router_fee,
+        } = if self.out_mint != NATIVE_MINT {
+            dsq.with_router_fee_of(&self.router.config.fees.prefund_swap_via_stake)
+        } else {
+            WithRouterFee::zero(dsq)
+        };
+        Ok(jup_quote(wsq.inp, out, fee, router_fee, &self.out_mint))
+    }
+
+    fn get_swap_and_account_metas(
+        &self,
+        swap_params: &SwapParams,
+    ) -> anyhow::Result<SwapAndAccountMetas> {
+        let bridge_stake_seed = rand::random();
+        Ok(SwapAndAccountMetas {
+            swap: Swap::StakeDexPrefundWithdrawStakeAndDepositStake { bridge_stake_seed },
+            account_metas: router_ix_account_metas(
+                &self.prefund_swap_via_stake_ix(swap_params, bridge_stake_seed)?,
+            ),
+        })
+    }
+
+    fn clone_amm(&self) -> Box<dyn Amm + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn unidirectional(&self) -> bool {
+        true
+    }
+}
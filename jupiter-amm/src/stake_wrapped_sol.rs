@@ -0,0 +1,189 @@
+use anyhow::anyhow;
+use jupiter_amm_interface::{
+    AccountMap, Amm, AmmContext, KeyedAccount, Quote, QuoteParams, Swap, SwapAndAccountMetas,
+    SwapParams,
+};
+use sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR;
+use sanctum_router::{find_fee_token_account_pda, SanctumRouter, SanctumRouterError, SwapMints};
+use sanctum_router_core::{
+    DepositSolQuoter, RouterConfig, RouterIx, StakeWrappedSolIxData,
+    StakeWrappedSolPrefixKeysOwned, TokenQuote, WithRouterFee, NATIVE_MINT, TOKEN_PROGRAM,
+};
+use solana_sdk::pubkey::Pubkey;
+use solido_legacy_core::STSOL_MINT_ADDR;
+
+use crate::{
+    check_quote_params, init_mint_from_keyed_account, jup_quote, router_ix_account_metas,
+    JupAccountMap, LABEL,
+};
+
+/// wSOL -> LST via the pool's DepositSol
+#[derive(Debug, Clone)]
+pub struct StakeWrappedSolAmm {
+    router: SanctumRouter,
+
+    /// The pool's main account this was created from
+    key: [u8; 32],
+
+    out_mint: [u8; 32],
+}
+
+impl StakeWrappedSolAmm {
+    /// `keyed_account` is the marinade state or a spl stake pool
+    ///
+    /// [`Amm::from_keyed_account`] for a router deployment other than
+    /// [`RouterConfig::MAINNET`]
+    pub fn from_keyed_account_with_config(
+        keyed_account: &KeyedAccount,
+        config: RouterConfig,
+    ) -> anyhow::Result<Self> {
+        let init_mint = init_mint_from_keyed_account(keyed_account, &config)?;
+        if matches!(init_mint.mint, NATIVE_MINT | STSOL_MINT_ADDR) {
+            return Err(anyhow!("{} does not support DepositSol", keyed_account.key));
+        }
+        let mut router = SanctumRouter::new(config);
+        router.init([init_mint])?;
+        Ok(Self {
+            router,
+            key: keyed_account.key.to_bytes(),
+            out_mint: init_mint.mint,
+        })
+    }
+
+    #[inline]
+    pub const fn out_mint(&self) -> &[u8; 32] {
+        &self.out_mint
+    }
+
+    #[inline]
+    const fn swap_mints(&self) -> [SwapMints; 1] {
+        [SwapMints::DepositSol { out: self.out_mint }]
+    }
+
+    pub fn quote_stake_wrapped_sol(
+        &self,
+        lamports: u64,
+    ) -> Result<WithRouterFee<TokenQuote>, SanctumRouterError> {
+        let q = match self.out_mint {
+            MSOL_MINT_ADDR => self
+                .router
+                .marinade_router
+                .deposit_sol_quoter()?
+                .quote_deposit_sol(lamports)?,
+            mint => self
+                .router
+                .try_find_spl_by_mint(&mint)?
+                .deposit_sol_quoter(self.router.try_curr_epoch()?)?
+                .quote_deposit_sol(lamports)?,
+        };
+        Ok(WithRouterFee::zero(q))
+    }
+
+    pub fn stake_wrapped_sol_ix(
+        &self,
+        swap_params: &SwapParams,
+    ) -> Result<RouterIx, SanctumRouterError> {
+        let prefix = StakeWrappedSolPrefixKeysOwned::default()
+            .with_config_consts(&self.router.config)
+            .with_user(swap_params.token_transfer_authority.to_bytes())
+            .with_wsol_mint(NATIVE_MINT)
+            .with_out_mint(self.out_mint)
+            .with_inp_wsol(swap_params.source_token_account.to_bytes())
+            .with_out_token(swap_params.destination_token_account.to_bytes())
+            .with_token_program(TOKEN_PROGRAM)
+            .with_out_fee_token(
+                find_fee_token_account_pda(&self.router.config, &self.out_mint)
+                    .ok_or(SanctumRouterError::InvalidPda)?
+                    .0,
+            );
+        let data = StakeWrappedSolIxData::new(swap_params.in_amount);
+        let ix = match self.out_mint {
+            MSOL_MINT_ADDR => RouterIx::stake_wrapped_sol(
+                &prefix,
+                data,
+                self.router.marinade_router.deposit_sol_suf_accs()?,
+            ),
+            mint => RouterIx::stake_wrapped_sol(
+                &prefix,
+                data,
+                self.router.try_find_spl_by_mint(&mint)?.sol_suf_accs()?,
+            ),
+        }
+        .ok_or(SanctumRouterError::TooManyAccounts)?;
+        Ok(ix.with_program_address(self.router.config.router_program))
+    }
+}
+
+impl Amm for StakeWrappedSolAmm {
+    /// `keyed_account` is the marinade state or a spl stake pool
+    fn from_keyed_account(
+        keyed_account: &KeyedAccount,
+        _amm_context: &AmmContext,
+    ) -> anyhow::Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::from_keyed_account_with_config(keyed_account, RouterConfig::MAINNET)
+    }
+
+    fn label(&self) -> String {
+        LABEL.to_owned()
+    }
+
+    fn program_id(&self) -> Pubkey {
+        Pubkey::new_from_array(self.router.config.router_program)
+    }
+
+    fn key(&self) -> Pubkey {
+        Pubkey::new_from_array(self.key)
+    }
+
+    fn get_reserve_mints(&self) -> Vec<Pubkey> {
+        [NATIVE_MINT, self.out_mint]
+            .map(Pubkey::new_from_array)
+            .into()
+    }
+
+    fn get_accounts_to_update(&self) -> Vec<Pubkey> {
+        self.router
+            .accounts_to_update(self.swap_mints())
+            .unwrap_or_default()
+            .into_iter()
+            .map(Pubkey::new_from_array)
+            .collect()
+    }
+
+    fn update(&mut self, account_map: &AccountMap) -> anyhow::Result<()> {
+        let swap_mints = self.swap_mints();
+        Ok(self
+            .router
+            .update(swap_mints, &JupAccountMap(account_map))?)
+    }
+
+    fn quote(&self, quote_params: &QuoteParams) -> anyhow::Result<Quote> {
+        check_quote_params(quote_params, &NATIVE_MINT, &self.out_mint)?;
+        let WithRouterFee {
+            quote: TokenQuote { inp, out, fee },
+            router_fee,
+        } = self.quote_stake_wrapped_sol(quote_params.amount)?;
+        Ok(jup_quote(inp, out, fee, router_fee, &self.out_mint))
+    }
+
+    fn get_swap_and_account_metas(
+        &self,
+        swap_params: &SwapParams,
+    ) -> anyhow::Result<SwapAndAccountMetas> {
+        Ok(SwapAndAccountMetas {
+            swap: Swap::StakeDexStakeWrappedSol,
+            account_metas: router_ix_account_metas(&self.stake_wrapped_sol_ix(swap_params)?),
+        })
+    }
+
+    fn clone_amm(&self) -> Box<dyn Amm + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn unidirectional(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,177 @@
+use anyhow::anyhow;
+use jupiter_amm_interface::{
+    AccountMap, Amm, AmmContext, KeyedAccount, Quote, QuoteParams, Swap, SwapAndAccountMetas,
+    SwapParams,
+};
+use sanctum_router::{find_fee_token_account_pda, SanctumRouter, SanctumRouterError, SwapMints};
+use sanctum_router_core::{
+    RouterConfig, RouterIx, TokenQuote, WithRouterFee, WithdrawSolQuoter, WithdrawWrappedSolIxData,
+    WithdrawWrappedSolPrefixAccsBuilder, NATIVE_MINT, TOKEN_PROGRAM,
+};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    check_quote_params, init_mint_from_keyed_account, jup_quote, router_ix_account_metas,
+    JupAccountMap, LABEL,
+};
+
+/// LST -> wSOL via the pool's WithdrawSol. Only supported by SPL stake pools.
+#[derive(Debug, Clone)]
+pub struct WithdrawWrappedSolAmm {
+    router: SanctumRouter,
+
+    /// The stake pool this was created from
+    key: [u8; 32],
+
+    inp_mint: [u8; 32],
+}
+
+impl WithdrawWrappedSolAmm {
+    /// `keyed_account` is a spl stake pool
+    ///
+    /// [`Amm::from_keyed_account`] for a router deployment other than
+    /// [`RouterConfig::MAINNET`]
+    pub fn from_keyed_account_with_config(
+        keyed_account: &KeyedAccount,
+        config: RouterConfig,
+    ) -> anyhow::Result<Self> {
+        let init_mint = init_mint_from_keyed_account(keyed_account, &config)?;
+        if init_mint.init.is_none() {
+            return Err(anyhow!(
+                "{} does not support WithdrawSol",
+                keyed_account.key
+            ));
+        }
+        let mut router = SanctumRouter::new(config);
+        router.init([init_mint])?;
+        Ok(Self {
+            router,
+            key: keyed_account.key.to_bytes(),
+            inp_mint: init_mint.mint,
+        })
+    }
+
+    #[inline]
+    pub const fn inp_mint(&self) -> &[u8; 32] {
+        &self.inp_mint
+    }
+
+    #[inline]
+    const fn swap_mints(&self) -> [SwapMints; 1] {
+        [SwapMints::WithdrawSol { inp: self.inp_mint }]
+    }
+
+    pub fn quote_withdraw_wrapped_sol(
+        &self,
+        tokens: u64,
+    ) -> Result<WithRouterFee<TokenQuote>, SanctumRouterError> {
+        Ok(self
+            .router
+            .try_find_spl_by_mint(&self.inp_mint)?
+            .withdraw_sol_quoter(self.router.try_curr_epoch()?)?
+            .quote_withdraw_sol(tokens)?
+            .with_router_fee(&self.router.config.fees.withdraw_wrapped_sol))
+    }
+
+    pub fn withdraw_wrapped_sol_ix(
+        &self,
+        swap_params: &SwapParams,
+    ) -> Result<RouterIx, SanctumRouterError> {
+        let prefix = WithdrawWrappedSolPrefixAccsBuilder::start()
+            .with_user(swap_params.token_transfer_authority.to_bytes())
+            .with_inp_token(swap_params.source_token_account.to_bytes())
+            .with_out_wsol(swap_params.destination_token_account.to_bytes())
+            .with_wsol_fee_token(
+                find_fee_token_account_pda(&self.router.config, &NATIVE_MINT)
+                    .ok_or(SanctumRouterError::InvalidPda)?
+                    .0,
+            )
+            .with_inp_mint(self.inp_mint)
+            .with_wsol_mint(NATIVE_MINT)
+            .with_token_program(TOKEN_PROGRAM)
+            .build();
+        Ok(RouterIx::withdraw_wrapped_sol(
+            &prefix,
+            WithdrawWrappedSolIxData::new(swap_params.in_amount),
+            self.router
+                .try_find_spl_by_mint(&self.inp_mint)?
+                .sol_suf_accs()?,
+        )
+        .ok_or(SanctumRouterError::TooManyAccounts)?
+        .with_program_address(self.router.config.router_program))
+    }
+}
+
+impl Amm for WithdrawWrappedSolAmm {
+    /// `keyed_account` is a spl stake pool
+    fn from_keyed_account(
+        keyed_account: &KeyedAccount,
+        _amm_context: &AmmContext,
+    ) -> anyhow::Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::from_keyed_account_with_config(keyed_account, RouterConfig::MAINNET)
+    }
+
+    fn label(&self) -> String {
+        LABEL.to_owned()
+    }
+
+    fn program_id(&self) -> Pubkey {
+        Pubkey::new_from_array(self.router.config.router_program)
+    }
+
+    fn key(&self) -> Pubkey {
+        Pubkey::new_from_array(self.key)
+    }
+
+    fn get_reserve_mints(&self) -> Vec<Pubkey> {
+        [self.inp_mint, NATIVE_MINT]
+            .map(Pubkey::new_from_array)
+            .into()
+    }
+
+    fn get_accounts_to_update(&self) -> Vec<Pubkey> {
+        self.router
+            .accounts_to_update(self.swap_mints())
+            .unwrap_or_default()
+            .into_iter()
+            .map(Pubkey::new_from_array)
+            .collect()
+    }
+
+    fn update(&mut self, account_map: &AccountMap) -> anyhow::Result<()> {
+        let swap_mints = self.swap_mints();
+        Ok(self
+            .router
+            .update(swap_mints, &JupAccountMap(account_map))?)
+    }
+
+    fn quote(&self, quote_params: &QuoteParams) -> anyhow::Result<Quote> {
+        check_quote_params(quote_params, &self.inp_mint, &NATIVE_MINT)?;
+        let WithRouterFee {
+            quote: TokenQuote { inp, out, fee },
+            router_fee,
+        } = self.quote_withdraw_wrapped_sol(quote_params.amount)?;
+        Ok(jup_quote(inp, out, fee, router_fee, &NATIVE_MINT))
+    }
+
+    fn get_swap_and_account_metas(
+        &self,
+        swap_params: &SwapParams,
+    ) -> anyhow::Result<SwapAndAccountMetas> {
+        Ok(SwapAndAccountMetas {
+            swap: Swap::StakeDexWithdrawWrappedSol,
+            account_metas: router_ix_account_metas(&self.withdraw_wrapped_sol_ix(swap_params)?),
+        })
+    }
+
+    fn clone_amm(&self) -> Box<dyn Amm + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn unidirectional(&self) -> bool {
+        true
+    }
+}
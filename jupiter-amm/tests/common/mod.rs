@@ -0,0 +1,192 @@
+#![allow(unused)] // each test binary only uses some of these
+
+use std::{fs, path::PathBuf, str::FromStr};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use jupiter_amm_interface::{
+    AccountMap, Amm, AmmContext, ClockRef, KeyedAccount, QuoteParams, SwapMode, SwapParams,
+};
+use sanctum_router_core::{SANCTUM_ROUTER_PROGRAM, SYSVAR_CLOCK};
+use serde::Deserialize;
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+/// bSOL's stake pool `last_update_epoch` at time of fixtures collection
+pub const BSOL_EPOCH: u64 = 802;
+
+/// lido's max stake validator at time of fixtures collection, also in bSOL's validator list
+pub const LIDOMAX_VOTE: Pubkey =
+    solana_sdk::pubkey!("8jxSHbS4qAnh5yueFp4D9ABXubKqMwXqF3HtdzQGuphp");
+
+pub const SIGNER: Pubkey = solana_sdk::pubkey!("J6ksVSmKYzHeVT3P4pzkPEDNP56hezuZanhXQ8JNTvS5");
+
+const JUPITER_PROGRAM: Pubkey = solana_sdk::pubkey!("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4");
+
+#[derive(Deserialize)]
+struct FixtureFile {
+    pubkey: String,
+    account: FixtureAccount,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FixtureAccount {
+    lamports: u64,
+    data: (String, String),
+    owner: String,
+    executable: bool,
+    rent_epoch: u64,
+}
+
+fn test_fixtures_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("test-fixtures")
+}
+
+fn read_fixture(path: PathBuf) -> (Pubkey, Account) {
+    let FixtureFile {
+        pubkey,
+        account:
+            FixtureAccount {
+                lamports,
+                data: (data, _encoding),
+                owner,
+                executable,
+                rent_epoch,
+            },
+    } = serde_json::from_slice(&fs::read(path).unwrap()).unwrap();
+    (
+        Pubkey::from_str(&pubkey).unwrap(),
+        Account {
+            lamports,
+            data: STANDARD.decode(data).unwrap(),
+            owner: Pubkey::from_str(&owner).unwrap(),
+            executable,
+            rent_epoch,
+        },
+    )
+}
+
+pub fn fixture_keyed_account(fname: &str) -> KeyedAccount {
+    let (key, account) = read_fixture(test_fixtures_dir().join(format!("{fname}.json")));
+    KeyedAccount {
+        key,
+        account,
+        params: None,
+    }
+}
+
+/// All accounts in test-fixtures/,
+/// plus a clock sysvar account since the fixtures do not include one
+pub fn fixtures_account_map(epoch: u64) -> AccountMap {
+    let mut res = AccountMap::default();
+    for entry in fs::read_dir(test_fixtures_dir()).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            let (pk, acc) = read_fixture(path);
+            res.insert(pk, acc);
+        }
+    }
+    res.insert(Pubkey::new_from_array(SYSVAR_CLOCK), clock_account(epoch));
+    res
+}
+
+fn clock_account(epoch: u64) -> Account {
+    // slot, epoch_start_timestamp, epoch, leader_schedule_epoch, unix_timestamp
+    let mut data = vec![0u8; 40];
+    data[16..24].copy_from_slice(&epoch.to_le_bytes());
+    Account {
+        lamports: 1_169_280,
+        data,
+        owner: solana_sdk::sysvar::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+pub fn amm_context() -> AmmContext {
+    AmmContext {
+        clock_ref: ClockRef::default(),
+    }
+}
+
+/// Updates `amm` with only the accounts it requested,
+/// asserting that they are all present in `account_map`
+pub fn update_from(amm: &mut impl Amm, account_map: &AccountMap) {
+    let requested: AccountMap = amm
+        .get_accounts_to_update()
+        .into_iter()
+        .map(|pk| {
+            let acc = account_map
+                .get(&pk)
+                .unwrap_or_else(|| panic!("{pk} missing from fixtures"));
+            (pk, acc.clone())
+        })
+        .collect();
+    amm.update(&requested).unwrap();
+}
+
+pub fn exact_in(input_mint: Pubkey, output_mint: Pubkey, amount: u64) -> QuoteParams {
+    QuoteParams {
+        amount,
+        input_mint,
+        output_mint,
+        swap_mode: SwapMode::ExactIn,
+    }
+}
+
+pub fn swap_params<'a>(
+    quote_params: &QuoteParams,
+    out_amount: u64,
+    source_token_account: Pubkey,
+    destination_token_account: Pubkey,
+) -> SwapParams<'a, 'a> {
+    SwapParams {
+        swap_mode: SwapMode::ExactIn,
+        in_amount: quote_params.amount,
+        out_amount,
+        source_mint: quote_params.input_mint,
+        destination_mint: quote_params.output_mint,
+        source_token_account,
+        destination_token_account,
+        token_transfer_authority: SIGNER,
+        quote_mint_to_referrer: None,
+        jupiter_program_id: &JUPITER_PROGRAM,
+        missing_dynamic_accounts_as_default: false,
+    }
+}
+
+/// Quotes `quote_params` and creates the swap's account metas,
+/// asserting basic invariants. Returns the quote's out_amount
+pub fn assert_quote_and_swap(
+    amm: &impl Amm,
+    quote_params: &QuoteParams,
+    source_token_account: Pubkey,
+    destination_token_account: Pubkey,
+) -> u64 {
+    let quote = amm.quote(quote_params).unwrap();
+    assert_eq!(quote.in_amount, quote_params.amount);
+    assert!(quote.out_amount > 0);
+    assert_eq!(quote.fee_mint, quote_params.output_mint);
+
+    let params = swap_params(
+        quote_params,
+        quote.out_amount,
+        source_token_account,
+        destination_token_account,
+    );
+    let metas = amm
+        .get_swap_and_account_metas(&params)
+        .unwrap()
+        .account_metas;
+    assert_eq!(
+        metas[0].pubkey,
+        Pubkey::new_from_array(SANCTUM_ROUTER_PROGRAM)
+    );
+    assert!(metas[1..].iter().any(|m| m.pubkey == SIGNER && m.is_signer));
+    assert!(metas[1..]
+        .iter()
+        .any(|m| m.pubkey == destination_token_account && m.is_writable));
+
+    quote.out_amount
+}
@@ -0,0 +1,70 @@
+use jupiter_amm_interface::Amm;
+use sanctum_router_core::NATIVE_MINT;
+use sanctum_router_jupiter_amm::DepositStakeAmm;
+use solana_sdk::pubkey::Pubkey;
+
+mod common;
+
+use common::*;
+
+/// Arbitrary stake account to deposit, quoting does not read it
+const INP_STAKE: Pubkey = Pubkey::new_from_array([1; 32]);
+
+fn assert_deposit_stake(pool_fixture: &str, dst_token_account: Pubkey) {
+    let accounts = fixtures_account_map(BSOL_EPOCH);
+    let mut amm =
+        DepositStakeAmm::from_keyed_account(&fixture_keyed_account(pool_fixture), &amm_context())
+            .unwrap();
+    update_from(&mut amm, &accounts);
+
+    let out_mint = Pubkey::new_from_array(*amm.out_mint());
+    assert_eq!(amm.get_reserve_mints(), [out_mint]);
+    assert_quote_and_swap(
+        &amm,
+        &exact_in(LIDOMAX_VOTE, out_mint, 10_000_000_000),
+        INP_STAKE,
+        dst_token_account,
+    );
+}
+
+#[test]
+fn deposit_stake_bsol() {
+    assert_deposit_stake(
+        "bsol-stake-pool",
+        solana_sdk::pubkey!("C2CejjJ5QXKNf58P2bJzafSAffhRL7iQYeHfKz4HNWwc"),
+    );
+}
+
+#[test]
+fn deposit_stake_reserve() {
+    assert_deposit_stake(
+        "reserve-pool",
+        solana_sdk::pubkey!("mDABzxpxQM4syX3QsuborrVd8v4qnPPSyj14BJuK5X6"),
+    );
+}
+
+#[test]
+fn deposit_stake_below_rent_exemption_errs() {
+    let accounts = fixtures_account_map(BSOL_EPOCH);
+    let mut amm =
+        DepositStakeAmm::from_keyed_account(&fixture_keyed_account("reserve-pool"), &amm_context())
+            .unwrap();
+    update_from(&mut amm, &accounts);
+
+    assert!(amm
+        .quote(&exact_in(
+            LIDOMAX_VOTE,
+            Pubkey::new_from_array(NATIVE_MINT),
+            1
+        ))
+        .is_err());
+}
+
+#[test]
+fn deposit_stake_rejects_lido() {
+    assert!(DepositStakeAmm::from_keyed_account(
+        &fixture_keyed_account("lido-state"),
+        &amm_context()
+    )
+    .is_err());
+}
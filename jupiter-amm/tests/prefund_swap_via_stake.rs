@@ -0,0 +1,48 @@
+use jupiter_amm_interface::Amm;
+use sanctum_router_core::NATIVE_MINT;
+use sanctum_router_jupiter_amm::PrefundSwapViaStakeAmm;
+use solana_sdk::pubkey::Pubkey;
+
+mod common;
+
+use common::*;
+
+#[test]
+fn prefund_swap_via_stake_bsol_to_reserve() {
+    let accounts = fixtures_account_map(BSOL_EPOCH);
+    let mut amm = PrefundSwapViaStakeAmm::from_keyed_accounts(
+        &fixture_keyed_account("bsol-stake-pool"),
+        &fixture_keyed_account("reserve-pool"),
+    )
+    .unwrap();
+    update_from(&mut amm, &accounts);
+
+    assert_quote_and_swap(
+        &amm,
+        &exact_in(
+            Pubkey::new_from_array(*amm.inp_mint()),
+            Pubkey::new_from_array(NATIVE_MINT),
+            1_000_000_000,
+        ),
+        solana_sdk::pubkey!("C2CejjJ5QXKNf58P2bJzafSAffhRL7iQYeHfKz4HNWwc"),
+        solana_sdk::pubkey!("mDABzxpxQM4syX3QsuborrVd8v4qnPPSyj14BJuK5X6"),
+    );
+}
+
+#[test]
+fn prefund_swap_via_stake_rejects_same_pool() {
+    assert!(PrefundSwapViaStakeAmm::from_keyed_accounts(
+        &fixture_keyed_account("bsol-stake-pool"),
+        &fixture_keyed_account("bsol-stake-pool"),
+    )
+    .is_err());
+}
+
+#[test]
+fn prefund_swap_via_stake_from_keyed_account_errs() {
+    assert!(PrefundSwapViaStakeAmm::from_keyed_account(
+        &fixture_keyed_account("bsol-stake-pool"),
+        &amm_context()
+    )
+    .is_err());
+}
@@ -0,0 +1,58 @@
+use jupiter_amm_interface::Amm;
+use sanctum_router_core::NATIVE_MINT;
+use sanctum_router_jupiter_amm::StakeWrappedSolAmm;
+use solana_sdk::pubkey::Pubkey;
+
+mod common;
+
+use common::*;
+
+const SIGNER_WSOL_TOKEN: Pubkey =
+    solana_sdk::pubkey!("mDABzxpxQM4syX3QsuborrVd8v4qnPPSyj14BJuK5X6");
+
+fn assert_stake_wrapped_sol(pool_fixture: &str, dst_token_account: Pubkey) {
+    let accounts = fixtures_account_map(BSOL_EPOCH);
+    let mut amm = StakeWrappedSolAmm::from_keyed_account(
+        &fixture_keyed_account(pool_fixture),
+        &amm_context(),
+    )
+    .unwrap();
+    update_from(&mut amm, &accounts);
+
+    let out_mint = Pubkey::new_from_array(*amm.out_mint());
+    assert_eq!(
+        amm.get_reserve_mints(),
+        [Pubkey::new_from_array(NATIVE_MINT), out_mint]
+    );
+    assert_quote_and_swap(
+        &amm,
+        &exact_in(Pubkey::new_from_array(NATIVE_MINT), out_mint, 1_000_000_000),
+        SIGNER_WSOL_TOKEN,
+        dst_token_account,
+    );
+}
+
+#[test]
+fn stake_wrapped_sol_bsol() {
+    assert_stake_wrapped_sol(
+        "bsol-stake-pool",
+        solana_sdk::pubkey!("C2CejjJ5QXKNf58P2bJzafSAffhRL7iQYeHfKz4HNWwc"),
+    );
+}
+
+#[test]
+fn stake_wrapped_sol_msol() {
+    assert_stake_wrapped_sol(
+        "marinade-state",
+        solana_sdk::pubkey!("YxrWgUB7ktCm17uks5Cyozv21G8y2Rtj3VTqoc7fSsT"),
+    );
+}
+
+#[test]
+fn stake_wrapped_sol_rejects_reserve() {
+    assert!(StakeWrappedSolAmm::from_keyed_account(
+        &fixture_keyed_account("reserve-pool"),
+        &amm_context()
+    )
+    .is_err());
+}
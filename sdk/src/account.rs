@@ -0,0 +1,72 @@
+use std::{collections::HashMap, hash::BuildHasher};
+
+use crate::SanctumRouterError;
+
+/// Borrowed view of an account's fields that the routers read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AccountRef<'a> {
+    pub owner: &'a [u8; 32],
+    pub data: &'a [u8],
+    pub lamports: u64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Account {
+    pub owner: [u8; 32],
+    pub data: Vec<u8>,
+    pub lamports: u64,
+}
+
+impl Account {
+    #[inline]
+    pub fn as_account_ref(&self) -> AccountRef<'_> {
+        AccountRef {
+            owner: &self.owner,
+            data: &self.data,
+            lamports: self.lamports,
+        }
+    }
+}
+
+/// A source of fetched account data to update routers with,
+/// e.g. a map of pubkey to account.
+pub trait AccountSource {
+    fn account(&self, pubkey: &[u8; 32]) -> Option<AccountRef<'_>>;
+}
+
+impl<S: BuildHasher> AccountSource for HashMap<[u8; 32], Account, S> {
+    #[inline]
+    fn account(&self, pubkey: &[u8; 32]) -> Option<AccountRef<'_>> {
+        self.get(pubkey).map(Account::as_account_ref)
+    }
+}
+
+/// Blanket for refs
+impl<T: AccountSource + ?Sized> AccountSource for &T {
+    #[inline]
+    fn account(&self, pubkey: &[u8; 32]) -> Option<AccountRef<'_>> {
+        (**self).account(pubkey)
+    }
+}
+
+/// Basically [`AccountSource::account()`], but returns
+/// [`SanctumRouterError::AccountMissing`] if account missing instead of `None`
+#[inline]
+pub fn get_account<'a, A: AccountSource + ?Sized>(
+    accounts: &'a A,
+    pubkey: &[u8; 32],
+) -> Result<AccountRef<'a>, SanctumRouterError> {
+    accounts
+        .account(pubkey)
+        .ok_or(SanctumRouterError::AccountMissing(*pubkey))
+}
+
+/// Basically [`AccountSource::account()`], but returns
+/// [`SanctumRouterError::AccountMissing`] if account missing instead of `None`
+#[inline]
+pub fn get_account_data<'a, A: AccountSource + ?Sized>(
+    accounts: &'a A,
+    pubkey: &[u8; 32],
+) -> Result<&'a [u8], SanctumRouterError> {
+    get_account(accounts, pubkey).map(|account| account.data)
+}
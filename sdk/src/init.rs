@@ -0,0 +1,27 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InitData {
+    Spl(SplInitData),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SplInitData {
+    pub stake_pool_addr: [u8; 32],
+
+    /// Can be read from stake pool account owner
+    pub stake_pool_program_addr: [u8; 32],
+
+    /// Can be read from stake pool account data
+    pub validator_list_addr: [u8; 32],
+
+    /// Can be read from stake pool account data
+    pub reserve_stake_addr: [u8; 32],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InitMint {
+    pub mint: [u8; 32],
+
+    /// Must be provided for SPL pools,
+    /// `None` for everything else
+    pub init: Option<InitData>,
+}
@@ -0,0 +1,22 @@
+// Implementation notes:
+//
+// - This crate contains the stateful router logic with plain rust types.
+//   The wasm sdk crate in `ts/sdk` is a binding layer over it and should
+//   only contain conversions to/from tsify types and wasm_bindgen exports.
+
+mod account;
+mod init;
+mod pda;
+mod router;
+mod routers;
+mod update;
+
+pub use account::*;
+pub use init::*;
+pub use pda::*;
+pub use router::*;
+pub use routers::*;
+pub use update::*;
+
+// errors are shared with the wasm sdk through core
+pub use sanctum_router_core::{PoolUpdateType, SanctumRouterError};
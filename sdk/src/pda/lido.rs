@@ -2,7 +2,7 @@ use solido_legacy_core::validator_stake_seeds;
 
 use crate::pda::find_pda;
 
-pub fn find_lido_validator_stake_account_pda_internal(
+pub fn find_lido_validator_stake_account_pda(
     vote_account: &[u8; 32],
     seed: u64,
 ) -> Option<([u8; 32], u8)> {
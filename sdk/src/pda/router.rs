@@ -0,0 +1,33 @@
+use sanctum_router_core::{
+    bridge_stake_seeds, fee_token_acc_seeds, RouterConfig, SLUMDOG_SEED, STAKE_PROGRAM,
+};
+
+use crate::pda::{find_pda, pk_create_with_seed};
+
+pub fn find_fee_token_account_pda(
+    config: &RouterConfig,
+    mint: &[u8; 32],
+) -> Option<([u8; 32], u8)> {
+    let (s1, s2) = fee_token_acc_seeds(mint);
+    find_pda(&[s1.as_slice(), s2.as_slice()], &config.router_program)
+}
+
+pub fn find_bridge_stake_acc_pda(
+    config: &RouterConfig,
+    user: &[u8; 32],
+    bridge_stake_seed: u32,
+) -> Option<([u8; 32], u8)> {
+    let (s1, s2, s3) = bridge_stake_seeds(user, bridge_stake_seed);
+    find_pda(
+        &[s1.as_slice(), s2.as_slice(), s3.as_slice()],
+        &config.router_program,
+    )
+}
+
+/// `Pubkey::create_with_seed(bridge_stake_pk)` to obtain the slumdog stake account addr
+pub fn create_slumdog_stake_addr(bridge_stake: &[u8; 32]) -> [u8; 32] {
+    // unwrap-safety:
+    // - seed.len() <= MAX_SEED_LEN
+    // - Stake program ID's last bytes are not PDA_MARKER
+    pk_create_with_seed(bridge_stake, SLUMDOG_SEED, &STAKE_PROGRAM).unwrap()
+}
@@ -4,7 +4,7 @@ use sanctum_spl_stake_pool_core::{deposit_auth_seeds, validator_stake_seeds, wit
 
 use crate::pda::find_pda;
 
-pub fn find_withdraw_auth_pda_internal(
+pub fn find_withdraw_auth_pda(
     program_id: &[u8; 32],
     stake_pool_addr: &[u8; 32],
 ) -> Option<([u8; 32], u8)> {
@@ -12,7 +12,7 @@ pub fn find_withdraw_auth_pda_internal(
     find_pda(&[s1.as_slice(), s2.as_slice()], program_id)
 }
 
-pub fn find_deposit_auth_pda_internal(
+pub fn find_deposit_auth_pda(
     program_id: &[u8; 32],
     stake_pool_addr: &[u8; 32],
 ) -> Option<([u8; 32], u8)> {
@@ -20,7 +20,7 @@ pub fn find_deposit_auth_pda_internal(
     find_pda(&[s1.as_slice(), s2.as_slice()], program_id)
 }
 
-pub fn find_validator_stake_account_pda_internal(
+pub fn find_validator_stake_account_pda(
     program_id: &[u8; 32],
     vote_account_addr: &[u8; 32],
     stake_pool_addr: &[u8; 32],
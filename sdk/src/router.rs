@@ -0,0 +1,241 @@
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+
+use sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR;
+use sanctum_router_core::{
+    PrefundParams, RentParams, RouterConfig, NATIVE_MINT, SYSVAR_CLOCK, SYSVAR_RENT,
+};
+use solido_legacy_core::STSOL_MINT_ADDR;
+
+use crate::{
+    get_account_data, AccountSource, InitMint, LidoRouterOwned, MarinadeRouterOwned, PoolUpdate,
+    PoolUpdateType, ReserveRouterOwned, SanctumRouterError, SplStakePoolRouterOwned, SwapMints,
+};
+
+/// The main top level router type that is an aggregation of all underlying stake pools
+///
+/// Starts out empty and needs to have individual mints
+/// init and updated for the specific swap
+/// before it can start operating for it.
+#[derive(Clone, Debug, Default)]
+pub struct SanctumRouter {
+    /// Program IDs and fixed accounts of the deployment to route for,
+    /// mainnet by default
+    pub config: RouterConfig,
+
+    pub lido_router: LidoRouterOwned,
+    pub marinade_router: MarinadeRouterOwned,
+    pub reserve_router: ReserveRouterOwned,
+
+    /// Fetched from sysvar clock
+    pub curr_epoch: Option<u64>,
+
+    /// Fetched from sysvar rent.
+    /// Updated together with the reserve pool for prefunding,
+    /// and for WithdrawStake for the rent-exemption of non-prefunded bridge stake accounts.
+    pub rent: Option<RentParams>,
+
+    /// Key is LST mint
+    pub spl_routers: HashMap<[u8; 32], SplStakePoolRouterOwned>,
+}
+
+/// Getters
+impl SanctumRouter {
+    pub fn find_spl_by_mint(&self, mint: &[u8; 32]) -> Option<&SplStakePoolRouterOwned> {
+        self.spl_routers.get(mint)
+    }
+
+    pub fn try_find_spl_by_mint(
+        &self,
+        mint: &[u8; 32],
+    ) -> Result<&SplStakePoolRouterOwned, SanctumRouterError> {
+        self.find_spl_by_mint(mint)
+            .ok_or(SanctumRouterError::RouterMissing(*mint))
+    }
+
+    pub fn try_curr_epoch(&self) -> Result<u64, SanctumRouterError> {
+        self.curr_epoch
+            .ok_or(SanctumRouterError::AccountMissing(SYSVAR_CLOCK))
+    }
+
+    pub fn try_rent(&self) -> Result<&RentParams, SanctumRouterError> {
+        self.rent
+            .as_ref()
+            .ok_or(SanctumRouterError::AccountMissing(SYSVAR_RENT))
+    }
+
+    /// Reserve pool state, rent and stake minimum delegation
+    /// that prefunded WithdrawStake quotes depend on
+    pub fn prefund_params(&self) -> Result<PrefundParams, SanctumRouterError> {
+        let (reserves_balance, reserves_fee) = self.reserve_router.prefund_params()?;
+        Ok(PrefundParams {
+            reserves_balance,
+            reserves_fee,
+            rent: *self.try_rent()?,
+            min_delegation: self.config.stake_min_delegation,
+        })
+    }
+}
+
+/// Init
+impl SanctumRouter {
+    /// Creates a new empty router for the deployment specified by `config`.
+    ///
+    /// Use [`Self::default`] for mainnet.
+    pub fn new(config: RouterConfig) -> Self {
+        Self {
+            config,
+            ..Default::default()
+        }
+    }
+
+    /// Initialize for specific mints.
+    ///
+    /// The mint must still be updated before it can be used.
+    ///
+    /// Calling this again for the same mint will **NOT** result in
+    /// any changes or reinitialization.
+    pub fn init(
+        &mut self,
+        init_mints: impl IntoIterator<Item = InitMint>,
+    ) -> Result<(), SanctumRouterError> {
+        init_mints
+            .into_iter()
+            .try_for_each(|InitMint { mint, init }| match mint {
+                // no-op for everything other than spl
+                NATIVE_MINT | MSOL_MINT_ADDR | STSOL_MINT_ADDR => Ok(()),
+                spl_mint => {
+                    let init_data = init.ok_or(SanctumRouterError::InvalidData)?;
+                    match self.spl_routers.entry(spl_mint) {
+                        Entry::Occupied(_already_init) => Ok(()),
+                        Entry::Vacant(v) => {
+                            v.insert(SplStakePoolRouterOwned::init(&init_data)?);
+                            Ok(())
+                        }
+                    }
+                }
+            })
+    }
+
+    /// Returns if the given mint has already been [`Self::init`]
+    pub fn is_init(&self, mint: &[u8; 32]) -> bool {
+        match *mint {
+            // everything other than spl does not need init() to be called
+            NATIVE_MINT | MSOL_MINT_ADDR | STSOL_MINT_ADDR => true,
+            spl_mint => self.spl_routers.contains_key(&spl_mint),
+        }
+    }
+}
+
+/// Update
+impl SanctumRouter {
+    /// Returns the accounts needed to update specific pools for given swap routes.
+    ///
+    /// Dedups returned pubkey list; all pubkeys in returned list guaranteed to be unique.
+    ///
+    /// mSOL WithdrawStake requires calling this and [`Self::update`] twice the first time,
+    /// since Marinade's stake accounts are only known after its stake list has been fetched.
+    pub fn accounts_to_update(
+        &self,
+        swap_mints: impl IntoIterator<Item = SwapMints>,
+    ) -> Result<Vec<[u8; 32]>, SanctumRouterError> {
+        let mut accounts = Vec::new();
+
+        for PoolUpdate { mint, ty } in dedup_pool_updates(swap_mints) {
+            // rent-exemption of the bridge stake account for non-prefunded SwapViaStake
+            if matches!(ty, PoolUpdateType::WithdrawStake) {
+                accounts.push(SYSVAR_RENT);
+            }
+            match mint {
+                NATIVE_MINT => {
+                    accounts.extend(ReserveRouterOwned::accounts_to_update(&self.config, ty));
+                    accounts.push(SYSVAR_RENT);
+                }
+                MSOL_MINT_ADDR => accounts.extend(self.marinade_router.accounts_to_update(ty)),
+                STSOL_MINT_ADDR => accounts.extend(LidoRouterOwned::accounts_to_update(ty)),
+                mint => accounts.extend(self.try_find_spl_by_mint(&mint)?.accounts_to_update(ty)),
+            }
+        }
+
+        accounts.sort();
+        accounts.dedup();
+        Ok(accounts)
+    }
+
+    /// Updates specific pools for given swap routes
+    pub fn update<A: AccountSource + ?Sized>(
+        &mut self,
+        swap_mints: impl IntoIterator<Item = SwapMints>,
+        accounts: &A,
+    ) -> Result<(), SanctumRouterError> {
+        // Use this state flag instead of just doing
+        // update if clock found in accounts
+        // because we want to fail if clock is supposed to be updated
+        // but wasn't fetched
+        let mut require_clock_update = false;
+        let mut require_rent_update = false;
+
+        for PoolUpdate { mint, ty } in dedup_pool_updates(swap_mints) {
+            // rent-exemption of the bridge stake account for non-prefunded SwapViaStake
+            require_rent_update |= matches!(ty, PoolUpdateType::WithdrawStake);
+            match mint {
+                NATIVE_MINT => {
+                    self.reserve_router.update(&self.config, ty, accounts)?;
+                    require_rent_update = true;
+                }
+                MSOL_MINT_ADDR => {
+                    self.marinade_router.update(ty, accounts)?;
+                    // delayed unstake tickets' claimable epoch
+                    require_clock_update |= matches!(ty, PoolUpdateType::DelayedUnstake);
+                }
+                STSOL_MINT_ADDR => {
+                    self.lido_router.update(ty, accounts)?;
+                    require_clock_update = true;
+                }
+                mint => {
+                    self.spl_routers
+                        .get_mut(&mint)
+                        .ok_or(SanctumRouterError::RouterMissing(mint))?
+                        .update(ty, accounts)?;
+                    require_clock_update = true;
+                }
+            }
+        }
+
+        if require_clock_update {
+            let curr_epoch =
+                get_account_data(accounts, &SYSVAR_CLOCK).and_then(try_clock_acc_data_epoch)?;
+            self.curr_epoch = Some(curr_epoch);
+        }
+
+        if require_rent_update {
+            let rent = get_account_data(accounts, &SYSVAR_RENT).and_then(try_rent_acc_data)?;
+            self.rent = Some(rent);
+        }
+
+        Ok(())
+    }
+}
+
+fn dedup_pool_updates(swap_mints: impl IntoIterator<Item = SwapMints>) -> HashSet<PoolUpdate> {
+    swap_mints
+        .into_iter()
+        .flat_map(SwapMints::into_pool_updates)
+        .collect()
+}
+
+const CLOCK_EPOCH_OFFSET: usize = 16;
+
+fn try_clock_acc_data_epoch(d: &[u8]) -> Result<u64, SanctumRouterError> {
+    clock_acc_data_epoch(d).ok_or(SanctumRouterError::InvalidData)
+}
+
+fn clock_acc_data_epoch(d: &[u8]) -> Option<u64> {
+    d.split_at_checked(CLOCK_EPOCH_OFFSET)?
+        .1
+        .first_chunk()
+        .map(|a| u64::from_le_bytes(*a))
+}
+
+fn try_rent_acc_data(d: &[u8]) -> Result<RentParams, SanctumRouterError> {
+    RentParams::from_sysvar_data(d).ok_or(SanctumRouterError::InvalidData)
+}
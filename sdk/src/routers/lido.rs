@@ -1,16 +1,13 @@
-use sanctum_router_core::{LidoWithdrawStakeQuoter, LidoWithdrawStakeSufAccs};
+use sanctum_router_core::{
+    LidoSolValueCalc, LidoWithdrawStakeQuoter, LidoWithdrawStakeSufAccs, WithdrawStakeQuote,
+};
 use solido_legacy_core::{
-    Lido, ListHeader, Validator, ValidatorList, STSOL_MINT_ADDR, SYSVAR_CLOCK,
+    Lido, LidoError, ListHeader, Validator, ValidatorList, STSOL_MINT_ADDR, SYSVAR_CLOCK,
 };
 
 use crate::{
-    err::{
-        account_missing_err, invalid_data_err, invalid_pda_err, unsupported_update_err,
-        SanctumRouterError,
-    },
-    interface::{get_account_data, AccountMap},
-    pda::lido::find_lido_validator_stake_account_pda_internal,
-    update::PoolUpdateType,
+    get_account_data, pda::find_lido_validator_stake_account_pda, AccountSource, PoolUpdateType,
+    SanctumRouterError,
 };
 
 #[derive(Clone, Debug, Default, PartialEq)]
@@ -37,14 +34,14 @@ impl LidoRouterOwned {
         ]
     }
 
-    pub fn init(accounts: &AccountMap) -> Result<Self, SanctumRouterError> {
-        let [s, v] = Self::init_accounts().map(|k| get_account_data(accounts, k));
+    pub fn init<A: AccountSource + ?Sized>(accounts: &A) -> Result<Self, SanctumRouterError> {
+        let [s, v] = Self::init_accounts().map(|k| get_account_data(accounts, &k));
         let state_data = s?;
         let validator_list_data = v?;
 
-        let state = Lido::borsh_de(state_data).map_err(|_e| invalid_data_err())?;
-        let ValidatorList { header, entries } =
-            ValidatorList::deserialize(validator_list_data).map_err(|_e| invalid_data_err())?;
+        let state = Lido::borsh_de(state_data).map_err(|_e| SanctumRouterError::InvalidData)?;
+        let ValidatorList { header, entries } = ValidatorList::deserialize(validator_list_data)
+            .map_err(|_e| SanctumRouterError::InvalidData)?;
         let validator_list = LidoValidatorListOwned {
             header,
             validators: entries.to_vec(),
@@ -60,22 +57,34 @@ impl LidoRouterOwned {
 /// Getters
 impl LidoRouterOwned {
     pub fn try_inner(&self) -> Result<&LidoRouterInner, SanctumRouterError> {
-        self.0
-            .as_ref()
-            .ok_or_else(|| account_missing_err(&solido_legacy_core::LIDO_STATE_ADDR))
+        self.0.as_ref().ok_or(SanctumRouterError::AccountMissing(
+            solido_legacy_core::LIDO_STATE_ADDR,
+        ))
+    }
+}
+
+/// SOL value
+impl LidoRouterOwned {
+    pub fn sol_value_calc(&self) -> Result<LidoSolValueCalc, SanctumRouterError> {
+        Ok(LidoSolValueCalc {
+            exchange_rate: &self.try_inner()?.state.exchange_rate,
+        })
     }
 }
 
 /// WithdrawStake
 impl LidoRouterOwned {
     /// Lido only allows withdrawing from max stake validator
+    ///
+    /// Set the returned quoter's minimum delegation with
+    /// [`LidoWithdrawStakeQuoter::with_min_delegation`]
     pub fn withdraw_stake_quoter(
         &self,
         curr_epoch: u64,
     ) -> Result<LidoWithdrawStakeQuoter, SanctumRouterError> {
         let inner = self.try_inner()?;
         LidoWithdrawStakeQuoter::new(&inner.state, &inner.validator_list.validators, curr_epoch)
-            .ok_or_else(invalid_data_err)
+            .ok_or(SanctumRouterError::InvalidData)
     }
 
     /// Lido only allows withdrawing from max stake validator
@@ -88,21 +97,53 @@ impl LidoRouterOwned {
             .validators
             .iter()
             .max_by_key(|v| v.effective_stake_balance())
-            .ok_or_else(invalid_data_err)?;
+            .ok_or(SanctumRouterError::InvalidData)?;
         let largest_stake_vote = max_validator.vote_account_address();
         Ok(LidoWithdrawStakeSufAccs {
             validator_list_addr: &inner.state.validator_list,
             largest_stake_vote,
-            stake_to_split: find_lido_validator_stake_account_pda_internal(
+            stake_to_split: find_lido_validator_stake_account_pda(
                 largest_stake_vote,
                 max_validator.stake_seeds().begin(),
             )
-            .ok_or_else(invalid_pda_err)?
+            .ok_or(SanctumRouterError::InvalidPda)?
             .0,
         })
     }
 }
 
+/// Apply
+///
+/// Update owned state to after a quote has executed so that subsequent quotes
+/// in the same simulation are consistent with it.
+impl LidoRouterOwned {
+    /// The exchange rate is only recomputed once per epoch so only the
+    /// validator's stake balance changes.
+    ///
+    /// A following `withdraw_stake_quoter()` picks up the new largest-stake validator.
+    pub fn apply_withdraw_stake(
+        &mut self,
+        quote: &WithdrawStakeQuote,
+    ) -> Result<(), SanctumRouterError> {
+        let validator = self
+            .0
+            .as_mut()
+            .ok_or(SanctumRouterError::AccountMissing(
+                solido_legacy_core::LIDO_STATE_ADDR,
+            ))?
+            .validator_list
+            .validators
+            .iter_mut()
+            .find(|v| v.vote_account_address() == &quote.out.vote)
+            .ok_or(SanctumRouterError::InvalidData)?;
+        validator.stake_accounts_balance = validator
+            .stake_accounts_balance
+            .checked_sub(quote.out.lamports.staked)
+            .ok_or(SanctumRouterError::Lido(LidoError::CalculationFailure))?;
+        Ok(())
+    }
+}
+
 /// Update
 impl LidoRouterOwned {
     pub fn accounts_to_update(ty: PoolUpdateType) -> impl Iterator<Item = [u8; 32]> {
@@ -119,17 +160,20 @@ impl LidoRouterOwned {
         .flatten()
     }
 
-    pub fn update(
+    pub fn update<A: AccountSource + ?Sized>(
         &mut self,
         ty: PoolUpdateType,
-        accounts: &AccountMap,
+        accounts: &A,
     ) -> Result<(), SanctumRouterError> {
         match ty {
             PoolUpdateType::WithdrawStake => {
                 *self = Self::init(accounts)?;
                 Ok(())
             }
-            _ => Err(unsupported_update_err(ty, &STSOL_MINT_ADDR)),
+            _ => Err(SanctumRouterError::UnsupportedUpdate {
+                ty,
+                mint: STSOL_MINT_ADDR,
+            }),
         }
     }
 }
@@ -0,0 +1,533 @@
+use sanctum_marinade_liquid_staking_core::{
+    MarinadeError, State as MarinadeState, ValidatorList, ValidatorRecord,
+    LIQ_POOL_MSOL_LEG_PUBKEY, LIQ_POOL_SOL_LEG_PUBKEY, STATE_PUBKEY, VALIDATOR_LIST_PUBKEY,
+};
+use sanctum_router_core::{
+    DepositStakeQuote, MarinadeDepositSolQuoter, MarinadeDepositSolSufAccs,
+    MarinadeDepositStakeQuoter, MarinadeDepositStakeSufAccs, MarinadeOrderUnstakeQuoter,
+    MarinadeSolValueCalc, MarinadeStakeAccount, MarinadeWithdrawSolQuoter,
+    MarinadeWithdrawSolSufAccs, MarinadeWithdrawStakeQuoter, MarinadeWithdrawStakeSufAccs,
+    TokenQuote, WithdrawStakeQuote, MARINADE_STAKE_LIST_PUBKEY, SYSVAR_CLOCK,
+};
+
+use crate::{
+    get_account, get_account_data,
+    pda::{
+        find_marinade_duplication_flag_pda, find_marinade_stake_deposit_authority_pda,
+        find_marinade_stake_withdraw_authority_pda,
+    },
+    AccountSource, PoolUpdateType, SanctumRouterError,
+};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MarinadeRouterOwned {
+    pub state: Option<MarinadeState>,
+    pub validator_records: Option<Vec<ValidatorRecord>>,
+    pub msol_leg_balance: Option<u64>,
+
+    /// Lamport balance of the liquidity pool's SOL leg
+    pub sol_leg_lamports: Option<u64>,
+
+    /// Records on the stake list
+    pub stake_records: Option<Vec<MarinadeStakeRecord>>,
+
+    /// The stake account WithdrawStake splits from,
+    /// that of [`Self::stake_record_to_split`].
+    ///
+    /// Since the stake list does not contain the stake accounts' vote accounts,
+    /// this is only available after an update that fetched this one stake account,
+    /// which is only known after `stake_records` has been fetched.
+    pub stake_account: Option<MarinadeStakeAccount>,
+
+    /// Stake accounts on the stake list that were fetched and found to be
+    /// undelegated or deactivating, which WithdrawStake cannot split from
+    pub inactive_stake_accounts: Vec<[u8; 32]>,
+}
+
+/// A record on Marinade's stake list
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MarinadeStakeRecord {
+    pub stake_account: [u8; 32],
+    pub last_update_delegated_lamports: u64,
+    pub is_emergency_unstaking: bool,
+}
+
+/// Getters
+impl MarinadeRouterOwned {
+    pub fn try_state(&self) -> Result<&MarinadeState, SanctumRouterError> {
+        self.state
+            .as_ref()
+            .ok_or(SanctumRouterError::AccountMissing(STATE_PUBKEY))
+    }
+
+    pub fn try_validator_records(&self) -> Result<&[ValidatorRecord], SanctumRouterError> {
+        self.validator_records
+            .as_ref()
+            .ok_or(SanctumRouterError::AccountMissing(VALIDATOR_LIST_PUBKEY))
+            .map(|v| v.as_slice())
+    }
+
+    pub fn try_msol_leg_balance(&self) -> Result<u64, SanctumRouterError> {
+        self.msol_leg_balance
+            .ok_or(SanctumRouterError::AccountMissing(LIQ_POOL_MSOL_LEG_PUBKEY))
+    }
+
+    pub fn try_sol_leg_lamports(&self) -> Result<u64, SanctumRouterError> {
+        self.sol_leg_lamports
+            .ok_or(SanctumRouterError::AccountMissing(LIQ_POOL_SOL_LEG_PUBKEY))
+    }
+
+    pub fn try_stake_records(&self) -> Result<&[MarinadeStakeRecord], SanctumRouterError> {
+        self.stake_records
+            .as_ref()
+            .ok_or(SanctumRouterError::AccountMissing(
+                MARINADE_STAKE_LIST_PUBKEY,
+            ))
+            .map(|v| v.as_slice())
+    }
+
+    /// Stake record with the most `last_update_delegated_lamports`
+    /// that is not emergency unstaking and whose stake account is not known to be inactive
+    pub fn stake_record_to_split(&self) -> Option<&MarinadeStakeRecord> {
+        self.stake_records
+            .iter()
+            .flatten()
+            .filter(|r| {
+                !r.is_emergency_unstaking
+                    && !self.inactive_stake_accounts.contains(&r.stake_account)
+            })
+            .max_by_key(|r| r.last_update_delegated_lamports)
+    }
+
+    /// Errors with the stake account of [`Self::stake_record_to_split`] as missing
+    /// if it has not been fetched yet
+    pub fn try_stake_account(&self) -> Result<&MarinadeStakeAccount, SanctumRouterError> {
+        match self.stake_account.as_ref() {
+            Some(s) => Ok(s),
+            None => {
+                self.try_stake_records()?;
+                Err(SanctumRouterError::AccountMissing(
+                    self.stake_record_to_split()
+                        .map_or(MARINADE_STAKE_LIST_PUBKEY, |r| r.stake_account),
+                ))
+            }
+        }
+    }
+}
+
+/// SOL value
+impl MarinadeRouterOwned {
+    pub fn sol_value_calc(&self) -> Result<MarinadeSolValueCalc, SanctumRouterError> {
+        Ok(MarinadeSolValueCalc {
+            state: self.try_state()?,
+        })
+    }
+}
+
+/// DepositSol
+impl MarinadeRouterOwned {
+    pub fn deposit_sol_quoter(&self) -> Result<MarinadeDepositSolQuoter, SanctumRouterError> {
+        Ok(MarinadeDepositSolQuoter {
+            state: self.try_state()?,
+            msol_leg_balance: self.try_msol_leg_balance()?,
+        })
+    }
+
+    pub fn deposit_sol_suf_accs(&self) -> Result<MarinadeDepositSolSufAccs, SanctumRouterError> {
+        self.try_state().map(MarinadeDepositSolSufAccs::from_state)
+    }
+}
+
+/// WithdrawSol (liquid unstake)
+impl MarinadeRouterOwned {
+    pub fn withdraw_sol_quoter(&self) -> Result<MarinadeWithdrawSolQuoter, SanctumRouterError> {
+        Ok(MarinadeWithdrawSolQuoter {
+            state: self.try_state()?,
+            sol_leg_lamports: self.try_sol_leg_lamports()?,
+        })
+    }
+
+    pub fn withdraw_sol_suf_accs(&self) -> Result<MarinadeWithdrawSolSufAccs, SanctumRouterError> {
+        self.try_state().map(MarinadeWithdrawSolSufAccs::from_state)
+    }
+}
+
+/// Delayed unstake (OrderUnstake, then Claim), outside the router program
+impl MarinadeRouterOwned {
+    /// `curr_epoch` is the epoch OrderUnstake executes in
+    pub fn order_unstake_quoter(
+        &self,
+        curr_epoch: u64,
+    ) -> Result<MarinadeOrderUnstakeQuoter, SanctumRouterError> {
+        Ok(MarinadeOrderUnstakeQuoter {
+            state: self.try_state()?,
+            curr_epoch,
+        })
+    }
+}
+
+/// DepositStake
+impl MarinadeRouterOwned {
+    pub fn deposit_stake_quoter(&self) -> Result<MarinadeDepositStakeQuoter, SanctumRouterError> {
+        Ok(MarinadeDepositStakeQuoter {
+            state: self.try_state()?,
+            msol_leg_balance: self.try_msol_leg_balance()?,
+            validator_records: self.try_validator_records()?,
+        })
+    }
+
+    pub fn deposit_stake_suf_accs(
+        &self,
+        vote_account: &[u8; 32],
+    ) -> Result<MarinadeDepositStakeSufAccs, SanctumRouterError> {
+        Ok(MarinadeDepositStakeSufAccs {
+            state: self.try_state()?,
+            duplication_flag: find_marinade_duplication_flag_pda(vote_account)
+                .ok_or(SanctumRouterError::InvalidPda)?
+                .0,
+        })
+    }
+}
+
+/// WithdrawStake
+impl MarinadeRouterOwned {
+    /// Set the returned quoter's minimum delegation with
+    /// [`MarinadeWithdrawStakeQuoter::with_min_delegation`]
+    pub fn withdraw_stake_quoter(&self) -> Result<MarinadeWithdrawStakeQuoter, SanctumRouterError> {
+        Ok(MarinadeWithdrawStakeQuoter::new(
+            self.try_state()?,
+            self.try_stake_account()?,
+        ))
+    }
+
+    /// Splits from [`WithdrawStakeQuote::stake_to_split`] of `quote`,
+    /// which must be a quote of this router
+    pub fn withdraw_stake_suf_accs(
+        &self,
+        quote: &WithdrawStakeQuote,
+    ) -> Result<MarinadeWithdrawStakeSufAccs, SanctumRouterError> {
+        let stake_to_split = quote
+            .stake_to_split
+            .ok_or(SanctumRouterError::InvalidData)?;
+        Ok(MarinadeWithdrawStakeSufAccs {
+            state: self.try_state()?,
+            stake_to_split,
+            stake_withdraw_authority: find_marinade_stake_withdraw_authority_pda()
+                .ok_or(SanctumRouterError::InvalidPda)?
+                .0,
+            stake_deposit_authority: find_marinade_stake_deposit_authority_pda()
+                .ok_or(SanctumRouterError::InvalidPda)?
+                .0,
+        })
+    }
+}
+
+/// Apply
+///
+/// Update owned state to after a quote has executed so that subsequent quotes
+/// in the same simulation are consistent with it.
+///
+/// See [`MarinadeDepositSolQuoter::apply_deposit_sol`]
+/// and [`MarinadeWithdrawSolQuoter::apply_withdraw_sol`]
+impl MarinadeRouterOwned {
+    pub fn apply_deposit_sol(&mut self, quote: &TokenQuote) -> Result<(), SanctumRouterError> {
+        self.msol_leg_balance = Some(
+            self.deposit_sol_quoter()?
+                .apply_deposit_sol(quote)
+                .msol_leg_balance,
+        );
+        Ok(())
+    }
+
+    pub fn apply_deposit_stake(
+        &mut self,
+        quote: &DepositStakeQuote,
+    ) -> Result<(), SanctumRouterError> {
+        self.msol_leg_balance = Some(
+            self.deposit_stake_quoter()?
+                .apply_deposit_stake(quote)
+                .msol_leg_balance,
+        );
+        Ok(())
+    }
+
+    /// The mSOL leg is also updated if it was fetched, since liquid unstaking
+    /// transfers the input mSOL to it less the treasury's cut of the fee
+    pub fn apply_withdraw_sol(&mut self, quote: &TokenQuote) -> Result<(), SanctumRouterError> {
+        let quoter = self.withdraw_sol_quoter()?;
+        let msol_leg_inc = quote.inp - quoter.treasury_cut(quote.fee);
+        self.sol_leg_lamports = Some(quoter.apply_withdraw_sol(quote).sol_leg_lamports);
+        self.msol_leg_balance = self
+            .msol_leg_balance
+            .map(|b| b.saturating_add(msol_leg_inc));
+        Ok(())
+    }
+
+    /// Withdrawing stake does not change the mSOL price,
+    /// only the active stake of the stake account that was split from,
+    /// [`WithdrawStakeQuote::stake_to_split`] of `quote`.
+    ///
+    /// A following `withdraw_stake_quoter()` still splits from the same stake account
+    /// until the next update.
+    pub fn apply_withdraw_stake(
+        &mut self,
+        quote: &WithdrawStakeQuote,
+    ) -> Result<(), SanctumRouterError> {
+        let addr = quote
+            .stake_to_split
+            .ok_or(SanctumRouterError::InvalidData)?;
+        let stake_account = self
+            .stake_account
+            .as_mut()
+            .filter(|s| s.addr == addr)
+            .ok_or(SanctumRouterError::InvalidData)?;
+        let delegated_lamports = stake_account
+            .delegated_lamports
+            .checked_sub(quote.out.lamports.staked)
+            .ok_or(SanctumRouterError::Marinade(
+                MarinadeError::SelectedStakeAccountHasNotEnoughFunds,
+            ))?;
+        stake_account.delegated_lamports = delegated_lamports;
+        if let Some(r) = self
+            .stake_records
+            .iter_mut()
+            .flatten()
+            .find(|r| r.stake_account == addr)
+        {
+            r.last_update_delegated_lamports = delegated_lamports;
+        }
+        Ok(())
+    }
+}
+
+/// Update
+impl MarinadeRouterOwned {
+    pub fn update_state(&mut self, data: &[u8]) -> Result<(), SanctumRouterError> {
+        self.state =
+            Some(MarinadeState::borsh_de(data).map_err(|_e| SanctumRouterError::InvalidData)?);
+        Ok(())
+    }
+
+    pub fn update_validator_records(
+        &mut self,
+        validator_list_data: &[u8],
+        count: usize,
+    ) -> Result<(), SanctumRouterError> {
+        let validator_list = ValidatorList::try_from_acc_data(validator_list_data, count)
+            .ok_or(SanctumRouterError::InvalidData)?;
+
+        self.validator_records = Some(validator_list.0.to_vec());
+        Ok(())
+    }
+
+    pub fn update_msol_leg_balance(
+        &mut self,
+        msol_leg_data: &[u8],
+    ) -> Result<(), SanctumRouterError> {
+        self.msol_leg_balance = Some(try_token_acc_amt(msol_leg_data)?);
+        Ok(())
+    }
+
+    pub fn update_sol_leg_lamports(&mut self, sol_leg_lamports: u64) {
+        self.sol_leg_lamports = Some(sol_leg_lamports);
+    }
+
+    /// `item_size` and `count` are those of `state.stake_system.stake_list`.
+    ///
+    /// Errors if `item_size` is not the size of the stake record layout
+    /// this parses, in case marinade changes it.
+    pub fn update_stake_records(
+        &mut self,
+        stake_list_data: &[u8],
+        item_size: usize,
+        count: usize,
+    ) -> Result<(), SanctumRouterError> {
+        if item_size != STAKE_RECORD_LEN {
+            return Err(SanctumRouterError::InvalidData);
+        }
+        let records = stake_list_data
+            .get(STAKE_LIST_DISCM_LEN..)
+            .and_then(|d| d.get(..count.checked_mul(item_size)?))
+            .ok_or(SanctumRouterError::InvalidData)?;
+        self.stake_records = Some(
+            records
+                .chunks_exact(item_size)
+                .map(|r| MarinadeStakeRecord {
+                    // unwrap-safety: r.len() == STAKE_RECORD_LEN
+                    stake_account: *r.first_chunk().unwrap(),
+                    last_update_delegated_lamports: u64::from_le_bytes(
+                        *r[STAKE_RECORD_LAST_UPDATE_DELEGATED_LAMPORTS_OFFSET..]
+                            .first_chunk()
+                            .unwrap(),
+                    ),
+                    is_emergency_unstaking: r[STAKE_RECORD_IS_EMERGENCY_UNSTAKING_OFFSET] != 0,
+                })
+                .collect(),
+        );
+        Ok(())
+    }
+
+    /// Sets `stake_account` to the stake account of [`Self::stake_record_to_split`]
+    /// if it is in `accounts`, `None` otherwise.
+    ///
+    /// If that stake account is undelegated or deactivating, it is added to
+    /// `inactive_stake_accounts` and `stake_account` is set to `None`,
+    /// so that the next stake record is fetched in the following update instead.
+    pub fn update_stake_account<A: AccountSource + ?Sized>(
+        &mut self,
+        accounts: &A,
+    ) -> Result<(), SanctumRouterError> {
+        self.try_stake_records()?;
+        self.stake_account = None;
+        let Some(r) = self.stake_record_to_split().copied() else {
+            return Ok(());
+        };
+        let Ok(data) = get_account_data(accounts, &r.stake_account) else {
+            return Ok(());
+        };
+        match active_stake_acc_vote(data) {
+            Some(vote) => {
+                self.stake_account = Some(MarinadeStakeAccount {
+                    addr: r.stake_account,
+                    vote,
+                    delegated_lamports: r.last_update_delegated_lamports,
+                    is_emergency_unstaking: r.is_emergency_unstaking,
+                });
+            }
+            None => self.inactive_stake_accounts.push(r.stake_account),
+        }
+        Ok(())
+    }
+
+    /// WithdrawStake requires 2 rounds of fetching accounts and updating
+    /// for the first update, since the one stake account to split from
+    /// is only known after the stake list has been fetched.
+    /// Subsequent updates fetch at most 3 accounts in a single round.
+    pub fn accounts_to_update(&self, ty: PoolUpdateType) -> impl Iterator<Item = [u8; 32]> + '_ {
+        match ty {
+            PoolUpdateType::DepositSol => {
+                [Some(STATE_PUBKEY), Some(LIQ_POOL_MSOL_LEG_PUBKEY), None]
+            }
+            PoolUpdateType::WithdrawSol => {
+                [Some(STATE_PUBKEY), Some(LIQ_POOL_SOL_LEG_PUBKEY), None]
+            }
+            // clock for delayed unstake tickets' claimable epoch
+            PoolUpdateType::DelayedUnstake => {
+                [STATE_PUBKEY, LIQ_POOL_SOL_LEG_PUBKEY, SYSVAR_CLOCK].map(Some)
+            }
+            PoolUpdateType::DepositStake => [
+                STATE_PUBKEY,
+                LIQ_POOL_MSOL_LEG_PUBKEY,
+                VALIDATOR_LIST_PUBKEY,
+            ]
+            .map(Some),
+            PoolUpdateType::WithdrawStake => [
+                Some(STATE_PUBKEY),
+                Some(MARINADE_STAKE_LIST_PUBKEY),
+                self.stake_record_to_split().map(|r| r.stake_account),
+            ],
+        }
+        .into_iter()
+        .flatten()
+    }
+
+    pub fn update<A: AccountSource + ?Sized>(
+        &mut self,
+        ty: PoolUpdateType,
+        accounts: &A,
+    ) -> Result<(), SanctumRouterError> {
+        match ty {
+            PoolUpdateType::DepositSol | PoolUpdateType::DepositStake => {
+                let [s, m] = [
+                    sanctum_marinade_liquid_staking_core::STATE_PUBKEY,
+                    sanctum_marinade_liquid_staking_core::LIQ_POOL_MSOL_LEG_PUBKEY,
+                ]
+                .map(|k| get_account_data(accounts, &k));
+                let state_data = s?;
+                let msol_leg_data = m?;
+
+                self.update_state(state_data)?;
+                self.update_msol_leg_balance(msol_leg_data)?;
+
+                if matches!(ty, PoolUpdateType::DepositStake) {
+                    let validator_records_data = get_account_data(
+                        accounts,
+                        &sanctum_marinade_liquid_staking_core::VALIDATOR_LIST_PUBKEY,
+                    )?;
+                    self.update_validator_records(
+                        validator_records_data,
+                        // unwrap-safety: state was just updated above
+                        self.try_state()
+                            .unwrap()
+                            .validator_system
+                            .validator_list
+                            .len() as usize,
+                    )?;
+                }
+
+                Ok(())
+            }
+            PoolUpdateType::WithdrawSol | PoolUpdateType::DelayedUnstake => {
+                let [s, l] =
+                    [STATE_PUBKEY, LIQ_POOL_SOL_LEG_PUBKEY].map(|k| get_account(accounts, &k));
+                let state_data = s?.data;
+                let sol_leg_lamports = l?.lamports;
+
+                self.update_state(state_data)?;
+                self.update_sol_leg_lamports(sol_leg_lamports);
+
+                Ok(())
+            }
+            PoolUpdateType::WithdrawStake => {
+                let [s, l] = [STATE_PUBKEY, MARINADE_STAKE_LIST_PUBKEY]
+                    .map(|k| get_account_data(accounts, &k));
+                let state_data = s?;
+                let stake_list_data = l?;
+
+                self.update_state(state_data)?;
+                // unwrap-safety: state was just updated above
+                let stake_list = &self.try_state().unwrap().stake_system.stake_list;
+                let (item_size, count) = (stake_list.item_size as usize, stake_list.len() as usize);
+                self.update_stake_records(stake_list_data, item_size, count)?;
+                self.update_stake_account(accounts)?;
+
+                Ok(())
+            }
+        }
+    }
+}
+
+const STAKE_LIST_DISCM_LEN: usize = 8;
+
+/// `state.stake_system.stake_list.item_size` of the stake record layout:
+/// stake account (32), last update delegated lamports (8), last update epoch (8),
+/// is emergency unstaking (1), padding
+const STAKE_RECORD_LEN: usize = 56;
+const STAKE_RECORD_LAST_UPDATE_DELEGATED_LAMPORTS_OFFSET: usize = 32;
+const STAKE_RECORD_IS_EMERGENCY_UNSTAKING_OFFSET: usize = 48;
+
+/// Returns the vote account `d` is delegated to if `d` is the data of
+/// a delegated stake account that is not deactivating
+fn active_stake_acc_vote(d: &[u8]) -> Option<[u8; 32]> {
+    const STAKE_STATE_STAKE: u32 = 2;
+    const VOTER_OFFSET: usize = 124;
+    const DEACTIVATION_EPOCH_OFFSET: usize = 172;
+
+    if u32::from_le_bytes(*d.first_chunk()?) != STAKE_STATE_STAKE {
+        return None;
+    }
+    let deactivation_epoch =
+        u64::from_le_bytes(*d.get(DEACTIVATION_EPOCH_OFFSET..)?.first_chunk()?);
+    if deactivation_epoch != u64::MAX {
+        return None;
+    }
+    d.get(VOTER_OFFSET..)?.first_chunk().copied()
+}
+
+fn try_token_acc_amt(d: &[u8]) -> Result<u64, SanctumRouterError> {
+    Ok(u64::from_le_bytes(
+        *d.get(..72)
+            .and_then(|s| s.last_chunk())
+            .ok_or(SanctumRouterError::InvalidData)?,
+    ))
+}
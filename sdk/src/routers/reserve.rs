@@ -0,0 +1,195 @@
+use sanctum_reserve_core::{Fee, FeeEnum, Pool, PoolBalance, ProtocolFee};
+use sanctum_router_core::{
+    DepositStakeQuote, RentParams, ReserveDepositStakeQuoter, ReserveDepositStakeSufAccs,
+    RouterConfig, NATIVE_MINT,
+};
+
+use crate::{
+    get_account, get_account_data, pda::find_reserve_stake_account_record_pda, AccountSource,
+    PoolUpdateType, SanctumRouterError,
+};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ReserveRouterOwned(pub Option<ReserveRouterInner>);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReserveRouterInner {
+    pub pool: Pool,
+    pub fee_account: Fee,
+    pub protocol_fee_account: ProtocolFee,
+    pub pool_sol_reserves: u64,
+}
+
+/// Init
+impl ReserveRouterOwned {
+    pub const fn init_accounts(config: &RouterConfig) -> [[u8; 32]; 4] {
+        [
+            config.reserve.pool,
+            config.reserve.fee,
+            config.reserve.protocol_fee,
+            config.reserve.pool_sol_reserves,
+        ]
+    }
+
+    pub fn init<A: AccountSource + ?Sized>(
+        config: &RouterConfig,
+        accounts: &A,
+    ) -> Result<Self, SanctumRouterError> {
+        let [p, f, pf] = [
+            config.reserve.pool,
+            config.reserve.fee,
+            config.reserve.protocol_fee,
+        ]
+        .map(|pk| get_account_data(accounts, &pk));
+        let pool_data = p?;
+        let fee_data = f?;
+        let protocol_fee_data = pf?;
+
+        let pool = Pool::anchor_de(pool_data).map_err(|_e| SanctumRouterError::InvalidData)?;
+        let fee_account = Fee::anchor_de(fee_data).map_err(|_e| SanctumRouterError::InvalidData)?;
+        let protocol_fee_account = ProtocolFee::anchor_de(protocol_fee_data)
+            .map_err(|_e| SanctumRouterError::InvalidData)?;
+        let pool_sol_reserves = get_account(accounts, &config.reserve.pool_sol_reserves)?.lamports;
+
+        Ok(Self(Some(ReserveRouterInner {
+            pool,
+            fee_account,
+            protocol_fee_account,
+            pool_sol_reserves,
+        })))
+    }
+}
+
+/// Getters
+impl ReserveRouterOwned {
+    pub fn try_inner(&self) -> Result<&ReserveRouterInner, SanctumRouterError> {
+        self.0.as_ref().ok_or(SanctumRouterError::AccountMissing(
+            sanctum_reserve_core::POOL,
+        ))
+    }
+}
+
+/// DepositStake
+impl ReserveRouterOwned {
+    pub fn deposit_stake_quoter(&self) -> Result<ReserveDepositStakeQuoter, SanctumRouterError> {
+        let inner = self.try_inner()?;
+        Ok(ReserveDepositStakeQuoter {
+            pool_incoming_stake: inner.pool.incoming_stake,
+            fee_account: &inner.fee_account.0,
+            protocol_fee_account: &inner.protocol_fee_account,
+            pool_sol_reserves: inner.pool_sol_reserves,
+        })
+    }
+
+    /// Returns `None` if stake acc record PDA invalid
+    pub fn deposit_stake_suf_accs(
+        &self,
+        config: &RouterConfig,
+        stake_account_addr: &[u8; 32],
+    ) -> Result<ReserveDepositStakeSufAccs, SanctumRouterError> {
+        Ok(ReserveDepositStakeSufAccs {
+            stake_acc_record_addr: find_reserve_stake_account_record_pda(
+                config,
+                stake_account_addr,
+            )
+            .ok_or(SanctumRouterError::InvalidPda)?
+            .0,
+            reserve: config.reserve,
+        })
+    }
+}
+
+/// Prefund
+impl ReserveRouterOwned {
+    pub fn prefund_params(&self) -> Result<(PoolBalance, &FeeEnum), SanctumRouterError> {
+        let inner = self.try_inner()?;
+        Ok((
+            PoolBalance {
+                pool_incoming_stake: inner.pool.incoming_stake,
+                sol_reserves_lamports: inner.pool_sol_reserves,
+            },
+            &inner.fee_account.0,
+        ))
+    }
+}
+
+/// Apply
+///
+/// Update owned state to after a quote has executed so that subsequent quotes
+/// in the same simulation are consistent with it.
+impl ReserveRouterOwned {
+    pub fn apply_deposit_stake(
+        &mut self,
+        quote: &DepositStakeQuote,
+    ) -> Result<(), SanctumRouterError> {
+        let ReserveDepositStakeQuoter {
+            pool_incoming_stake,
+            pool_sol_reserves,
+            ..
+        } = self
+            .deposit_stake_quoter()?
+            .apply_deposit_stake(quote)
+            .map_err(SanctumRouterError::Reserve)?;
+        self.set_pool_balance(pool_incoming_stake, pool_sol_reserves)
+    }
+
+    /// Applies the instant unstake of the slumdog stake that repays
+    /// the flash loan of a prefunded WithdrawStake
+    pub fn apply_prefund(&mut self, rent: &RentParams) -> Result<(), SanctumRouterError> {
+        let ReserveDepositStakeQuoter {
+            pool_incoming_stake,
+            pool_sol_reserves,
+            ..
+        } = self
+            .deposit_stake_quoter()?
+            .after_prefund(rent)
+            .map_err(SanctumRouterError::Reserve)?;
+        self.set_pool_balance(pool_incoming_stake, pool_sol_reserves)
+    }
+
+    fn set_pool_balance(
+        &mut self,
+        pool_incoming_stake: u64,
+        pool_sol_reserves: u64,
+    ) -> Result<(), SanctumRouterError> {
+        let inner = self.0.as_mut().ok_or(SanctumRouterError::AccountMissing(
+            sanctum_reserve_core::POOL,
+        ))?;
+        inner.pool.incoming_stake = pool_incoming_stake;
+        inner.pool_sol_reserves = pool_sol_reserves;
+        Ok(())
+    }
+}
+
+/// Update
+impl ReserveRouterOwned {
+    pub fn accounts_to_update(
+        config: &RouterConfig,
+        ty: PoolUpdateType,
+    ) -> impl Iterator<Item = [u8; 32]> {
+        match ty {
+            PoolUpdateType::DepositStake => Self::init_accounts(config).map(Some),
+            _ => [None; 4],
+        }
+        .into_iter()
+        .flatten()
+    }
+
+    pub fn update<A: AccountSource + ?Sized>(
+        &mut self,
+        config: &RouterConfig,
+        ty: PoolUpdateType,
+        accounts: &A,
+    ) -> Result<(), SanctumRouterError> {
+        match ty {
+            PoolUpdateType::DepositStake => {
+                *self = Self::init(config, accounts)?;
+                Ok(())
+            }
+            _ => Err(SanctumRouterError::UnsupportedUpdate {
+                ty,
+                mint: NATIVE_MINT,
+            }),
+        }
+    }
+}
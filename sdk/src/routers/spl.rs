@@ -1,7 +1,7 @@
-use bs58_fixed_wasm::Bs58Array;
 use sanctum_router_core::{
-    SplDepositSolQuoter, SplDepositStakeQuoter, SplDepositStakeSufAccs, SplSolSufAccs,
-    SplWithdrawSolQuoter, SplWithdrawStakeQuoter, SplWithdrawStakeSufAccs,
+    DepositStakeQuote, SplDepositSolQuoter, SplDepositStakeQuoter, SplDepositStakeSufAccs,
+    SplSolSufAccs, SplSolValueCalc, SplWithdrawSolQuoter, SplWithdrawStakeQuoter,
+    SplWithdrawStakeSufAccs, TokenQuote, WithdrawStakeQuote,
 };
 use sanctum_spl_stake_pool_core::{
     SplStakePoolError, StakePool, ValidatorList, ValidatorListHeader, ValidatorStakeInfo,
@@ -9,14 +9,9 @@ use sanctum_spl_stake_pool_core::{
 };
 
 use crate::{
-    err::{account_missing_err, invalid_data_err, invalid_pda_err, spl_err, SanctumRouterError},
-    init::{InitData, SplInitData},
-    interface::{get_account, get_account_data, AccountMap},
-    pda::spl::{
-        find_deposit_auth_pda_internal, find_validator_stake_account_pda_internal,
-        find_withdraw_auth_pda_internal,
-    },
-    update::PoolUpdateType,
+    get_account, get_account_data,
+    pda::{find_deposit_auth_pda, find_validator_stake_account_pda, find_withdraw_auth_pda},
+    AccountSource, InitData, PoolUpdateType, SanctumRouterError, SplInitData,
 };
 
 #[derive(Clone, Debug, Default, PartialEq)]
@@ -52,10 +47,10 @@ pub struct ValidatorListOwned {
 impl SplStakePoolRouterOwned {
     pub fn init(
         InitData::Spl(SplInitData {
-            stake_pool_program_addr: Bs58Array(stake_pool_program_addr),
-            stake_pool_addr: Bs58Array(stake_pool_addr),
-            validator_list_addr: Bs58Array(validator_list_addr),
-            reserve_stake_addr: Bs58Array(reserve_stake_addr),
+            stake_pool_program_addr,
+            stake_pool_addr,
+            validator_list_addr,
+            reserve_stake_addr,
         }): &InitData,
     ) -> Result<Self, SanctumRouterError> {
         Ok(SplStakePoolRouterOwned {
@@ -63,17 +58,17 @@ impl SplStakePoolRouterOwned {
             stake_pool_addr: *stake_pool_addr,
             validator_list_addr: *validator_list_addr,
             reserve_stake_addr: *reserve_stake_addr,
-            deposit_authority_program_address: find_deposit_auth_pda_internal(
+            deposit_authority_program_address: find_deposit_auth_pda(
                 stake_pool_program_addr,
                 stake_pool_addr,
             )
-            .ok_or_else(invalid_pda_err)?
+            .ok_or(SanctumRouterError::InvalidPda)?
             .0,
-            withdraw_authority_program_address: find_withdraw_auth_pda_internal(
+            withdraw_authority_program_address: find_withdraw_auth_pda(
                 stake_pool_program_addr,
                 stake_pool_addr,
             )
-            .ok_or_else(invalid_pda_err)?
+            .ok_or(SanctumRouterError::InvalidPda)?
             .0,
             stake_pool: Default::default(),
             validator_list: Default::default(),
@@ -87,19 +82,19 @@ impl SplStakePoolRouterOwned {
     pub fn try_stake_pool(&self) -> Result<&StakePool, SanctumRouterError> {
         self.stake_pool
             .as_ref()
-            .ok_or_else(|| account_missing_err(&self.stake_pool_addr))
+            .ok_or(SanctumRouterError::AccountMissing(self.stake_pool_addr))
     }
 
     pub fn try_validator_list(&self) -> Result<&[ValidatorStakeInfo], SanctumRouterError> {
         self.validator_list
             .as_ref()
-            .ok_or_else(|| account_missing_err(&self.validator_list_addr))
+            .ok_or(SanctumRouterError::AccountMissing(self.validator_list_addr))
             .map(|vl| vl.validators.as_slice())
     }
 
     pub fn try_reserve_stake_lamports(&self) -> Result<u64, SanctumRouterError> {
         self.reserve_stake_lamports
-            .ok_or_else(|| account_missing_err(&self.reserve_stake_addr))
+            .ok_or(SanctumRouterError::AccountMissing(self.reserve_stake_addr))
     }
 }
 
@@ -115,6 +110,15 @@ impl SplStakePoolRouterOwned {
     }
 }
 
+/// SOL value
+impl SplStakePoolRouterOwned {
+    pub fn sol_value_calc(&self) -> Result<SplSolValueCalc, SanctumRouterError> {
+        Ok(SplSolValueCalc {
+            stake_pool: self.try_stake_pool()?,
+        })
+    }
+}
+
 /// DepositSol
 impl SplStakePoolRouterOwned {
     pub fn deposit_sol_quoter(
@@ -164,18 +168,18 @@ impl SplStakePoolRouterOwned {
             .try_validator_list()?
             .iter()
             .find(|v| v.vote_account_address() == vote_account)
-            .ok_or_else(|| spl_err(SplStakePoolError::ValidatorNotFound))?;
+            .ok_or_else(|| SanctumRouterError::Spl(SplStakePoolError::ValidatorNotFound))?;
         Ok(SplDepositStakeSufAccs {
             stake_pool_addr: &self.stake_pool_addr,
             stake_pool_program: &self.stake_pool_program,
             stake_pool: self.try_stake_pool()?,
-            validator_stake: find_validator_stake_account_pda_internal(
+            validator_stake: find_validator_stake_account_pda(
                 &self.stake_pool_program,
                 validator_stake_info.vote_account_address(),
                 &self.stake_pool_addr,
                 validator_stake_info.validator_seed_suffix(),
             )
-            .ok_or_else(invalid_pda_err)?
+            .ok_or(SanctumRouterError::InvalidPda)?
             .0,
             stake_deposit_authority: &self.deposit_authority_program_address,
             stake_withdraw_authority: &self.withdraw_authority_program_address,
@@ -205,29 +209,141 @@ impl SplStakePoolRouterOwned {
             .try_validator_list()?
             .iter()
             .find(|v| v.vote_account_address() == vote_account)
-            .ok_or_else(|| spl_err(SplStakePoolError::ValidatorNotFound))?;
+            .ok_or_else(|| SanctumRouterError::Spl(SplStakePoolError::ValidatorNotFound))?;
         Ok(SplWithdrawStakeSufAccs {
             stake_pool_addr: &self.stake_pool_addr,
             stake_pool_program: &self.stake_pool_program,
             stake_pool: self.try_stake_pool()?,
-            validator_stake: find_validator_stake_account_pda_internal(
+            validator_stake: find_validator_stake_account_pda(
                 &self.stake_pool_program,
                 validator_stake_info.vote_account_address(),
                 &self.stake_pool_addr,
                 validator_stake_info.validator_seed_suffix(),
             )
-            .ok_or_else(invalid_pda_err)?
+            .ok_or(SanctumRouterError::InvalidPda)?
             .0,
             stake_withdraw_authority: &self.withdraw_authority_program_address,
         })
     }
 }
 
+/// Apply
+///
+/// Update owned state to after a quote has executed so that subsequent quotes
+/// in the same simulation are consistent with it.
+///
+/// Deposit fees are minted to the fee accounts as pool tokens
+/// and therefore count towards `pool_token_supply`.
+impl SplStakePoolRouterOwned {
+    pub fn apply_deposit_sol(
+        &mut self,
+        TokenQuote { inp, out, fee }: &TokenQuote,
+    ) -> Result<(), SanctumRouterError> {
+        let stake_pool = self.try_stake_pool_mut()?;
+        stake_pool.total_lamports = checked_add(stake_pool.total_lamports, *inp)?;
+        stake_pool.pool_token_supply =
+            checked_add(stake_pool.pool_token_supply, checked_add(*out, *fee)?)?;
+        if let Some(r) = self.reserve_stake_lamports.as_mut() {
+            *r = checked_add(*r, *inp)?;
+        }
+        Ok(())
+    }
+
+    pub fn apply_withdraw_sol(
+        &mut self,
+        TokenQuote { inp, out, fee }: &TokenQuote,
+    ) -> Result<(), SanctumRouterError> {
+        let stake_pool = self.try_stake_pool_mut()?;
+        stake_pool.total_lamports = checked_sub(stake_pool.total_lamports, *out)?;
+        // withdrawal fee is transferred to the manager, only the rest is burnt
+        stake_pool.pool_token_supply =
+            checked_sub(stake_pool.pool_token_supply, checked_sub(*inp, *fee)?)?;
+        if let Some(r) = self.reserve_stake_lamports.as_mut() {
+            *r = checked_sub(*r, *out)?;
+        }
+        Ok(())
+    }
+
+    /// The staked lamports are merged into the validator's stake account,
+    /// while unstaked lamports go to the reserve.
+    pub fn apply_deposit_stake(
+        &mut self,
+        DepositStakeQuote { inp, out, fee }: &DepositStakeQuote,
+    ) -> Result<(), SanctumRouterError> {
+        let vsi = self.try_validator_mut(&inp.vote)?;
+        vsi.set_active_stake_lamports(checked_add(
+            vsi.active_stake_lamports(),
+            inp.lamports.staked,
+        )?);
+
+        let stake_pool = self.try_stake_pool_mut()?;
+        stake_pool.total_lamports = checked_add(stake_pool.total_lamports, inp.lamports.total())?;
+        stake_pool.pool_token_supply =
+            checked_add(stake_pool.pool_token_supply, checked_add(*out, *fee)?)?;
+        if let Some(r) = self.reserve_stake_lamports.as_mut() {
+            *r = checked_add(*r, inp.lamports.unstaked)?;
+        }
+        Ok(())
+    }
+
+    pub fn apply_withdraw_stake(
+        &mut self,
+        WithdrawStakeQuote { inp, out, fee, .. }: &WithdrawStakeQuote,
+    ) -> Result<(), SanctumRouterError> {
+        let vsi = self.try_validator_mut(&out.vote)?;
+        vsi.set_active_stake_lamports(checked_sub(
+            vsi.active_stake_lamports(),
+            out.lamports.staked,
+        )?);
+
+        let stake_pool = self.try_stake_pool_mut()?;
+        stake_pool.total_lamports = checked_sub(stake_pool.total_lamports, out.lamports.total())?;
+        // withdrawal fee is transferred to the manager, only the rest is burnt
+        stake_pool.pool_token_supply =
+            checked_sub(stake_pool.pool_token_supply, checked_sub(*inp, *fee)?)?;
+        Ok(())
+    }
+
+    fn try_stake_pool_mut(&mut self) -> Result<&mut StakePool, SanctumRouterError> {
+        self.stake_pool
+            .as_mut()
+            .ok_or(SanctumRouterError::AccountMissing(self.stake_pool_addr))
+    }
+
+    fn try_validator_mut(
+        &mut self,
+        vote_account: &[u8; 32],
+    ) -> Result<&mut ValidatorStakeInfo, SanctumRouterError> {
+        self.validator_list
+            .as_mut()
+            .ok_or(SanctumRouterError::AccountMissing(self.validator_list_addr))?
+            .validators
+            .iter_mut()
+            .find(|v| v.vote_account_address() == vote_account)
+            .ok_or(SanctumRouterError::Spl(
+                SplStakePoolError::ValidatorNotFound,
+            ))
+    }
+}
+
+fn checked_add(a: u64, b: u64) -> Result<u64, SanctumRouterError> {
+    a.checked_add(b).ok_or(SanctumRouterError::Spl(
+        SplStakePoolError::CalculationFailure,
+    ))
+}
+
+fn checked_sub(a: u64, b: u64) -> Result<u64, SanctumRouterError> {
+    a.checked_sub(b).ok_or(SanctumRouterError::Spl(
+        SplStakePoolError::CalculationFailure,
+    ))
+}
+
 /// Update
 impl SplStakePoolRouterOwned {
     pub fn update_stake_pool(&mut self, stake_pool_data: &[u8]) -> Result<(), SanctumRouterError> {
-        self.stake_pool =
-            Some(StakePool::borsh_de(stake_pool_data).map_err(|_e| invalid_data_err())?);
+        self.stake_pool = Some(
+            StakePool::borsh_de(stake_pool_data).map_err(|_e| SanctumRouterError::InvalidData)?,
+        );
         Ok(())
     }
 
@@ -235,8 +351,8 @@ impl SplStakePoolRouterOwned {
         &mut self,
         validator_list_data: &[u8],
     ) -> Result<(), SanctumRouterError> {
-        let validator_list =
-            ValidatorList::deserialize(validator_list_data).map_err(|_e| invalid_data_err())?;
+        let validator_list = ValidatorList::deserialize(validator_list_data)
+            .map_err(|_e| SanctumRouterError::InvalidData)?;
         self.validator_list = Some(ValidatorListOwned {
             header: validator_list.header,
             validators: validator_list.validators.to_vec(),
@@ -261,30 +377,35 @@ impl SplStakePoolRouterOwned {
                 Some(self.validator_list_addr),
                 None,
             ],
+            PoolUpdateType::DelayedUnstake => [None; 4],
         }
         .into_iter()
         .flatten()
     }
 
-    pub fn update(
+    pub fn update<A: AccountSource + ?Sized>(
         &mut self,
         ty: PoolUpdateType,
-        accounts: &AccountMap,
+        accounts: &A,
     ) -> Result<(), SanctumRouterError> {
-        let stake_pool_data = get_account_data(accounts, self.stake_pool_addr)?;
+        let stake_pool_data = get_account_data(accounts, &self.stake_pool_addr)?;
         self.update_stake_pool(stake_pool_data)?;
 
         match ty {
             PoolUpdateType::DepositSol => Ok(()),
             PoolUpdateType::WithdrawSol => {
                 self.reserve_stake_lamports =
-                    Some(get_account(accounts, self.reserve_stake_addr)?.lamports);
+                    Some(get_account(accounts, &self.reserve_stake_addr)?.lamports);
                 Ok(())
             }
             PoolUpdateType::DepositStake | PoolUpdateType::WithdrawStake => {
-                let validator_list_data = get_account_data(accounts, self.validator_list_addr)?;
+                let validator_list_data = get_account_data(accounts, &self.validator_list_addr)?;
                 self.update_validator_list(validator_list_data)
             }
+            PoolUpdateType::DelayedUnstake => Err(SanctumRouterError::UnsupportedUpdate {
+                ty,
+                mint: self.try_stake_pool()?.pool_mint,
+            }),
         }
     }
 }
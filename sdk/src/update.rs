@@ -0,0 +1,110 @@
+use sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR;
+use sanctum_router_core::{PoolUpdateType, NATIVE_MINT};
+
+/// - `inp` input mint
+/// - `out` output mint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SwapMints {
+    DepositSol { out: [u8; 32] },
+    DepositStake { out: [u8; 32] },
+    PrefundSwapViaStake { inp: [u8; 32], out: [u8; 32] },
+    SwapViaStake { inp: [u8; 32], out: [u8; 32] },
+    WithdrawSol { inp: [u8; 32] },
+    PrefundWithdrawStake { inp: [u8; 32] },
+    MarinadeDelayedUnstake,
+}
+
+pub type IntoPoolUpdateIter = core::iter::Flatten<core::array::IntoIter<Option<PoolUpdate>, 3>>;
+
+impl SwapMints {
+    #[inline]
+    pub fn into_pool_updates(self) -> IntoPoolUpdateIter {
+        match self {
+            SwapMints::DepositSol { out } => [
+                Some(PoolUpdate {
+                    mint: out,
+                    ty: PoolUpdateType::DepositSol,
+                }),
+                None,
+                None,
+            ],
+
+            SwapMints::DepositStake { out } => [
+                Some(PoolUpdate {
+                    mint: out,
+                    ty: PoolUpdateType::DepositStake,
+                }),
+                None,
+                None,
+            ],
+
+            SwapMints::PrefundSwapViaStake { inp, out } => [
+                PoolUpdate {
+                    mint: inp,
+                    ty: PoolUpdateType::WithdrawStake,
+                },
+                PoolUpdate {
+                    mint: out,
+                    ty: PoolUpdateType::DepositStake,
+                },
+                // reserve pool for prefund
+                PoolUpdate {
+                    mint: NATIVE_MINT,
+                    ty: PoolUpdateType::DepositStake,
+                },
+            ]
+            .map(Some),
+
+            SwapMints::SwapViaStake { inp, out } => [
+                Some(PoolUpdate {
+                    mint: inp,
+                    ty: PoolUpdateType::WithdrawStake,
+                }),
+                Some(PoolUpdate {
+                    mint: out,
+                    ty: PoolUpdateType::DepositStake,
+                }),
+                None,
+            ],
+
+            SwapMints::WithdrawSol { inp } => [
+                Some(PoolUpdate {
+                    mint: inp,
+                    ty: PoolUpdateType::WithdrawSol,
+                }),
+                None,
+                None,
+            ],
+
+            SwapMints::PrefundWithdrawStake { inp } => [
+                Some(PoolUpdate {
+                    mint: inp,
+                    ty: PoolUpdateType::WithdrawStake,
+                }),
+                // reserve pool for prefund
+                Some(PoolUpdate {
+                    mint: NATIVE_MINT,
+                    ty: PoolUpdateType::DepositStake,
+                }),
+                None,
+            ],
+
+            SwapMints::MarinadeDelayedUnstake => [
+                Some(PoolUpdate {
+                    mint: MSOL_MINT_ADDR,
+                    ty: PoolUpdateType::DelayedUnstake,
+                }),
+                None,
+                None,
+            ],
+        }
+        .into_iter()
+        .flatten()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PoolUpdate {
+    pub mint: [u8; 32],
+    pub ty: PoolUpdateType,
+}
@@ -0,0 +1,226 @@
+//! Quote, apply the quote, then quote again,
+//! checking that the owned state moved by exactly what the quote says
+
+use sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR;
+use sanctum_router::SwapMints;
+use sanctum_router_core::{
+    ActiveStakeParams, DepositSolQuoter, DepositStakeQuoter, StakeAccountLamports,
+    WithdrawSolQuoter, WithdrawStakeQuoter,
+};
+
+mod common;
+
+use common::*;
+
+const ONE_SOL: u64 = 1_000_000_000;
+
+/// Offset of `Delegation::voter_pubkey` in a delegated stake account's data
+const STAKE_ACC_VOTER_OFFSET: usize = 124;
+
+#[test]
+fn spl_apply_withdraw_sol_bsol() {
+    let accounts = fixtures_accounts(BSOL_EPOCH);
+    let init = spl_init_mint("bsol-stake-pool");
+    let inp = init.mint;
+    let mut router = updated_router([init], &[SwapMints::WithdrawSol { inp }], &accounts);
+    let curr_epoch = router.try_curr_epoch().unwrap();
+    let spl = router.spl_routers.get_mut(&inp).unwrap();
+
+    let (total_lamports_before, supply_before) = {
+        let p = spl.try_stake_pool().unwrap();
+        (p.total_lamports, p.pool_token_supply)
+    };
+    let reserve_before = spl.try_reserve_stake_lamports().unwrap();
+    let q1 = spl
+        .withdraw_sol_quoter(curr_epoch)
+        .unwrap()
+        .quote_withdraw_sol(ONE_SOL)
+        .unwrap();
+
+    spl.apply_withdraw_sol(&q1).unwrap();
+
+    let after = spl.try_stake_pool().unwrap();
+    assert_eq!(total_lamports_before - after.total_lamports, q1.out);
+    // the withdrawal fee is transferred to the manager instead of burnt
+    assert_eq!(supply_before - after.pool_token_supply, q1.inp - q1.fee);
+    assert_eq!(
+        reserve_before - spl.try_reserve_stake_lamports().unwrap(),
+        q1.out
+    );
+
+    let q2 = spl
+        .withdraw_sol_quoter(curr_epoch)
+        .unwrap()
+        .quote_withdraw_sol(ONE_SOL)
+        .unwrap();
+    // the fee stays in the pool, so the exchange rate can only go up
+    assert!(q2.out >= q1.out);
+}
+
+#[test]
+fn marinade_apply_deposit_sol() {
+    let accounts = fixtures_accounts(BSOL_EPOCH);
+    let mut router = updated_router(
+        [],
+        &[SwapMints::DepositSol {
+            out: MSOL_MINT_ADDR,
+        }],
+        &accounts,
+    );
+    let marinade = &mut router.marinade_router;
+
+    let leg_before = marinade.try_msol_leg_balance().unwrap();
+    let q1 = marinade
+        .deposit_sol_quoter()
+        .unwrap()
+        .quote_deposit_sol(ONE_SOL)
+        .unwrap();
+
+    marinade.apply_deposit_sol(&q1).unwrap();
+
+    // filled from the mSOL leg first
+    assert_eq!(
+        leg_before - marinade.try_msol_leg_balance().unwrap(),
+        q1.out.min(leg_before)
+    );
+
+    let q2 = marinade
+        .deposit_sol_quoter()
+        .unwrap()
+        .quote_deposit_sol(ONE_SOL)
+        .unwrap();
+    // depositing does not change the mSOL price
+    assert_eq!(q2, q1);
+}
+
+#[test]
+fn marinade_apply_deposit_stake() {
+    let accounts = fixtures_accounts(BSOL_EPOCH);
+    let mut router = updated_router(
+        [],
+        &[SwapMints::DepositStake {
+            out: MSOL_MINT_ADDR,
+        }],
+        &accounts,
+    );
+    let marinade = &mut router.marinade_router;
+
+    let (_, stake_acc) = fixture_account("marinade-deposit-stake");
+    let vote = *stake_acc.data[STAKE_ACC_VOTER_OFFSET..]
+        .first_chunk()
+        .unwrap();
+    let inp = ActiveStakeParams {
+        vote,
+        lamports: StakeAccountLamports {
+            staked: 10 * ONE_SOL,
+            unstaked: 2_282_880,
+        },
+    };
+    let leg_before = marinade.try_msol_leg_balance().unwrap();
+    let q1 = marinade
+        .deposit_stake_quoter()
+        .unwrap()
+        .quote_deposit_stake(inp)
+        .unwrap();
+
+    marinade.apply_deposit_stake(&q1).unwrap();
+
+    assert_eq!(
+        leg_before - marinade.try_msol_leg_balance().unwrap(),
+        q1.out.min(leg_before)
+    );
+
+    let q2 = marinade
+        .deposit_stake_quoter()
+        .unwrap()
+        .quote_deposit_stake(inp)
+        .unwrap();
+    assert_eq!(q2, q1);
+}
+
+#[test]
+fn marinade_apply_withdraw_sol() {
+    let accounts = fixtures_accounts(BSOL_EPOCH);
+    let mut router = updated_router(
+        [],
+        &[SwapMints::WithdrawSol {
+            inp: MSOL_MINT_ADDR,
+        }],
+        &accounts,
+    );
+    let marinade = &mut router.marinade_router;
+
+    let sol_leg_before = marinade.try_sol_leg_lamports().unwrap();
+    let msol_leg_before = marinade.try_msol_leg_balance().unwrap();
+    let quoter = marinade.withdraw_sol_quoter().unwrap();
+    let q1 = quoter.quote_withdraw_sol(ONE_SOL).unwrap();
+    let treasury_cut = quoter.treasury_cut(q1.fee);
+
+    marinade.apply_withdraw_sol(&q1).unwrap();
+
+    assert_eq!(
+        sol_leg_before - marinade.try_sol_leg_lamports().unwrap(),
+        q1.out
+    );
+    assert_eq!(
+        marinade.try_msol_leg_balance().unwrap() - msol_leg_before,
+        q1.inp - treasury_cut
+    );
+
+    let q2 = marinade
+        .withdraw_sol_quoter()
+        .unwrap()
+        .quote_withdraw_sol(ONE_SOL)
+        .unwrap();
+    // less liquidity left, so the liquid unstake fee can only go up
+    assert!(q2.out <= q1.out);
+    assert!(q2.fee >= q1.fee);
+}
+
+#[test]
+fn marinade_apply_withdraw_stake() {
+    let accounts = fixtures_accounts(BSOL_EPOCH);
+    let mut router = updated_router(
+        [],
+        &[SwapMints::PrefundWithdrawStake {
+            inp: MSOL_MINT_ADDR,
+        }],
+        &accounts,
+    );
+    let marinade = &mut router.marinade_router;
+
+    let stake_before = *marinade.try_stake_account().unwrap();
+    let q1 = marinade
+        .withdraw_stake_quoter()
+        .unwrap()
+        .quote_withdraw_stake(10 * ONE_SOL, None)
+        .unwrap();
+    assert_eq!(q1.stake_to_split, Some(stake_before.addr));
+
+    marinade.apply_withdraw_stake(&q1).unwrap();
+
+    let stake_after = marinade.try_stake_account().unwrap();
+    assert_eq!(
+        stake_before.delegated_lamports - stake_after.delegated_lamports,
+        q1.out.lamports.staked
+    );
+    let record = marinade
+        .try_stake_records()
+        .unwrap()
+        .iter()
+        .find(|r| r.stake_account == stake_before.addr)
+        .unwrap();
+    assert_eq!(
+        record.last_update_delegated_lamports,
+        stake_after.delegated_lamports
+    );
+
+    let q2 = marinade
+        .withdraw_stake_quoter()
+        .unwrap()
+        .quote_withdraw_stake(10 * ONE_SOL, None)
+        .unwrap();
+    // still splits from the same stake account at the same mSOL price
+    assert_eq!(q2.stake_to_split, q1.stake_to_split);
+    assert_eq!(q2.out, q1.out);
+}
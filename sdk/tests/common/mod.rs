@@ -0,0 +1,155 @@
+#![allow(unused)] // each test binary only uses some of these
+
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use const_crypto::bs58;
+use sanctum_router::{Account, InitData, InitMint, SanctumRouter, SplInitData, SwapMints};
+use sanctum_router_core::{RentParams, SYSVAR_CLOCK, SYSVAR_RENT};
+use sanctum_spl_stake_pool_core::StakePool;
+use serde::Deserialize;
+
+/// bSOL's stake pool `last_update_epoch` at time of fixtures collection
+pub const BSOL_EPOCH: u64 = 802;
+
+/// lido's max stake validator at time of fixtures collection, also in bSOL's validator list
+pub const LIDOMAX_VOTE: [u8; 32] =
+    bs58::decode_pubkey("8jxSHbS4qAnh5yueFp4D9ABXubKqMwXqF3HtdzQGuphp");
+
+const SYSVAR_PROGRAM: [u8; 32] = bs58::decode_pubkey("Sysvar1111111111111111111111111111111111111");
+
+pub type FixtureAccounts = HashMap<[u8; 32], Account>;
+
+#[derive(Deserialize)]
+struct FixtureFile {
+    pubkey: String,
+    account: FixtureAccount,
+}
+
+#[derive(Deserialize)]
+struct FixtureAccount {
+    lamports: u64,
+    data: (String, String),
+    owner: String,
+}
+
+fn test_fixtures_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("test-fixtures")
+}
+
+fn read_fixture(path: PathBuf) -> ([u8; 32], Account) {
+    let FixtureFile {
+        pubkey,
+        account:
+            FixtureAccount {
+                lamports,
+                data: (data, _encoding),
+                owner,
+            },
+    } = serde_json::from_slice(&fs::read(path).unwrap()).unwrap();
+    (
+        bs58::decode_pubkey(&pubkey),
+        Account {
+            owner: bs58::decode_pubkey(&owner),
+            data: STANDARD.decode(data).unwrap(),
+            lamports,
+        },
+    )
+}
+
+pub fn fixture_account(fname: &str) -> ([u8; 32], Account) {
+    read_fixture(test_fixtures_dir().join(format!("{fname}.json")))
+}
+
+/// All accounts in test-fixtures/,
+/// plus clock and rent sysvar accounts since the fixtures do not include them
+pub fn fixtures_accounts(epoch: u64) -> FixtureAccounts {
+    let mut res = FixtureAccounts::default();
+    for entry in fs::read_dir(test_fixtures_dir()).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            let (pk, acc) = read_fixture(path);
+            res.insert(pk, acc);
+        }
+    }
+    res.insert(SYSVAR_CLOCK, clock_account(epoch));
+    res.insert(SYSVAR_RENT, rent_account());
+    res
+}
+
+fn clock_account(epoch: u64) -> Account {
+    // slot, epoch_start_timestamp, epoch, leader_schedule_epoch, unix_timestamp
+    let mut data = vec![0u8; 40];
+    data[16..24].copy_from_slice(&epoch.to_le_bytes());
+    Account {
+        owner: SYSVAR_PROGRAM,
+        data,
+        lamports: 1_169_280,
+    }
+}
+
+fn rent_account() -> Account {
+    let RentParams {
+        lamports_per_byte_year,
+        exemption_threshold,
+        burn_percent,
+    } = RentParams::DEFAULT;
+    let mut data = Vec::with_capacity(17);
+    data.extend(lamports_per_byte_year.to_le_bytes());
+    data.extend(exemption_threshold.to_le_bytes());
+    data.push(burn_percent);
+    Account {
+        owner: SYSVAR_PROGRAM,
+        data,
+        lamports: 1_009_200,
+    }
+}
+
+/// `InitMint` of the spl stake pool in fixture `fname`
+pub fn spl_init_mint(fname: &str) -> InitMint {
+    let (stake_pool_addr, account) = fixture_account(fname);
+    let stake_pool = StakePool::borsh_de(account.data.as_slice()).unwrap();
+    InitMint {
+        mint: stake_pool.pool_mint,
+        init: Some(InitData::Spl(SplInitData {
+            stake_pool_addr,
+            stake_pool_program_addr: account.owner,
+            validator_list_addr: stake_pool.validator_list,
+            reserve_stake_addr: stake_pool.reserve_stake,
+        })),
+    }
+}
+
+/// Creates a mainnet router for `swap_mints`, initializing `init_mints`
+/// and updating it from `accounts` with only the accounts it requested,
+/// asserting that they are all present.
+///
+/// Runs 2 update rounds since marinade's WithdrawStake only knows
+/// which stake account it splits from after the first
+pub fn updated_router(
+    init_mints: impl IntoIterator<Item = InitMint>,
+    swap_mints: &[SwapMints],
+    accounts: &FixtureAccounts,
+) -> SanctumRouter {
+    let mut router = SanctumRouter::default();
+    router.init(init_mints).unwrap();
+    for _ in 0..2 {
+        let requested: FixtureAccounts = router
+            .accounts_to_update(swap_mints.iter().copied())
+            .unwrap()
+            .into_iter()
+            .map(|pk| {
+                let acc = accounts
+                    .get(&pk)
+                    .unwrap_or_else(|| panic!("{pk:?} missing from fixtures"));
+                (pk, acc.clone())
+            })
+            .collect();
+        router
+            .update(swap_mints.iter().copied(), &requested)
+            .unwrap();
+    }
+    router
+}
@@ -0,0 +1,152 @@
+use sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR;
+use sanctum_router::{SanctumRouterError, SwapMints};
+use sanctum_router_core::{
+    ActiveStakeParams, DepositSolQuoter, DepositStakeQuoter, SolValueCalc, StakeAccountLamports,
+    WithdrawSolQuoter, WithdrawStakeQuoter,
+};
+
+mod common;
+
+use common::*;
+
+const ONE_SOL: u64 = 1_000_000_000;
+
+#[test]
+fn deposit_sol_bsol() {
+    let accounts = fixtures_accounts(BSOL_EPOCH);
+    let init = spl_init_mint("bsol-stake-pool");
+    let out = init.mint;
+    let router = updated_router([init], &[SwapMints::DepositSol { out }], &accounts);
+    let spl = router.try_find_spl_by_mint(&out).unwrap();
+
+    let q = spl
+        .deposit_sol_quoter(router.try_curr_epoch().unwrap())
+        .unwrap()
+        .quote_deposit_sol(ONE_SOL)
+        .unwrap();
+    assert_eq!(q.inp, ONE_SOL);
+    assert!(q.out > 0);
+    // fees can only reduce output from the fee-free conversion
+    let fee_free = spl
+        .sol_value_calc()
+        .unwrap()
+        .tokens_for_sol(ONE_SOL)
+        .unwrap();
+    assert!(q.out + q.fee <= fee_free);
+}
+
+#[test]
+fn deposit_sol_msol() {
+    let accounts = fixtures_accounts(BSOL_EPOCH);
+    let out = MSOL_MINT_ADDR;
+    let router = updated_router([], &[SwapMints::DepositSol { out }], &accounts);
+    let marinade = &router.marinade_router;
+
+    let q = marinade
+        .deposit_sol_quoter()
+        .unwrap()
+        .quote_deposit_sol(ONE_SOL)
+        .unwrap();
+    assert_eq!(q.inp, ONE_SOL);
+    assert!(q.out > 0);
+    let fee_free = marinade
+        .sol_value_calc()
+        .unwrap()
+        .tokens_for_sol(ONE_SOL)
+        .unwrap();
+    assert!(q.out + q.fee <= fee_free);
+}
+
+#[test]
+fn withdraw_sol_bsol() {
+    let accounts = fixtures_accounts(BSOL_EPOCH);
+    let init = spl_init_mint("bsol-stake-pool");
+    let inp = init.mint;
+    let router = updated_router([init], &[SwapMints::WithdrawSol { inp }], &accounts);
+    let spl = router.try_find_spl_by_mint(&inp).unwrap();
+
+    let q = spl
+        .withdraw_sol_quoter(router.try_curr_epoch().unwrap())
+        .unwrap()
+        .quote_withdraw_sol(ONE_SOL)
+        .unwrap();
+    assert_eq!(q.inp, ONE_SOL);
+    assert!(q.out > 0);
+    let fee_free = spl.sol_value_calc().unwrap().sol_value(ONE_SOL).unwrap();
+    assert!(q.out + q.fee <= fee_free);
+}
+
+#[test]
+fn deposit_stake_bsol() {
+    let accounts = fixtures_accounts(BSOL_EPOCH);
+    let init = spl_init_mint("bsol-stake-pool");
+    let out = init.mint;
+    let router = updated_router([init], &[SwapMints::DepositStake { out }], &accounts);
+    let spl = router.try_find_spl_by_mint(&out).unwrap();
+
+    let inp = ActiveStakeParams {
+        vote: LIDOMAX_VOTE,
+        lamports: StakeAccountLamports {
+            staked: 1_000 * ONE_SOL,
+            unstaked: 2_282_880,
+        },
+    };
+    let q = spl
+        .deposit_stake_quoter(router.try_curr_epoch().unwrap())
+        .unwrap()
+        .quote_deposit_stake(inp)
+        .unwrap();
+    assert_eq!(q.inp, inp);
+    assert!(q.out > 0);
+    let fee_free = spl
+        .sol_value_calc()
+        .unwrap()
+        .tokens_for_sol(inp.lamports.total())
+        .unwrap();
+    assert!(q.out + q.fee <= fee_free);
+    // suffix accounts can be derived for the quoted validator
+    spl.deposit_stake_suf_accs(&q.inp.vote).unwrap();
+}
+
+#[test]
+fn withdraw_stake_picosol() {
+    let accounts = fixtures_accounts(BSOL_EPOCH);
+    let init = spl_init_mint("picosol-stake-pool");
+    let inp = init.mint;
+    let router = updated_router(
+        [init],
+        &[SwapMints::PrefundWithdrawStake { inp }],
+        &accounts,
+    );
+    let spl = router.try_find_spl_by_mint(&inp).unwrap();
+
+    let q = spl
+        .withdraw_stake_quoter(router.try_curr_epoch().unwrap())
+        .unwrap()
+        .quote_withdraw_stake(ONE_SOL, None)
+        .unwrap();
+    assert_eq!(q.inp, ONE_SOL);
+    assert!(q.out.lamports.staked > 0);
+    assert!(spl
+        .try_validator_list()
+        .unwrap()
+        .iter()
+        .any(|v| *v.vote_account_address() == q.out.vote));
+    let fee_free = spl.sol_value_calc().unwrap().sol_value(ONE_SOL).unwrap();
+    assert!(q.out.lamports.total() <= fee_free);
+    // suffix accounts can be derived for the quoted validator
+    spl.withdraw_stake_suf_accs(&q.out.vote).unwrap();
+    // prefunding params are available since the reserve pool was updated too
+    router.prefund_params().unwrap();
+}
+
+#[test]
+fn accounts_to_update_uninit_spl_mint_fails() {
+    let (bsol_pool, _) = fixture_account("bsol-stake-pool");
+    let router = sanctum_router::SanctumRouter::default();
+    // any mint that is not wSOL, mSOL or stSOL must be init first
+    assert_eq!(
+        router.accounts_to_update([SwapMints::DepositSol { out: bsol_pool }]),
+        Err(SanctumRouterError::RouterMissing(bsol_pool))
+    );
+}
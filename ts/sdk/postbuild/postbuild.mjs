@@ -1,10 +1,15 @@
-import { appendFileSync, readFileSync } from "fs";
+import { appendFileSync, readFileSync, writeFileSync } from "fs";
 
 // Paths
 const PKG_DIR = `${import.meta.dirname}/../pkg`;
 const INDEX_JS_PATH = `${PKG_DIR}/index.js`;
 const INDEX_D_TS_PATH = `${PKG_DIR}/index.d.ts`;
 const INDEX_BG_WASM_PATH = `${PKG_DIR}/index_bg.wasm`;
+const PACKAGE_JSON_PATH = `${PKG_DIR}/package.json`;
+
+// crate is named sanctum-router-wasm to not conflict with the native
+// sanctum-router crate, but we want to keep the npm package name
+const NPM_PACKAGE_NAME = "@sanctumso/sanctum-router";
 
 const WASM_B64_CONST_NAME = "WASM_BIN_B64";
 const INIT_SYNC_EMBED_FN_NAME = "initSyncEmbed";
@@ -40,7 +45,14 @@ export const ${WASM_B64_CONST_NAME} = "${b64}";
 `;
 }
 
+function renamePackage() {
+  const pkgJson = JSON.parse(readFileSync(PACKAGE_JSON_PATH, "utf8"));
+  pkgJson.name = NPM_PACKAGE_NAME;
+  writeFileSync(PACKAGE_JSON_PATH, JSON.stringify(pkgJson, null, 2) + "\n");
+}
+
 function main() {
+  renamePackage();
   appendFileSync(INDEX_D_TS_PATH, CONST_D_TS_APPENDS);
   appendFileSync(INDEX_JS_PATH, CONST_INDEX_JS_APPENDS);
   appendFileSync(INDEX_JS_PATH, indexJsWasmEmbedAppend());
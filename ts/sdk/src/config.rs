@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+use tsify_next::Tsify;
+
+use sanctum_router_core::RouterFee;
+
+use crate::interface::B58PK;
+
+/// Program IDs, fixed accounts and router fees of a sanctum router deployment.
+///
+/// Omitted fields default to mainnet's.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase")]
+pub struct RouterConfig {
+    #[tsify(optional)]
+    pub router_program: Option<B58PK>,
+
+    #[tsify(optional)]
+    pub prefunder: Option<B58PK>,
+
+    #[tsify(optional)]
+    pub wsol_bridge_in: Option<B58PK>,
+
+    #[tsify(optional)]
+    pub sol_bridge_out: Option<B58PK>,
+
+    #[tsify(optional)]
+    pub wsol_fee_token_account: Option<B58PK>,
+
+    #[tsify(optional)]
+    pub reserve: Option<ReserveConfig>,
+
+    #[tsify(optional)]
+    pub fees: Option<RouterFeeSchedule>,
+
+    /// The stake program's minimum delegation, in lamports
+    #[tsify(optional)]
+    pub stake_min_delegation: Option<u64>,
+}
+
+/// Program ID and fixed accounts of the sanctum reserve deployment.
+///
+/// Omitted fields default to mainnet's.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+#[serde(rename_all = "camelCase")]
+pub struct ReserveConfig {
+    #[tsify(optional)]
+    pub program: Option<B58PK>,
+
+    #[tsify(optional)]
+    pub pool: Option<B58PK>,
+
+    #[tsify(optional)]
+    pub pool_sol_reserves: Option<B58PK>,
+
+    #[tsify(optional)]
+    pub fee: Option<B58PK>,
+
+    #[tsify(optional)]
+    pub protocol_fee: Option<B58PK>,
+
+    #[tsify(optional)]
+    pub protocol_fee_vault: Option<B58PK>,
+}
+
+/// Router fees charged by each instruction type.
+///
+/// Use this to override the fees quoted if the on-chain fees change
+/// before this library is updated.
+///
+/// Omitted fields default to mainnet's.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase")]
+pub struct RouterFeeSchedule {
+    #[tsify(optional)]
+    pub withdraw_wrapped_sol: Option<RouterFee>,
+
+    #[tsify(optional)]
+    pub deposit_stake: Option<RouterFee>,
+
+    #[tsify(optional)]
+    pub swap_via_stake: Option<RouterFee>,
+
+    #[tsify(optional)]
+    pub prefund_swap_via_stake: Option<RouterFee>,
+}
+
+/// Mainnet if `config` is omitted
+pub(crate) fn router_config(config: Option<RouterConfig>) -> sanctum_router_core::RouterConfig {
+    config.map_or(sanctum_router_core::RouterConfig::MAINNET, Into::into)
+}
+
+impl From<RouterConfig> for sanctum_router_core::RouterConfig {
+    #[inline]
+    fn from(
+        RouterConfig {
+            router_program,
+            prefunder,
+            wsol_bridge_in,
+            sol_bridge_out,
+            wsol_fee_token_account,
+            reserve,
+            fees,
+            stake_min_delegation,
+        }: RouterConfig,
+    ) -> Self {
+        let d = Self::MAINNET;
+        Self {
+            router_program: router_program.map_or(d.router_program, |pk| pk.0),
+            prefunder: prefunder.map_or(d.prefunder, |pk| pk.0),
+            wsol_bridge_in: wsol_bridge_in.map_or(d.wsol_bridge_in, |pk| pk.0),
+            sol_bridge_out: sol_bridge_out.map_or(d.sol_bridge_out, |pk| pk.0),
+            wsol_fee_token_account: wsol_fee_token_account
+                .map_or(d.wsol_fee_token_account, |pk| pk.0),
+            reserve: reserve.map_or(d.reserve, Into::into),
+            fees: fees.map_or(d.fees, Into::into),
+            stake_min_delegation: stake_min_delegation.unwrap_or(d.stake_min_delegation),
+        }
+    }
+}
+
+impl From<ReserveConfig> for sanctum_router_core::ReserveConfig {
+    #[inline]
+    fn from(
+        ReserveConfig {
+            program,
+            pool,
+            pool_sol_reserves,
+            fee,
+            protocol_fee,
+            protocol_fee_vault,
+        }: ReserveConfig,
+    ) -> Self {
+        let d = Self::MAINNET;
+        Self {
+            program: program.map_or(d.program, |pk| pk.0),
+            pool: pool.map_or(d.pool, |pk| pk.0),
+            pool_sol_reserves: pool_sol_reserves.map_or(d.pool_sol_reserves, |pk| pk.0),
+            fee: fee.map_or(d.fee, |pk| pk.0),
+            protocol_fee: protocol_fee.map_or(d.protocol_fee, |pk| pk.0),
+            protocol_fee_vault: protocol_fee_vault.map_or(d.protocol_fee_vault, |pk| pk.0),
+        }
+    }
+}
+
+impl From<RouterFeeSchedule> for sanctum_router_core::RouterFeeSchedule {
+    #[inline]
+    fn from(
+        RouterFeeSchedule {
+            withdraw_wrapped_sol,
+            deposit_stake,
+            swap_via_stake,
+            prefund_swap_via_stake,
+        }: RouterFeeSchedule,
+    ) -> Self {
+        let d = Self::MAINNET;
+        Self {
+            withdraw_wrapped_sol: withdraw_wrapped_sol.unwrap_or(d.withdraw_wrapped_sol),
+            deposit_stake: deposit_stake.unwrap_or(d.deposit_stake),
+            swap_via_stake: swap_via_stake.unwrap_or(d.swap_via_stake),
+            prefund_swap_via_stake: prefund_swap_via_stake.unwrap_or(d.prefund_swap_via_stake),
+        }
+    }
+}
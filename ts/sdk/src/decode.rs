@@ -0,0 +1,210 @@
+//! Decoding of router instructions back into their args and accounts
+
+use sanctum_router_core::{
+    DepositSolIxSuffixKeysOwned, DepositStakeIxSuffixKeysOwned, RouterInstruction,
+    RouterIxAccounts, WithdrawSolIxSuffixKeysOwned, WithdrawStakeIxSuffixKeysOwned,
+};
+use serde::{Deserialize, Serialize};
+use tsify_next::Tsify;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    config::{router_config, RouterConfig},
+    err::{not_router_ix_err, router_ix_decode_err, SanctumRouterError},
+    interface::{Instruction, B58PK},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum RouterIxKind {
+    StakeWrappedSol,
+    SwapViaStake,
+    CreateFeeTokenAccount,
+    CloseFeeTokenAccount,
+    WithdrawFees,
+    DepositStake,
+    PrefundWithdrawStake,
+    PrefundSwapViaStake,
+    WithdrawWrappedSol,
+}
+
+impl From<&RouterInstruction> for RouterIxKind {
+    #[inline]
+    fn from(ix: &RouterInstruction) -> Self {
+        match ix {
+            RouterInstruction::StakeWrappedSol { .. } => Self::StakeWrappedSol,
+            RouterInstruction::SwapViaStake { .. } => Self::SwapViaStake,
+            RouterInstruction::CreateFeeTokenAccount => Self::CreateFeeTokenAccount,
+            RouterInstruction::CloseFeeTokenAccount => Self::CloseFeeTokenAccount,
+            RouterInstruction::WithdrawFees => Self::WithdrawFees,
+            RouterInstruction::DepositStake => Self::DepositStake,
+            RouterInstruction::PrefundWithdrawStake { .. } => Self::PrefundWithdrawStake,
+            RouterInstruction::PrefundSwapViaStake { .. } => Self::PrefundSwapViaStake,
+            RouterInstruction::WithdrawWrappedSol { .. } => Self::WithdrawWrappedSol,
+        }
+    }
+}
+
+/// The pool program a suffix's accounts are for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum SuffixPool {
+    Spl,
+    Marinade,
+    Lido,
+    Reserve,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedIxSuffix {
+    pub pool: SuffixPool,
+    pub accounts: Box<[B58PK]>,
+}
+
+impl DecodedIxSuffix {
+    fn new(pool: SuffixPool, accounts: &[[u8; 32]]) -> Self {
+        Self {
+            pool,
+            accounts: accounts.iter().copied().map(B58PK::new).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedRouterIx {
+    pub kind: RouterIxKind,
+
+    /// Input amount of the instruction, omitted if the instruction does not have one
+    #[tsify(optional)]
+    pub amount: Option<u64>,
+
+    /// Omitted if the instruction does not use a bridge stake account
+    #[tsify(optional)]
+    pub bridge_stake_seed: Option<u32>,
+
+    /// The instruction's own accounts, excluding the pool-specific suffixes
+    pub prefix: Box<[B58PK]>,
+
+    /// Pool-specific suffix accounts, in instruction order.
+    /// For SwapViaStake and PrefundSwapViaStake, this is the WithdrawStake suffix
+    /// followed by the DepositStake suffix.
+    pub suffixes: Box<[DecodedIxSuffix]>,
+
+    /// Trailing accounts not part of the instruction's known layout
+    pub remaining: Box<[B58PK]>,
+}
+
+/// Inverse of the various `*Ix()` functions.
+///
+/// Uses mainnet's deployment if `config` is omitted
+///
+/// @param {Instruction} ix
+/// @param {RouterConfig | undefined} config
+#[wasm_bindgen(js_name = decodeRouterIx)]
+pub fn decode_router_ix(
+    ix: Instruction,
+    config: Option<RouterConfig>,
+) -> Result<DecodedRouterIx, SanctumRouterError> {
+    let config = router_config(config);
+    if ix.program_address.0 != config.router_program {
+        return Err(not_router_ix_err(&ix.program_address.0));
+    }
+    let accounts: Vec<[u8; 32]> = ix.accounts.iter().map(|a| a.address.0).collect();
+
+    let decoded = RouterInstruction::try_from_buf(&ix.data).map_err(router_ix_decode_err)?;
+    let (decoded_accounts, remaining) = decoded
+        .try_split_accounts(&accounts, &config)
+        .map_err(router_ix_decode_err)?;
+    let (prefix, suffixes): (&[[u8; 32]], Vec<DecodedIxSuffix>) = match &decoded_accounts {
+        RouterIxAccounts::StakeWrappedSol { prefix, suffix } => {
+            (prefix.0.as_slice(), vec![deposit_sol_suffix(suffix)])
+        }
+        RouterIxAccounts::SwapViaStake {
+            prefix,
+            withdraw,
+            deposit,
+        } => (
+            prefix.0.as_slice(),
+            vec![
+                withdraw_stake_suffix(withdraw),
+                deposit_stake_suffix(deposit),
+            ],
+        ),
+        RouterIxAccounts::CreateFeeTokenAccount(accs) => (accs.0.as_slice(), vec![]),
+        RouterIxAccounts::CloseFeeTokenAccount(accs) => (accs.0.as_slice(), vec![]),
+        RouterIxAccounts::WithdrawFees(accs) => (accs.0.as_slice(), vec![]),
+        RouterIxAccounts::DepositStake { prefix, suffix } => {
+            (prefix.0.as_slice(), vec![deposit_stake_suffix(suffix)])
+        }
+        RouterIxAccounts::PrefundWithdrawStake { prefix, suffix } => {
+            (prefix.0.as_slice(), vec![withdraw_stake_suffix(suffix)])
+        }
+        RouterIxAccounts::PrefundSwapViaStake {
+            prefix,
+            withdraw,
+            deposit,
+        } => (
+            prefix.0.as_slice(),
+            vec![
+                withdraw_stake_suffix(withdraw),
+                deposit_stake_suffix(deposit),
+            ],
+        ),
+        RouterIxAccounts::WithdrawWrappedSol { prefix, suffix } => {
+            (prefix.0.as_slice(), vec![withdraw_sol_suffix(suffix)])
+        }
+    };
+
+    Ok(DecodedRouterIx {
+        kind: RouterIxKind::from(&decoded),
+        amount: decoded.amount(),
+        bridge_stake_seed: decoded.bridge_stake_seed(),
+        prefix: prefix.iter().copied().map(B58PK::new).collect(),
+        suffixes: suffixes.into_boxed_slice(),
+        remaining: remaining.iter().copied().map(B58PK::new).collect(),
+    })
+}
+
+fn deposit_sol_suffix(suffix: &DepositSolIxSuffixKeysOwned) -> DecodedIxSuffix {
+    match suffix {
+        DepositSolIxSuffixKeysOwned::Spl(k) => DecodedIxSuffix::new(SuffixPool::Spl, &k.0),
+        DepositSolIxSuffixKeysOwned::Marinade(k) => {
+            DecodedIxSuffix::new(SuffixPool::Marinade, &k.0)
+        }
+    }
+}
+
+fn withdraw_sol_suffix(suffix: &WithdrawSolIxSuffixKeysOwned) -> DecodedIxSuffix {
+    match suffix {
+        WithdrawSolIxSuffixKeysOwned::Spl(k) => DecodedIxSuffix::new(SuffixPool::Spl, &k.0),
+        WithdrawSolIxSuffixKeysOwned::Marinade(k) => {
+            DecodedIxSuffix::new(SuffixPool::Marinade, &k.0)
+        }
+    }
+}
+
+fn deposit_stake_suffix(suffix: &DepositStakeIxSuffixKeysOwned) -> DecodedIxSuffix {
+    match suffix {
+        DepositStakeIxSuffixKeysOwned::Spl(k) => DecodedIxSuffix::new(SuffixPool::Spl, &k.0),
+        DepositStakeIxSuffixKeysOwned::Marinade(k) => {
+            DecodedIxSuffix::new(SuffixPool::Marinade, &k.0)
+        }
+        DepositStakeIxSuffixKeysOwned::Reserve(k) => {
+            DecodedIxSuffix::new(SuffixPool::Reserve, &k.0)
+        }
+    }
+}
+
+fn withdraw_stake_suffix(suffix: &WithdrawStakeIxSuffixKeysOwned) -> DecodedIxSuffix {
+    match suffix {
+        WithdrawStakeIxSuffixKeysOwned::Spl(k) => DecodedIxSuffix::new(SuffixPool::Spl, &k.0),
+        WithdrawStakeIxSuffixKeysOwned::Lido(k) => DecodedIxSuffix::new(SuffixPool::Lido, &k.0),
+        WithdrawStakeIxSuffixKeysOwned::Marinade(k) => {
+            DecodedIxSuffix::new(SuffixPool::Marinade, &k.0)
+        }
+    }
+}
@@ -1,13 +1,18 @@
 use sanctum_marinade_liquid_staking_core::MarinadeError;
 use sanctum_reserve_core::ReserveError;
-use sanctum_router_core::{PrefundSwapViaStakeQuoteErr, PrefundWithdrawStakeQuoteErr};
+use sanctum_router::PoolUpdateType;
+use sanctum_router_core::{
+    ExactOutQuoteErr, MarinadeDelayedUnstakeErr, MarinadeWithdrawSolQuoteErr,
+    PrefundSwapViaStakeQuoteErr, PrefundWithdrawStakeQuoteErr, RouterIxDecodeErr, SplitErr,
+    SwapViaStakeQuoteErr, WithdrawSolDepositSolQuoteErr, WithdrawStakeQuoteErr,
+};
 use sanctum_spl_stake_pool_core::SplStakePoolError;
 use serde::{Deserialize, Serialize};
 use solido_legacy_core::LidoError;
 use tsify_next::Tsify;
 use wasm_bindgen::prelude::*;
 
-use crate::{interface::Bs58PkString, update::PoolUpdateType};
+use crate::interface::Bs58PkString;
 
 /// All {@link Error} objects thrown by SDK functions will start with
 /// `{SanctumRouterErr}:`, so that the `SanctumRouterErr` error code can be
@@ -35,6 +40,34 @@ pub struct SanctumRouterError {
     pub cause: Option<String>,
 }
 
+/// Classifies errors shared with the native sdk by [`SanctumRouterErr`] code,
+/// using their `Display` as the cause
+impl From<sanctum_router::SanctumRouterError> for SanctumRouterError {
+    fn from(e: sanctum_router::SanctumRouterError) -> Self {
+        use sanctum_router::SanctumRouterError as E;
+
+        let code = match e {
+            E::AccountMissing(_) => SanctumRouterErr::AccountMissingErr,
+            E::InvalidPda => SanctumRouterErr::InvalidPdaErr,
+            E::InvalidData => SanctumRouterErr::InvalidDataErr,
+            E::RouterMissing(_) => SanctumRouterErr::RouterMissingErr,
+            E::UnsupportedUpdate { .. } => SanctumRouterErr::UnsupportedUpdateErr,
+            E::Spl(e) => spl_err_code(e),
+            E::Marinade(e) => marinade_err_code(e),
+            E::Lido(e) => lido_err_code(e),
+            E::Reserve(e) => reserve_err_code(e),
+            E::BelowMinimumDelegation => SanctumRouterErr::UserErr,
+            E::TooManyAccounts => SanctumRouterErr::InternalErr,
+        };
+        let cause = match e {
+            // the code already says it all
+            E::InvalidPda | E::InvalidData => None,
+            e => Some(e.to_string()),
+        };
+        Self { code, cause }
+    }
+}
+
 impl From<SanctumRouterError> for JsValue {
     fn from(SanctumRouterError { code, cause }: SanctumRouterError) -> Self {
         let suf = cause.unwrap_or_default();
@@ -66,138 +99,174 @@ pub fn all_sanctum_router_errs() -> AllSanctumRouterErrs {
 const ERR_CODE_MSG_SEP: &str = ":";
 
 pub fn invalid_pda_err() -> SanctumRouterError {
-    SanctumRouterError {
-        code: SanctumRouterErr::InvalidPdaErr,
-        cause: None,
-    }
+    sanctum_router::SanctumRouterError::InvalidPda.into()
 }
 
-// TODO: maybe add more details here
 pub fn invalid_data_err() -> SanctumRouterError {
+    sanctum_router::SanctumRouterError::InvalidData.into()
+}
+
+pub fn router_missing_err(mint: &[u8; 32]) -> SanctumRouterError {
+    sanctum_router::SanctumRouterError::RouterMissing(*mint).into()
+}
+
+pub fn account_missing_err(pubkey: &[u8; 32]) -> SanctumRouterError {
+    sanctum_router::SanctumRouterError::AccountMissing(*pubkey).into()
+}
+
+pub fn unsupported_mint_pair_err(inp: &[u8; 32], out: &[u8; 32]) -> SanctumRouterError {
+    let b58inpstr = Bs58PkString::encode(inp);
+    let b58outstr = Bs58PkString::encode(out);
     SanctumRouterError {
-        code: SanctumRouterErr::InvalidDataErr,
-        cause: None,
+        code: SanctumRouterErr::UserErr,
+        cause: Some(format!("unsupported mint pair {b58inpstr} -> {b58outstr}")),
     }
 }
 
-pub fn router_missing_err(mint: &[u8; 32]) -> SanctumRouterError {
+pub fn fee_token_account_missing_err(
+    mint: &[u8; 32],
+    fee_token_account: &[u8; 32],
+) -> SanctumRouterError {
     let b58mintstr = Bs58PkString::encode(mint);
+    let b58pkstr = Bs58PkString::encode(fee_token_account);
     SanctumRouterError {
-        code: SanctumRouterErr::RouterMissingErr,
-        cause: Some(format!("router missing for mint {b58mintstr}")),
+        code: SanctumRouterErr::AccountMissingErr,
+        cause: Some(format!(
+            "fee token account {b58pkstr} for mint {b58mintstr} does not exist"
+        )),
     }
 }
 
-pub fn account_missing_err(pubkey: &[u8; 32]) -> SanctumRouterError {
-    let b58pkstr = Bs58PkString::encode(pubkey);
+pub fn not_router_ix_err(program: &[u8; 32]) -> SanctumRouterError {
+    let b58programstr = Bs58PkString::encode(program);
     SanctumRouterError {
-        code: SanctumRouterErr::AccountMissingErr,
-        cause: Some(format!("{b58pkstr} missing from AccountMap")),
+        code: SanctumRouterErr::UserErr,
+        cause: Some(format!("{b58programstr} is not the router program")),
+    }
+}
+
+pub fn router_ix_decode_err(e: RouterIxDecodeErr) -> SanctumRouterError {
+    SanctumRouterError {
+        code: SanctumRouterErr::InvalidDataErr,
+        cause: Some(format!("RouterIxDecodeErr::{e}")),
     }
 }
 
 pub fn marinade_err(e: MarinadeError) -> SanctumRouterError {
-    const MARINADE_ERR_PREFIX: &str = "MarinadeError::";
+    sanctum_router::SanctumRouterError::Marinade(e).into()
+}
+
+pub fn marinade_withdraw_sol_err(e: MarinadeWithdrawSolQuoteErr) -> SanctumRouterError {
+    match e {
+        MarinadeWithdrawSolQuoteErr::Marinade(e) => marinade_err(e),
+        MarinadeWithdrawSolQuoteErr::InsufficientLiquidity => SanctumRouterError {
+            code: SanctumRouterErr::PoolErr,
+            cause: Some("InsufficientLiquidity".to_owned()),
+        },
+    }
+}
+
+pub fn marinade_delayed_unstake_err(e: MarinadeDelayedUnstakeErr) -> SanctumRouterError {
+    match e {
+        MarinadeDelayedUnstakeErr::Marinade(e) => marinade_err(e),
+        MarinadeDelayedUnstakeErr::WithdrawAmountIsTooLow
+        | MarinadeDelayedUnstakeErr::TicketNotDue => SanctumRouterError {
+            code: SanctumRouterErr::UserErr,
+            cause: Some(format!("{e}")),
+        },
+    }
+}
+
+pub fn spl_err(e: SplStakePoolError) -> SanctumRouterError {
+    sanctum_router::SanctumRouterError::Spl(e).into()
+}
+
+pub fn lido_err(e: LidoError) -> SanctumRouterError {
+    sanctum_router::SanctumRouterError::Lido(e).into()
+}
 
-    let (code, cause) = match e {
+pub fn reserve_err(e: ReserveError) -> SanctumRouterError {
+    sanctum_router::SanctumRouterError::Reserve(e).into()
+}
+
+fn marinade_err_code(e: MarinadeError) -> SanctumRouterErr {
+    match e {
         MarinadeError::DepositAmountIsTooLow
         | MarinadeError::TooLowDelegationInDepositingStake
         | MarinadeError::WithdrawStakeLamportsIsTooLow
         | MarinadeError::SelectedStakeAccountHasNotEnoughFunds
         | MarinadeError::StakeAccountRemainderTooLow
-        | MarinadeError::WrongValidatorAccountOrIndex => (
-            SanctumRouterErr::UserErr,
-            format!("{MARINADE_ERR_PREFIX}{e}"),
-        ),
+        | MarinadeError::WrongValidatorAccountOrIndex => SanctumRouterErr::UserErr,
         MarinadeError::ProgramIsPaused
         | MarinadeError::StakingIsCapped
         | MarinadeError::WithdrawStakeAccountIsNotEnabled
-        | MarinadeError::StakeAccountIsEmergencyUnstaking => (
-            SanctumRouterErr::PoolErr,
-            format!("{MARINADE_ERR_PREFIX}{e}"),
-        ),
-        MarinadeError::CalculationFailure => (
-            SanctumRouterErr::InternalErr,
-            format!("{MARINADE_ERR_PREFIX}{e}"),
-        ),
-    };
-    SanctumRouterError {
-        code,
-        cause: Some(cause),
+        | MarinadeError::StakeAccountIsEmergencyUnstaking => SanctumRouterErr::PoolErr,
+        MarinadeError::CalculationFailure => SanctumRouterErr::InternalErr,
     }
 }
 
-pub fn spl_err(e: SplStakePoolError) -> SanctumRouterError {
-    const SPL_ERR_PREFIX: &str = "SplStakePoolError::";
-
-    let (code, cause) = match e {
+fn spl_err_code(e: SplStakePoolError) -> SanctumRouterErr {
+    match e {
         SplStakePoolError::IncorrectDepositVoteAddress
         | SplStakePoolError::IncorrectWithdrawVoteAddress
         | SplStakePoolError::InvalidSolDepositAuthority
         | SplStakePoolError::InvalidStakeDepositAuthority
-        | SplStakePoolError::ValidatorNotFound => {
-            (SanctumRouterErr::UserErr, format!("{SPL_ERR_PREFIX}{e}"))
-        }
+        | SplStakePoolError::ValidatorNotFound => SanctumRouterErr::UserErr,
         SplStakePoolError::InvalidState
         | SplStakePoolError::StakeListAndPoolOutOfDate
         | SplStakePoolError::SolWithdrawalTooLarge
-        | SplStakePoolError::StakeLamportsNotEqualToMinimum => {
-            (SanctumRouterErr::PoolErr, format!("{SPL_ERR_PREFIX}{e}"))
-        }
-        SplStakePoolError::CalculationFailure => (
-            SanctumRouterErr::InternalErr,
-            format!("{SPL_ERR_PREFIX}{e}"),
-        ),
-    };
-
-    SanctumRouterError {
-        code,
-        cause: Some(cause),
+        | SplStakePoolError::StakeLamportsNotEqualToMinimum => SanctumRouterErr::PoolErr,
+        SplStakePoolError::CalculationFailure => SanctumRouterErr::InternalErr,
     }
 }
 
-pub fn lido_err(e: LidoError) -> SanctumRouterError {
-    const LIDO_ERR_PREFIX: &str = "LidoError::";
-
-    let (code, cause) = match e {
-        LidoError::ValidatorWithMoreStakeExists => {
-            (SanctumRouterErr::UserErr, format!("{LIDO_ERR_PREFIX}{e}"))
-        }
+fn lido_err_code(e: LidoError) -> SanctumRouterErr {
+    match e {
+        LidoError::ValidatorWithMoreStakeExists => SanctumRouterErr::UserErr,
         LidoError::InvalidAmount | LidoError::ExchangeRateNotUpdatedInThisEpoch => {
-            (SanctumRouterErr::PoolErr, format!("{LIDO_ERR_PREFIX}{e}"))
+            SanctumRouterErr::PoolErr
         }
-        LidoError::CalculationFailure => (
-            SanctumRouterErr::InternalErr,
-            format!("{LIDO_ERR_PREFIX}{e}"),
-        ),
-    };
+        LidoError::CalculationFailure => SanctumRouterErr::InternalErr,
+    }
+}
 
-    SanctumRouterError {
-        code,
-        cause: Some(cause),
+fn reserve_err_code(e: ReserveError) -> SanctumRouterErr {
+    match e {
+        ReserveError::NotEnoughLiquidity => SanctumRouterErr::PoolErr,
+        ReserveError::InternalError => SanctumRouterErr::InternalErr,
     }
 }
 
-pub fn reserve_err(e: ReserveError) -> SanctumRouterError {
-    const RESERVE_ERR_PREFIX: &str = "ReserveError::";
-
-    let (code, cause) = match e {
-        ReserveError::NotEnoughLiquidity => (
-            SanctumRouterErr::PoolErr,
-            format!("{RESERVE_ERR_PREFIX}{e}"),
-        ),
-        ReserveError::InternalError => (
-            SanctumRouterErr::InternalErr,
-            format!("{RESERVE_ERR_PREFIX}{e}"),
-        ),
-    };
+pub fn below_min_delegation_err() -> SanctumRouterError {
+    sanctum_router::SanctumRouterError::BelowMinimumDelegation.into()
+}
 
-    SanctumRouterError {
-        code,
-        cause: Some(cause),
+pub fn too_many_accounts_err() -> SanctumRouterError {
+    sanctum_router::SanctumRouterError::TooManyAccounts.into()
+}
+
+pub fn wsq_err<E>(
+    e: WithdrawStakeQuoteErr<E>,
+    handle_pool: fn(E) -> SanctumRouterError,
+) -> SanctumRouterError {
+    match e {
+        WithdrawStakeQuoteErr::Pool(e) => handle_pool(e),
+        WithdrawStakeQuoteErr::BelowMinimumDelegation => below_min_delegation_err(),
     }
 }
 
+pub fn spl_wsq_err(e: WithdrawStakeQuoteErr<SplStakePoolError>) -> SanctumRouterError {
+    wsq_err(e, spl_err)
+}
+
+pub fn lido_wsq_err(e: WithdrawStakeQuoteErr<LidoError>) -> SanctumRouterError {
+    wsq_err(e, lido_err)
+}
+
+pub fn marinade_wsq_err(e: WithdrawStakeQuoteErr<MarinadeError>) -> SanctumRouterError {
+    wsq_err(e, marinade_err)
+}
+
 pub fn prefund_wsq_err<E>(
     e: PrefundWithdrawStakeQuoteErr<E>,
     handle_pool: fn(E) -> SanctumRouterError,
@@ -205,6 +274,20 @@ pub fn prefund_wsq_err<E>(
     match e {
         PrefundWithdrawStakeQuoteErr::Reserve(e) => reserve_err(e),
         PrefundWithdrawStakeQuoteErr::Pool(e) => handle_pool(e),
+        PrefundWithdrawStakeQuoteErr::BelowMinimumDelegation => below_min_delegation_err(),
+    }
+}
+
+pub fn exact_out_err<E>(
+    e: ExactOutQuoteErr<E>,
+    handle_pool: fn(E) -> SanctumRouterError,
+) -> SanctumRouterError {
+    match e {
+        ExactOutQuoteErr::OutTooLarge => SanctumRouterError {
+            code: SanctumRouterErr::PoolErr,
+            cause: Some("OutTooLarge".to_owned()),
+        },
+        ExactOutQuoteErr::Pool(e) => handle_pool(e),
     }
 }
 
@@ -221,13 +304,65 @@ pub fn prefund_svsq_err<W, D>(
         PrefundSwapViaStakeQuoteErr::Reserve(e) => reserve_err(e),
         PrefundSwapViaStakeQuoteErr::WithdrawStake(e) => handle_w(e),
         PrefundSwapViaStakeQuoteErr::DepositStake(e) => handle_d(e),
+        PrefundSwapViaStakeQuoteErr::BelowMinimumDelegation => below_min_delegation_err(),
     }
 }
 
-pub fn unsupported_update_err(ty: PoolUpdateType, mint: &[u8; 32]) -> SanctumRouterError {
-    let b58mintstr = Bs58PkString::encode(mint);
+pub fn svsq_err<W, D>(
+    e: SwapViaStakeQuoteErr<W, D>,
+    handle_w: fn(W) -> SanctumRouterError,
+    handle_d: fn(D) -> SanctumRouterError,
+) -> SanctumRouterError {
+    match e {
+        SwapViaStakeQuoteErr::NoMatch => SanctumRouterError {
+            code: SanctumRouterErr::PoolErr,
+            cause: Some("NoMatch".to_owned()),
+        },
+        SwapViaStakeQuoteErr::WithdrawStake(e) => handle_w(e),
+        SwapViaStakeQuoteErr::DepositStake(e) => handle_d(e),
+    }
+}
+
+pub fn wsdsq_err<W, D>(
+    e: WithdrawSolDepositSolQuoteErr<W, D>,
+    handle_w: fn(W) -> SanctumRouterError,
+    handle_d: fn(D) -> SanctumRouterError,
+) -> SanctumRouterError {
+    match e {
+        WithdrawSolDepositSolQuoteErr::WithdrawSol(e) => handle_w(e),
+        WithdrawSolDepositSolQuoteErr::DepositSol(e) => handle_d(e),
+    }
+}
+
+/// `leg_errs` are the names and last quote errors of the split's legs,
+/// appended to the cause so that users can tell why no leg could take more input
+pub fn split_err(e: SplitErr, leg_errs: &[(String, SanctumRouterError)]) -> SanctumRouterError {
+    let code = match e {
+        SplitErr::NoCapacity { .. } => SanctumRouterErr::PoolErr,
+        SplitErr::ZeroParts => SanctumRouterErr::UserErr,
+        SplitErr::LenMismatch => SanctumRouterErr::InternalErr,
+    };
+    let cause = leg_errs.iter().fold(
+        format!("SplitErr::{e}"),
+        |mut cause,
+         (
+            leg,
+            SanctumRouterError {
+                code,
+                cause: leg_cause,
+            },
+        )| {
+            let leg_cause = leg_cause.as_deref().unwrap_or_default();
+            cause.push_str(&format!("; {leg}: {code:?}{ERR_CODE_MSG_SEP}{leg_cause}"));
+            cause
+        },
+    );
     SanctumRouterError {
-        code: SanctumRouterErr::UnsupportedUpdateErr,
-        cause: Some(format!("{ty:?} not supported by pool of mint {b58mintstr}")),
+        code,
+        cause: Some(cause),
     }
 }
+
+pub fn unsupported_update_err(ty: PoolUpdateType, mint: &[u8; 32]) -> SanctumRouterError {
+    sanctum_router::SanctumRouterError::UnsupportedUpdate { ty, mint: *mint }.into()
+}
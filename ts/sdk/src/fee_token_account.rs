@@ -0,0 +1,187 @@
+//! Admin instructions for managing the router's fee token accounts
+
+use bs58_fixed_wasm::Bs58Array;
+use sanctum_router::find_fee_token_account_pda;
+use sanctum_router_core::{
+    CloseFeeTokenAccountIxAccsBuilder, CloseFeeTokenAccountIxData,
+    CreateFeeTokenAccountIxAccsBuilder, CreateFeeTokenAccountIxData, RouterIx,
+    WithdrawFeesIxAccsBuilder, WithdrawFeesIxData, SYSTEM_PROGRAM, TOKEN_PROGRAM,
+};
+use serde::{Deserialize, Serialize};
+use tsify_next::Tsify;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    config::{router_config, RouterConfig},
+    err::{fee_token_account_missing_err, invalid_pda_err, SanctumRouterError},
+    interface::{AccountMap, Instruction, B58PK},
+};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateFeeTokenAccountParams {
+    /// Pays for the rent-exemption of the new fee token account
+    pub payer: B58PK,
+
+    /// Mint of the fee token account to create
+    pub mint: B58PK,
+
+    /// Owner program of `mint`. Defaults to tokenkeg if omitted
+    #[tsify(optional)]
+    pub token_program: Option<B58PK>,
+}
+
+/// Uses mainnet's deployment if `config` is omitted
+///
+/// @param {CreateFeeTokenAccountParams} arg0
+/// @param {RouterConfig | undefined} config
+#[wasm_bindgen(js_name = createFeeTokenAccountIx)]
+pub fn create_fee_token_account_ix(
+    CreateFeeTokenAccountParams {
+        payer: Bs58Array(payer),
+        mint: Bs58Array(mint),
+        token_program,
+    }: CreateFeeTokenAccountParams,
+    config: Option<RouterConfig>,
+) -> Result<Instruction, SanctumRouterError> {
+    let config = router_config(config);
+    let (fee_token_account, _bump) =
+        find_fee_token_account_pda(&config, &mint).ok_or_else(invalid_pda_err)?;
+    let ix = RouterIx::create_fee_token_account(
+        &CreateFeeTokenAccountIxAccsBuilder::start()
+            .with_payer(payer)
+            .with_fee_token_account(fee_token_account)
+            .with_mint(mint)
+            .with_token_program(token_program.map_or(TOKEN_PROGRAM, |pk| pk.0))
+            .with_system_program(SYSTEM_PROGRAM)
+            .build(),
+        CreateFeeTokenAccountIxData::new(),
+    );
+    Ok(Instruction::from(
+        &ix.with_program_address(config.router_program),
+    ))
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase")]
+pub struct CloseFeeTokenAccountParams {
+    /// The router program's admin
+    pub admin: B58PK,
+
+    /// Mint of the fee token account to close
+    pub mint: B58PK,
+
+    /// Account to refund the fee token account's rent lamports to
+    pub close_to: B58PK,
+
+    /// Owner program of `mint`. Defaults to tokenkeg if omitted
+    #[tsify(optional)]
+    pub token_program: Option<B58PK>,
+}
+
+/// Uses mainnet's deployment if `config` is omitted
+///
+/// @param {CloseFeeTokenAccountParams} arg0
+/// @param {RouterConfig | undefined} config
+#[wasm_bindgen(js_name = closeFeeTokenAccountIx)]
+pub fn close_fee_token_account_ix(
+    CloseFeeTokenAccountParams {
+        admin: Bs58Array(admin),
+        mint: Bs58Array(mint),
+        close_to: Bs58Array(close_to),
+        token_program,
+    }: CloseFeeTokenAccountParams,
+    config: Option<RouterConfig>,
+) -> Result<Instruction, SanctumRouterError> {
+    let config = router_config(config);
+    let (fee_token_account, _bump) =
+        find_fee_token_account_pda(&config, &mint).ok_or_else(invalid_pda_err)?;
+    let ix = RouterIx::close_fee_token_account(
+        &CloseFeeTokenAccountIxAccsBuilder::start()
+            .with_admin(admin)
+            .with_fee_token_account(fee_token_account)
+            .with_close_to(close_to)
+            .with_mint(mint)
+            .with_token_program(token_program.map_or(TOKEN_PROGRAM, |pk| pk.0))
+            .build(),
+        CloseFeeTokenAccountIxData::new(),
+    );
+    Ok(Instruction::from(
+        &ix.with_program_address(config.router_program),
+    ))
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawFeesParams {
+    /// The router program's admin
+    pub admin: B58PK,
+
+    /// Mint of the fee token account to withdraw fees from
+    pub mint: B58PK,
+
+    /// Token account of `mint` to receive the withdrawn fees
+    pub withdraw_to: B58PK,
+
+    /// Owner program of `mint`. Defaults to tokenkeg if omitted
+    #[tsify(optional)]
+    pub token_program: Option<B58PK>,
+}
+
+/// Uses mainnet's deployment if `config` is omitted
+///
+/// @param {WithdrawFeesParams} arg0
+/// @param {RouterConfig | undefined} config
+#[wasm_bindgen(js_name = withdrawFeesIx)]
+pub fn withdraw_fees_ix(
+    WithdrawFeesParams {
+        admin: Bs58Array(admin),
+        mint: Bs58Array(mint),
+        withdraw_to: Bs58Array(withdraw_to),
+        token_program,
+    }: WithdrawFeesParams,
+    config: Option<RouterConfig>,
+) -> Result<Instruction, SanctumRouterError> {
+    let config = router_config(config);
+    let (fee_token_account, _bump) =
+        find_fee_token_account_pda(&config, &mint).ok_or_else(invalid_pda_err)?;
+    let ix = RouterIx::withdraw_fees(
+        &WithdrawFeesIxAccsBuilder::start()
+            .with_admin(admin)
+            .with_fee_token_account(fee_token_account)
+            .with_withdraw_to(withdraw_to)
+            .with_mint(mint)
+            .with_token_program(token_program.map_or(TOKEN_PROGRAM, |pk| pk.0))
+            .build(),
+        WithdrawFeesIxData::new(),
+    );
+    Ok(Instruction::from(
+        &ix.with_program_address(config.router_program),
+    ))
+}
+
+/// Precondition check for swap instructions that collect router fees into
+/// the fee token account of `mint`.
+///
+/// No-op if `accounts` is `None`.
+/// Otherwise, returns [`fee_token_account_missing_err()`] if the fee token account
+/// is not in `accounts` or does not exist (empty data)
+pub(crate) fn check_fee_token_account_exists(
+    config: &sanctum_router_core::RouterConfig,
+    accounts: Option<&AccountMap>,
+    mint: &[u8; 32],
+) -> Result<(), SanctumRouterError> {
+    let accounts = match accounts {
+        None => return Ok(()),
+        Some(a) => a,
+    };
+    let (fee_token_account, _bump) =
+        find_fee_token_account_pda(config, mint).ok_or_else(invalid_pda_err)?;
+    match accounts.0.get(&B58PK::new(fee_token_account)) {
+        Some(acc) if !acc.data.is_empty() => Ok(()),
+        _ => Err(fee_token_account_missing_err(mint, &fee_token_account)),
+    }
+}
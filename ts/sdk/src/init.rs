@@ -25,3 +25,29 @@ pub struct SplInitData {
     /// Can be read from stake pool account data
     pub reserve_stake_addr: B58PK,
 }
+
+impl From<InitData> for sanctum_router::InitData {
+    #[inline]
+    fn from(InitData::Spl(spl): InitData) -> Self {
+        Self::Spl(spl.into())
+    }
+}
+
+impl From<SplInitData> for sanctum_router::SplInitData {
+    #[inline]
+    fn from(
+        SplInitData {
+            stake_pool_addr,
+            stake_pool_program_addr,
+            validator_list_addr,
+            reserve_stake_addr,
+        }: SplInitData,
+    ) -> Self {
+        Self {
+            stake_pool_addr: stake_pool_addr.0,
+            stake_pool_program_addr: stake_pool_program_addr.0,
+            validator_list_addr: validator_list_addr.0,
+            reserve_stake_addr: reserve_stake_addr.0,
+        }
+    }
+}
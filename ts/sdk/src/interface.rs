@@ -4,13 +4,13 @@ use std::collections::HashMap;
 
 use bs58_fixed::Bs58String;
 use bs58_fixed_wasm::Bs58Array;
+use sanctum_router::{AccountRef, AccountSource};
+use sanctum_router_core::RouterIx;
 use serde::{Deserialize, Serialize};
 use serde_bytes::ByteBuf;
 use tsify_next::Tsify;
 use wasm_bindgen::prelude::wasm_bindgen;
 
-use crate::err::{account_missing_err, SanctumRouterError};
-
 #[tsify_next::declare]
 pub type B58PK = Bs58Array<32, 44>;
 
@@ -66,18 +66,6 @@ impl AccountMeta {
     }
 }
 
-#[inline] // inlining reduces binary size by a bit
-pub fn keys_signer_writer_to_account_metas<const N: usize>(
-    keys: &[&[u8; 32]; N],
-    signer: &[bool; N],
-    writer: &[bool; N],
-) -> [AccountMeta; N] {
-    core::array::from_fn(|i| {
-        let k = keys[i];
-        AccountMeta::new(*k, Role::from_signer_writable(signer[i], writer[i]))
-    })
-}
-
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Tsify)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
 #[serde(rename_all = "camelCase")]
@@ -87,28 +75,60 @@ pub struct Instruction {
     pub program_address: B58PK,
 }
 
+impl Instruction {
+    /// For instructions of programs other than the router program
+    pub(crate) fn new(
+        program_address: [u8; 32],
+        keys: &[[u8; 32]],
+        is_signer: &[bool],
+        is_writer: &[bool],
+        data: &[u8],
+    ) -> Self {
+        Self {
+            program_address: B58PK::new(program_address),
+            accounts: keys
+                .iter()
+                .zip(is_signer)
+                .zip(is_writer)
+                .map(|((k, signer), writer)| {
+                    AccountMeta::new(*k, Role::from_signer_writable(*signer, *writer))
+                })
+                .collect(),
+            data: ByteBuf::from(data),
+        }
+    }
+}
+
+impl From<&RouterIx> for Instruction {
+    #[inline]
+    fn from(ix: &RouterIx) -> Self {
+        Self {
+            program_address: B58PK::new(*ix.program_address()),
+            accounts: ix
+                .accounts()
+                .map(|(k, signer, writer)| {
+                    AccountMeta::new(*k, Role::from_signer_writable(signer, writer))
+                })
+                .collect(),
+            data: ByteBuf::from(ix.data()),
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize, Tsify)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
 #[serde(rename_all = "camelCase")]
 pub struct AccountMap(pub HashMap<B58PK, Account>);
 
-// pass pubkey by value instead of ref to accomodate B58PK::new
-pub(crate) fn get_account(
-    accounts: &AccountMap,
-    pubkey: [u8; 32],
-) -> Result<&Account, SanctumRouterError> {
-    accounts
-        .0
-        .get(&B58PK::new(pubkey))
-        .ok_or_else(|| account_missing_err(&pubkey))
-}
-
-/// Basically HashMap.get(), but returns [`account_missing_err()`] if account missing instead of `None`
-pub(crate) fn get_account_data(
-    accounts: &AccountMap,
-    pubkey: [u8; 32],
-) -> Result<&[u8], SanctumRouterError> {
-    get_account(accounts, pubkey).map(|account| account.data.as_ref())
+impl AccountSource for AccountMap {
+    #[inline]
+    fn account(&self, pubkey: &[u8; 32]) -> Option<AccountRef<'_>> {
+        self.0.get(&B58PK::new(*pubkey)).map(|acc| AccountRef {
+            owner: &acc.owner.0,
+            data: &acc.data,
+            lamports: acc.lamports,
+        })
+    }
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize, Tsify)]
@@ -1,7 +1,9 @@
+mod config;
+mod decode;
 mod err;
+mod fee_token_account;
 mod init;
 mod interface;
 mod pda;
 mod router;
-mod routers;
 mod update;
@@ -0,0 +1,108 @@
+use bs58_fixed_wasm::Bs58Array;
+use sanctum_router_core::{
+    quote_best as core_quote_best, RouteKind, RouteQuote, SolValueCalc, WithRouterFee,
+};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    err::{invalid_data_err, SanctumRouterError},
+    router::{
+        deposit_sol::{quote_deposit_sol, DepositSolQuoteParams},
+        sol_value::sol_value_calc,
+        swap_via_stake::{quote_prefund_swap_via_stake, quote_swap_via_stake},
+        token_pair::TokenQuoteParams,
+        withdraw_sol::{quote_withdraw_sol, WithdrawSolQuoteParams},
+        SanctumRouterHandle,
+    },
+};
+
+/// Quotes every route that can swap `params.amt` of `params.inp` to `params.out`
+/// and returns the successful quotes ranked from most to least `netOut` output tokens.
+///
+/// Routes whose quote fails, e.g. because their pools have not been
+/// initialized or updated, are excluded. Returns an empty array if no route quotes successfully.
+///
+/// Requires `update()` to be called for each route to consider before calling this function
+#[wasm_bindgen(js_name = quoteBest)]
+pub fn quote_best(this: &SanctumRouterHandle, params: TokenQuoteParams) -> Box<[RouteQuote]> {
+    let TokenQuoteParams {
+        amt,
+        inp: Bs58Array(inp_mint),
+        out: Bs58Array(out_mint),
+    } = params;
+    core_quote_best(&inp_mint, &out_mint, amt, |route, amt| {
+        quote_route(this, route, amt, &params)
+    })
+    .iter()
+    .copied()
+    .collect()
+}
+
+fn quote_route(
+    this: &SanctumRouterHandle,
+    route: RouteKind,
+    amt: u64,
+    params: &TokenQuoteParams,
+) -> Result<RouteQuote, SanctumRouterError> {
+    let (inp, out, router_fee, bridge_rent) = match route {
+        RouteKind::DepositSol => {
+            let WithRouterFee { quote, router_fee } = quote_deposit_sol(
+                this,
+                DepositSolQuoteParams {
+                    amt,
+                    out: params.out,
+                },
+            )?
+            .quote;
+            (quote.inp, quote.out, router_fee, 0)
+        }
+        RouteKind::WithdrawSol => {
+            let WithRouterFee { quote, router_fee } = quote_withdraw_sol(
+                this,
+                WithdrawSolQuoteParams {
+                    amt,
+                    inp: params.inp,
+                },
+            )?
+            .quote;
+            (quote.inp, quote.out, router_fee, 0)
+        }
+        RouteKind::SwapViaStake => {
+            let q = quote_swap_via_stake(
+                this,
+                TokenQuoteParams {
+                    amt,
+                    ..params.clone()
+                },
+            )?;
+            let WithRouterFee { quote, router_fee } = q.quote;
+            (quote.inp, quote.out, router_fee, q.bridge_rent)
+        }
+        RouteKind::PrefundSwapViaStake => {
+            let WithRouterFee { quote, router_fee } = quote_prefund_swap_via_stake(
+                this,
+                TokenQuoteParams {
+                    amt,
+                    ..params.clone()
+                },
+            )?
+            .quote
+            .quote;
+            (quote.inp, quote.out, router_fee, 0)
+        }
+    };
+    let bridge_rent_tokens = match bridge_rent {
+        0 => 0,
+        rent => sol_value_calc(&this.0, &params.out.0)?
+            .tokens_for_sol(rent)
+            .ok_or_else(invalid_data_err)?,
+    };
+    Ok(RouteQuote {
+        route,
+        inp,
+        out,
+        router_fee,
+        bridge_rent,
+        net_out: out.saturating_sub(bridge_rent_tokens),
+    })
+}
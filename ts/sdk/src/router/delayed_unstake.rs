@@ -0,0 +1,256 @@
+//! Marinade delayed unstake (OrderUnstake, then Claim).
+//!
+//! These are marinade program instructions, not router program instructions,
+//! so no router fees are charged.
+
+use bs58_fixed_wasm::Bs58Array;
+use sanctum_marinade_liquid_staking_core::{
+    MARINADE_STAKING_PROGRAM, MSOL_MINT_ADDR, RESERVE_PUBKEY, STATE_PUBKEY,
+};
+use sanctum_router_core::{
+    MarinadeClaimIxAccsBuilder, MarinadeClaimIxData, MarinadeOrderUnstakeIxAccsBuilder,
+    MarinadeOrderUnstakeIxData, MarinadeOrderUnstakeQuote as CoreQuote, MarinadeTicketAccount,
+    MARINADE_CLAIM_IX_IS_SIGNER, MARINADE_CLAIM_IX_IS_WRITER, MARINADE_ORDER_UNSTAKE_IX_IS_SIGNER,
+    MARINADE_ORDER_UNSTAKE_IX_IS_WRITER, SYSTEM_PROGRAM, SYSVAR_CLOCK, SYSVAR_RENT, TOKEN_PROGRAM,
+};
+use serde::{Deserialize, Serialize};
+use tsify_next::Tsify;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    err::{
+        account_missing_err, invalid_data_err, marinade_delayed_unstake_err, SanctumRouterError,
+    },
+    interface::{AccountMap, Instruction, B58PK},
+    router::SanctumRouterHandle,
+};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase")]
+pub struct MarinadeOrderUnstakeQuoteParams {
+    /// Input mSOL amount
+    pub amt: u64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase")]
+pub struct MarinadeOrderUnstakeQuote {
+    /// mSOL burnt
+    pub inp: u64,
+
+    /// Lamports claimable from the ticket, after fees
+    pub lamports: u64,
+
+    /// In lamports, charged by marinade
+    pub fee: u64,
+
+    /// Epoch from which the ticket can be claimed
+    pub claimable_epoch: u64,
+}
+
+impl From<CoreQuote> for MarinadeOrderUnstakeQuote {
+    #[inline]
+    fn from(
+        CoreQuote {
+            inp,
+            lamports,
+            fee,
+            claimable_epoch,
+        }: CoreQuote,
+    ) -> Self {
+        Self {
+            inp,
+            lamports,
+            fee,
+            claimable_epoch,
+        }
+    }
+}
+
+/// Requires `update()` to be called with `marinadeDelayedUnstake`
+/// before calling this function
+#[wasm_bindgen(js_name = quoteMarinadeOrderUnstake)]
+pub fn quote_marinade_order_unstake(
+    this: &SanctumRouterHandle,
+    params: MarinadeOrderUnstakeQuoteParams,
+) -> Result<MarinadeOrderUnstakeQuote, SanctumRouterError> {
+    this.0
+        .marinade_router
+        .order_unstake_quoter(this.0.try_curr_epoch()?)?
+        .quote_order_unstake(params.amt)
+        .map(Into::into)
+        .map_err(marinade_delayed_unstake_err)
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase")]
+pub struct MarinadeOrderUnstakeParams {
+    /// Input mSOL amount
+    pub amt: u64,
+
+    /// mSOL token account to burn `amt` mSOL from.
+    /// Its owner becomes the ticket's beneficiary
+    pub signer_inp: B58PK,
+
+    /// Signing authority of `self.signer_inp`; user making the unstake.
+    pub signer: B58PK,
+
+    /// New ticket account.
+    ///
+    /// Must be created earlier in the same transaction with
+    /// 88 bytes of space and owner set to the marinade program
+    pub new_ticket_account: B58PK,
+}
+
+/// Requires `update()` to be called with `marinadeDelayedUnstake`
+/// before calling this function
+#[wasm_bindgen(js_name = marinadeOrderUnstakeIx)]
+pub fn marinade_order_unstake_ix(
+    this: &SanctumRouterHandle,
+    MarinadeOrderUnstakeParams {
+        amt,
+        signer_inp: Bs58Array(signer_inp),
+        signer: Bs58Array(signer),
+        new_ticket_account: Bs58Array(new_ticket_account),
+    }: MarinadeOrderUnstakeParams,
+) -> Result<Instruction, SanctumRouterError> {
+    // fail early instead of at execution time
+    quote_marinade_order_unstake(this, MarinadeOrderUnstakeQuoteParams { amt })?;
+
+    let keys = MarinadeOrderUnstakeIxAccsBuilder::start()
+        .with_state(STATE_PUBKEY)
+        .with_msol_mint(MSOL_MINT_ADDR)
+        .with_burn_msol_from(signer_inp)
+        .with_burn_msol_authority(signer)
+        .with_new_ticket_account(new_ticket_account)
+        .with_clock(SYSVAR_CLOCK)
+        .with_rent(SYSVAR_RENT)
+        .with_token_program(TOKEN_PROGRAM)
+        .build();
+    Ok(Instruction::new(
+        MARINADE_STAKING_PROGRAM,
+        &keys.0,
+        &MARINADE_ORDER_UNSTAKE_IX_IS_SIGNER.0,
+        &MARINADE_ORDER_UNSTAKE_IX_IS_WRITER.0,
+        &MarinadeOrderUnstakeIxData::new(amt).to_buf(),
+    ))
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase")]
+pub struct MarinadeTicket {
+    pub ticket: B58PK,
+
+    /// System account the ticket's lamports are claimed to
+    pub beneficiary: B58PK,
+
+    pub lamports: u64,
+
+    pub created_epoch: u64,
+
+    pub claimable_epoch: u64,
+
+    /// Whether `claimable_epoch` has been reached.
+    ///
+    /// The claim may still fail on-chain if it is made too soon after the start of
+    /// `claimable_epoch` or if marinade's reserve does not yet have enough SOL.
+    /// Both resolve themselves with time, so retry later if so.
+    pub claimable: bool,
+}
+
+/// Returns the parsed tickets in the same order as `tickets`.
+///
+/// Requires `update()` to be called with `marinadeDelayedUnstake`
+/// before calling this function
+///
+/// @param {B58PK[]} tickets
+/// @param {AccountMap} accounts fetched data of each of `tickets`
+#[wasm_bindgen(js_name = marinadeTickets)]
+pub fn marinade_tickets(
+    this: &SanctumRouterHandle,
+    // Clippy complains, needed for wasm_bindgen
+    #[allow(clippy::boxed_local)] tickets: Box<[B58PK]>,
+    accounts: &AccountMap,
+) -> Result<Box<[MarinadeTicket]>, SanctumRouterError> {
+    let curr_epoch = this.0.try_curr_epoch()?;
+    tickets
+        .iter()
+        .map(|Bs58Array(ticket)| {
+            let acc = try_ticket_account(accounts, ticket)?;
+            Ok(MarinadeTicket {
+                ticket: B58PK::new(*ticket),
+                beneficiary: B58PK::new(acc.beneficiary),
+                lamports: acc.lamports,
+                created_epoch: acc.created_epoch,
+                claimable_epoch: acc.claimable_epoch(),
+                claimable: acc.is_claimable(curr_epoch),
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase")]
+pub struct MarinadeClaimParams {
+    pub ticket: B58PK,
+}
+
+/// Claims `params.ticket`'s lamports to its beneficiary, closing it.
+///
+/// Returns `UserErr` with cause `TicketNotDue` if the ticket's claimable epoch
+/// has not been reached yet.
+///
+/// Requires `update()` to be called with `marinadeDelayedUnstake`
+/// before calling this function
+///
+/// @param {MarinadeClaimParams} params
+/// @param {AccountMap} accounts fetched data of `params.ticket`
+#[wasm_bindgen(js_name = marinadeClaimIx)]
+pub fn marinade_claim_ix(
+    this: &SanctumRouterHandle,
+    MarinadeClaimParams {
+        ticket: Bs58Array(ticket),
+    }: MarinadeClaimParams,
+    accounts: &AccountMap,
+) -> Result<Instruction, SanctumRouterError> {
+    let acc = try_ticket_account(accounts, &ticket)?;
+    acc.check_claimable(this.0.try_curr_epoch()?)
+        .map_err(marinade_delayed_unstake_err)?;
+
+    let keys = MarinadeClaimIxAccsBuilder::start()
+        .with_state(STATE_PUBKEY)
+        .with_reserve(RESERVE_PUBKEY)
+        .with_ticket_account(ticket)
+        .with_transfer_sol_to(acc.beneficiary)
+        .with_clock(SYSVAR_CLOCK)
+        .with_system_program(SYSTEM_PROGRAM)
+        .build();
+    Ok(Instruction::new(
+        MARINADE_STAKING_PROGRAM,
+        &keys.0,
+        &MARINADE_CLAIM_IX_IS_SIGNER.0,
+        &MARINADE_CLAIM_IX_IS_WRITER.0,
+        &MarinadeClaimIxData::new().to_buf(),
+    ))
+}
+
+fn try_ticket_account(
+    accounts: &AccountMap,
+    ticket: &[u8; 32],
+) -> Result<MarinadeTicketAccount, SanctumRouterError> {
+    let acc = accounts
+        .0
+        .get(&B58PK::new(*ticket))
+        .ok_or_else(|| account_missing_err(ticket))?;
+    if acc.owner.0 != MARINADE_STAKING_PROGRAM {
+        return Err(invalid_data_err());
+    }
+    MarinadeTicketAccount::from_acc_data(&acc.data)
+        .filter(|t| t.state == STATE_PUBKEY)
+        .ok_or_else(invalid_data_err)
+}
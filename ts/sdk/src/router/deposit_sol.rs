@@ -1,18 +1,21 @@
+use bs58_fixed_wasm::Bs58Array;
+use sanctum_router::{find_fee_token_account_pda, SanctumRouter};
 use sanctum_router_core::{
-    DepositSolQuoter, DepositSolSufAccs, StakeWrappedSolIxData, StakeWrappedSolPrefixKeysOwned,
-    WithRouterFee, NATIVE_MINT, SANCTUM_ROUTER_PROGRAM, STAKE_WRAPPED_SOL_PREFIX_ACCS_LEN,
-    STAKE_WRAPPED_SOL_PREFIX_IS_SIGNER, STAKE_WRAPPED_SOL_PREFIX_IS_WRITER, TOKEN_PROGRAM,
+    quote_batch, DepositSolQuoter, RouterConfig, RouterIx, StakeWrappedSolIxData,
+    StakeWrappedSolPrefixKeysOwned, TokenQuote, WithRouterFee, NATIVE_MINT, TOKEN_PROGRAM,
 };
 use serde::{Deserialize, Serialize};
-use serde_bytes::ByteBuf;
 use tsify_next::Tsify;
 use wasm_bindgen::prelude::*;
 
 use crate::{
-    err::{invalid_pda_err, marinade_err, spl_err, SanctumRouterError},
-    interface::{keys_signer_writer_to_account_metas, AccountMeta, Instruction, B58PK},
-    pda::router::find_fee_token_account_pda_internal,
-    router::{token_pair::TokenQuoteWithRouterFee, SanctumRouterHandle},
+    err::{
+        exact_out_err, invalid_pda_err, marinade_err, spl_err, too_many_accounts_err,
+        SanctumRouterError,
+    },
+    fee_token_account::check_fee_token_account_exists,
+    interface::{AccountMap, Instruction, B58PK},
+    router::{fee::FeeBreakdown, token_pair::TokenQuoteWithRouterFee, SanctumRouterHandle},
 };
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
@@ -33,21 +36,127 @@ pub fn quote_deposit_sol(
     params: DepositSolQuoteParams,
 ) -> Result<TokenQuoteWithRouterFee, SanctumRouterError> {
     let out_mint = params.out.0;
-    match out_mint {
+    let quote = match out_mint {
         sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR => this
             .0
             .marinade_router
             .deposit_sol_quoter()?
             .quote_deposit_sol(params.amt)
+            .map(|q| (q, 0))
             .map_err(marinade_err),
-        mint => this
-            .0
-            .try_find_spl_by_mint(&mint)?
-            .deposit_sol_quoter(this.0.try_curr_epoch()?)?
-            .quote_deposit_sol(params.amt)
-            .map_err(spl_err),
+        mint => {
+            let quoter = this
+                .0
+                .try_find_spl_by_mint(&mint)?
+                .deposit_sol_quoter(this.0.try_curr_epoch()?)?;
+            quoter
+                .quote_deposit_sol(params.amt)
+                .map(|q| (q, quoter.referral_fee(q.fee)))
+                .map_err(spl_err)
+        }
+    }?;
+    conv_quote(&this.0, &out_mint, quote)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositSolBatchQuoteParams {
+    /// Input lamport amounts
+    pub amts: Box<[u64]>,
+
+    /// Output mint
+    pub out: B58PK,
+}
+
+/// Like `quoteDepositSol()`, but quotes each of `params.amts` with the same quoter,
+/// returning the quotes in the same order.
+///
+/// Errors if any of the amounts fails to quote.
+///
+/// Requires `update()` to be called before calling this function
+#[wasm_bindgen(js_name = quoteDepositSolBatch)]
+pub fn quote_deposit_sol_batch(
+    this: &SanctumRouterHandle,
+    DepositSolBatchQuoteParams {
+        amts,
+        out: Bs58Array(out_mint),
+    }: DepositSolBatchQuoteParams,
+) -> Result<Box<[TokenQuoteWithRouterFee]>, SanctumRouterError> {
+    let amts = amts.iter().copied();
+    match out_mint {
+        sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR => quote_batch(
+            this.0.marinade_router.deposit_sol_quoter()?,
+            amts,
+            |quoter, amt| quoter.quote_deposit_sol(amt).map(|q| (q, 0)),
+        )
+        .map(|res| conv_quote(&this.0, &out_mint, res.map_err(marinade_err)?))
+        .collect(),
+        mint => quote_batch(
+            this.0
+                .try_find_spl_by_mint(&mint)?
+                .deposit_sol_quoter(this.0.try_curr_epoch()?)?,
+            amts,
+            |quoter, amt| {
+                quoter
+                    .quote_deposit_sol(amt)
+                    .map(|q| (q, quoter.referral_fee(q.fee)))
+            },
+        )
+        .map(|res| conv_quote(&this.0, &out_mint, res.map_err(spl_err)?))
+        .collect(),
     }
-    .map(|q| TokenQuoteWithRouterFee(WithRouterFee::zero(q)))
+}
+
+/// Quotes the minimum input lamports required to receive at least `params.amt` output tokens.
+///
+/// Requires `update()` to be called before calling this function
+#[wasm_bindgen(js_name = quoteDepositSolExactOut)]
+pub fn quote_deposit_sol_exact_out(
+    this: &SanctumRouterHandle,
+    DepositSolQuoteParams {
+        amt,
+        out: Bs58Array(out_mint),
+    }: DepositSolQuoteParams,
+) -> Result<TokenQuoteWithRouterFee, SanctumRouterError> {
+    let quote = match out_mint {
+        sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR => this
+            .0
+            .marinade_router
+            .deposit_sol_quoter()?
+            .quote_deposit_sol_exact_out(amt)
+            .map(|q| (q, 0))
+            .map_err(|e| exact_out_err(e, marinade_err)),
+        mint => {
+            let quoter = this
+                .0
+                .try_find_spl_by_mint(&mint)?
+                .deposit_sol_quoter(this.0.try_curr_epoch()?)?;
+            quoter
+                .quote_deposit_sol_exact_out(amt)
+                .map(|q| (q, quoter.referral_fee(q.fee)))
+                .map_err(|e| exact_out_err(e, spl_err))
+        }
+    }?;
+    conv_quote(&this.0, &out_mint, quote)
+}
+
+/// `referral_fee` is the part of `quote.fee` paid to the referrer
+pub(crate) fn conv_quote(
+    this: &SanctumRouter,
+    out_mint: &[u8; 32],
+    (quote, referral_fee): (TokenQuote, u64),
+) -> Result<TokenQuoteWithRouterFee, SanctumRouterError> {
+    let quote = WithRouterFee::zero(quote);
+    Ok(TokenQuoteWithRouterFee {
+        fees: FeeBreakdown::new(
+            this,
+            &NATIVE_MINT,
+            out_mint,
+            quote.deposit_sol_fees(referral_fee),
+        )?,
+        quote,
+    })
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
@@ -71,82 +180,50 @@ pub struct DepositSolSwapParams {
 }
 
 /// Requires `update()` to be called before calling this function
+///
+/// If `accounts` is provided, also checks that the router's fee token account
+/// for the output mint exists, returning `AccountMissingErr` if it does not.
 #[wasm_bindgen(js_name = depositSolIx)]
 pub fn deposit_sol_ix(
     this: &SanctumRouterHandle,
     params: DepositSolSwapParams,
+    accounts: Option<AccountMap>,
 ) -> Result<Instruction, SanctumRouterError> {
     let out_mint = params.out.0;
-    let (prefix_metas, data) = deposit_sol_prefix_metas_and_data(&params)?;
-
-    let metas: Box<[AccountMeta]> = match out_mint {
+    let config = &this.0.config;
+    check_fee_token_account_exists(config, accounts.as_ref(), &out_mint)?;
+    let ix = RouterIx::stake_wrapped_sol_prefix(
+        &deposit_sol_prefix_keys(config, &params)?,
+        StakeWrappedSolIxData::new(params.amt),
+    )
+    .with_program_address(config.router_program);
+
+    let ix = match out_mint {
         sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR => {
-            let router = this.0.marinade_router.deposit_sol_suf_accs()?;
-
-            let suffix_accounts = keys_signer_writer_to_account_metas(
-                &router.suffix_accounts().as_borrowed().0,
-                &router.suffix_is_signer().0,
-                &router.suffix_is_writable().0,
-            );
-
-            [prefix_metas.as_ref(), suffix_accounts.as_ref()]
-                .concat()
-                .into()
-        }
-        mint => {
-            let router = this.0.try_find_spl_by_mint(&mint)?.sol_suf_accs()?;
-
-            let suffix_accounts = keys_signer_writer_to_account_metas(
-                &router.suffix_accounts().as_borrowed().0,
-                &router.suffix_is_signer().0,
-                &router.suffix_is_writable().0,
-            );
-
-            [prefix_metas.as_ref(), suffix_accounts.as_ref()]
-                .concat()
-                .into()
+            ix.with_deposit_sol_suffix(this.0.marinade_router.deposit_sol_suf_accs()?)
         }
-    };
-
-    let ix = Instruction {
-        program_address: B58PK::new(SANCTUM_ROUTER_PROGRAM),
-        accounts: metas,
-        data: ByteBuf::from(data.to_buf()),
-    };
+        mint => ix.with_deposit_sol_suffix(this.0.try_find_spl_by_mint(&mint)?.sol_suf_accs()?),
+    }
+    .ok_or_else(too_many_accounts_err)?;
 
-    Ok(ix)
+    Ok(Instruction::from(&ix))
 }
 
-fn deposit_sol_prefix_metas_and_data(
+fn deposit_sol_prefix_keys(
+    config: &RouterConfig,
     swap_params: &DepositSolSwapParams,
-) -> Result<
-    (
-        [AccountMeta; STAKE_WRAPPED_SOL_PREFIX_ACCS_LEN],
-        StakeWrappedSolIxData,
-    ),
-    SanctumRouterError,
-> {
-    let metas = keys_signer_writer_to_account_metas(
-        &StakeWrappedSolPrefixKeysOwned::default()
-            .with_consts()
-            .with_user(swap_params.signer.0)
-            .with_wsol_mint(NATIVE_MINT)
-            .with_out_mint(swap_params.out.0)
-            .with_inp_wsol(swap_params.signer_inp.0)
-            .with_out_token(swap_params.signer_out.0)
-            .with_token_program(TOKEN_PROGRAM)
-            .with_out_fee_token(
-                find_fee_token_account_pda_internal(&swap_params.out.0)
-                    .ok_or_else(invalid_pda_err)?
-                    .0,
-            )
-            .as_borrowed()
-            .0,
-        &STAKE_WRAPPED_SOL_PREFIX_IS_SIGNER.0,
-        &STAKE_WRAPPED_SOL_PREFIX_IS_WRITER.0,
-    );
-
-    let data = StakeWrappedSolIxData::new(swap_params.amt);
-
-    Ok((metas, data))
+) -> Result<StakeWrappedSolPrefixKeysOwned, SanctumRouterError> {
+    Ok(StakeWrappedSolPrefixKeysOwned::default()
+        .with_config_consts(config)
+        .with_user(swap_params.signer.0)
+        .with_wsol_mint(NATIVE_MINT)
+        .with_out_mint(swap_params.out.0)
+        .with_inp_wsol(swap_params.signer_inp.0)
+        .with_out_token(swap_params.signer_out.0)
+        .with_token_program(TOKEN_PROGRAM)
+        .with_out_fee_token(
+            find_fee_token_account_pda(config, &swap_params.out.0)
+                .ok_or_else(invalid_pda_err)?
+                .0,
+        ))
 }
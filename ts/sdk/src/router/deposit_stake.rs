@@ -1,19 +1,28 @@
+use bs58_fixed_wasm::Bs58Array;
+use sanctum_router::{find_fee_token_account_pda, SanctumRouter};
 use sanctum_router_core::{
-    ActiveStakeParams, DepositStakeIxAccsBuilder, DepositStakeIxData, DepositStakeQuoter,
-    DepositStakeSufAccs, StakeAccountLamports, WithRouterFee, DEPOSIT_STAKE_IX_ACCS_LEN,
-    DEPOSIT_STAKE_IX_IS_SIGNER, DEPOSIT_STAKE_IX_IS_WRITER_NON_WSOL_OUT,
-    DEPOSIT_STAKE_IX_IS_WRITER_WSOL_OUT, NATIVE_MINT, SANCTUM_ROUTER_PROGRAM,
+    quote_exact_out, ActiveStakeParams, DepositStakeIxAccsBuilder, DepositStakeIxData,
+    DepositStakeIxKeysOwned, DepositStakeQuoter, ExactOutQuoteErr, RouterConfig, RouterFee,
+    RouterIx, StakeAccountLamports, WithRouterFee, NATIVE_MINT,
 };
+use sanctum_spl_stake_pool_core::STAKE_ACCOUNT_RENT_EXEMPT_LAMPORTS;
 use serde::{Deserialize, Serialize};
-use serde_bytes::ByteBuf;
 use tsify_next::Tsify;
 use wasm_bindgen::prelude::*;
 
 use crate::{
-    err::{invalid_pda_err, marinade_err, reserve_err, spl_err, SanctumRouterError},
-    interface::{keys_signer_writer_to_account_metas, AccountMeta, Instruction, B58PK},
-    pda::router::find_fee_token_account_pda_internal,
-    router::SanctumRouterHandle,
+    err::{
+        exact_out_err, invalid_pda_err, marinade_err, reserve_err, spl_err, too_many_accounts_err,
+        SanctumRouterError,
+    },
+    fee_token_account::check_fee_token_account_exists,
+    interface::{AccountMap, Instruction, B58PK},
+    router::{
+        fee::{deposit_stake_fee_kind, FeeBreakdown},
+        swap_via_stake::router_fee_for_out,
+        token_pair::TokenQuoteParams,
+        SanctumRouterHandle,
+    },
 };
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
@@ -56,12 +65,17 @@ pub struct DepositStakeQuote {
     pub fee: u64,
 }
 
-// need to use a simple newtype here instead of type alias
-// otherwise wasm_bindgen shits itself with missing generics
 #[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
 #[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
 #[serde(rename_all = "camelCase")]
-pub struct DepositStakeQuoteWithRouterFee(pub(crate) WithRouterFee<DepositStakeQuote>);
+pub struct DepositStakeQuoteWithRouterFee {
+    #[serde(flatten)]
+    pub(crate) quote: WithRouterFee<DepositStakeQuote>,
+
+    /// Omitted if the SOL value of either mint cannot be calculated
+    #[tsify(optional)]
+    pub(crate) fees: Option<FeeBreakdown>,
+}
 
 /// Requires `update()` to be called before calling this function
 #[wasm_bindgen(js_name = quoteDepositStake)]
@@ -92,15 +106,85 @@ pub fn quote_deposit_stake(
                 .map_err(spl_err)
         }
     }
-    .map(|q| {
-        conv_quote(if params.out.0 != sanctum_router_core::NATIVE_MINT {
-            q.with_router_fee()
-        } else {
-            WithRouterFee::zero(q)
-        })
+    .and_then(|q| {
+        conv_quote(
+            &this.0,
+            &out_mint,
+            q.with_router_fee_of(&router_fee_for_out(
+                this.0.config.fees.deposit_stake,
+                &out_mint,
+            )),
+        )
     })
 }
 
+/// Quotes the minimum actively staked lamports a stake account must have
+/// to receive at least `params.amt` output tokens after router fees.
+///
+/// `params.inp` is the vote account the stake account is delegated to.
+/// The stake account is assumed to only have its rent-exempt lamports unstaked.
+///
+/// Requires `update()` to be called before calling this function
+#[wasm_bindgen(js_name = quoteDepositStakeExactOut)]
+pub fn quote_deposit_stake_exact_out(
+    this: &SanctumRouterHandle,
+    TokenQuoteParams {
+        amt,
+        inp: Bs58Array(vote),
+        out: Bs58Array(out_mint),
+    }: TokenQuoteParams,
+) -> Result<DepositStakeQuoteWithRouterFee, SanctumRouterError> {
+    match out_mint {
+        NATIVE_MINT => quote_exact_out_inner(
+            this.0.reserve_router.deposit_stake_quoter()?,
+            &vote,
+            amt,
+            &RouterFee::ZERO,
+        )
+        .map_err(|e| exact_out_err(e, reserve_err)),
+        sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR => quote_exact_out_inner(
+            this.0.marinade_router.deposit_stake_quoter()?,
+            &vote,
+            amt,
+            &this.0.config.fees.deposit_stake,
+        )
+        .map_err(|e| exact_out_err(e, marinade_err)),
+        mint => quote_exact_out_inner(
+            this.0
+                .try_find_spl_by_mint(&mint)?
+                .deposit_stake_quoter(this.0.try_curr_epoch()?)?,
+            &vote,
+            amt,
+            &this.0.config.fees.deposit_stake,
+        )
+        .map_err(|e| exact_out_err(e, spl_err)),
+    }
+    .and_then(|q| conv_quote(&this.0, &out_mint, q))
+}
+
+fn quote_exact_out_inner<D: DepositStakeQuoter>(
+    quoter: D,
+    vote: &[u8; 32],
+    tokens: u64,
+    router_fee: &RouterFee,
+) -> Result<WithRouterFee<sanctum_router_core::DepositStakeQuote>, ExactOutQuoteErr<D::Error>> {
+    quote_exact_out(
+        tokens,
+        |staked| {
+            quoter
+                .quote_deposit_stake(ActiveStakeParams {
+                    vote: *vote,
+                    lamports: StakeAccountLamports {
+                        staked,
+                        unstaked: STAKE_ACCOUNT_RENT_EXEMPT_LAMPORTS,
+                    },
+                })
+                .map(|q| q.with_router_fee_of(router_fee))
+        },
+        |q| q.quote.out,
+    )
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
 #[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
 #[serde(rename_all = "camelCase")]
@@ -123,122 +207,94 @@ pub struct DepositStakeSwapParams {
 
 /// Requires `update()` to be called before calling this function
 ///
+/// If `accounts` is provided, also checks that the router's fee token account
+/// for the output mint exists, returning `AccountMissingErr` if it does not.
+///
 /// @param {SanctumRouterHandle} _this
 /// @param {DepositStakeSwapParams} params
+/// @param {AccountMap | undefined} accounts
 #[wasm_bindgen(js_name = depositStakeIx)]
 pub fn deposit_stake_ix(
     this: &SanctumRouterHandle,
     params: DepositStakeSwapParams,
+    accounts: Option<AccountMap>,
 ) -> Result<Instruction, SanctumRouterError> {
     let out_mint = params.out.0;
+    let config = &this.0.config;
+    check_fee_token_account_exists(config, accounts.as_ref(), &out_mint)?;
     let vote_account = params.inp.0;
     let stake_account = params.signer_inp.0;
-    let (prefix_metas, data) = deposit_stake_prefix_metas_and_data(&params)?;
+    let ix = RouterIx::deposit_stake_prefix(
+        &deposit_stake_prefix_keys(config, &params)?,
+        DepositStakeIxData::new(),
+    )
+    .with_program_address(config.router_program);
 
-    let metas: Box<[AccountMeta]> = match out_mint {
-        sanctum_router_core::NATIVE_MINT => {
-            let router = this
-                .0
+    let ix = match out_mint {
+        NATIVE_MINT => ix.with_deposit_stake_suffix(
+            this.0
                 .reserve_router
-                .deposit_stake_suf_accs(&stake_account)?;
-
-            let suffix_accounts = keys_signer_writer_to_account_metas(
-                &router.suffix_accounts().as_borrowed().0,
-                &router.suffix_is_signer().0,
-                &router.suffix_is_writable().0,
-            );
-
-            [prefix_metas.as_ref(), suffix_accounts.as_ref()]
-                .concat()
-                .into()
-        }
-        sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR => {
-            let router = this
-                .0
+                .deposit_stake_suf_accs(config, &stake_account)?,
+        ),
+        sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR => ix.with_deposit_stake_suffix(
+            this.0
                 .marinade_router
-                .deposit_stake_suf_accs(&vote_account)?;
-
-            let suffix_accounts = keys_signer_writer_to_account_metas(
-                &router.suffix_accounts().as_borrowed().0,
-                &router.suffix_is_signer().0,
-                &router.suffix_is_writable().0,
-            );
-
-            [prefix_metas.as_ref(), suffix_accounts.as_ref()]
-                .concat()
-                .into()
-        }
-        mint => {
-            let router = this
-                .0
+                .deposit_stake_suf_accs(&vote_account)?,
+        ),
+        mint => ix.with_deposit_stake_suffix(
+            this.0
                 .try_find_spl_by_mint(&mint)?
-                .deposit_stake_suf_accs(&vote_account)?;
-
-            let suffix_accounts = keys_signer_writer_to_account_metas(
-                &router.suffix_accounts().as_borrowed().0,
-                &router.suffix_is_signer().0,
-                &router.suffix_is_writable().0,
-            );
-
-            [prefix_metas.as_ref(), suffix_accounts.as_ref()]
-                .concat()
-                .into()
-        }
-    };
-
-    let ix = Instruction {
-        program_address: B58PK::new(SANCTUM_ROUTER_PROGRAM),
-        accounts: metas,
-        data: ByteBuf::from(data.to_buf()),
-    };
+                .deposit_stake_suf_accs(&vote_account)?,
+        ),
+    }
+    .ok_or_else(too_many_accounts_err)?;
 
-    Ok(ix)
+    Ok(Instruction::from(&ix))
 }
 
 fn conv_quote(
-    WithRouterFee {
+    this: &SanctumRouter,
+    out_mint: &[u8; 32],
+    quote: WithRouterFee<sanctum_router_core::DepositStakeQuote>,
+) -> Result<DepositStakeQuoteWithRouterFee, SanctumRouterError> {
+    // input stake is valued in lamports
+    let fees = FeeBreakdown::new(
+        this,
+        &NATIVE_MINT,
+        out_mint,
+        quote.fees(deposit_stake_fee_kind(out_mint)),
+    )?;
+    let WithRouterFee {
         quote: sanctum_router_core::DepositStakeQuote { inp, out, fee },
         router_fee,
-    }: WithRouterFee<sanctum_router_core::DepositStakeQuote>,
-) -> DepositStakeQuoteWithRouterFee {
-    DepositStakeQuoteWithRouterFee(WithRouterFee {
-        quote: DepositStakeQuote {
-            inp: inp.lamports,
-            vote: B58PK::new(inp.vote),
-            out,
-            fee,
+    } = quote;
+    Ok(DepositStakeQuoteWithRouterFee {
+        quote: WithRouterFee {
+            quote: DepositStakeQuote {
+                inp: inp.lamports,
+                vote: B58PK::new(inp.vote),
+                out,
+                fee,
+            },
+            router_fee,
         },
-        router_fee,
+        fees,
     })
 }
 
-fn deposit_stake_prefix_metas_and_data(
+fn deposit_stake_prefix_keys(
+    config: &RouterConfig,
     swap_params: &DepositStakeSwapParams,
-) -> Result<([AccountMeta; DEPOSIT_STAKE_IX_ACCS_LEN], DepositStakeIxData), SanctumRouterError> {
-    let metas = keys_signer_writer_to_account_metas(
-        &DepositStakeIxAccsBuilder::start()
-            .with_user(swap_params.signer.0)
-            .with_out_token(swap_params.signer_out.0)
-            .with_out_fee_token(
-                find_fee_token_account_pda_internal(&swap_params.out.0)
-                    .ok_or_else(invalid_pda_err)?
-                    .0,
-            )
-            .with_out_mint(swap_params.out.0)
-            .with_inp_stake(swap_params.signer_inp.0)
-            .build()
-            .as_borrowed()
-            .0,
-        &DEPOSIT_STAKE_IX_IS_SIGNER.0,
-        &if swap_params.out.0 == NATIVE_MINT {
-            DEPOSIT_STAKE_IX_IS_WRITER_WSOL_OUT
-        } else {
-            DEPOSIT_STAKE_IX_IS_WRITER_NON_WSOL_OUT
-        }
-        .0,
-    );
-
-    let data = DepositStakeIxData::new();
-
-    Ok((metas, data))
+) -> Result<DepositStakeIxKeysOwned, SanctumRouterError> {
+    Ok(DepositStakeIxAccsBuilder::start()
+        .with_user(swap_params.signer.0)
+        .with_out_token(swap_params.signer_out.0)
+        .with_out_fee_token(
+            find_fee_token_account_pda(config, &swap_params.out.0)
+                .ok_or_else(invalid_pda_err)?
+                .0,
+        )
+        .with_out_mint(swap_params.out.0)
+        .with_inp_stake(swap_params.signer_inp.0)
+        .build())
 }
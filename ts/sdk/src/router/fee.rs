@@ -0,0 +1,61 @@
+use sanctum_router::SanctumRouter;
+use sanctum_router_core::{
+    Fee, FeeBreakdown as CoreFeeBreakdown, FeeComponent, FeeKind, NATIVE_MINT,
+};
+use serde::{Deserialize, Serialize};
+use tsify_next::Tsify;
+
+use crate::{
+    err::{invalid_data_err, SanctumRouterError},
+    router::sol_value::sol_value_calc,
+};
+
+/// Fees of a quote, each in its native unit and converted to SOL
+/// at the respective pool's current exchange rate
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeBreakdown {
+    /// Only nonzero fees are included
+    pub components: Box<[FeeComponent]>,
+
+    /// Sum of all `components`' `lamports`
+    pub total_lamports: u64,
+}
+
+impl FeeBreakdown {
+    /// `inp_mint` and `out_mint` should be wSOL for stake accounts.
+    ///
+    /// Returns `None` if the SOL value of either mint cannot be calculated,
+    /// so that quoting does not fail just because its fees cannot be converted to SOL.
+    pub(crate) fn new(
+        this: &SanctumRouter,
+        inp_mint: &[u8; 32],
+        out_mint: &[u8; 32],
+        fees: impl IntoIterator<Item = Fee>,
+    ) -> Result<Option<Self>, SanctumRouterError> {
+        let (inp_calc, out_calc) = match (
+            sol_value_calc(this, inp_mint),
+            sol_value_calc(this, out_mint),
+        ) {
+            (Ok(i), Ok(o)) => (i, o),
+            _ => return Ok(None),
+        };
+        let breakdown =
+            CoreFeeBreakdown::new(fees, &*inp_calc, &*out_calc).ok_or_else(invalid_data_err)?;
+        Ok(Some(Self {
+            components: breakdown.iter().copied().collect(),
+            total_lamports: breakdown.total_lamports(),
+        }))
+    }
+}
+
+/// Deposit stake fees for output to wSOL are charged by the reserve pool
+#[inline]
+pub(crate) fn deposit_stake_fee_kind(out_mint: &[u8; 32]) -> FeeKind {
+    if *out_mint == NATIVE_MINT {
+        FeeKind::ReserveUnstake
+    } else {
+        FeeKind::PoolManager
+    }
+}
@@ -1,19 +1,15 @@
-use std::collections::hash_map::Entry;
-
 use bs58_fixed_wasm::Bs58Array;
-use sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR;
-use sanctum_router_core::NATIVE_MINT;
+use sanctum_router::SanctumRouter;
 use serde::{Deserialize, Serialize};
-use solido_legacy_core::STSOL_MINT_ADDR;
 use tsify_next::Tsify;
 use wasm_bindgen::prelude::*;
 
 use crate::{
-    err::{invalid_data_err, SanctumRouterError},
+    config::{router_config, RouterConfig},
+    err::SanctumRouterError,
     init::InitData,
     interface::B58PK,
-    router::{SanctumRouter, SanctumRouterHandle},
-    routers::SplStakePoolRouterOwned,
+    router::SanctumRouterHandle,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Tsify)]
@@ -40,27 +36,8 @@ pub fn init(
     // Clippy complains, needed for wasm_bindgen
     #[allow(clippy::boxed_local)] init_mints: Box<[InitMint]>,
 ) -> Result<(), SanctumRouterError> {
-    init_mints.iter().try_for_each(
-        |InitMint {
-             mint: Bs58Array(mint),
-             init,
-         }| {
-            match *mint {
-                // no-op for everything other than spl
-                NATIVE_MINT | MSOL_MINT_ADDR | STSOL_MINT_ADDR => Ok(()),
-                spl_mint => {
-                    let init_data = init.ok_or_else(invalid_data_err)?;
-                    match this.spl_routers.entry(spl_mint) {
-                        Entry::Occupied(_already_init) => Ok(()),
-                        Entry::Vacant(v) => {
-                            v.insert(SplStakePoolRouterOwned::init(&init_data)?);
-                            Ok(())
-                        }
-                    }
-                }
-            }
-        },
-    )
+    this.init(init_mints.iter().copied().map(Into::into))
+        .map_err(Into::into)
 }
 
 /// Returns if the given mints have already been {@link init}.
@@ -78,20 +55,32 @@ pub fn is_init(
 ) -> Box<[u8]> {
     mints
         .iter()
-        .map(|Bs58Array(mint)| {
-            u8::from(match *mint {
-                // everything other than spl does not need init() to be called
-                NATIVE_MINT | MSOL_MINT_ADDR | STSOL_MINT_ADDR => true,
-                spl_mint => this.spl_routers.contains_key(&spl_mint),
-            })
-        })
+        .map(|Bs58Array(mint)| u8::from(this.is_init(mint)))
         .collect()
 }
 
+impl From<InitMint> for sanctum_router::InitMint {
+    #[inline]
+    fn from(InitMint { mint, init }: InitMint) -> Self {
+        Self {
+            mint: mint.0,
+            init: init.map(Into::into),
+        }
+    }
+}
+
 /// Creates a new empty router that needs to have individual mints
 /// init and updated for the specific swap
 /// before it can start operating for it.
+///
+/// Routes for mainnet's deployment if `config` is omitted.
+///
+/// @param {RouterConfig | undefined} config
 #[wasm_bindgen(js_name = newSanctumRouter)]
-pub fn new_sanctum_router() -> Result<SanctumRouterHandle, SanctumRouterError> {
-    Ok(SanctumRouterHandle(SanctumRouter::default()))
+pub fn new_sanctum_router(
+    config: Option<RouterConfig>,
+) -> Result<SanctumRouterHandle, SanctumRouterError> {
+    Ok(SanctumRouterHandle(SanctumRouter::new(router_config(
+        config,
+    ))))
 }
@@ -0,0 +1,160 @@
+use bs58_fixed_wasm::Bs58Array;
+use sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR;
+use sanctum_router::SanctumRouter;
+use sanctum_router_core::{
+    DepositSolQuoter, SolValueCalc, WithdrawSolQuoter, WithdrawStakeQuoter, WsolSolValueCalc,
+    NATIVE_MINT,
+};
+use serde::{Deserialize, Serialize};
+use solido_legacy_core::STSOL_MINT_ADDR;
+use tsify_next::Tsify;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    err::{invalid_data_err, SanctumRouterError},
+    interface::B58PK,
+    router::SanctumRouterHandle,
+};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase")]
+pub struct SolValue {
+    pub mint: B58PK,
+
+    /// Lamports that `amt` tokens are worth at the pool's exchange rate, without fees
+    pub sol_value: u64,
+
+    /// Tokens that `amt` lamports are worth at the pool's exchange rate, without fees
+    pub tokens_for_sol: u64,
+
+    /// Tokens received for depositing `amt` lamports with DepositSol, after pool fees.
+    ///
+    /// Undefined if the pool does not support DepositSol or was not updated for it.
+    #[tsify(optional)]
+    pub deposit_sol: Option<u64>,
+
+    /// Lamports received for withdrawing `amt` tokens with WithdrawSol,
+    /// after pool fees but before router fees.
+    ///
+    /// Undefined if the pool does not support WithdrawSol or was not updated for it.
+    #[tsify(optional)]
+    pub withdraw_sol: Option<u64>,
+
+    /// Total lamports of the stake account received for withdrawing `amt` tokens with
+    /// WithdrawStake from the pool's default validator, after pool fees.
+    ///
+    /// Undefined if the pool does not support WithdrawStake or was not updated for it.
+    #[tsify(optional)]
+    pub withdraw_stake: Option<u64>,
+}
+
+/// Returns the SOL value of `amt` tokens and the tokens that `amt` lamports are worth
+/// for each of `mints`, both as fee-free exchange rates and as effective rates after
+/// each pool's deposit and withdraw fees.
+///
+/// The fee-free exchange rates require `update()` to have been called for any swap
+/// involving the mint. The effective rates are undefined unless `update()`
+/// was called for the respective swap.
+#[wasm_bindgen(js_name = getSolValue)]
+pub fn get_sol_value(
+    this: &SanctumRouterHandle,
+    // Clippy complains, needed for wasm_bindgen
+    #[allow(clippy::boxed_local)] mints: Box<[B58PK]>,
+    amt: u64,
+) -> Result<Box<[SolValue]>, SanctumRouterError> {
+    mints
+        .iter()
+        .map(|Bs58Array(mint)| sol_value(&this.0, mint, amt))
+        .collect()
+}
+
+fn sol_value(
+    this: &SanctumRouter,
+    mint: &[u8; 32],
+    amt: u64,
+) -> Result<SolValue, SanctumRouterError> {
+    let curr_epoch = this.curr_epoch;
+    let (sol_value, tokens_for_sol, deposit_sol, withdraw_sol, withdraw_stake) = match *mint {
+        NATIVE_MINT => {
+            let (sv, ts) = fee_free(WsolSolValueCalc, amt)?;
+            (sv, ts, Some(amt), Some(amt), None)
+        }
+        MSOL_MINT_ADDR => {
+            let r = &this.marinade_router;
+            let (sv, ts) = fee_free(r.sol_value_calc()?, amt)?;
+            let deposit_sol = r
+                .deposit_sol_quoter()
+                .ok()
+                .and_then(|q| q.quote_deposit_sol(amt).ok())
+                .map(|q| q.out);
+            let withdraw_stake = r
+                .withdraw_stake_quoter()
+                .ok()
+                .and_then(|q| {
+                    q.with_min_delegation(this.config.stake_min_delegation)
+                        .quote_withdraw_stake(amt, None)
+                        .ok()
+                })
+                .map(|q| q.out.lamports.total());
+            (sv, ts, deposit_sol, None, withdraw_stake)
+        }
+        STSOL_MINT_ADDR => {
+            let r = &this.lido_router;
+            let (sv, ts) = fee_free(r.sol_value_calc()?, amt)?;
+            let withdraw_stake = curr_epoch
+                .and_then(|e| r.withdraw_stake_quoter(e).ok())
+                .and_then(|q| {
+                    q.with_min_delegation(this.config.stake_min_delegation)
+                        .quote_withdraw_stake(amt, None)
+                        .ok()
+                })
+                .map(|q| q.out.lamports.total());
+            (sv, ts, None, None, withdraw_stake)
+        }
+        mint => {
+            let r = this.try_find_spl_by_mint(&mint)?;
+            let (sv, ts) = fee_free(r.sol_value_calc()?, amt)?;
+            let deposit_sol = curr_epoch
+                .and_then(|e| r.deposit_sol_quoter(e).ok())
+                .and_then(|q| q.quote_deposit_sol(amt).ok())
+                .map(|q| q.out);
+            let withdraw_sol = curr_epoch
+                .and_then(|e| r.withdraw_sol_quoter(e).ok())
+                .and_then(|q| q.quote_withdraw_sol(amt).ok())
+                .map(|q| q.out);
+            let withdraw_stake = curr_epoch
+                .and_then(|e| r.withdraw_stake_quoter(e).ok())
+                .and_then(|q| q.quote_withdraw_stake(amt, None).ok())
+                .map(|q| q.out.lamports.total());
+            (sv, ts, deposit_sol, withdraw_sol, withdraw_stake)
+        }
+    };
+    Ok(SolValue {
+        mint: B58PK::new(*mint),
+        sol_value,
+        tokens_for_sol,
+        deposit_sol,
+        withdraw_sol,
+        withdraw_stake,
+    })
+}
+
+fn fee_free(calc: impl SolValueCalc, amt: u64) -> Result<(u64, u64), SanctumRouterError> {
+    calc.sol_value(amt)
+        .zip(calc.tokens_for_sol(amt))
+        .ok_or_else(invalid_data_err)
+}
+
+/// The fee-free SOL value calculator of `mint`'s pool
+pub(crate) fn sol_value_calc<'a>(
+    this: &'a SanctumRouter,
+    mint: &[u8; 32],
+) -> Result<Box<dyn SolValueCalc + 'a>, SanctumRouterError> {
+    Ok(match *mint {
+        NATIVE_MINT => Box::new(WsolSolValueCalc),
+        MSOL_MINT_ADDR => Box::new(this.marinade_router.sol_value_calc()?),
+        STSOL_MINT_ADDR => Box::new(this.lido_router.sol_value_calc()?),
+        mint => Box::new(this.try_find_spl_by_mint(&mint)?.sol_value_calc()?),
+    })
+}
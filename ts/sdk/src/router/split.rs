@@ -0,0 +1,533 @@
+use bs58_fixed_wasm::Bs58Array;
+use sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR;
+use sanctum_router::SanctumRouter;
+use sanctum_router_core::{
+    split_input, ActiveStakeParams, DepositStakeQuote, DepositStakeQuoter,
+    MarinadeDepositStakeQuoter, PrefundParams, ReserveDepositStakeQuoter, SplDepositStakeQuoter,
+    SplWithdrawSolQuoter, SplWithdrawStakeValQuoter, SplitAlloc, WithdrawSolQuoter,
+    WithdrawStakeQuote, WithdrawStakeQuoter, DEFAULT_MAX_BRIDGE_CANDIDATES, DEFAULT_SPLIT_PARTS,
+    NATIVE_MINT,
+};
+use sanctum_spl_stake_pool_core::{SplStakePoolError, StakePool};
+use serde::{Deserialize, Serialize};
+use solido_legacy_core::STSOL_MINT_ADDR;
+use tsify_next::Tsify;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    err::{
+        lido_wsq_err, marinade_err, marinade_wsq_err, prefund_wsq_err, reserve_err, spl_err,
+        spl_wsq_err, split_err, SanctumRouterErr, SanctumRouterError,
+    },
+    fee_token_account::check_fee_token_account_exists,
+    interface::{AccountMap, Bs58PkString, Instruction, B58PK},
+    router::{
+        swap_via_stake::{
+            map_quote, prefund_swap_via_stake_ix_for_wsq, router_fee_for_out,
+            PrefundSwapViaStakeQuoteWithRouterFee, SwapViaStakeSwapParams,
+        },
+        token_pair::TokenQuoteWithRouterFee,
+        withdraw_sol::{
+            conv_quote as conv_withdraw_sol_quote, withdraw_sol_ix, WithdrawSolSwapParams,
+        },
+        SanctumRouterHandle,
+    },
+};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitQuoteParams {
+    /// Total input LST amount
+    pub amt: u64,
+
+    /// Input mint
+    pub inp: B58PK,
+
+    /// Output mint
+    pub out: B58PK,
+
+    /// Number of equal parts to divide `amt` into.
+    /// Higher gives better output at the cost of more computation.
+    /// Defaults to 16 if omitted
+    #[tsify(optional)]
+    pub parts: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase", tag = "route")]
+pub enum SplitLegQuote {
+    /// `quote.quote.bridge.vote` is the bridge validator of this leg
+    PrefundSwapViaStake {
+        quote: PrefundSwapViaStakeQuoteWithRouterFee,
+    },
+
+    /// Only available if output mint is wSOL
+    WithdrawSol { quote: TokenQuoteWithRouterFee },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitQuote {
+    /// Total input tokens that will leave the user's wallet
+    pub inp: u64,
+
+    /// Total output tokens received, after all fees
+    pub out: u64,
+
+    /// Only legs that were allocated input
+    pub legs: Box<[SplitLegQuote]>,
+}
+
+/// Splits `params.amt` across multiple PrefundSwapViaStake bridge validators,
+/// plus a WithdrawSol leg if output mint is wSOL, to maximize total output.
+///
+/// Use for large swaps that exceed a single validator's active stake or a single pool's liquidity.
+///
+/// Up to 256 bridge validators from the input pool's validator list are considered.
+///
+/// Requires `update()` to be called with `prefundSwapViaStake`, and `withdrawSol` for the
+/// WithdrawSol leg to be considered, before calling this function
+#[wasm_bindgen(js_name = quoteSplit)]
+pub fn quote_split(
+    this: &SanctumRouterHandle,
+    params: SplitQuoteParams,
+) -> Result<SplitQuote, SanctumRouterError> {
+    quote_split_inner(&this.0, &params).map(|(quote, _wsqs)| quote)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitSwapParams {
+    /// Total input LST amount
+    pub amt: u64,
+
+    /// Input mint
+    pub inp: B58PK,
+
+    /// Output mint
+    pub out: B58PK,
+
+    /// Same as {@link SplitQuoteParams}.parts
+    #[tsify(optional)]
+    pub parts: Option<u64>,
+
+    /// Input token account to transfer `amt` tokens from
+    pub signer_inp: B58PK,
+
+    /// Output token account to receive tokens to
+    pub signer_out: B58PK,
+
+    /// Bridge stake seed of the first PrefundSwapViaStake leg.
+    /// Subsequent legs use consecutive seeds
+    pub bridge_stake_seed_start: u32,
+
+    /// Signing authority of `self.signer_inp`; user making the swap.
+    pub signer: B58PK,
+}
+
+/// Returns one instruction per leg of `quoteSplit()`'s returned quote, in the same order.
+///
+/// Each PrefundSwapViaStake leg uses a distinct bridge stake seed,
+/// starting from `params.bridgeStakeSeedStart`.
+///
+/// Requires `update()` to be called before calling this function
+///
+/// If `accounts` is provided, also checks that the router's fee token account
+/// for the output mint exists, returning `AccountMissingErr` if it does not.
+#[wasm_bindgen(js_name = splitIxs)]
+pub fn split_ixs(
+    this: &SanctumRouterHandle,
+    params: SplitSwapParams,
+    accounts: Option<AccountMap>,
+) -> Result<Box<[Instruction]>, SanctumRouterError> {
+    let SplitSwapParams {
+        amt,
+        inp,
+        out,
+        parts,
+        signer_inp,
+        signer_out,
+        bridge_stake_seed_start,
+        signer,
+    } = params;
+    // checked once here instead of in each leg's ix
+    check_fee_token_account_exists(&this.0.config, accounts.as_ref(), &out.0)?;
+    let (SplitQuote { legs, .. }, wsqs) = quote_split_inner(
+        &this.0,
+        &SplitQuoteParams {
+            amt,
+            inp,
+            out,
+            parts,
+        },
+    )?;
+
+    let mut bridge_stake_seed = bridge_stake_seed_start;
+    legs.iter()
+        .zip(wsqs.iter())
+        .map(|(leg, wsq)| match (leg, wsq) {
+            (SplitLegQuote::PrefundSwapViaStake { quote }, Some(wsq)) => {
+                // the leg's quoted WithdrawStake, which for marinade
+                // includes the stake account to split from
+                let ix = prefund_swap_via_stake_ix_for_wsq(
+                    &this.0,
+                    &SwapViaStakeSwapParams {
+                        amt: quote.quote.quote.quote.inp,
+                        inp,
+                        out,
+                        signer_inp,
+                        signer_out,
+                        bridge_stake_seed,
+                        signer,
+                        bridge_vote: Some(quote.quote.quote.quote.bridge.vote),
+                    },
+                    wsq,
+                )?;
+                bridge_stake_seed = bridge_stake_seed.wrapping_add(1);
+                Ok(ix)
+            }
+            (SplitLegQuote::PrefundSwapViaStake { .. }, None) => Err(SanctumRouterError {
+                code: SanctumRouterErr::InternalErr,
+                cause: None,
+            }),
+            (SplitLegQuote::WithdrawSol { quote }, _) => withdraw_sol_ix(
+                this,
+                WithdrawSolSwapParams {
+                    amt: quote.quote.quote.inp,
+                    inp,
+                    signer_inp,
+                    signer_out,
+                    signer,
+                },
+                None,
+            ),
+        })
+        .collect()
+}
+
+/// Also returns the WithdrawStake quote of each PrefundSwapViaStake leg,
+/// `None` for the WithdrawSol leg
+fn quote_split_inner(
+    this: &SanctumRouter,
+    params: &SplitQuoteParams,
+) -> Result<(SplitQuote, LegWsqs), SanctumRouterError> {
+    let SplitQuoteParams {
+        inp: Bs58Array(inp_mint),
+        out: Bs58Array(out_mint),
+        ..
+    } = params;
+
+    macro_rules! match_deposit_stake {
+        ($w_itr:expr, $handle_w:expr) => {
+            match *out_mint {
+                NATIVE_MINT => split_legs(this, params, $w_itr, $handle_w, ReserveOut),
+                MSOL_MINT_ADDR => {
+                    let d = this.marinade_router.deposit_stake_quoter()?;
+                    split_legs(this, params, $w_itr, $handle_w, d)
+                }
+                out => {
+                    let d = this
+                        .try_find_spl_by_mint(&out)?
+                        .deposit_stake_quoter(this.try_curr_epoch()?)?;
+                    split_legs(this, params, $w_itr, $handle_w, SplOut::new(d))
+                }
+            }
+        };
+    }
+
+    match *inp_mint {
+        STSOL_MINT_ADDR => {
+            let w_itr = std::iter::once(
+                this.lido_router
+                    .withdraw_stake_quoter(this.try_curr_epoch()?)?
+                    .with_min_delegation(this.config.stake_min_delegation),
+            );
+            match_deposit_stake!(w_itr, lido_wsq_err)
+        }
+        MSOL_MINT_ADDR => {
+            let w_itr = std::iter::once(
+                this.marinade_router
+                    .withdraw_stake_quoter()?
+                    .with_min_delegation(this.config.stake_min_delegation),
+            );
+            match_deposit_stake!(w_itr, marinade_wsq_err)
+        }
+        inp => {
+            let router = this.try_find_spl_by_mint(&inp)?;
+            let w_itr = SplWithdrawStakeValQuoter::all(
+                router.try_stake_pool()?,
+                router.try_validator_list()?,
+                this.try_curr_epoch()?,
+            )
+            .map_err(spl_err)?
+            .map(|w| w.with_min_delegation(this.config.stake_min_delegation));
+            match_deposit_stake!(w_itr, spl_wsq_err)
+        }
+    }
+}
+
+/// Pool state shared by all PrefundSwapViaStake legs of a split.
+///
+/// Every leg repays its prefund flash loan to the reserve pool,
+/// and deposits its withdrawn stake into the same output pool.
+#[derive(Clone)]
+struct SplitState<'a, D> {
+    reserve: ReserveDepositStakeQuoter<'a>,
+    d: D,
+}
+
+/// Output pool of the PrefundSwapViaStake legs of a split
+trait SplitDepositStake: Clone {
+    /// Quotes depositing `stake` and updates pool state to after the deposit has executed
+    fn quote_apply(
+        &mut self,
+        reserve: &mut ReserveDepositStakeQuoter,
+        stake: ActiveStakeParams,
+    ) -> Result<DepositStakeQuote, SanctumRouterError>;
+}
+
+/// Output is wSOL, deposits go into [`SplitState::reserve`]
+#[derive(Debug, Clone, Copy)]
+struct ReserveOut;
+
+impl SplitDepositStake for ReserveOut {
+    fn quote_apply(
+        &mut self,
+        reserve: &mut ReserveDepositStakeQuoter,
+        stake: ActiveStakeParams,
+    ) -> Result<DepositStakeQuote, SanctumRouterError> {
+        let quote = reserve.quote_deposit_stake(stake).map_err(reserve_err)?;
+        *reserve = reserve.apply_deposit_stake(&quote).map_err(reserve_err)?;
+        Ok(quote)
+    }
+}
+
+impl SplitDepositStake for MarinadeDepositStakeQuoter<'_> {
+    fn quote_apply(
+        &mut self,
+        _reserve: &mut ReserveDepositStakeQuoter,
+        stake: ActiveStakeParams,
+    ) -> Result<DepositStakeQuote, SanctumRouterError> {
+        let quote = self.quote_deposit_stake(stake).map_err(marinade_err)?;
+        *self = self.apply_deposit_stake(&quote);
+        Ok(quote)
+    }
+}
+
+/// Output is an spl pool.
+///
+/// `quoter.stake_pool` is borrowed, so deposits are applied to
+/// an owned copy of it instead, `stake_pool`.
+#[derive(Debug, Clone)]
+struct SplOut<'a> {
+    quoter: SplDepositStakeQuoter<'a>,
+    stake_pool: StakePool,
+}
+
+impl<'a> SplOut<'a> {
+    fn new(quoter: SplDepositStakeQuoter<'a>) -> Self {
+        Self {
+            stake_pool: quoter.stake_pool.clone(),
+            quoter,
+        }
+    }
+}
+
+impl SplitDepositStake for SplOut<'_> {
+    /// Same as `SplStakePoolRouterOwned::apply_deposit_stake()` in the sdk,
+    /// except that the validator list is left unchanged since spl pools
+    /// do not cap how much stake each validator can receive
+    fn quote_apply(
+        &mut self,
+        _reserve: &mut ReserveDepositStakeQuoter,
+        stake: ActiveStakeParams,
+    ) -> Result<DepositStakeQuote, SanctumRouterError> {
+        let quote = SplDepositStakeQuoter {
+            stake_pool: &self.stake_pool,
+            ..self.quoter.clone()
+        }
+        .quote_deposit_stake(stake)
+        .map_err(spl_err)?;
+        let calc_err = || spl_err(SplStakePoolError::CalculationFailure);
+        self.stake_pool.total_lamports = self
+            .stake_pool
+            .total_lamports
+            .checked_add(quote.inp.lamports.total())
+            .ok_or_else(calc_err)?;
+        self.stake_pool.pool_token_supply = quote
+            .out
+            .checked_add(quote.fee)
+            .and_then(|minted| self.stake_pool.pool_token_supply.checked_add(minted))
+            .ok_or_else(calc_err)?;
+        Ok(quote)
+    }
+}
+
+type LegWsqs = Box<[Option<WithdrawStakeQuote>]>;
+
+type Leg<'a, S> = Box<dyn Fn(&mut S, u64) -> Result<u64, SanctumRouterError> + 'a>;
+
+fn split_legs<W: WithdrawStakeQuoter, D: SplitDepositStake>(
+    this: &SanctumRouter,
+    SplitQuoteParams {
+        amt,
+        inp: Bs58Array(inp_mint),
+        out: Bs58Array(out_mint),
+        parts,
+    }: &SplitQuoteParams,
+    w_itr: impl IntoIterator<Item = W>,
+    handle_w: fn(W::Error) -> SanctumRouterError,
+    d: D,
+) -> Result<(SplitQuote, LegWsqs), SanctumRouterError> {
+    let amt = *amt;
+    let parts = parts.unwrap_or(DEFAULT_SPLIT_PARTS);
+    let prefund = this.prefund_params()?;
+    let state = SplitState {
+        reserve: this.reserve_router.deposit_stake_quoter()?,
+        d,
+    };
+    let router_fee = router_fee_for_out(this.config.fees.prefund_swap_via_stake, out_mint);
+    let withdraw_wrapped_sol_fee = this.config.fees.withdraw_wrapped_sol;
+    let ws: Vec<W> = w_itr
+        .into_iter()
+        .take(DEFAULT_MAX_BRIDGE_CANDIDATES)
+        .collect();
+
+    let quote_prefund_leg = |w: &W, s: &mut SplitState<D>, inp: u64| {
+        let wsq = w
+            .quote_prefund_withdraw_stake(
+                inp,
+                None,
+                &PrefundParams {
+                    reserves_balance: s.reserve.pool_balance(),
+                    ..prefund
+                },
+            )
+            .map_err(|e| prefund_wsq_err(e, handle_w))?;
+        s.reserve = s
+            .reserve
+            .after_prefund(&prefund.rent)
+            .map_err(reserve_err)?;
+        let dsq = s.d.quote_apply(&mut s.reserve, wsq.quote.out)?;
+        Ok::<_, SanctumRouterError>((wsq, dsq))
+    };
+    let quote_prefund_leg = &quote_prefund_leg;
+    // WithdrawSol leg is only considered if the quoter is available,
+    // i.e. input is an spl pool that was updated for WithdrawSol.
+    // It draws on the input pool's own reserves, independent of `state`.
+    let mut leg_errs: Vec<(String, SanctumRouterError)> = Vec::new();
+    let withdraw_sol_quoter = match (*out_mint, this.find_spl_by_mint(inp_mint)) {
+        (NATIVE_MINT, Some(r)) => {
+            match this
+                .try_curr_epoch()
+                .and_then(|curr_epoch| r.withdraw_sol_quoter(curr_epoch))
+            {
+                Ok(q) => Some(q),
+                Err(e) => {
+                    leg_errs.push(("WithdrawSol".to_owned(), e.into()));
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+    let quote_withdraw_sol_leg = &|q: &SplWithdrawSolQuoter, inp: u64| {
+        q.quote_withdraw_sol(inp)
+            .map(|q| q.with_router_fee(&withdraw_wrapped_sol_fee))
+            .map_err(spl_err)
+    };
+
+    let mut leg_names: Vec<String> = ws
+        .iter()
+        .enumerate()
+        .map(|(i, w)| match w.fixed_vote() {
+            Some(vote) => format!("PrefundSwapViaStake {}", Bs58PkString::encode(vote)),
+            None => format!("PrefundSwapViaStake #{i}"),
+        })
+        .collect();
+    let mut legs: Vec<Leg<SplitState<D>>> = ws
+        .iter()
+        .map(|w| -> Leg<SplitState<D>> {
+            Box::new(move |s: &mut SplitState<D>, inp: u64| {
+                quote_prefund_leg(w, s, inp)
+                    .map(|(_wsq, dsq)| dsq.with_router_fee_of(&router_fee).quote.out)
+            })
+        })
+        .collect();
+    if let Some(q) = withdraw_sol_quoter.as_ref() {
+        leg_names.push("WithdrawSol".to_owned());
+        legs.push(Box::new(move |_s: &mut SplitState<D>, inp: u64| {
+            quote_withdraw_sol_leg(q, inp).map(|q| q.quote.out)
+        }));
+    }
+
+    let mut allocs = vec![SplitAlloc::default(); legs.len()];
+    // first error of each leg
+    let mut first_errs: Vec<Option<SanctumRouterError>> = legs.iter().map(|_| None).collect();
+    let out = split_input(amt, parts, &state, &legs, &mut allocs, |i, e| {
+        first_errs[i] = Some(e)
+    })
+    .map_err(|e| {
+        leg_errs.extend(
+            leg_names
+                .into_iter()
+                .zip(first_errs)
+                .filter_map(|(name, e)| e.map(|e| (name, e))),
+        );
+        split_err(e, &leg_errs)
+    })?;
+
+    // requote allocated legs in order for their full quotes
+    let mut state = state;
+    let (legs, wsqs): (Vec<_>, Vec<_>) = allocs
+        .iter()
+        .enumerate()
+        .filter(|(_i, a)| a.inp > 0)
+        .map(|(i, SplitAlloc { inp, .. })| {
+            // legs executed successfully in the same order during the split
+            // so they always requote successfully
+            let internal_err = || SanctumRouterError {
+                code: SanctumRouterErr::InternalErr,
+                cause: None,
+            };
+            match (ws.get(i), withdraw_sol_quoter.as_ref()) {
+                (Some(w), _) => {
+                    let (wsq, dsq) =
+                        quote_prefund_leg(w, &mut state, *inp).map_err(|_| internal_err())?;
+                    let leg_wsq = wsq.quote;
+                    Ok((
+                        SplitLegQuote::PrefundSwapViaStake {
+                            quote: map_quote(this, inp_mint, out_mint, wsq, dsq)?,
+                        },
+                        Some(leg_wsq),
+                    ))
+                }
+                (None, Some(q)) => {
+                    let quote = quote_withdraw_sol_leg(q, *inp).map_err(|_| internal_err())?;
+                    Ok((
+                        SplitLegQuote::WithdrawSol {
+                            quote: conv_withdraw_sol_quote(this, inp_mint, quote)?,
+                        },
+                        None,
+                    ))
+                }
+                (None, None) => Err(internal_err()),
+            }
+        })
+        .collect::<Result<Vec<_>, SanctumRouterError>>()?
+        .into_iter()
+        .unzip();
+
+    Ok((
+        SplitQuote {
+            inp: amt,
+            out,
+            legs: legs.into(),
+        },
+        wsqs.into(),
+    ))
+}
@@ -1,34 +1,41 @@
 use bs58_fixed_wasm::Bs58Array;
 use sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR;
+use sanctum_router::{
+    create_slumdog_stake_addr, find_bridge_stake_acc_pda, find_fee_token_account_pda,
+    find_reserve_stake_account_record_pda, SanctumRouter,
+};
 use sanctum_router_core::{
-    quote_prefund_swap_via_stake as core_quote, DepositStakeQuote, DepositStakeSufAccs, Prefund,
-    PrefundSwapViaStakeIxData, PrefundSwapViaStakePrefixAccsBuilder, SplWithdrawStakeValQuoter,
-    StakeAccountLamports, WithRouterFee, WithdrawStakeQuote, WithdrawStakeSufAccs, NATIVE_MINT,
-    PREFUNDER, PREFUND_SWAP_VIA_STAKE_PREFIX_ACCS_LEN, PREFUND_SWAP_VIA_STAKE_PREFIX_IS_SIGNER,
-    PREFUND_SWAP_VIA_STAKE_PREFIX_IS_WRITER_NON_WSOL_OUT,
-    PREFUND_SWAP_VIA_STAKE_PREFIX_IS_WRITER_WSOL_OUT, SANCTUM_ROUTER_PROGRAM, STAKE_PROGRAM,
-    SYSTEM_PROGRAM, SYSVAR_CLOCK,
+    prefund_swap_via_stake_fees, quote_batch, quote_prefund_swap_via_stake as core_quote,
+    quote_prefund_swap_via_stake_best_out as core_quote_best_out,
+    quote_prefund_swap_via_stake_diagnosed_all as core_quote_diagnosed,
+    quote_prefund_swap_via_stake_exact_out as core_quote_exact_out,
+    quote_swap_via_stake as core_quote_non_prefund, swap_via_stake_fees, DepositStakeQuote,
+    Prefund, PrefundSwapViaStakeIxData, PrefundSwapViaStakePrefixAccsBuilder,
+    PrefundSwapViaStakePrefixKeysOwned, PrefundSwapViaStakeQuoteErr, RouterConfig, RouterFee,
+    RouterIx, SplWithdrawStakeValQuoter, StakeAccountLamports, SwapViaStakeIxData,
+    SwapViaStakePrefixAccsBuilder, SwapViaStakePrefixKeysOwned, WithRouterFee, WithdrawStakeQuote,
+    WithdrawStakeQuoter, DEFAULT_MAX_BRIDGE_CANDIDATES, NATIVE_MINT, STAKE_PROGRAM, SYSTEM_PROGRAM,
+    SYSVAR_CLOCK,
 };
+use sanctum_spl_stake_pool_core::SplStakePoolError;
 use serde::{Deserialize, Serialize};
-use serde_bytes::ByteBuf;
 use solido_legacy_core::STSOL_MINT_ADDR;
 use tsify_next::Tsify;
 use wasm_bindgen::prelude::*;
 
 use crate::{
     err::{
-        invalid_pda_err, lido_err, marinade_err, prefund_svsq_err, reserve_err, spl_err,
-        SanctumRouterError,
+        exact_out_err, invalid_pda_err, lido_wsq_err, marinade_err, marinade_wsq_err,
+        prefund_svsq_err, reserve_err, spl_err, spl_wsq_err, svsq_err, too_many_accounts_err,
+        SanctumRouterErr, SanctumRouterError,
     },
-    interface::{keys_signer_writer_to_account_metas, AccountMeta, Instruction, B58PK},
-    pda::{
-        reserve::find_reserve_stake_account_record_pda_internal,
-        router::{
-            create_slumdog_stake_internal, find_bridge_stake_acc_internal,
-            find_fee_token_account_pda_internal,
-        },
+    fee_token_account::check_fee_token_account_exists,
+    interface::{AccountMap, Instruction, B58PK},
+    router::{
+        fee::{deposit_stake_fee_kind, FeeBreakdown},
+        token_pair::{TokenBatchQuoteParams, TokenQuoteParams},
+        SanctumRouterHandle,
     },
-    router::{token_pair::TokenQuoteParams, SanctumRouter, SanctumRouterHandle},
 };
 
 /// Select parameters of an active stake account
@@ -70,15 +77,18 @@ pub struct SwapViaStakeQuote {
     pub bridge: ActiveStakeParams,
 }
 
-// need to use a simple newtype here instead of type alias
-// otherwise wasm_bindgen shits itself with missing generics
 // TODO: this type name is very long but keeps consistency with naming conventions
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
 #[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
 #[serde(rename_all = "camelCase")]
-pub struct PrefundSwapViaStakeQuoteWithRouterFee(
-    pub(crate) Prefund<WithRouterFee<SwapViaStakeQuote>>,
-);
+pub struct PrefundSwapViaStakeQuoteWithRouterFee {
+    #[serde(flatten)]
+    pub(crate) quote: Prefund<WithRouterFee<SwapViaStakeQuote>>,
+
+    /// Omitted if the SOL value of either mint cannot be calculated
+    #[tsify(optional)]
+    pub(crate) fees: Option<FeeBreakdown>,
+}
 
 /// Requires `update()` to be called before calling this function
 #[wasm_bindgen(js_name = quotePrefundSwapViaStake)]
@@ -90,22 +100,268 @@ pub fn quote_prefund_swap_via_stake(
         out: Bs58Array(out_mint),
     }: TokenQuoteParams,
 ) -> Result<PrefundSwapViaStakeQuoteWithRouterFee, SanctumRouterError> {
-    quote_prefund_swap_via_stake_inner(&this.0, amt, &inp_mint, &out_mint)
-        .map(|(wsq, dsq)| map_quote(&out_mint, wsq, dsq))
+    quote_prefund_swap_via_stake_inner(&this.0, amt, &inp_mint, &out_mint, None)
+        .and_then(|(wsq, dsq)| map_quote(&this.0, &inp_mint, &out_mint, wsq, dsq))
+}
+
+/// Like `quotePrefundSwapViaStake()`, but evaluates every bridge validator candidate,
+/// up to a bound, and returns the quote with the most output tokens instead of the first
+/// valid one. Ties are broken in favour of the earliest validator in the validator list.
+///
+/// Pass the returned quote's `bridge.vote` to `prefundSwapViaStakeIx()`
+/// to use the same bridge validator for the instruction.
+///
+/// Requires `update()` to be called before calling this function
+#[wasm_bindgen(js_name = quotePrefundSwapViaStakeBestOut)]
+pub fn quote_prefund_swap_via_stake_best_out(
+    this: &SanctumRouterHandle,
+    TokenQuoteParams {
+        amt,
+        inp: Bs58Array(inp_mint),
+        out: Bs58Array(out_mint),
+    }: TokenQuoteParams,
+) -> Result<PrefundSwapViaStakeQuoteWithRouterFee, SanctumRouterError> {
+    quote_prefund_swap_via_stake_inner(
+        &this.0,
+        amt,
+        &inp_mint,
+        &out_mint,
+        Some(DEFAULT_MAX_BRIDGE_CANDIDATES),
+    )
+    .and_then(|(wsq, dsq)| map_quote(&this.0, &inp_mint, &out_mint, wsq, dsq))
+}
+
+/// Which leg of a SwapViaStake a bridge validator was rejected on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub enum StakeLeg {
+    WithdrawStake,
+    DepositStake,
+}
+
+/// A bridge validator that was skipped because of an error
+/// specific to its vote account
+#[derive(Debug, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+#[serde(rename_all = "camelCase")]
+pub struct BridgeRejection {
+    /// Vote account of the bridge validator, if known
+    #[tsify(optional)]
+    pub vote: Option<B58PK>,
+
+    pub leg: StakeLeg,
+
+    /// Same as the `SanctumRouterErr` code of the error
+    /// that would have been thrown for this validator
+    pub code: SanctumRouterErr,
+
+    #[tsify(optional)]
+    pub cause: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase")]
+pub struct PrefundSwapViaStakeDiagnosis {
+    /// Undefined if no bridge validator gives a valid quote
+    #[tsify(optional)]
+    pub quote: Option<PrefundSwapViaStakeQuoteWithRouterFee>,
+
+    /// All bridge validators that were rejected, including those after `quote`'s,
+    /// in validator list order
+    pub rejections: Box<[BridgeRejection]>,
+}
+
+/// Like `quotePrefundSwapViaStake()`, but also returns every bridge validator that was
+/// rejected because of errors specific to their vote accounts, e.g. the validator
+/// is not on the output pool's validator list. All bridge validators are evaluated,
+/// even after the one `quote` uses.
+///
+/// Instead of throwing a `NoMatch` error, `quote` is left undefined
+/// so that `rejections` can explain why the swap has no route.
+///
+/// Requires `update()` to be called before calling this function
+#[wasm_bindgen(js_name = quotePrefundSwapViaStakeDiagnosed)]
+pub fn quote_prefund_swap_via_stake_diagnosed(
+    this: &SanctumRouterHandle,
+    TokenQuoteParams {
+        amt,
+        inp: Bs58Array(inp_mint),
+        out: Bs58Array(out_mint),
+    }: TokenQuoteParams,
+) -> Result<PrefundSwapViaStakeDiagnosis, SanctumRouterError> {
+    let (quote, rejections) =
+        quote_prefund_swap_via_stake_diagnosed_inner(&this.0, amt, &inp_mint, &out_mint)?;
+    Ok(PrefundSwapViaStakeDiagnosis {
+        quote: quote
+            .map(|(wsq, dsq)| map_quote(&this.0, &inp_mint, &out_mint, wsq, dsq))
+            .transpose()?,
+        rejections: rejections.into(),
+    })
+}
+
+/// Like `quotePrefundSwapViaStake()`, but quotes each of `params.amts` with the same
+/// quoters, returning the quotes in the same order.
+///
+/// The bridge validator is selected independently for each amount.
+///
+/// Errors if any of the amounts fails to quote.
+///
+/// Requires `update()` to be called before calling this function
+#[wasm_bindgen(js_name = quotePrefundSwapViaStakeBatch)]
+pub fn quote_prefund_swap_via_stake_batch(
+    this: &SanctumRouterHandle,
+    TokenBatchQuoteParams {
+        amts,
+        inp: Bs58Array(inp_mint),
+        out: Bs58Array(out_mint),
+    }: TokenBatchQuoteParams,
+) -> Result<Box<[PrefundSwapViaStakeQuoteWithRouterFee]>, SanctumRouterError> {
+    quote_prefund_swap_via_stake_batch_inner(&this.0, &amts, &inp_mint, &out_mint, None)?
+        .into_iter()
+        .map(|(wsq, dsq)| map_quote(&this.0, &inp_mint, &out_mint, wsq, dsq))
+        .collect()
+}
+
+/// Quotes the minimum input LST tokens required to receive at least `params.amt`
+/// output tokens after all fees.
+///
+/// The returned quote's `bridge.vote` is the chosen bridge vote account.
+///
+/// Requires `update()` to be called before calling this function
+#[wasm_bindgen(js_name = quotePrefundSwapViaStakeExactOut)]
+pub fn quote_prefund_swap_via_stake_exact_out(
+    this: &SanctumRouterHandle,
+    TokenQuoteParams {
+        amt,
+        inp: Bs58Array(inp_mint),
+        out: Bs58Array(out_mint),
+    }: TokenQuoteParams,
+) -> Result<PrefundSwapViaStakeQuoteWithRouterFee, SanctumRouterError> {
+    quote_prefund_swap_via_stake_exact_out_inner(&this.0, amt, &inp_mint, &out_mint)
+        .and_then(|(wsq, dsq)| conv_prefund_quote(&this.0, &inp_mint, &out_mint, wsq, dsq))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapViaStakeQuoteWithRouterFee {
+    #[serde(flatten)]
+    pub(crate) quote: WithRouterFee<SwapViaStakeQuote>,
+
+    /// Omitted if the SOL value of either mint cannot be calculated
+    #[tsify(optional)]
+    pub(crate) fees: Option<FeeBreakdown>,
+
+    /// Lamports the user pays for the rent-exemption of the bridge stake account,
+    /// on top of `inp` input tokens.
+    ///
+    /// This is deposited along with the withdrawn stake, so `out` includes its value
+    pub(crate) bridge_rent: u64,
+}
+
+/// Quote for the non-prefunded SwapViaStake instruction,
+/// where the user pays for the rent-exemption of the bridge stake account
+/// instead of the reserve prefund fee.
+///
+/// Requires `update()` to be called before calling this function
+#[wasm_bindgen(js_name = quoteSwapViaStake)]
+pub fn quote_swap_via_stake(
+    this: &SanctumRouterHandle,
+    TokenQuoteParams {
+        amt,
+        inp: Bs58Array(inp_mint),
+        out: Bs58Array(out_mint),
+    }: TokenQuoteParams,
+) -> Result<SwapViaStakeQuoteWithRouterFee, SanctumRouterError> {
+    let bridge_rent = this.0.try_rent()?.stake_account_rent_exempt_lamports();
+    quote_swap_via_stake_inner(&this.0, amt, &inp_mint, &out_mint, bridge_rent).and_then(
+        |(wsq, dsq)| map_quote_non_prefund(&this.0, &inp_mint, &out_mint, wsq, dsq, bridge_rent),
+    )
 }
 
 #[inline] // inlining reduces binary size slightly
-fn map_quote(
+pub(crate) fn map_quote(
+    this: &SanctumRouter,
+    inp_mint: &[u8; 32],
     out_mint: &[u8; 32],
-    Prefund {
-        quote: WithdrawStakeQuote {
-            inp, fee: inp_fee, ..
-        },
-        prefund_fee,
-    }: Prefund<WithdrawStakeQuote>,
+    wsq: Prefund<WithdrawStakeQuote>,
+    dsq: DepositStakeQuote,
+) -> Result<PrefundSwapViaStakeQuoteWithRouterFee, SanctumRouterError> {
+    conv_prefund_quote(
+        this,
+        inp_mint,
+        out_mint,
+        wsq,
+        dsq.with_router_fee_of(&router_fee_for_out(
+            this.config.fees.prefund_swap_via_stake,
+            out_mint,
+        )),
+    )
+}
+
+#[inline] // inlining reduces binary size slightly
+fn map_quote_non_prefund(
+    this: &SanctumRouter,
+    inp_mint: &[u8; 32],
+    out_mint: &[u8; 32],
+    wsq: WithdrawStakeQuote,
     dsq: DepositStakeQuote,
-) -> PrefundSwapViaStakeQuoteWithRouterFee {
-    let WithRouterFee {
+    bridge_rent: u64,
+) -> Result<SwapViaStakeQuoteWithRouterFee, SanctumRouterError> {
+    let dsq = dsq.with_router_fee_of(&router_fee_for_out(
+        this.config.fees.swap_via_stake,
+        out_mint,
+    ));
+    Ok(SwapViaStakeQuoteWithRouterFee {
+        fees: FeeBreakdown::new(
+            this,
+            inp_mint,
+            out_mint,
+            swap_via_stake_fees(&wsq, &dsq, deposit_stake_fee_kind(out_mint)),
+        )?,
+        quote: conv_quote(wsq, dsq),
+        bridge_rent,
+    })
+}
+
+fn conv_prefund_quote(
+    this: &SanctumRouter,
+    inp_mint: &[u8; 32],
+    out_mint: &[u8; 32],
+    wsq: Prefund<WithdrawStakeQuote>,
+    dsq: WithRouterFee<DepositStakeQuote>,
+) -> Result<PrefundSwapViaStakeQuoteWithRouterFee, SanctumRouterError> {
+    Ok(PrefundSwapViaStakeQuoteWithRouterFee {
+        fees: FeeBreakdown::new(
+            this,
+            inp_mint,
+            out_mint,
+            prefund_swap_via_stake_fees(&wsq, &dsq, deposit_stake_fee_kind(out_mint)),
+        )?,
+        quote: Prefund {
+            quote: conv_quote(wsq.quote, dsq),
+            prefund_fee: wsq.prefund_fee,
+        },
+    })
+}
+
+/// Router fees are not charged for deposits into the reserve pool
+#[inline]
+pub(crate) const fn router_fee_for_out(fee: RouterFee, out_mint: &[u8; 32]) -> RouterFee {
+    if !matches!(*out_mint, sanctum_router_core::NATIVE_MINT) {
+        fee
+    } else {
+        RouterFee::ZERO
+    }
+}
+
+#[inline] // inlining reduces binary size slightly
+fn conv_quote(
+    WithdrawStakeQuote {
+        inp, fee: inp_fee, ..
+    }: WithdrawStakeQuote,
+    WithRouterFee {
         quote:
             DepositStakeQuote {
                 inp:
@@ -118,31 +374,28 @@ fn map_quote(
                 ..
             },
         router_fee,
-    } = if *out_mint != sanctum_router_core::NATIVE_MINT {
-        dsq.with_router_fee()
-    } else {
-        WithRouterFee::zero(dsq)
-    };
-    PrefundSwapViaStakeQuoteWithRouterFee(Prefund {
-        quote: WithRouterFee {
-            quote: SwapViaStakeQuote {
-                inp,
-                out,
-                inp_fee,
-                out_fee,
-                bridge: ActiveStakeParams {
-                    vote: B58PK::new(bridge_vote),
-                    lamports: bridge_lamports_bef_deposit,
-                },
+    }: WithRouterFee<DepositStakeQuote>,
+) -> WithRouterFee<SwapViaStakeQuote> {
+    WithRouterFee {
+        quote: SwapViaStakeQuote {
+            inp,
+            out,
+            inp_fee,
+            out_fee,
+            bridge: ActiveStakeParams {
+                vote: B58PK::new(bridge_vote),
+                lamports: bridge_lamports_bef_deposit,
             },
-            router_fee,
         },
-        prefund_fee,
-    })
+        router_fee,
+    }
 }
 
 // Used by both quote and ix
 //
+// `best_out_max_candidates` selects the best-output bridge validator search
+// over at most that many candidates instead of taking the first valid one.
+//
 // TODO: this is potentially a very expensive N^2 operation since we need to
 // iterate through the validators of the pool we're withdrawing stake from and then
 // the pool we're depositing stake into. Optimize if needed.
@@ -151,8 +404,50 @@ fn quote_prefund_swap_via_stake_inner(
     amt: u64,
     inp_mint: &[u8; 32],
     out_mint: &[u8; 32],
+    best_out_max_candidates: Option<usize>,
 ) -> Result<(Prefund<WithdrawStakeQuote>, DepositStakeQuote), SanctumRouterError> {
-    let (reserves_balance, reserves_fee) = this.reserve_router.prefund_params()?;
+    quote_prefund_swap_via_stake_batch_inner(
+        this,
+        &[amt],
+        inp_mint,
+        out_mint,
+        best_out_max_candidates,
+    )
+    // exactly 1 quote for 1 amt
+    .map(|mut quotes| quotes.swap_remove(0))
+}
+
+// Same as `quote_prefund_swap_via_stake_inner()`, but selects the quoters once for all `amts`
+fn quote_prefund_swap_via_stake_batch_inner(
+    this: &SanctumRouter,
+    amts: &[u64],
+    inp_mint: &[u8; 32],
+    out_mint: &[u8; 32],
+    best_out_max_candidates: Option<usize>,
+) -> Result<Vec<(Prefund<WithdrawStakeQuote>, DepositStakeQuote)>, SanctumRouterError> {
+    let prefund = this.prefund_params()?;
+
+    // `$w_itr` is evaluated once per amount since the withdraw stake quoter iterators
+    // are consumed by each quote
+    macro_rules! quote {
+        ($w_itr:expr, $d:expr, $amt:expr) => {
+            match best_out_max_candidates {
+                None => core_quote($w_itr, $d, $amt, &prefund),
+                Some(max_candidates) => {
+                    core_quote_best_out($w_itr, $d, $amt, &prefund, max_candidates)
+                }
+            }
+        };
+    }
+
+    macro_rules! quote_all {
+        ($w_itr:expr, $d:expr, $handle_w:expr, $handle_d:expr) => {
+            quote_batch($d, amts.iter().copied(), |d, amt| {
+                quote!($w_itr, d, amt).map_err(|e| prefund_svsq_err(e, $handle_w, $handle_d))
+            })
+            .collect()
+        };
+    }
 
     // TODO: if we used dyn or some other means we could reduce
     // number of total match arms (Withdraw + Deposit) from n^2 to n
@@ -164,22 +459,203 @@ fn quote_prefund_swap_via_stake_inner(
                     let d = this
                         .reserve_router
                         .deposit_stake_quoter()?
-                        .after_prefund()
+                        .after_prefund(&prefund.rent)
+                        .map_err(reserve_err)?;
+                    quote_all!($w_itr, d, $handle_w, reserve_err)
+                }
+                MSOL_MINT_ADDR => {
+                    let d = this.marinade_router.deposit_stake_quoter()?;
+                    quote_all!($w_itr, d, $handle_w, marinade_err)
+                }
+                out => {
+                    let d = this
+                        .try_find_spl_by_mint(&out)?
+                        .deposit_stake_quoter(this.try_curr_epoch()?)?;
+                    quote_all!($w_itr, d, $handle_w, spl_err)
+                }
+            }
+        };
+    }
+
+    match *inp_mint {
+        STSOL_MINT_ADDR => {
+            let w = this
+                .lido_router
+                .withdraw_stake_quoter(this.try_curr_epoch()?)?
+                .with_min_delegation(this.config.stake_min_delegation);
+            match_deposit_stake!(std::iter::once(&w), lido_wsq_err)
+        }
+        MSOL_MINT_ADDR => {
+            let w = this
+                .marinade_router
+                .withdraw_stake_quoter()?
+                .with_min_delegation(this.config.stake_min_delegation);
+            match_deposit_stake!(std::iter::once(&w), marinade_wsq_err)
+        }
+        inp => {
+            let router = this.try_find_spl_by_mint(&inp)?;
+            let (stake_pool, validator_list, curr_epoch, min_delegation) = (
+                router.try_stake_pool()?,
+                router.try_validator_list()?,
+                this.try_curr_epoch()?,
+                this.config.stake_min_delegation,
+            );
+            match_deposit_stake!(
+                SplWithdrawStakeValQuoter::all(stake_pool, validator_list, curr_epoch)
+                    .map_err(spl_err)?
+                    .map(|w| w.with_min_delegation(min_delegation)),
+                spl_wsq_err
+            )
+        }
+    }
+}
+
+// Same as `quote_prefund_swap_via_stake_inner()` with no `best_out_max_candidates`,
+// but also returns all rejected bridge validators, and `None` instead of NoMatch
+fn quote_prefund_swap_via_stake_diagnosed_inner(
+    this: &SanctumRouter,
+    amt: u64,
+    inp_mint: &[u8; 32],
+    out_mint: &[u8; 32],
+) -> Result<
+    (
+        Option<(Prefund<WithdrawStakeQuote>, DepositStakeQuote)>,
+        Vec<BridgeRejection>,
+    ),
+    SanctumRouterError,
+> {
+    let prefund = this.prefund_params()?;
+    let mut rejections = Vec::new();
+
+    macro_rules! quote {
+        ($w_itr:expr, $d:expr, $handle_w:expr, $handle_d:expr) => {
+            match core_quote_diagnosed($w_itr, $d, amt, &prefund, |r| {
+                rejections.push(conv_bridge_rejection(r, $handle_w, $handle_d))
+            }) {
+                Ok(q) => Some(q),
+                Err(PrefundSwapViaStakeQuoteErr::NoMatch) => None,
+                Err(e) => return Err(prefund_svsq_err(e, $handle_w, $handle_d)),
+            }
+        };
+    }
+
+    macro_rules! match_deposit_stake {
+        ($w_itr:expr, $handle_w:expr) => {
+            match *out_mint {
+                NATIVE_MINT => {
+                    let d = this
+                        .reserve_router
+                        .deposit_stake_quoter()?
+                        .after_prefund(&prefund.rent)
+                        .map_err(reserve_err)?;
+                    quote!($w_itr, d, $handle_w, reserve_err)
+                }
+                MSOL_MINT_ADDR => {
+                    let d = this.marinade_router.deposit_stake_quoter()?;
+                    quote!($w_itr, d, $handle_w, marinade_err)
+                }
+                out => {
+                    let d = this
+                        .try_find_spl_by_mint(&out)?
+                        .deposit_stake_quoter(this.try_curr_epoch()?)?;
+                    quote!($w_itr, d, $handle_w, spl_err)
+                }
+            }
+        };
+    }
+
+    let quote = match *inp_mint {
+        STSOL_MINT_ADDR => {
+            let w_itr = std::iter::once(
+                this.lido_router
+                    .withdraw_stake_quoter(this.try_curr_epoch()?)?
+                    .with_min_delegation(this.config.stake_min_delegation),
+            );
+            match_deposit_stake!(w_itr, lido_wsq_err)
+        }
+        MSOL_MINT_ADDR => {
+            let w_itr = std::iter::once(
+                this.marinade_router
+                    .withdraw_stake_quoter()?
+                    .with_min_delegation(this.config.stake_min_delegation),
+            );
+            match_deposit_stake!(w_itr, marinade_wsq_err)
+        }
+        inp => {
+            let router = this.try_find_spl_by_mint(&inp)?;
+            let w_itr = SplWithdrawStakeValQuoter::all(
+                router.try_stake_pool()?,
+                router.try_validator_list()?,
+                this.try_curr_epoch()?,
+            )
+            .map_err(spl_err)?
+            .map(|w| w.with_min_delegation(this.config.stake_min_delegation));
+            match_deposit_stake!(w_itr, spl_wsq_err)
+        }
+    };
+    Ok((quote, rejections))
+}
+
+fn conv_bridge_rejection<W, D>(
+    sanctum_router_core::BridgeRejection { vote, err }: sanctum_router_core::BridgeRejection<W, D>,
+    handle_w: fn(W) -> SanctumRouterError,
+    handle_d: fn(D) -> SanctumRouterError,
+) -> BridgeRejection {
+    let leg = match err {
+        PrefundSwapViaStakeQuoteErr::DepositStake(_) => StakeLeg::DepositStake,
+        _ => StakeLeg::WithdrawStake,
+    };
+    let SanctumRouterError { code, cause } = prefund_svsq_err(err, handle_w, handle_d);
+    BridgeRejection {
+        vote: vote.map(B58PK::new),
+        leg,
+        code,
+        cause,
+    }
+}
+
+// TODO: same N^2 concern as `quote_prefund_swap_via_stake_inner()`,
+// and each validator additionally requires a search over input amounts
+fn quote_prefund_swap_via_stake_exact_out_inner(
+    this: &SanctumRouter,
+    out_amt: u64,
+    inp_mint: &[u8; 32],
+    out_mint: &[u8; 32],
+) -> Result<
+    (
+        Prefund<WithdrawStakeQuote>,
+        WithRouterFee<DepositStakeQuote>,
+    ),
+    SanctumRouterError,
+> {
+    let prefund = this.prefund_params()?;
+    let router_fee = router_fee_for_out(this.config.fees.prefund_swap_via_stake, out_mint);
+
+    macro_rules! match_deposit_stake {
+        ($w_itr:expr, $handle_w:expr) => {
+            match *out_mint {
+                NATIVE_MINT => {
+                    let d = this
+                        .reserve_router
+                        .deposit_stake_quoter()?
+                        .after_prefund(&prefund.rent)
                         .map_err(reserve_err)?;
-                    core_quote($w_itr, d, amt, &reserves_balance, reserves_fee)
-                        .map_err(|e| prefund_svsq_err(e, $handle_w, reserve_err))
+                    core_quote_exact_out($w_itr, d, out_amt, &prefund, &router_fee).map_err(|e| {
+                        exact_out_err(e, |e| prefund_svsq_err(e, $handle_w, reserve_err))
+                    })
                 }
                 MSOL_MINT_ADDR => {
                     let d = this.marinade_router.deposit_stake_quoter()?;
-                    core_quote($w_itr, d, amt, &reserves_balance, reserves_fee)
-                        .map_err(|e| prefund_svsq_err(e, $handle_w, marinade_err))
+                    core_quote_exact_out($w_itr, d, out_amt, &prefund, &router_fee).map_err(|e| {
+                        exact_out_err(e, |e| prefund_svsq_err(e, $handle_w, marinade_err))
+                    })
                 }
                 out => {
                     let d = this
                         .try_find_spl_by_mint(&out)?
                         .deposit_stake_quoter(this.try_curr_epoch()?)?;
-                    core_quote($w_itr, d, amt, &reserves_balance, reserves_fee)
-                        .map_err(|e| prefund_svsq_err(e, $handle_w, spl_err))
+                    core_quote_exact_out($w_itr, d, out_amt, &prefund, &router_fee)
+                        .map_err(|e| exact_out_err(e, |e| prefund_svsq_err(e, $handle_w, spl_err)))
                 }
             }
         };
@@ -189,9 +665,18 @@ fn quote_prefund_swap_via_stake_inner(
         STSOL_MINT_ADDR => {
             let w_itr = std::iter::once(
                 this.lido_router
-                    .withdraw_stake_quoter(this.try_curr_epoch()?)?,
+                    .withdraw_stake_quoter(this.try_curr_epoch()?)?
+                    .with_min_delegation(this.config.stake_min_delegation),
             );
-            match_deposit_stake!(w_itr, lido_err)
+            match_deposit_stake!(w_itr, lido_wsq_err)
+        }
+        MSOL_MINT_ADDR => {
+            let w_itr = std::iter::once(
+                this.marinade_router
+                    .withdraw_stake_quoter()?
+                    .with_min_delegation(this.config.stake_min_delegation),
+            );
+            match_deposit_stake!(w_itr, marinade_wsq_err)
         }
         inp => {
             let router = this.try_find_spl_by_mint(&inp)?;
@@ -200,8 +685,74 @@ fn quote_prefund_swap_via_stake_inner(
                 router.try_validator_list()?,
                 this.try_curr_epoch()?,
             )
-            .map_err(spl_err)?;
-            match_deposit_stake!(w_itr, spl_err)
+            .map_err(spl_err)?
+            .map(|w| w.with_min_delegation(this.config.stake_min_delegation));
+            match_deposit_stake!(w_itr, spl_wsq_err)
+        }
+    }
+}
+
+// Used by both quote and ix
+//
+// TODO: same N^2 concern as `quote_prefund_swap_via_stake_inner()`
+fn quote_swap_via_stake_inner(
+    this: &SanctumRouter,
+    amt: u64,
+    inp_mint: &[u8; 32],
+    out_mint: &[u8; 32],
+    bridge_rent: u64,
+) -> Result<(WithdrawStakeQuote, DepositStakeQuote), SanctumRouterError> {
+    macro_rules! match_deposit_stake {
+        ($w_itr:expr, $handle_w:expr) => {
+            match *out_mint {
+                NATIVE_MINT => {
+                    let d = this.reserve_router.deposit_stake_quoter()?;
+                    core_quote_non_prefund($w_itr, d, amt, bridge_rent)
+                        .map_err(|e| svsq_err(e, $handle_w, reserve_err))
+                }
+                MSOL_MINT_ADDR => {
+                    let d = this.marinade_router.deposit_stake_quoter()?;
+                    core_quote_non_prefund($w_itr, d, amt, bridge_rent)
+                        .map_err(|e| svsq_err(e, $handle_w, marinade_err))
+                }
+                out => {
+                    let d = this
+                        .try_find_spl_by_mint(&out)?
+                        .deposit_stake_quoter(this.try_curr_epoch()?)?;
+                    core_quote_non_prefund($w_itr, d, amt, bridge_rent)
+                        .map_err(|e| svsq_err(e, $handle_w, spl_err))
+                }
+            }
+        };
+    }
+
+    match *inp_mint {
+        STSOL_MINT_ADDR => {
+            let w_itr = std::iter::once(
+                this.lido_router
+                    .withdraw_stake_quoter(this.try_curr_epoch()?)?
+                    .with_min_delegation(this.config.stake_min_delegation),
+            );
+            match_deposit_stake!(w_itr, lido_wsq_err)
+        }
+        MSOL_MINT_ADDR => {
+            let w_itr = std::iter::once(
+                this.marinade_router
+                    .withdraw_stake_quoter()?
+                    .with_min_delegation(this.config.stake_min_delegation),
+            );
+            match_deposit_stake!(w_itr, marinade_wsq_err)
+        }
+        inp => {
+            let router = this.try_find_spl_by_mint(&inp)?;
+            let w_itr = SplWithdrawStakeValQuoter::all(
+                router.try_stake_pool()?,
+                router.try_validator_list()?,
+                this.try_curr_epoch()?,
+            )
+            .map_err(spl_err)?
+            .map(|w| w.with_min_delegation(this.config.stake_min_delegation));
+            match_deposit_stake!(w_itr, spl_wsq_err)
         }
     }
 }
@@ -239,153 +790,273 @@ pub struct SwapViaStakeSwapParams {
 
 /// Requires `update()` to be called before calling this function
 ///
+/// If `accounts` is provided, also checks that the router's fee token account
+/// for the output mint exists, returning `AccountMissingErr` if it does not.
+///
 /// @param {SanctumRouterHandle} _this
 /// @param {SwapViaStakeSwapParams} params
+/// @param {AccountMap | undefined} accounts
 #[wasm_bindgen(js_name = prefundSwapViaStakeIx)]
 pub fn prefund_swap_via_stake_ix(
     this: &SanctumRouterHandle,
     params: SwapViaStakeSwapParams,
+    accounts: Option<AccountMap>,
 ) -> Result<Instruction, SanctumRouterError> {
     let inp_mint = params.inp.0;
     let out_mint = params.out.0;
+    let config = &this.0.config;
+    check_fee_token_account_exists(config, accounts.as_ref(), &out_mint)?;
 
-    let (prefix_metas, data, bridge_stake) = prefund_swap_via_stake_prefix(&params)?;
-    let vote = match params.bridge_vote {
-        Some(Bs58Array(vote)) => vote,
+    let wsq = match params.bridge_vote {
+        Some(Bs58Array(vote)) => {
+            quote_bridge_withdraw_stake(&this.0, params.amt, &inp_mint, &vote)?
+        }
         None => {
-            let (_wsq, dsq) =
-                quote_prefund_swap_via_stake_inner(&this.0, params.amt, &inp_mint, &out_mint)?;
-            dsq.inp.vote
+            let (wsq, _dsq) = quote_prefund_swap_via_stake_inner(
+                &this.0, params.amt, &inp_mint, &out_mint, None,
+            )?;
+            wsq.quote
         }
     };
 
-    let mut metas = Vec::from(prefix_metas);
+    prefund_swap_via_stake_ix_for_wsq(&this.0, &params, &wsq)
+}
 
-    match inp_mint {
-        STSOL_MINT_ADDR => {
-            let w_router = this.0.lido_router.withdraw_stake_suf_accs()?;
-            let w_suf = keys_signer_writer_to_account_metas(
-                &w_router.suffix_accounts().as_borrowed().0,
-                &w_router.suffix_is_signer().0,
-                &w_router.suffix_is_writable().0,
-            );
-            metas.extend(w_suf);
+/// [`prefund_swap_via_stake_ix`] for an already quoted WithdrawStake leg `wsq`,
+/// without checking the fee token account
+pub(crate) fn prefund_swap_via_stake_ix_for_wsq(
+    this: &SanctumRouter,
+    params: &SwapViaStakeSwapParams,
+    wsq: &WithdrawStakeQuote,
+) -> Result<Instruction, SanctumRouterError> {
+    let (prefix, bridge_stake) = prefund_swap_via_stake_prefix_keys(&this.config, params)?;
+    let ix = with_suffixes(
+        RouterIx::prefund_swap_via_stake_prefix(
+            &prefix,
+            PrefundSwapViaStakeIxData::new(params.amt, params.bridge_stake_seed),
+        )
+        .with_program_address(this.config.router_program),
+        this,
+        &params.inp.0,
+        &params.out.0,
+        wsq,
+        &bridge_stake,
+    )?;
+
+    Ok(Instruction::from(&ix))
+}
+
+/// Requires `update()` to be called before calling this function
+///
+/// If `accounts` is provided, also checks that the router's fee token account
+/// for the output mint exists, returning `AccountMissingErr` if it does not.
+///
+/// @param {SanctumRouterHandle} _this
+/// @param {SwapViaStakeSwapParams} params
+/// @param {AccountMap | undefined} accounts
+#[wasm_bindgen(js_name = swapViaStakeIx)]
+pub fn swap_via_stake_ix(
+    this: &SanctumRouterHandle,
+    params: SwapViaStakeSwapParams,
+    accounts: Option<AccountMap>,
+) -> Result<Instruction, SanctumRouterError> {
+    let inp_mint = params.inp.0;
+    let out_mint = params.out.0;
+    let config = &this.0.config;
+    check_fee_token_account_exists(config, accounts.as_ref(), &out_mint)?;
+
+    let (prefix, bridge_stake) = swap_via_stake_prefix_keys(config, &params)?;
+    let wsq = match params.bridge_vote {
+        Some(Bs58Array(vote)) => {
+            quote_bridge_withdraw_stake(&this.0, params.amt, &inp_mint, &vote)?
         }
-        inp => {
-            let w_router = this
-                .0
-                .try_find_spl_by_mint(&inp)?
-                .withdraw_stake_suf_accs(&vote)?;
-            let w_suf = keys_signer_writer_to_account_metas(
-                &w_router.suffix_accounts().as_borrowed().0,
-                &w_router.suffix_is_signer().0,
-                &w_router.suffix_is_writable().0,
-            );
-            metas.extend(w_suf);
+        None => {
+            let bridge_rent = this.0.try_rent()?.stake_account_rent_exempt_lamports();
+            let (wsq, _dsq) =
+                quote_swap_via_stake_inner(&this.0, params.amt, &inp_mint, &out_mint, bridge_rent)?;
+            wsq
         }
     };
 
-    match out_mint {
-        NATIVE_MINT => {
-            let d_router = this
-                .0
-                .reserve_router
-                .deposit_stake_suf_accs(&bridge_stake)?;
-            let d_suf = keys_signer_writer_to_account_metas(
-                &d_router.suffix_accounts().as_borrowed().0,
-                &d_router.suffix_is_signer().0,
-                &d_router.suffix_is_writable().0,
-            );
-            metas.extend(d_suf);
+    let ix = with_suffixes(
+        RouterIx::swap_via_stake_prefix(
+            &prefix,
+            SwapViaStakeIxData::new(params.amt, params.bridge_stake_seed),
+        )
+        .with_program_address(config.router_program),
+        &this.0,
+        &inp_mint,
+        &out_mint,
+        &wsq,
+        &bridge_stake,
+    )?;
+
+    Ok(Instruction::from(&ix))
+}
+
+/// Quotes only the WithdrawStake leg of a swap via stake for a bridge vote
+/// provided by the user, to form the instruction without quoting the full swap.
+///
+/// Only the quote's vote and, for marinade, the stake account it is split from are used,
+/// neither of which depend on whether the swap is prefunded.
+fn quote_bridge_withdraw_stake(
+    this: &SanctumRouter,
+    amt: u64,
+    inp_mint: &[u8; 32],
+    vote: &[u8; 32],
+) -> Result<WithdrawStakeQuote, SanctumRouterError> {
+    match *inp_mint {
+        STSOL_MINT_ADDR => this
+            .lido_router
+            .withdraw_stake_quoter(this.try_curr_epoch()?)?
+            .with_min_delegation(this.config.stake_min_delegation)
+            .quote_withdraw_stake(amt, Some(vote))
+            .map_err(lido_wsq_err),
+        MSOL_MINT_ADDR => this
+            .marinade_router
+            .withdraw_stake_quoter()?
+            .with_min_delegation(this.config.stake_min_delegation)
+            .quote_withdraw_stake(amt, Some(vote))
+            .map_err(marinade_wsq_err),
+        inp => {
+            let router = this.try_find_spl_by_mint(&inp)?;
+            let validator_list = router.try_validator_list()?;
+            SplWithdrawStakeValQuoter::all(
+                router.try_stake_pool()?,
+                validator_list,
+                this.try_curr_epoch()?,
+            )
+            .map_err(spl_err)?
+            .find(|w| w.validator.vote_account_address() == vote)
+            .ok_or_else(|| {
+                // on the list but excluded by the pool's preferred withdraw validator
+                spl_err(
+                    if validator_list
+                        .iter()
+                        .any(|v| v.vote_account_address() == vote)
+                    {
+                        SplStakePoolError::IncorrectWithdrawVoteAddress
+                    } else {
+                        SplStakePoolError::ValidatorNotFound
+                    },
+                )
+            })?
+            .with_min_delegation(this.config.stake_min_delegation)
+            .quote_withdraw_stake(amt, Some(vote))
+            .map_err(spl_wsq_err)
         }
-        MSOL_MINT_ADDR => {
-            let d_router = this.0.marinade_router.deposit_stake_suf_accs(&vote)?;
-            let d_suf = keys_signer_writer_to_account_metas(
-                &d_router.suffix_accounts().as_borrowed().0,
-                &d_router.suffix_is_signer().0,
-                &d_router.suffix_is_writable().0,
-            );
-            metas.extend(d_suf);
+    }
+}
+
+/// Appends the WithdrawStake suffix accounts of `inp_mint`'s pool
+/// followed by the DepositStake suffix accounts of `out_mint`'s pool.
+///
+/// `wsq` is the quote of the WithdrawStake leg, whose output stake account is the bridge.
+///
+/// Common to both PrefundSwapViaStake and SwapViaStake
+fn with_suffixes(
+    ix: RouterIx,
+    this: &SanctumRouter,
+    inp_mint: &[u8; 32],
+    out_mint: &[u8; 32],
+    wsq: &WithdrawStakeQuote,
+    bridge_stake: &[u8; 32],
+) -> Result<RouterIx, SanctumRouterError> {
+    let vote = &wsq.out.vote;
+    let ix = match *inp_mint {
+        STSOL_MINT_ADDR => {
+            ix.with_withdraw_stake_suffix(this.lido_router.withdraw_stake_suf_accs()?)
         }
-        out => {
-            let d_router = this
-                .0
-                .try_find_spl_by_mint(&out)?
-                .deposit_stake_suf_accs(&vote)?;
-            let d_suf = keys_signer_writer_to_account_metas(
-                &d_router.suffix_accounts().as_borrowed().0,
-                &d_router.suffix_is_signer().0,
-                &d_router.suffix_is_writable().0,
-            );
-            metas.extend(d_suf);
+        MSOL_MINT_ADDR => {
+            ix.with_withdraw_stake_suffix(this.marinade_router.withdraw_stake_suf_accs(wsq)?)
         }
-    };
+        inp => ix.with_withdraw_stake_suffix(
+            this.try_find_spl_by_mint(&inp)?
+                .withdraw_stake_suf_accs(vote)?,
+        ),
+    }
+    .ok_or_else(too_many_accounts_err)?;
 
-    let ix = Instruction {
-        program_address: B58PK::new(SANCTUM_ROUTER_PROGRAM),
-        accounts: metas.into(),
-        data: ByteBuf::from(data.to_buf()),
-    };
+    let ix = match *out_mint {
+        NATIVE_MINT => ix.with_deposit_stake_suffix(
+            this.reserve_router
+                .deposit_stake_suf_accs(&this.config, bridge_stake)?,
+        ),
+        MSOL_MINT_ADDR => {
+            ix.with_deposit_stake_suffix(this.marinade_router.deposit_stake_suf_accs(vote)?)
+        }
+        out => ix.with_deposit_stake_suffix(
+            this.try_find_spl_by_mint(&out)?
+                .deposit_stake_suf_accs(vote)?,
+        ),
+    }
+    .ok_or_else(too_many_accounts_err)?;
 
     Ok(ix)
 }
 
-/// Returns `(meta, ix_data, bridge_stake_addr)`
-fn prefund_swap_via_stake_prefix(
+/// Returns `(prefix_keys, bridge_stake_addr)`
+fn prefund_swap_via_stake_prefix_keys(
+    config: &RouterConfig,
     swap_params: &SwapViaStakeSwapParams,
-) -> Result<
-    (
-        [AccountMeta; PREFUND_SWAP_VIA_STAKE_PREFIX_ACCS_LEN],
-        PrefundSwapViaStakeIxData,
-        [u8; 32],
-    ),
-    SanctumRouterError,
-> {
+) -> Result<(PrefundSwapViaStakePrefixKeysOwned, [u8; 32]), SanctumRouterError> {
     let (bridge_stake, _bump) =
-        find_bridge_stake_acc_internal(&swap_params.signer.0, swap_params.bridge_stake_seed)
+        find_bridge_stake_acc_pda(config, &swap_params.signer.0, swap_params.bridge_stake_seed)
             .ok_or_else(invalid_pda_err)?;
-    let slumdog_stake = create_slumdog_stake_internal(&bridge_stake);
+    let slumdog_stake = create_slumdog_stake_addr(&bridge_stake);
     let (slumdog_stake_acc_record, _bump) =
-        find_reserve_stake_account_record_pda_internal(&slumdog_stake)
+        find_reserve_stake_account_record_pda(config, &slumdog_stake)
             .ok_or_else(invalid_pda_err)?;
-    let metas = keys_signer_writer_to_account_metas(
-        &PrefundSwapViaStakePrefixAccsBuilder::start()
-            .with_user(swap_params.signer.0)
-            .with_inp_token(swap_params.signer_inp.0)
-            .with_out_token(swap_params.signer_out.0)
-            .with_out_fee_token(
-                find_fee_token_account_pda_internal(&swap_params.out.0)
-                    .ok_or_else(invalid_pda_err)?
-                    .0,
-            )
-            .with_inp_mint(swap_params.inp.0)
-            .with_out_mint(swap_params.out.0)
-            .with_prefunder(PREFUNDER)
-            .with_bridge_stake(bridge_stake)
-            .with_slumdog_stake(slumdog_stake)
-            .with_slumdog_stake_acc_record(slumdog_stake_acc_record)
-            .with_unstake_program(sanctum_reserve_core::UNSTAKE_PROGRAM)
-            .with_unstake_pool(sanctum_reserve_core::POOL)
-            .with_unstake_fee(sanctum_reserve_core::FEE)
-            .with_unstake_pool_sol_reserves(sanctum_reserve_core::POOL_SOL_RESERVES)
-            .with_unstake_protocol_fee(sanctum_reserve_core::PROTOCOL_FEE)
-            .with_unstake_protocol_fee_dest(sanctum_reserve_core::PROTOCOL_FEE_VAULT)
-            .with_clock(SYSVAR_CLOCK)
-            .with_stake_program(STAKE_PROGRAM)
-            .with_system_program(SYSTEM_PROGRAM)
-            .build()
-            .as_borrowed()
-            .0,
-        &PREFUND_SWAP_VIA_STAKE_PREFIX_IS_SIGNER.0,
-        &if swap_params.out.0 == NATIVE_MINT {
-            PREFUND_SWAP_VIA_STAKE_PREFIX_IS_WRITER_WSOL_OUT
-        } else {
-            PREFUND_SWAP_VIA_STAKE_PREFIX_IS_WRITER_NON_WSOL_OUT
-        }
-        .0,
-    );
+    let prefix = PrefundSwapViaStakePrefixAccsBuilder::start()
+        .with_user(swap_params.signer.0)
+        .with_inp_token(swap_params.signer_inp.0)
+        .with_out_token(swap_params.signer_out.0)
+        .with_out_fee_token(
+            find_fee_token_account_pda(config, &swap_params.out.0)
+                .ok_or_else(invalid_pda_err)?
+                .0,
+        )
+        .with_inp_mint(swap_params.inp.0)
+        .with_out_mint(swap_params.out.0)
+        .with_prefunder(config.prefunder)
+        .with_bridge_stake(bridge_stake)
+        .with_slumdog_stake(slumdog_stake)
+        .with_slumdog_stake_acc_record(slumdog_stake_acc_record)
+        .with_unstake_program(config.reserve.program)
+        .with_unstake_pool(config.reserve.pool)
+        .with_unstake_fee(config.reserve.fee)
+        .with_unstake_pool_sol_reserves(config.reserve.pool_sol_reserves)
+        .with_unstake_protocol_fee(config.reserve.protocol_fee)
+        .with_unstake_protocol_fee_dest(config.reserve.protocol_fee_vault)
+        .with_clock(SYSVAR_CLOCK)
+        .with_stake_program(STAKE_PROGRAM)
+        .with_system_program(SYSTEM_PROGRAM)
+        .build();
 
-    let data = PrefundSwapViaStakeIxData::new(swap_params.amt, swap_params.bridge_stake_seed);
+    Ok((prefix, bridge_stake))
+}
+
+/// Returns `(prefix_keys, bridge_stake_addr)`
+fn swap_via_stake_prefix_keys(
+    config: &RouterConfig,
+    swap_params: &SwapViaStakeSwapParams,
+) -> Result<(SwapViaStakePrefixKeysOwned, [u8; 32]), SanctumRouterError> {
+    let (bridge_stake, _bump) =
+        find_bridge_stake_acc_pda(config, &swap_params.signer.0, swap_params.bridge_stake_seed)
+            .ok_or_else(invalid_pda_err)?;
+    let prefix = SwapViaStakePrefixAccsBuilder::start()
+        .with_user(swap_params.signer.0)
+        .with_inp_token(swap_params.signer_inp.0)
+        .with_out_token(swap_params.signer_out.0)
+        .with_bridge_stake(bridge_stake)
+        .with_out_fee_token(
+            find_fee_token_account_pda(config, &swap_params.out.0)
+                .ok_or_else(invalid_pda_err)?
+                .0,
+        )
+        .with_inp_mint(swap_params.inp.0)
+        .with_out_mint(swap_params.out.0)
+        .build();
 
-    Ok((metas, data, bridge_stake))
+    Ok((prefix, bridge_stake))
 }
@@ -2,7 +2,7 @@ use sanctum_router_core::{TokenQuote, WithRouterFee};
 use serde::{Deserialize, Serialize};
 use tsify_next::Tsify;
 
-use crate::interface::B58PK;
+use crate::{interface::B58PK, router::fee::FeeBreakdown};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
 #[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
@@ -17,12 +17,30 @@ pub struct TokenQuoteParams {
     pub out: B58PK,
 }
 
-// need to use a simple newtype here instead of type alias
-// otherwise wasm_bindgen shits itself with missing generics
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenBatchQuoteParams {
+    pub amts: Box<[u64]>,
+
+    /// Input mint
+    pub inp: B58PK,
+
+    /// Output mint
+    pub out: B58PK,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
 #[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
 #[serde(rename_all = "camelCase")]
-pub struct TokenQuoteWithRouterFee(pub(crate) WithRouterFee<TokenQuote>);
+pub struct TokenQuoteWithRouterFee {
+    #[serde(flatten)]
+    pub(crate) quote: WithRouterFee<TokenQuote>,
+
+    /// Omitted if the SOL value of either mint cannot be calculated
+    #[tsify(optional)]
+    pub(crate) fees: Option<FeeBreakdown>,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
 #[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
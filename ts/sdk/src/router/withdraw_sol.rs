@@ -1,19 +1,27 @@
+use bs58_fixed_wasm::Bs58Array;
+use sanctum_router::{find_fee_token_account_pda, SanctumRouter};
 use sanctum_router_core::{
-    WithdrawSolQuoter, WithdrawSolSufAccs, WithdrawWrappedSolIxData,
-    WithdrawWrappedSolPrefixAccsBuilder, NATIVE_MINT, SANCTUM_ROUTER_PROGRAM, TOKEN_PROGRAM,
-    WITHDRAW_WRAPPED_SOL_PREFIX_ACCS_LEN, WITHDRAW_WRAPPED_SOL_PREFIX_IS_SIGNER,
-    WITHDRAW_WRAPPED_SOL_PREFIX_IS_WRITER,
+    quote_batch, quote_exact_out, ExactOutQuoteErr, RouterConfig, RouterIx, TokenQuote,
+    WithRouterFee, WithdrawSolQuoter, WithdrawWrappedSolIxData,
+    WithdrawWrappedSolPrefixAccsBuilder, WithdrawWrappedSolPrefixKeysOwned, NATIVE_MINT,
+    TOKEN_PROGRAM,
 };
 use serde::{Deserialize, Serialize};
-use serde_bytes::ByteBuf;
 use tsify_next::Tsify;
 use wasm_bindgen::prelude::*;
 
 use crate::{
-    err::{invalid_pda_err, spl_err, SanctumRouterError},
-    interface::{keys_signer_writer_to_account_metas, AccountMeta, Instruction, B58PK},
-    pda::router::find_fee_token_account_pda_internal,
-    router::{token_pair::TokenQuoteWithRouterFee, SanctumRouterHandle},
+    err::{
+        exact_out_err, invalid_pda_err, marinade_withdraw_sol_err, spl_err, too_many_accounts_err,
+        unsupported_mint_pair_err, SanctumRouterError,
+    },
+    fee_token_account::check_fee_token_account_exists,
+    interface::{AccountMap, Instruction, B58PK},
+    router::{
+        fee::FeeBreakdown,
+        token_pair::{TokenQuoteParams, TokenQuoteWithRouterFee},
+        SanctumRouterHandle,
+    },
 };
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
@@ -34,12 +42,144 @@ pub fn quote_withdraw_sol(
     params: WithdrawSolQuoteParams,
 ) -> Result<TokenQuoteWithRouterFee, SanctumRouterError> {
     let inp_mint = params.inp.0;
-    this.0
-        .try_find_spl_by_mint(&inp_mint)?
-        .withdraw_sol_quoter(this.0.try_curr_epoch()?)?
-        .quote_withdraw_sol(params.amt)
-        .map(|q| TokenQuoteWithRouterFee(q.withdraw_sol_with_router_fee()))
-        .map_err(spl_err)
+    let quote = match inp_mint {
+        sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR => this
+            .0
+            .marinade_router
+            .withdraw_sol_quoter()?
+            .quote_withdraw_sol(params.amt)
+            .map_err(marinade_withdraw_sol_err),
+        mint => this
+            .0
+            .try_find_spl_by_mint(&mint)?
+            .withdraw_sol_quoter(this.0.try_curr_epoch()?)?
+            .quote_withdraw_sol(params.amt)
+            .map_err(spl_err),
+    }?;
+    conv_quote(
+        &this.0,
+        &inp_mint,
+        quote.with_router_fee(&this.0.config.fees.withdraw_wrapped_sol),
+    )
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawSolBatchQuoteParams {
+    /// Input LST amounts
+    pub amts: Box<[u64]>,
+
+    /// Input mint
+    pub inp: B58PK,
+}
+
+/// Like `quoteWithdrawSol()`, but quotes each of `params.amts` with the same quoter,
+/// returning the quotes in the same order.
+///
+/// Errors if any of the amounts fails to quote.
+///
+/// Requires `update()` to be called before calling this function
+#[wasm_bindgen(js_name = quoteWithdrawSolBatch)]
+pub fn quote_withdraw_sol_batch(
+    this: &SanctumRouterHandle,
+    WithdrawSolBatchQuoteParams {
+        amts,
+        inp: Bs58Array(inp_mint),
+    }: WithdrawSolBatchQuoteParams,
+) -> Result<Box<[TokenQuoteWithRouterFee]>, SanctumRouterError> {
+    let amts = amts.iter().copied();
+    let fee = &this.0.config.fees.withdraw_wrapped_sol;
+    match inp_mint {
+        sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR => quote_batch(
+            this.0.marinade_router.withdraw_sol_quoter()?,
+            amts,
+            WithdrawSolQuoter::quote_withdraw_sol,
+        )
+        .map(|res| {
+            let q = res.map_err(marinade_withdraw_sol_err)?;
+            conv_quote(&this.0, &inp_mint, q.with_router_fee(fee))
+        })
+        .collect(),
+        mint => quote_batch(
+            this.0
+                .try_find_spl_by_mint(&mint)?
+                .withdraw_sol_quoter(this.0.try_curr_epoch()?)?,
+            amts,
+            WithdrawSolQuoter::quote_withdraw_sol,
+        )
+        .map(|res| {
+            let q = res.map_err(spl_err)?;
+            conv_quote(&this.0, &inp_mint, q.with_router_fee(fee))
+        })
+        .collect(),
+    }
+}
+
+/// Quotes the minimum input LST tokens required to receive at least `params.amt`
+/// lamports after router fees.
+///
+/// `params.out` must be wSOL.
+///
+/// Requires `update()` to be called before calling this function
+#[wasm_bindgen(js_name = quoteWithdrawSolExactOut)]
+pub fn quote_withdraw_sol_exact_out(
+    this: &SanctumRouterHandle,
+    TokenQuoteParams {
+        amt,
+        inp: Bs58Array(inp_mint),
+        out: Bs58Array(out_mint),
+    }: TokenQuoteParams,
+) -> Result<TokenQuoteWithRouterFee, SanctumRouterError> {
+    if out_mint != NATIVE_MINT {
+        return Err(unsupported_mint_pair_err(&inp_mint, &out_mint));
+    }
+    let quote = match inp_mint {
+        sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR => quote_exact_out_after_router_fee(
+            &this.0,
+            &this.0.marinade_router.withdraw_sol_quoter()?,
+            amt,
+        )
+        .map_err(|e| exact_out_err(e, marinade_withdraw_sol_err)),
+        mint => quote_exact_out_after_router_fee(
+            &this.0,
+            &this
+                .0
+                .try_find_spl_by_mint(&mint)?
+                .withdraw_sol_quoter(this.0.try_curr_epoch()?)?,
+            amt,
+        )
+        .map_err(|e| exact_out_err(e, spl_err)),
+    }?;
+    conv_quote(&this.0, &inp_mint, quote)
+}
+
+/// Exact out on the lamports received after router fees
+fn quote_exact_out_after_router_fee<Q: WithdrawSolQuoter>(
+    this: &SanctumRouter,
+    quoter: &Q,
+    amt: u64,
+) -> Result<WithRouterFee<TokenQuote>, ExactOutQuoteErr<Q::Error>> {
+    quote_exact_out(
+        amt,
+        |tokens| {
+            quoter
+                .quote_withdraw_sol(tokens)
+                .map(|q| q.with_router_fee(&this.config.fees.withdraw_wrapped_sol))
+        },
+        |q| q.quote.out,
+    )
+}
+
+pub(crate) fn conv_quote(
+    this: &SanctumRouter,
+    inp_mint: &[u8; 32],
+    quote: WithRouterFee<TokenQuote>,
+) -> Result<TokenQuoteWithRouterFee, SanctumRouterError> {
+    Ok(TokenQuoteWithRouterFee {
+        fees: FeeBreakdown::new(this, inp_mint, &NATIVE_MINT, quote.withdraw_sol_fees())?,
+        quote,
+    })
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
@@ -63,60 +203,49 @@ pub struct WithdrawSolSwapParams {
 }
 
 /// Requires `update()` to be called before calling this function
+///
+/// If `accounts` is provided, also checks that the router's fee token account
+/// for the output mint (wSOL) exists, returning `AccountMissingErr` if it does not.
 #[wasm_bindgen(js_name = withdrawSolIx)]
 pub fn withdraw_sol_ix(
     this: &SanctumRouterHandle,
     params: WithdrawSolSwapParams,
+    accounts: Option<AccountMap>,
 ) -> Result<Instruction, SanctumRouterError> {
-    let inp_mint = params.inp.0;
-    let router = this.0.try_find_spl_by_mint(&inp_mint)?.sol_suf_accs()?;
-
-    let (prefix_metas, data) = withdraw_wrapped_sol_prefix_metas_and_data(&params)?;
-
-    let suffix_accounts = keys_signer_writer_to_account_metas(
-        &router.suffix_accounts().as_borrowed().0,
-        &router.suffix_is_signer().0,
-        &router.suffix_is_writable().0,
-    );
-
-    Ok(Instruction {
-        program_address: B58PK::new(SANCTUM_ROUTER_PROGRAM),
-        accounts: [prefix_metas.as_ref(), suffix_accounts.as_ref()]
-            .concat()
-            .into(),
-        data: ByteBuf::from(data.to_buf()),
-    })
+    let config = &this.0.config;
+    check_fee_token_account_exists(config, accounts.as_ref(), &NATIVE_MINT)?;
+    let ix = RouterIx::withdraw_wrapped_sol_prefix(
+        &withdraw_wrapped_sol_prefix_keys(config, &params)?,
+        WithdrawWrappedSolIxData::new(params.amt),
+    )
+    .with_program_address(config.router_program);
+
+    let ix = match params.inp.0 {
+        sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR => {
+            ix.with_withdraw_sol_suffix(this.0.marinade_router.withdraw_sol_suf_accs()?)
+        }
+        mint => ix.with_withdraw_sol_suffix(this.0.try_find_spl_by_mint(&mint)?.sol_suf_accs()?),
+    }
+    .ok_or_else(too_many_accounts_err)?;
+
+    Ok(Instruction::from(&ix))
 }
 
-fn withdraw_wrapped_sol_prefix_metas_and_data(
+fn withdraw_wrapped_sol_prefix_keys(
+    config: &RouterConfig,
     swap_params: &WithdrawSolSwapParams,
-) -> Result<
-    (
-        [AccountMeta; WITHDRAW_WRAPPED_SOL_PREFIX_ACCS_LEN],
-        WithdrawWrappedSolIxData,
-    ),
-    SanctumRouterError,
-> {
-    let metas = keys_signer_writer_to_account_metas(
-        &WithdrawWrappedSolPrefixAccsBuilder::start()
-            .with_user(&swap_params.signer.0)
-            .with_inp_token(&swap_params.signer_inp.0)
-            .with_out_wsol(&swap_params.signer_out.0)
-            .with_wsol_fee_token(
-                &find_fee_token_account_pda_internal(&NATIVE_MINT)
-                    .ok_or_else(invalid_pda_err)?
-                    .0,
-            )
-            .with_inp_mint(&swap_params.inp.0)
-            .with_wsol_mint(&NATIVE_MINT)
-            .with_token_program(&TOKEN_PROGRAM)
-            .build()
-            .0,
-        &WITHDRAW_WRAPPED_SOL_PREFIX_IS_SIGNER.0,
-        &WITHDRAW_WRAPPED_SOL_PREFIX_IS_WRITER.0,
-    );
-
-    let data = WithdrawWrappedSolIxData::new(swap_params.amt);
-
-    Ok((metas, data))
+) -> Result<WithdrawWrappedSolPrefixKeysOwned, SanctumRouterError> {
+    Ok(WithdrawWrappedSolPrefixAccsBuilder::start()
+        .with_user(swap_params.signer.0)
+        .with_inp_token(swap_params.signer_inp.0)
+        .with_out_wsol(swap_params.signer_out.0)
+        .with_wsol_fee_token(
+            find_fee_token_account_pda(config, &NATIVE_MINT)
+                .ok_or_else(invalid_pda_err)?
+                .0,
+        )
+        .with_inp_mint(swap_params.inp.0)
+        .with_wsol_mint(NATIVE_MINT)
+        .with_token_program(TOKEN_PROGRAM)
+        .build())
 }
@@ -0,0 +1,191 @@
+use bs58_fixed_wasm::Bs58Array;
+use sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR;
+use sanctum_router::SanctumRouter;
+use sanctum_router_core::{
+    quote_withdraw_sol_deposit_sol as core_quote, WithdrawSolDepositSolQuote, WithdrawSolQuoter,
+    NATIVE_MINT,
+};
+use serde::{Deserialize, Serialize};
+use tsify_next::Tsify;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    err::{marinade_err, marinade_withdraw_sol_err, spl_err, wsdsq_err, SanctumRouterError},
+    fee_token_account::check_fee_token_account_exists,
+    interface::{AccountMap, Instruction, B58PK},
+    router::{
+        deposit_sol::{deposit_sol_ix, DepositSolSwapParams},
+        fee::FeeBreakdown,
+        token_pair::TokenQuoteParams,
+        withdraw_sol::{withdraw_sol_ix, WithdrawSolSwapParams},
+        SanctumRouterHandle,
+    },
+};
+
+// need to use a simple struct here to attach the fee breakdown
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawSolDepositSolQuoteWithFees {
+    #[serde(flatten)]
+    pub(crate) quote: WithdrawSolDepositSolQuote,
+
+    /// Omitted if the SOL value of either mint cannot be calculated
+    #[tsify(optional)]
+    pub(crate) fees: Option<FeeBreakdown>,
+}
+
+/// Quotes the two-instruction route of WithdrawWrappedSol from `params.inp`'s pool
+/// followed by StakeWrappedSol into `params.out`'s pool.
+///
+/// Requires `update()` to be called with `withdrawSol` for `params.inp`
+/// and `depositSol` for `params.out` before calling this function
+#[wasm_bindgen(js_name = quoteWithdrawSolDepositSol)]
+pub fn quote_withdraw_sol_deposit_sol(
+    this: &SanctumRouterHandle,
+    TokenQuoteParams {
+        amt,
+        inp: Bs58Array(inp_mint),
+        out: Bs58Array(out_mint),
+    }: TokenQuoteParams,
+) -> Result<WithdrawSolDepositSolQuoteWithFees, SanctumRouterError> {
+    let (quote, referral_fee) = quote_inner(&this.0, amt, &inp_mint, &out_mint)?;
+    Ok(WithdrawSolDepositSolQuoteWithFees {
+        fees: FeeBreakdown::new(&this.0, &inp_mint, &out_mint, quote.fees(referral_fee))?,
+        quote,
+    })
+}
+
+// Returns the quote and the part of its DepositSol leg's fee paid to the referrer
+fn quote_inner(
+    this: &SanctumRouter,
+    amt: u64,
+    inp_mint: &[u8; 32],
+    out_mint: &[u8; 32],
+) -> Result<(WithdrawSolDepositSolQuote, u64), SanctumRouterError> {
+    match *inp_mint {
+        MSOL_MINT_ADDR => quote_deposit_sol_leg(
+            this,
+            this.marinade_router.withdraw_sol_quoter()?,
+            amt,
+            out_mint,
+            marinade_withdraw_sol_err,
+        ),
+        inp => quote_deposit_sol_leg(
+            this,
+            this.try_find_spl_by_mint(&inp)?
+                .withdraw_sol_quoter(this.try_curr_epoch()?)?,
+            amt,
+            out_mint,
+            spl_err,
+        ),
+    }
+}
+
+fn quote_deposit_sol_leg<W: WithdrawSolQuoter>(
+    this: &SanctumRouter,
+    w: W,
+    amt: u64,
+    out_mint: &[u8; 32],
+    handle_w: fn(W::Error) -> SanctumRouterError,
+) -> Result<(WithdrawSolDepositSolQuote, u64), SanctumRouterError> {
+    match *out_mint {
+        MSOL_MINT_ADDR => {
+            let d = this.marinade_router.deposit_sol_quoter()?;
+            core_quote(w, d, amt, &this.config.fees.withdraw_wrapped_sol)
+                .map(|q| (q, 0))
+                .map_err(|e| wsdsq_err(e, handle_w, marinade_err))
+        }
+        out => {
+            let d = this
+                .try_find_spl_by_mint(&out)?
+                .deposit_sol_quoter(this.try_curr_epoch()?)?;
+            core_quote(w, &d, amt, &this.config.fees.withdraw_wrapped_sol)
+                .map(|q| (q, d.referral_fee(q.deposit_sol.quote.fee)))
+                .map_err(|e| wsdsq_err(e, handle_w, spl_err))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawSolDepositSolSwapParams {
+    /// Input LST amount
+    pub amt: u64,
+
+    /// Input mint
+    pub inp: B58PK,
+
+    /// Output mint
+    pub out: B58PK,
+
+    /// Input token account to transfer `amt` tokens from
+    pub signer_inp: B58PK,
+
+    /// Intermediate wSOL token account that receives the withdrawn SOL
+    /// and is then transferred from for the deposit.
+    /// Must already exist.
+    pub signer_bridge_wsol: B58PK,
+
+    /// Output token account to receive tokens to
+    pub signer_out: B58PK,
+
+    /// Signing authority of `self.signer_inp` and `self.signer_bridge_wsol`; user making the swap.
+    pub signer: B58PK,
+}
+
+/// Returns the [WithdrawWrappedSol, StakeWrappedSol] instructions of the
+/// two-instruction route quoted by `quoteWithdrawSolDepositSol()`,
+/// which must be executed in the same transaction in order.
+///
+/// The StakeWrappedSol instruction deposits the wSOL amount
+/// quoted to be received from the WithdrawWrappedSol instruction.
+///
+/// Requires `update()` to be called before calling this function
+///
+/// If `accounts` is provided, also checks that the router's fee token accounts
+/// for wSOL and the output mint exist, returning `AccountMissingErr` if they do not.
+#[wasm_bindgen(js_name = withdrawSolDepositSolIxs)]
+pub fn withdraw_sol_deposit_sol_ixs(
+    this: &SanctumRouterHandle,
+    params: WithdrawSolDepositSolSwapParams,
+    accounts: Option<AccountMap>,
+) -> Result<Box<[Instruction]>, SanctumRouterError> {
+    let WithdrawSolDepositSolSwapParams {
+        amt,
+        inp,
+        out,
+        signer_inp,
+        signer_bridge_wsol,
+        signer_out,
+        signer,
+    } = params;
+    check_fee_token_account_exists(&this.0.config, accounts.as_ref(), &NATIVE_MINT)?;
+    check_fee_token_account_exists(&this.0.config, accounts.as_ref(), &out.0)?;
+
+    let (quote, _referral_fee) = quote_inner(&this.0, amt, &inp.0, &out.0)?;
+    let withdraw_sol = withdraw_sol_ix(
+        this,
+        WithdrawSolSwapParams {
+            amt,
+            inp,
+            signer_inp,
+            signer_out: signer_bridge_wsol,
+            signer,
+        },
+        None,
+    )?;
+    let deposit_sol = deposit_sol_ix(
+        this,
+        DepositSolSwapParams {
+            amt: quote.bridge_lamports(),
+            out,
+            signer_inp: signer_bridge_wsol,
+            signer_out,
+            signer,
+        },
+        None,
+    )?;
+    Ok([withdraw_sol, deposit_sol].into())
+}
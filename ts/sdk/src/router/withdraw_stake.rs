@@ -1,24 +1,26 @@
+use sanctum_marinade_liquid_staking_core::MSOL_MINT_ADDR;
+use sanctum_router::{
+    create_slumdog_stake_addr, find_bridge_stake_acc_pda, find_reserve_stake_account_record_pda,
+    SanctumRouter,
+};
 use sanctum_router_core::{
-    ActiveStakeParams, Prefund, PrefundWithdrawStakeIxData, PrefundWithdrawStakePrefixAccsBuilder,
-    StakeAccountLamports, WithdrawStakeQuoter, WithdrawStakeSufAccs, PREFUNDER,
-    PREFUND_WITHDRAW_STAKE_PREFIX_ACCS_LEN, PREFUND_WITHDRAW_STAKE_PREFIX_IS_SIGNER,
-    PREFUND_WITHDRAW_STAKE_PREFIX_IS_WRITER, SANCTUM_ROUTER_PROGRAM, STAKE_PROGRAM, SYSTEM_PROGRAM,
-    SYSVAR_CLOCK,
+    quote_exact_out, ActiveStakeParams, Prefund, PrefundWithdrawStakeIxData,
+    PrefundWithdrawStakePrefixAccsBuilder, PrefundWithdrawStakePrefixKeysOwned, RouterConfig,
+    RouterIx, StakeAccountLamports, WithdrawStakeQuoter, NATIVE_MINT, STAKE_PROGRAM,
+    SYSTEM_PROGRAM, SYSVAR_CLOCK,
 };
 use serde::{Deserialize, Serialize};
-use serde_bytes::ByteBuf;
 use solido_legacy_core::LidoError;
 use tsify_next::Tsify;
 use wasm_bindgen::prelude::*;
 
 use crate::{
-    err::{invalid_pda_err, lido_err, prefund_wsq_err, spl_err, SanctumRouterError},
-    interface::{keys_signer_writer_to_account_metas, AccountMeta, Instruction, B58PK},
-    pda::{
-        reserve::find_reserve_stake_account_record_pda_internal,
-        router::{create_slumdog_stake_internal, find_bridge_stake_acc_internal},
+    err::{
+        exact_out_err, invalid_pda_err, lido_err, lido_wsq_err, marinade_wsq_err, prefund_wsq_err,
+        spl_err, too_many_accounts_err, SanctumRouterError,
     },
-    router::SanctumRouterHandle,
+    interface::{Instruction, B58PK},
+    router::{fee::FeeBreakdown, SanctumRouterHandle},
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
@@ -55,12 +57,19 @@ pub struct WithdrawStakeQuote {
     pub fee: u64,
 }
 
-// need to use a simple newtype here instead of type alias
+// need to use a simple struct here instead of type alias
 // otherwise wasm_bindgen shits itself with missing generics
 #[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
 #[tsify(into_wasm_abi, from_wasm_abi, large_number_types_as_bigints)]
 #[serde(rename_all = "camelCase")]
-pub struct PrefundWithdrawStakeQuote(pub(crate) Prefund<WithdrawStakeQuote>);
+pub struct PrefundWithdrawStakeQuote {
+    #[serde(flatten)]
+    pub(crate) quote: Prefund<WithdrawStakeQuote>,
+
+    /// Omitted if the SOL value of either mint cannot be calculated
+    #[tsify(optional)]
+    pub(crate) fees: Option<FeeBreakdown>,
+}
 
 /// Requires `update()` to be called before calling this function
 #[wasm_bindgen(js_name = quotePrefundWithdrawStake)]
@@ -71,23 +80,87 @@ pub fn quote_prefund_withdraw_stake(
     let inp_mint = params.inp.0;
     let out_vote = params.out.map(|pk| pk.0);
     let out_vote = out_vote.as_ref();
-    let (reserves_balance, reserves_fee) = this.0.reserve_router.prefund_params()?;
+    let prefund = this.0.prefund_params()?;
     let quote = match inp_mint {
         solido_legacy_core::STSOL_MINT_ADDR => this
             .0
             .lido_router
             .withdraw_stake_quoter(this.0.try_curr_epoch()?)?
-            .quote_prefund_withdraw_stake(params.amt, out_vote, &reserves_balance, reserves_fee)
-            .map_err(|e| prefund_wsq_err(e, lido_err)),
+            .with_min_delegation(this.0.config.stake_min_delegation)
+            .quote_prefund_withdraw_stake(params.amt, out_vote, &prefund)
+            .map_err(|e| prefund_wsq_err(e, lido_wsq_err)),
+        MSOL_MINT_ADDR => this
+            .0
+            .marinade_router
+            .withdraw_stake_quoter()?
+            .with_min_delegation(this.0.config.stake_min_delegation)
+            .quote_prefund_withdraw_stake(params.amt, out_vote, &prefund)
+            .map_err(|e| prefund_wsq_err(e, marinade_wsq_err)),
         mint => {
             let router = this.0.try_find_spl_by_mint(&mint)?;
             router
                 .withdraw_stake_quoter(this.0.try_curr_epoch()?)?
-                .quote_prefund_withdraw_stake(params.amt, out_vote, &reserves_balance, reserves_fee)
+                .quote_prefund_withdraw_stake(params.amt, out_vote, &prefund)
                 .map_err(|e| prefund_wsq_err(e, spl_err))
         }
     }?;
-    Ok(conv_prefund_quote(quote))
+    conv_prefund_quote(&this.0, &inp_mint, quote)
+}
+
+/// Quotes the minimum input LST tokens required to withdraw a stake account
+/// with at least `params.amt` total lamports, after subtracting the prefund fee.
+///
+/// Requires `update()` to be called before calling this function
+#[wasm_bindgen(js_name = quotePrefundWithdrawStakeExactOut)]
+pub fn quote_prefund_withdraw_stake_exact_out(
+    this: &SanctumRouterHandle,
+    params: WithdrawStakeQuoteParams,
+) -> Result<PrefundWithdrawStakeQuote, SanctumRouterError> {
+    let inp_mint = params.inp.0;
+    let out_vote = params.out.map(|pk| pk.0);
+    let out_vote = out_vote.as_ref();
+    let prefund = this.0.prefund_params()?;
+    let quote = match inp_mint {
+        solido_legacy_core::STSOL_MINT_ADDR => {
+            let quoter = this
+                .0
+                .lido_router
+                .withdraw_stake_quoter(this.0.try_curr_epoch()?)?
+                .with_min_delegation(this.0.config.stake_min_delegation);
+            quote_exact_out(
+                params.amt,
+                |tokens| quoter.quote_prefund_withdraw_stake(tokens, out_vote, &prefund),
+                |q| q.quote.out.lamports.total(),
+            )
+            .map_err(|e| exact_out_err(e, |e| prefund_wsq_err(e, lido_wsq_err)))
+        }
+        MSOL_MINT_ADDR => {
+            let quoter = this
+                .0
+                .marinade_router
+                .withdraw_stake_quoter()?
+                .with_min_delegation(this.0.config.stake_min_delegation);
+            quote_exact_out(
+                params.amt,
+                |tokens| quoter.quote_prefund_withdraw_stake(tokens, out_vote, &prefund),
+                |q| q.quote.out.lamports.total(),
+            )
+            .map_err(|e| exact_out_err(e, |e| prefund_wsq_err(e, marinade_wsq_err)))
+        }
+        mint => {
+            let quoter = this
+                .0
+                .try_find_spl_by_mint(&mint)?
+                .withdraw_stake_quoter(this.0.try_curr_epoch()?)?;
+            quote_exact_out(
+                params.amt,
+                |tokens| quoter.quote_prefund_withdraw_stake(tokens, out_vote, &prefund),
+                |q| q.quote.out.lamports.total(),
+            )
+            .map_err(|e| exact_out_err(e, |e| prefund_wsq_err(e, spl_err)))
+        }
+    }?;
+    conv_prefund_quote(&this.0, &inp_mint, quote)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
@@ -121,9 +194,14 @@ pub fn prefund_withdraw_stake_ix(
 ) -> Result<Instruction, SanctumRouterError> {
     let inp_mint = params.inp.0;
     let vote = params.out.0;
-    let (prefix_metas, data) = prefund_withdraw_stake_prefix_metas_and_data(&params)?;
+    let config = &this.0.config;
+    let ix = RouterIx::prefund_withdraw_stake_prefix(
+        &prefund_withdraw_stake_prefix_keys(config, &params)?,
+        PrefundWithdrawStakeIxData::new(params.amt, params.bridge_stake_seed),
+    )
+    .with_program_address(config.router_program);
 
-    let metas: Box<[AccountMeta]> = match inp_mint {
+    let ix = match inp_mint {
         solido_legacy_core::STSOL_MINT_ADDR => {
             let router = this.0.lido_router.withdraw_stake_suf_accs()?;
 
@@ -131,107 +209,88 @@ pub fn prefund_withdraw_stake_ix(
                 return Err(lido_err(LidoError::ValidatorWithMoreStakeExists));
             }
 
-            let suffix_accounts = keys_signer_writer_to_account_metas(
-                &router.suffix_accounts().as_borrowed().0,
-                &router.suffix_is_signer().0,
-                &router.suffix_is_writable().0,
-            );
-
-            [prefix_metas.as_ref(), suffix_accounts.as_ref()]
-                .concat()
-                .into()
+            ix.with_withdraw_stake_suffix(router)
         }
-        mint => {
-            let router = this
-                .0
-                .try_find_spl_by_mint(&mint)?
-                .withdraw_stake_suf_accs(&vote)?;
-
-            let suffix_accounts = keys_signer_writer_to_account_metas(
-                &router.suffix_accounts().as_borrowed().0,
-                &router.suffix_is_signer().0,
-                &router.suffix_is_writable().0,
-            );
-
-            [prefix_metas.as_ref(), suffix_accounts.as_ref()]
-                .concat()
-                .into()
+        MSOL_MINT_ADDR => {
+            let router = &this.0.marinade_router;
+            // marinade can have multiple stake accounts for `vote`,
+            // split from the one the quote selects
+            let quote = router
+                .withdraw_stake_quoter()?
+                .with_min_delegation(config.stake_min_delegation)
+                .quote_withdraw_stake(params.amt, Some(&vote))
+                .map_err(marinade_wsq_err)?;
+            ix.with_withdraw_stake_suffix(router.withdraw_stake_suf_accs(&quote)?)
         }
-    };
-
-    let ix = Instruction {
-        program_address: B58PK::new(SANCTUM_ROUTER_PROGRAM),
-        accounts: metas,
-        data: ByteBuf::from(data.to_buf()),
-    };
+        mint => ix.with_withdraw_stake_suffix(
+            this.0
+                .try_find_spl_by_mint(&mint)?
+                .withdraw_stake_suf_accs(&vote)?,
+        ),
+    }
+    .ok_or_else(too_many_accounts_err)?;
 
-    Ok(ix)
+    Ok(Instruction::from(&ix))
 }
 
 fn conv_prefund_quote(
-    Prefund {
+    this: &SanctumRouter,
+    inp_mint: &[u8; 32],
+    quote: Prefund<sanctum_router_core::WithdrawStakeQuote>,
+) -> Result<PrefundWithdrawStakeQuote, SanctumRouterError> {
+    // output stake is valued in lamports
+    let fees = FeeBreakdown::new(this, inp_mint, &NATIVE_MINT, quote.fees())?;
+    let Prefund {
         quote:
             sanctum_router_core::WithdrawStakeQuote {
                 inp,
                 out: ActiveStakeParams { vote, lamports },
                 fee,
+                ..
             },
         prefund_fee,
-    }: Prefund<sanctum_router_core::WithdrawStakeQuote>,
-) -> PrefundWithdrawStakeQuote {
-    PrefundWithdrawStakeQuote(Prefund {
-        quote: WithdrawStakeQuote {
-            inp,
-            vote: B58PK::new(vote),
-            out: lamports,
-            fee,
+    } = quote;
+    Ok(PrefundWithdrawStakeQuote {
+        quote: Prefund {
+            quote: WithdrawStakeQuote {
+                inp,
+                vote: B58PK::new(vote),
+                out: lamports,
+                fee,
+            },
+            prefund_fee,
         },
-        prefund_fee,
+        fees,
     })
 }
 
-fn prefund_withdraw_stake_prefix_metas_and_data(
+fn prefund_withdraw_stake_prefix_keys(
+    config: &RouterConfig,
     swap_params: &WithdrawStakeSwapParams,
-) -> Result<
-    (
-        [AccountMeta; PREFUND_WITHDRAW_STAKE_PREFIX_ACCS_LEN],
-        PrefundWithdrawStakeIxData,
-    ),
-    SanctumRouterError,
-> {
+) -> Result<PrefundWithdrawStakePrefixKeysOwned, SanctumRouterError> {
     let (bridge_stake, _bump) =
-        find_bridge_stake_acc_internal(&swap_params.signer.0, swap_params.bridge_stake_seed)
+        find_bridge_stake_acc_pda(config, &swap_params.signer.0, swap_params.bridge_stake_seed)
             .ok_or_else(invalid_pda_err)?;
-    let slumdog_stake = create_slumdog_stake_internal(&bridge_stake);
+    let slumdog_stake = create_slumdog_stake_addr(&bridge_stake);
     let (slumdog_stake_acc_record, _bump) =
-        find_reserve_stake_account_record_pda_internal(&slumdog_stake)
+        find_reserve_stake_account_record_pda(config, &slumdog_stake)
             .ok_or_else(invalid_pda_err)?;
-    let metas = keys_signer_writer_to_account_metas(
-        &PrefundWithdrawStakePrefixAccsBuilder::start()
-            .with_user(swap_params.signer.0)
-            .with_bridge_stake(bridge_stake)
-            .with_slumdog_stake(slumdog_stake)
-            .with_slumdog_stake_acc_record(slumdog_stake_acc_record)
-            .with_inp_mint(swap_params.inp.0)
-            .with_inp_token(swap_params.signer_inp.0)
-            .with_clock(SYSVAR_CLOCK)
-            .with_prefunder(PREFUNDER)
-            .with_stake_program(STAKE_PROGRAM)
-            .with_system_program(SYSTEM_PROGRAM)
-            .with_unstake_program(sanctum_reserve_core::UNSTAKE_PROGRAM)
-            .with_unstake_pool(sanctum_reserve_core::POOL)
-            .with_unstake_fee(sanctum_reserve_core::FEE)
-            .with_unstake_pool_sol_reserves(sanctum_reserve_core::POOL_SOL_RESERVES)
-            .with_unstake_protocol_fee(sanctum_reserve_core::PROTOCOL_FEE)
-            .with_unstake_protocol_fee_dest(sanctum_reserve_core::PROTOCOL_FEE_VAULT)
-            .build()
-            .as_borrowed()
-            .0,
-        &PREFUND_WITHDRAW_STAKE_PREFIX_IS_SIGNER.0,
-        &PREFUND_WITHDRAW_STAKE_PREFIX_IS_WRITER.0,
-    );
-
-    let data = PrefundWithdrawStakeIxData::new(swap_params.amt, swap_params.bridge_stake_seed);
-
-    Ok((metas, data))
+    Ok(PrefundWithdrawStakePrefixAccsBuilder::start()
+        .with_user(swap_params.signer.0)
+        .with_bridge_stake(bridge_stake)
+        .with_slumdog_stake(slumdog_stake)
+        .with_slumdog_stake_acc_record(slumdog_stake_acc_record)
+        .with_inp_mint(swap_params.inp.0)
+        .with_inp_token(swap_params.signer_inp.0)
+        .with_clock(SYSVAR_CLOCK)
+        .with_prefunder(config.prefunder)
+        .with_stake_program(STAKE_PROGRAM)
+        .with_system_program(SYSTEM_PROGRAM)
+        .with_unstake_program(config.reserve.program)
+        .with_unstake_pool(config.reserve.pool)
+        .with_unstake_fee(config.reserve.fee)
+        .with_unstake_pool_sol_reserves(config.reserve.pool_sol_reserves)
+        .with_unstake_protocol_fee(config.reserve.protocol_fee)
+        .with_unstake_protocol_fee_dest(config.reserve.protocol_fee_vault)
+        .build())
 }
@@ -1,4 +1,3 @@
-use sanctum_router_core::NATIVE_MINT;
 use serde::{Deserialize, Serialize};
 use tsify_next::Tsify;
 
@@ -13,89 +12,29 @@ pub enum SwapMints {
     DepositSol { out: B58PK },
     DepositStake { out: B58PK },
     PrefundSwapViaStake { inp: B58PK, out: B58PK },
+    SwapViaStake { inp: B58PK, out: B58PK },
     WithdrawSol { inp: B58PK },
     PrefundWithdrawStake { inp: B58PK },
+    MarinadeDelayedUnstake,
 }
 
-pub(crate) type IntoPoolUpdateIter =
-    core::iter::Flatten<core::array::IntoIter<Option<PoolUpdate>, 3>>;
-
-impl SwapMints {
+impl From<SwapMints> for sanctum_router::SwapMints {
     #[inline]
-    pub(crate) fn into_pool_updates(self) -> IntoPoolUpdateIter {
-        match self {
-            SwapMints::DepositSol { out } => [
-                Some(PoolUpdate {
-                    mint: out.0,
-                    ty: PoolUpdateType::DepositSol,
-                }),
-                None,
-                None,
-            ],
-
-            SwapMints::DepositStake { out } => [
-                Some(PoolUpdate {
-                    mint: out.0,
-                    ty: PoolUpdateType::DepositStake,
-                }),
-                None,
-                None,
-            ],
-
-            SwapMints::PrefundSwapViaStake { inp, out } => [
-                PoolUpdate {
-                    mint: inp.0,
-                    ty: PoolUpdateType::WithdrawStake,
-                },
-                PoolUpdate {
-                    mint: out.0,
-                    ty: PoolUpdateType::DepositStake,
-                },
-                // reserve pool for prefund
-                PoolUpdate {
-                    mint: NATIVE_MINT,
-                    ty: PoolUpdateType::DepositStake,
-                },
-            ]
-            .map(Some),
-
-            SwapMints::WithdrawSol { inp } => [
-                Some(PoolUpdate {
-                    mint: inp.0,
-                    ty: PoolUpdateType::WithdrawSol,
-                }),
-                None,
-                None,
-            ],
-
-            SwapMints::PrefundWithdrawStake { inp } => [
-                Some(PoolUpdate {
-                    mint: inp.0,
-                    ty: PoolUpdateType::WithdrawStake,
-                }),
-                // reserve pool for prefund
-                Some(PoolUpdate {
-                    mint: NATIVE_MINT,
-                    ty: PoolUpdateType::DepositStake,
-                }),
-                None,
-            ],
+    fn from(sm: SwapMints) -> Self {
+        match sm {
+            SwapMints::DepositSol { out } => Self::DepositSol { out: out.0 },
+            SwapMints::DepositStake { out } => Self::DepositStake { out: out.0 },
+            SwapMints::PrefundSwapViaStake { inp, out } => Self::PrefundSwapViaStake {
+                inp: inp.0,
+                out: out.0,
+            },
+            SwapMints::SwapViaStake { inp, out } => Self::SwapViaStake {
+                inp: inp.0,
+                out: out.0,
+            },
+            SwapMints::WithdrawSol { inp } => Self::WithdrawSol { inp: inp.0 },
+            SwapMints::PrefundWithdrawStake { inp } => Self::PrefundWithdrawStake { inp: inp.0 },
+            SwapMints::MarinadeDelayedUnstake => Self::MarinadeDelayedUnstake,
         }
-        .into_iter()
-        .flatten()
     }
 }
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum PoolUpdateType {
-    DepositSol,
-    DepositStake,
-    WithdrawSol,
-    WithdrawStake,
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct PoolUpdate {
-    pub mint: [u8; 32],
-    pub ty: PoolUpdateType,
-}
@@ -0,0 +1,303 @@
+import { describe, expect, it } from "vitest";
+import {
+  accountsToUpdate,
+  closeFeeTokenAccountIx,
+  createFeeTokenAccountIx,
+  decodeRouterIx,
+  depositSolIx,
+  depositStakeIx,
+  prefundSwapViaStakeIx,
+  prefundWithdrawStakeIx,
+  swapViaStakeIx,
+  update,
+  withdrawFeesIx,
+  withdrawSolIx,
+  type Instruction,
+  type RouterIxKind,
+  type SanctumRouterHandle,
+  type SuffixPool,
+  type SwapMints,
+} from "@sanctumso/sanctum-router";
+import {
+  BSOL_MINT,
+  fetchAccountMap,
+  localRpc,
+  MSOL_MINT,
+  NATIVE_MINT,
+  parseRouterErr,
+  PICOSOL_MINT,
+  routerForSwaps,
+  STSOL_MINT,
+  testFixturesStakeAcc,
+  testFixturesTokenAcc,
+} from "../utils";
+
+const BRIDGE_STAKE_SEED = 1;
+
+const TOKEN_ACC_NAMES = {
+  [NATIVE_MINT]: "signer-wsol-token",
+  [MSOL_MINT]: "signer-msol-token",
+  [BSOL_MINT]: "signer-bsol-token",
+  [PICOSOL_MINT]: "signer-picosol-token",
+  [STSOL_MINT]: "signer-stsol-token",
+} as const;
+
+type TokenMint = keyof typeof TOKEN_ACC_NAMES;
+
+const WITHDRAW_STAKE_MINTS: [TokenMint, SuffixPool][] = [
+  [PICOSOL_MINT, "Spl"],
+  [STSOL_MINT, "Lido"],
+  [MSOL_MINT, "Marinade"],
+];
+
+const DEPOSIT_STAKE_MINTS: [TokenMint, SuffixPool][] = [
+  [BSOL_MINT, "Spl"],
+  [MSOL_MINT, "Marinade"],
+  [NATIVE_MINT, "Reserve"],
+];
+
+/**
+ * `routerForSwaps()`, followed by a second update round,
+ * since marinade's WithdrawStake only knows which stake account
+ * it splits from after the first
+ */
+async function routerForDecodeSwaps(
+  swapMints: SwapMints[]
+): Promise<SanctumRouterHandle> {
+  const rpc = localRpc();
+  const router = await routerForSwaps(rpc, swapMints);
+  const accs = accountsToUpdate(router, swapMints);
+  update(router, swapMints, await fetchAccountMap(rpc, accs));
+  return router;
+}
+
+// Marinade's withdraw stake requires a larger amount than the other pools'
+function amtFor(inp: TokenMint): bigint {
+  return inp === MSOL_MINT ? 10_000_000_000n : 1_000_000_000n;
+}
+
+function tokenAcc(mint: TokenMint) {
+  return testFixturesTokenAcc(TOKEN_ACC_NAMES[mint]);
+}
+
+function expectRoundTrip(
+  ix: Instruction,
+  expected: {
+    kind: RouterIxKind;
+    amount?: bigint;
+    bridgeStakeSeed?: number;
+    pools: SuffixPool[];
+  }
+) {
+  const decoded = decodeRouterIx(ix);
+  expect(decoded.kind).toBe(expected.kind);
+  expect(decoded.amount).toBe(expected.amount);
+  expect(decoded.bridgeStakeSeed).toBe(expected.bridgeStakeSeed);
+  expect(decoded.suffixes.map(({ pool }) => pool)).toStrictEqual(
+    expected.pools
+  );
+  expect(decoded.remaining).toStrictEqual([]);
+  expect([
+    ...decoded.prefix,
+    ...decoded.suffixes.flatMap(({ accounts }) => accounts),
+  ]).toStrictEqual(ix.accounts.map(({ address }) => address));
+}
+
+describe("Decode Test", async () => {
+  it("decode-stake-wrapped-sol", async () => {
+    for (const [out, pool] of [
+      [PICOSOL_MINT, "Spl"],
+      [MSOL_MINT, "Marinade"],
+    ] as const) {
+      const router = await routerForDecodeSwaps([{ swap: "depositSol", out }]);
+      const { addr: signerInp, owner: signer } = tokenAcc(NATIVE_MINT);
+      const amt = 1_000_000n;
+      const ix = depositSolIx(router, {
+        amt,
+        out,
+        signerInp,
+        signerOut: tokenAcc(out).addr,
+        signer,
+      });
+      expectRoundTrip(ix, {
+        kind: "StakeWrappedSol",
+        amount: amt,
+        pools: [pool],
+      });
+    }
+  });
+
+  it("decode-withdraw-wrapped-sol", async () => {
+    for (const [inp, pool] of [
+      [PICOSOL_MINT, "Spl"],
+      [MSOL_MINT, "Marinade"],
+    ] as const) {
+      const router = await routerForDecodeSwaps([{ swap: "withdrawSol", inp }]);
+      const { addr: signerInp, owner: signer } = tokenAcc(inp);
+      const amt = 1_000_000n;
+      const ix = withdrawSolIx(router, {
+        amt,
+        inp,
+        signerInp,
+        signerOut: tokenAcc(NATIVE_MINT).addr,
+        signer,
+      });
+      expectRoundTrip(ix, {
+        kind: "WithdrawWrappedSol",
+        amount: amt,
+        pools: [pool],
+      });
+    }
+  });
+
+  it("decode-deposit-stake", async () => {
+    for (const [stakeAccName, out, pool] of [
+      ["picosol-deposit-stake", PICOSOL_MINT, "Spl"],
+      ["marinade-deposit-stake", MSOL_MINT, "Marinade"],
+      ["reserve-deposit-stake", NATIVE_MINT, "Reserve"],
+    ] as const) {
+      const router = await routerForDecodeSwaps([
+        { swap: "depositStake", out },
+      ]);
+      const {
+        addr: signerInp,
+        vote,
+        withdrawer: signer,
+      } = testFixturesStakeAcc(stakeAccName);
+      const ix = depositStakeIx(router, {
+        inp: vote,
+        out,
+        signerInp,
+        signerOut: tokenAcc(out).addr,
+        signer,
+      });
+      expectRoundTrip(ix, { kind: "DepositStake", pools: [pool] });
+    }
+  });
+
+  it("decode-prefund-withdraw-stake", async () => {
+    for (const [inp, pool] of WITHDRAW_STAKE_MINTS) {
+      const router = await routerForDecodeSwaps([
+        { swap: "prefundWithdrawStake", inp },
+      ]);
+      const { addr: signerInp, owner: signer } = tokenAcc(inp);
+      const amt = amtFor(inp);
+      const ix = prefundWithdrawStakeIx(router, {
+        amt,
+        inp,
+        signerInp,
+        bridgeStakeSeed: BRIDGE_STAKE_SEED,
+        signer,
+      });
+      expectRoundTrip(ix, {
+        kind: "PrefundWithdrawStake",
+        amount: amt,
+        bridgeStakeSeed: BRIDGE_STAKE_SEED,
+        pools: [pool],
+      });
+    }
+  });
+
+  it("decode-prefund-swap-via-stake", async () => {
+    for (const [inp, withdrawPool] of WITHDRAW_STAKE_MINTS) {
+      for (const [out, depositPool] of DEPOSIT_STAKE_MINTS) {
+        if (inp === out) {
+          continue;
+        }
+        const router = await routerForDecodeSwaps([
+          { swap: "prefundSwapViaStake", inp, out },
+        ]);
+        const { addr: signerInp, owner: signer } = tokenAcc(inp);
+        const amt = amtFor(inp);
+        const ix = prefundSwapViaStakeIx(router, {
+          amt,
+          inp,
+          out,
+          signerInp,
+          signerOut: tokenAcc(out).addr,
+          signer,
+          bridgeStakeSeed: BRIDGE_STAKE_SEED,
+        });
+        expectRoundTrip(ix, {
+          kind: "PrefundSwapViaStake",
+          amount: amt,
+          bridgeStakeSeed: BRIDGE_STAKE_SEED,
+          pools: [withdrawPool, depositPool],
+        });
+      }
+    }
+  });
+
+  it("decode-swap-via-stake", async () => {
+    for (const [out, depositPool] of DEPOSIT_STAKE_MINTS) {
+      const inp = PICOSOL_MINT;
+      const router = await routerForDecodeSwaps([
+        { swap: "swapViaStake", inp, out },
+      ]);
+      const { addr: signerInp, owner: signer } = tokenAcc(inp);
+      const amt = amtFor(inp);
+      const ix = swapViaStakeIx(router, {
+        amt,
+        inp,
+        out,
+        signerInp,
+        signerOut: tokenAcc(out).addr,
+        signer,
+        bridgeStakeSeed: BRIDGE_STAKE_SEED,
+      });
+      expectRoundTrip(ix, {
+        kind: "SwapViaStake",
+        amount: amt,
+        bridgeStakeSeed: BRIDGE_STAKE_SEED,
+        pools: ["Spl", depositPool],
+      });
+    }
+  });
+
+  it("decode-fee-token-account-ixs", () => {
+    const { addr, owner } = tokenAcc(PICOSOL_MINT);
+    const mint = PICOSOL_MINT;
+    expectRoundTrip(createFeeTokenAccountIx({ payer: owner, mint }), {
+      kind: "CreateFeeTokenAccount",
+      pools: [],
+    });
+    expectRoundTrip(
+      closeFeeTokenAccountIx({ admin: owner, mint, closeTo: owner }),
+      { kind: "CloseFeeTokenAccount", pools: [] }
+    );
+    expectRoundTrip(withdrawFeesIx({ admin: owner, mint, withdrawTo: addr }), {
+      kind: "WithdrawFees",
+      pools: [],
+    });
+  });
+
+  it("decode-unknown-suffix-program-fails", async () => {
+    const out = PICOSOL_MINT;
+    const router = await routerForDecodeSwaps([{ swap: "depositSol", out }]);
+    const { addr: signerInp, owner: signer } = tokenAcc(NATIVE_MINT);
+    const ix = depositSolIx(router, {
+      amt: 1_000_000n,
+      out,
+      signerInp,
+      signerOut: tokenAcc(out).addr,
+      signer,
+    });
+    const { prefix } = decodeRouterIx(ix);
+    const accounts = ix.accounts.map((meta, i) =>
+      // replace the suffix's program with a program that is not a known pool's
+      i === prefix.length ? { ...meta, address: NATIVE_MINT } : meta
+    );
+    try {
+      decodeRouterIx({ ...ix, accounts });
+      expect.fail("should have thrown");
+    } catch (e) {
+      expect(e).toSatisfy((e) => {
+        const [code, cause] = parseRouterErr(e);
+        return (
+          code === "InvalidDataErr" &&
+          cause === "RouterIxDecodeErr::UnknownSuffixProgram"
+        );
+      });
+    }
+  });
+});
@@ -0,0 +1,116 @@
+import {
+  closeFeeTokenAccountIx,
+  createFeeTokenAccountIx,
+  findFeeTokenAccountPda,
+  initSyncEmbed,
+  withdrawFeesIx,
+  type AccountMeta,
+  type RouterConfig,
+} from "@sanctumso/sanctum-router";
+import { beforeAll, describe, expect, it } from "vitest";
+import { PICOSOL_MINT, testFixturesTokenAcc } from "../utils";
+
+const SANCTUM_ROUTER_PROGRAM = "stkitrT1Uoy18Dk1fTrgPw8W6MVzoCfYoAFT4MLsmhq";
+const TOKEN_PROGRAM = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const TOKEN_2022_PROGRAM = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+const SYSTEM_PROGRAM = "11111111111111111111111111111111";
+// not deployed, only used to check that `config` is respected
+const OTHER_ROUTER_PROGRAM = "11111111111111111111111111111112";
+
+// AccountMeta roles
+const READONLY = 0;
+const WRITABLE = 1;
+const READONLY_SIGNER = 2;
+const WRITABLE_SIGNER = 3;
+
+const mint = PICOSOL_MINT;
+const { addr: tokenAcc, owner: user } = testFixturesTokenAcc(
+  "signer-picosol-token"
+);
+
+describe("Fee Token Account Test", () => {
+  beforeAll(() => {
+    initSyncEmbed();
+  });
+
+  it("create-fee-token-account-ix", () => {
+    const ix = createFeeTokenAccountIx({ payer: user, mint });
+    expect(ix.programAddress).toStrictEqual(SANCTUM_ROUTER_PROGRAM);
+    expect(Array.from(ix.data)).toStrictEqual([2]);
+    expect(ix.accounts).toStrictEqual([
+      { address: user, role: WRITABLE_SIGNER },
+      { address: findFeeTokenAccountPda(mint)[0], role: WRITABLE },
+      { address: mint, role: READONLY },
+      { address: TOKEN_PROGRAM, role: READONLY },
+      { address: SYSTEM_PROGRAM, role: READONLY },
+    ] satisfies AccountMeta[]);
+  });
+
+  it("close-fee-token-account-ix", () => {
+    const ix = closeFeeTokenAccountIx({
+      admin: user,
+      mint,
+      closeTo: tokenAcc,
+    });
+    expect(ix.programAddress).toStrictEqual(SANCTUM_ROUTER_PROGRAM);
+    expect(Array.from(ix.data)).toStrictEqual([3]);
+    expect(ix.accounts).toStrictEqual([
+      { address: user, role: READONLY_SIGNER },
+      { address: findFeeTokenAccountPda(mint)[0], role: WRITABLE },
+      { address: tokenAcc, role: WRITABLE },
+      { address: mint, role: READONLY },
+      { address: TOKEN_PROGRAM, role: READONLY },
+    ] satisfies AccountMeta[]);
+  });
+
+  it("withdraw-fees-ix", () => {
+    const ix = withdrawFeesIx({ admin: user, mint, withdrawTo: tokenAcc });
+    expect(ix.programAddress).toStrictEqual(SANCTUM_ROUTER_PROGRAM);
+    expect(Array.from(ix.data)).toStrictEqual([4]);
+    expect(ix.accounts).toStrictEqual([
+      { address: user, role: READONLY_SIGNER },
+      { address: findFeeTokenAccountPda(mint)[0], role: WRITABLE },
+      { address: tokenAcc, role: WRITABLE },
+      { address: mint, role: READONLY },
+      { address: TOKEN_PROGRAM, role: READONLY },
+    ] satisfies AccountMeta[]);
+  });
+
+  it("fee-token-account-ixs-use-config-and-token-program", () => {
+    const config: RouterConfig = { routerProgram: OTHER_ROUTER_PROGRAM };
+    const feeTokenAcc = findFeeTokenAccountPda(mint, config)[0];
+    expect(feeTokenAcc).not.toStrictEqual(findFeeTokenAccountPda(mint)[0]);
+
+    const ixs = [
+      createFeeTokenAccountIx(
+        { payer: user, mint, tokenProgram: TOKEN_2022_PROGRAM },
+        config
+      ),
+      closeFeeTokenAccountIx(
+        {
+          admin: user,
+          mint,
+          closeTo: tokenAcc,
+          tokenProgram: TOKEN_2022_PROGRAM,
+        },
+        config
+      ),
+      withdrawFeesIx(
+        {
+          admin: user,
+          mint,
+          withdrawTo: tokenAcc,
+          tokenProgram: TOKEN_2022_PROGRAM,
+        },
+        config
+      ),
+    ];
+    for (const ix of ixs) {
+      expect(ix.programAddress).toStrictEqual(OTHER_ROUTER_PROGRAM);
+      expect(ix.accounts[1].address).toStrictEqual(feeTokenAcc);
+      const addrs = ix.accounts.map(({ address }) => address);
+      expect(addrs).toContain(TOKEN_2022_PROGRAM);
+      expect(addrs).not.toContain(TOKEN_PROGRAM);
+    }
+  });
+});
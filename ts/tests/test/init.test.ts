@@ -1,4 +1,6 @@
 import {
+  accountsToUpdate,
+  findFeeTokenAccountPda,
   init,
   isInit,
   newSanctumRouter,
@@ -47,4 +49,44 @@ describe("Init Test", () => {
     expect(isInitRet[0]).toEqual(1);
     expect(isInitRet[1]).toEqual(0);
   });
+
+  it("config overrides mainnet program IDs and fixed accounts", () => {
+    // arbitrary keys that are not part of the mainnet deployment
+    const routerProgram = BSOL_MINT;
+    const reservePool = PICOSOL_MINT;
+
+    expect(findFeeTokenAccountPda(MSOL_MINT, { routerProgram })).not.toEqual(
+      findFeeTokenAccountPda(MSOL_MINT)
+    );
+    expect(findFeeTokenAccountPda(MSOL_MINT, {})).toEqual(
+      findFeeTokenAccountPda(MSOL_MINT)
+    );
+
+    const router = newSanctumRouter({ reserve: { pool: reservePool } });
+    const accs = accountsToUpdate(router, [
+      { swap: "depositStake", out: NATIVE_MINT },
+    ]);
+    expect(accs).toContain(reservePool);
+  });
+
+  it("reserve pool updates also fetch sysvar rent", () => {
+    const router = newSanctumRouter();
+    const rent = "SysvarRent111111111111111111111111111111111";
+    expect(
+      accountsToUpdate(router, [{ swap: "depositStake", out: NATIVE_MINT }])
+    ).toContain(rent);
+    expect(
+      accountsToUpdate(router, [{ swap: "depositStake", out: MSOL_MINT }])
+    ).not.toContain(rent);
+  });
+
+  it("withdraw stake updates also fetch sysvar rent for the bridge stake", () => {
+    const router = newSanctumRouter();
+    const rent = "SysvarRent111111111111111111111111111111111";
+    expect(
+      accountsToUpdate(router, [
+        { swap: "swapViaStake", inp: STSOL_MINT, out: MSOL_MINT },
+      ])
+    ).toContain(rent);
+  });
 });
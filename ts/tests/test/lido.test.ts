@@ -40,6 +40,28 @@ describe("Lido Test", async () => {
     }
   });
 
+  it("lido-prefund-withraw-stake-fails-below-min-delegation", async () => {
+    const rpc = localRpc();
+    const router = await routerForSwaps(
+      rpc,
+      [{ swap: "prefundWithdrawStake", inp: STSOL_MINT }],
+      // 1M SOL, more than the stake account withdrawn for 1 stSOL
+      { stakeMinDelegation: 1_000_000_000_000_000n }
+    );
+    try {
+      quotePrefundWithdrawStake(router, {
+        amt: 1_000_000_000n,
+        inp: STSOL_MINT,
+      });
+      expect.fail("should have thrown");
+    } catch (e) {
+      expect(e).toSatisfy((e) => {
+        const [code, cause] = parseRouterErr(e);
+        return code === "UserErr" && cause === "BelowMinimumDelegation";
+      });
+    }
+  });
+
   // PrefundSwapViaStake
 
   it("lido-prefund-swap-via-stake-into-reserve", async () => {
@@ -1,8 +1,92 @@
-import { describe, it } from "vitest";
-import { depositSolFixturesTest, depositStakeFixturesTest } from "../utils";
+import { describe, expect, it } from "vitest";
+import {
+  address,
+  getAddressEncoder,
+  getU64Encoder,
+  type Address,
+} from "@solana/kit";
+import {
+  CURR_EPOCH,
+  depositSolFixturesTest,
+  depositStakeFixturesTest,
+  fetchAccountMap,
+  localRpc,
+  mapTup,
+  MSOL_MINT,
+  NATIVE_MINT,
+  parseRouterErr,
+  PICO_VOTE_ACC,
+  PICOSOL_MINT,
+  readTestFixturesJsonFile,
+  routerForSwaps,
+  testFixturesTokenAcc,
+  withdrawSolFixturesTest,
+} from "../utils";
+import {
+  accountsToUpdate,
+  marinadeClaimIx,
+  marinadeTickets,
+  prefundSwapViaStakeIx,
+  quoteMarinadeOrderUnstake,
+  quoteBest,
+  quoteDepositSol,
+  quoteDepositSolExactOut,
+  quotePrefundSwapViaStake,
+  quotePrefundWithdrawStake,
+  quoteWithdrawSol,
+  quoteWithdrawSolDepositSol,
+  quoteWithdrawSolExactOut,
+  update,
+  type SanctumRouterHandle,
+  type SwapMints,
+} from "@sanctumso/sanctum-router";
 
 const MSOL_TOKEN_ACC_NAME = "signer-msol-token";
 
+const MARINADE_PROGRAM = address(
+  "MarBmsSgKXdrN1egZf5sqe1TMai9K1rChYNDJgjq7aD"
+);
+const MARINADE_STATE = address(
+  "8szGkuLTAux9XMgZ2vtY39jVSowEcpBfFfD8hXSEqdGC"
+);
+const TICKET_ACCOUNT_DISCM = [133, 77, 18, 98, 211, 1, 231, 3];
+
+// Stake account of the marinade-stake-list fixture's stake record with the most
+// last_update_delegated_lamports, which WithdrawStake splits from.
+// The marinade-stake-largest fixture is hand-made, delegated to pico's vote
+// with 1k SOL stake to stay within the test validator's stake warmup limits.
+const STAKE_LARGEST = "Aptihj1VjHmwha4bP2RJ4idp4EuZoV5DzASpVoas58aS";
+
+/**
+ * `routerForSwaps()`, followed by the second update round WithdrawStake requires
+ * to fetch the stake account to split from on the stake list
+ */
+async function routerForWithdrawStakeSwaps(
+  swapMints: SwapMints[]
+): Promise<SanctumRouterHandle> {
+  const rpc = localRpc();
+  const router = await routerForSwaps(rpc, swapMints);
+  const accs = accountsToUpdate(router, swapMints);
+  update(router, swapMints, await fetchAccountMap(rpc, accs));
+  return router;
+}
+
+function ticketAccData(
+  beneficiary: Address,
+  lamports: bigint,
+  createdEpoch: bigint
+): Uint8Array {
+  const addrEnc = getAddressEncoder();
+  const u64Enc = getU64Encoder();
+  return new Uint8Array([
+    ...TICKET_ACCOUNT_DISCM,
+    ...addrEnc.encode(MARINADE_STATE),
+    ...addrEnc.encode(beneficiary),
+    ...u64Enc.encode(lamports),
+    ...u64Enc.encode(createdEpoch),
+  ]);
+}
+
 describe("Marinade Test", async () => {
   // DepositSol
   it("marinade-deposit-sol", async () => {
@@ -12,6 +96,95 @@ describe("Marinade Test", async () => {
     });
   });
 
+  it("marinade-deposit-sol-exact-out", async () => {
+    const rpc = localRpc();
+    const router = await routerForSwaps(rpc, [
+      { swap: "depositSol", out: MSOL_MINT },
+    ]);
+    const amt = 1_000_000n;
+    const exactOut = quoteDepositSolExactOut(router, { amt, out: MSOL_MINT });
+    expect(exactOut.quote.out >= amt).toBe(true);
+
+    const exactIn = quoteDepositSol(router, {
+      amt: exactOut.quote.inp,
+      out: MSOL_MINT,
+    });
+    expect(exactIn).toStrictEqual(exactOut);
+  });
+
+  // WithdrawSol (liquid unstake)
+  it("marinade-withdraw-sol", async () => {
+    await withdrawSolFixturesTest(1_000_000_000n, {
+      inp: MSOL_TOKEN_ACC_NAME,
+      out: "signer-wsol-token",
+    });
+  });
+
+  it("marinade-withdraw-sol-exact-out", async () => {
+    const rpc = localRpc();
+    const router = await routerForSwaps(rpc, [
+      { swap: "withdrawSol", inp: MSOL_MINT },
+    ]);
+    const amt = 1_000_000_000n;
+    const exactOut = quoteWithdrawSolExactOut(router, {
+      amt,
+      inp: MSOL_MINT,
+      out: NATIVE_MINT,
+    });
+    // liq pool fee charged in mSOL
+    expect(exactOut.quote.fee > 0n).toBe(true);
+    expect(exactOut.quote.out >= amt).toBe(true);
+
+    const exactIn = quoteWithdrawSol(router, {
+      amt: exactOut.quote.inp,
+      inp: MSOL_MINT,
+    });
+    expect(exactIn).toStrictEqual(exactOut);
+  });
+
+  it("marinade-quote-best-into-wsol-includes-withdraw-sol", async () => {
+    const rpc = localRpc();
+    const router = await routerForSwaps(rpc, [
+      { swap: "withdrawSol", inp: MSOL_MINT },
+    ]);
+    const amt = 1_000_000_000n;
+    const ranked = quoteBest(router, { amt, inp: MSOL_MINT, out: NATIVE_MINT });
+    const withdrawSol = ranked.find(({ route }) => route === "WithdrawSol")!;
+    const { quote, routerFee } = quoteWithdrawSol(router, {
+      amt,
+      inp: MSOL_MINT,
+    });
+    expect(withdrawSol).toStrictEqual({
+      route: "WithdrawSol",
+      inp: quote.inp,
+      out: quote.out,
+      routerFee,
+      bridgeRent: 0n,
+      netOut: quote.out,
+    });
+  });
+
+  // WithdrawSol + DepositSol
+  it("marinade-withdraw-sol-deposit-sol-into-picosol", async () => {
+    const rpc = localRpc();
+    const router = await routerForSwaps(rpc, [
+      { swap: "withdrawSol", inp: MSOL_MINT },
+      { swap: "depositSol", out: PICOSOL_MINT },
+    ]);
+    const amt = 1_000_000_000n;
+    const { withdrawSol, depositSol } = quoteWithdrawSolDepositSol(router, {
+      amt,
+      inp: MSOL_MINT,
+      out: PICOSOL_MINT,
+    });
+    expect(withdrawSol).toStrictEqual(
+      quoteWithdrawSol(router, { amt, inp: MSOL_MINT })
+    );
+    expect(depositSol).toStrictEqual(
+      quoteDepositSol(router, { amt: withdrawSol.quote.out, out: PICOSOL_MINT })
+    );
+  });
+
   // DepositStake
   it("marinade-deposit-stake", async () => {
     await depositStakeFixturesTest({
@@ -19,4 +192,170 @@ describe("Marinade Test", async () => {
       out: MSOL_TOKEN_ACC_NAME,
     });
   });
+
+  // PrefundWithdrawStake
+  it("marinade-prefund-withdraw-stake-requires-second-update", async () => {
+    const rpc = localRpc();
+    const swapMints = [
+      { swap: "prefundWithdrawStake", inp: MSOL_MINT } as const,
+    ];
+    const router = await routerForSwaps(rpc, swapMints);
+    // the stake account to split from is only known after the first update
+    const stakeList = "Anv3XE7e5saNdm16MU6bniYS59Mpv7DzQXHAhxJUmAKW";
+    expect(accountsToUpdate(router, swapMints)).toContain(stakeList);
+    expect(accountsToUpdate(router, swapMints)).toContain(STAKE_LARGEST);
+    try {
+      quotePrefundWithdrawStake(router, {
+        amt: 1_000_000_000n,
+        inp: MSOL_MINT,
+      });
+      expect.fail("should have thrown");
+    } catch (e) {
+      expect(e).toSatisfy((e) => {
+        const [code] = parseRouterErr(e);
+        return code === "AccountMissingErr";
+      });
+    }
+  });
+
+  it("marinade-prefund-withdraw-stake-after-second-update", async () => {
+    const router = await routerForWithdrawStakeSwaps([
+      { swap: "prefundWithdrawStake", inp: MSOL_MINT },
+    ]);
+    const amt = 10_000_000_000n;
+    const { quote, prefundFee } = quotePrefundWithdrawStake(router, {
+      amt,
+      inp: MSOL_MINT,
+    });
+    expect(quote.inp).toBe(amt);
+    expect(quote.vote).toBe(PICO_VOTE_ACC);
+    expect(quote.out.staked > 0n).toBe(true);
+    expect(prefundFee > 0n).toBe(true);
+  });
+
+  // PrefundSwapViaStake
+  it("marinade-prefund-swap-via-stake-ix-stake-to-split", async () => {
+    const [{ addr: signerInp, owner: signer }, { addr: signerOut }] = mapTup(
+      [MSOL_TOKEN_ACC_NAME, "signer-picosol-token"],
+      testFixturesTokenAcc
+    );
+    const router = await routerForWithdrawStakeSwaps([
+      { swap: "prefundSwapViaStake", inp: MSOL_MINT, out: PICOSOL_MINT },
+    ]);
+    const amt = 10_000_000_000n;
+    const { quote } = quotePrefundSwapViaStake(router, {
+      amt,
+      inp: MSOL_MINT,
+      out: PICOSOL_MINT,
+    });
+    expect(quote.quote.bridge.vote).toBe(PICO_VOTE_ACC);
+
+    const params = {
+      amt,
+      inp: MSOL_MINT,
+      out: PICOSOL_MINT,
+      signerInp,
+      signerOut,
+      signer,
+      bridgeStakeSeed: 0,
+    };
+    const expected = [
+      MARINADE_PROGRAM,
+      MARINADE_STATE,
+      readTestFixturesJsonFile("marinade-treasury-msol-account").pubkey,
+      readTestFixturesJsonFile("marinade-validator-list").pubkey,
+      readTestFixturesJsonFile("marinade-stake-list").pubkey,
+      // stake withdraw authority PDA
+      "9eG63CdHjsfhHmobHgLtESGC8GabbmRcaSpHAZrtmhco",
+      // stake deposit authority PDA
+      "4bZ6o3eUUNXhKuqjdCnCoPAoLgWiuLYixKaxoa8PpiKk",
+      STAKE_LARGEST,
+      "SysvarC1ock11111111111111111111111111111111",
+      "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+      "Stake11111111111111111111111111111111111111",
+      "11111111111111111111111111111111",
+    ];
+    for (const bridgeVote of [undefined, quote.quote.bridge.vote]) {
+      const ix = prefundSwapViaStakeIx(router, { ...params, bridgeVote });
+      const addrs = ix.accounts.map(({ address }) => address);
+      const start = addrs.indexOf(MARINADE_PROGRAM);
+      expect(start).toBeGreaterThan(0);
+      expect(addrs.slice(start, start + expected.length)).toStrictEqual(
+        expected
+      );
+    }
+  });
+
+  // Delayed unstake
+  it("marinade-order-unstake-quote", async () => {
+    const rpc = localRpc();
+    const router = await routerForSwaps(rpc, [
+      { swap: "marinadeDelayedUnstake" },
+    ]);
+    const amt = 1_000_000_000n;
+    const quote = quoteMarinadeOrderUnstake(router, { amt });
+    expect(quote.inp).toBe(amt);
+    expect(quote.lamports > 0n).toBe(true);
+    expect(quote.claimableEpoch).toBe(BigInt(CURR_EPOCH + 1));
+  });
+
+  it("marinade-tickets-claimable", async () => {
+    const rpc = localRpc();
+    const router = await routerForSwaps(rpc, [
+      { swap: "marinadeDelayedUnstake" },
+    ]);
+    const beneficiary = address(
+      "6rKg3xxAdihxfKGZdS7GQcrYCd6cE2EeLCJpnsezYnQK"
+    );
+    const due = "G5xW4LcBsW9AWKGNYYy2ZSACq46s25L4cLWyR1MzkRF9";
+    const notDue = "E5sZL7DFWZsqfHMhQpudvufxSw17W2fqPBvHhPbMRRa9";
+    const accounts = new Map([
+      [
+        due,
+        {
+          owner: MARINADE_PROGRAM,
+          data: ticketAccData(
+            beneficiary,
+            1_000_000_000n,
+            BigInt(CURR_EPOCH - 1)
+          ),
+          lamports: 1_000_000_000n,
+        },
+      ],
+      [
+        notDue,
+        {
+          owner: MARINADE_PROGRAM,
+          data: ticketAccData(
+            beneficiary,
+            1_000_000_000n,
+            BigInt(CURR_EPOCH)
+          ),
+          lamports: 1_000_000_000n,
+        },
+      ],
+    ]);
+
+    const [dueTicket, notDueTicket] = marinadeTickets(
+      router,
+      [due, notDue],
+      accounts
+    );
+    expect(dueTicket.beneficiary).toBe(beneficiary);
+    expect(dueTicket.claimable).toBe(true);
+    expect(notDueTicket.claimableEpoch).toBe(BigInt(CURR_EPOCH + 1));
+    expect(notDueTicket.claimable).toBe(false);
+
+    const ix = marinadeClaimIx(router, { ticket: due }, accounts);
+    expect(ix.programAddress).toBe(MARINADE_PROGRAM);
+    try {
+      marinadeClaimIx(router, { ticket: notDue }, accounts);
+      expect.fail("should have thrown");
+    } catch (e) {
+      expect(e).toSatisfy((e) => {
+        const [code, cause] = parseRouterErr(e);
+        return code === "UserErr" && cause === "TicketNotDue";
+      });
+    }
+  });
 });
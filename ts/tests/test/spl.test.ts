@@ -1,16 +1,38 @@
 import { describe, expect, it } from "vitest";
 import {
+  BSOL_MINT,
   depositSolFixturesTest,
+  MSOL_MINT,
   depositStakeFixturesTest,
   localRpc,
+  NATIVE_MINT,
   parseRouterErr,
+  PICO_VOTE_ACC,
   PICOSOL_MINT,
   prefundSwapViaStakeFixturesTest,
   prefundWithdrawStakeFixturesTest,
   routerForSwaps,
+  STAKE_ACCOUNT_RENT_EXEMPT_LAMPORTS,
+  STSOL_MINT,
+  swapViaStakeFixturesTest,
   withdrawSolFixturesTest,
 } from "../utils";
-import { quoteWithdrawSol } from "@sanctumso/sanctum-router";
+import {
+  getSolValue,
+  quoteBest,
+  quoteSplit,
+  quoteDepositSol,
+  quoteDepositSolBatch,
+  quoteDepositSolExactOut,
+  quotePrefundSwapViaStake,
+  quotePrefundSwapViaStakeBatch,
+  quotePrefundSwapViaStakeBestOut,
+  quotePrefundSwapViaStakeDiagnosed,
+  quotePrefundSwapViaStakeExactOut,
+  quoteWithdrawSol,
+  quoteWithdrawSolDepositSol,
+  quoteWithdrawSolExactOut,
+} from "@sanctumso/sanctum-router";
 
 const PICOSOL_TOKEN_ACC_NAME = "signer-picosol-token";
 
@@ -23,6 +45,41 @@ describe("SPL Test", async () => {
     });
   });
 
+  it("spl-picosol-deposit-sol-exact-out", async () => {
+    const rpc = localRpc();
+    const router = await routerForSwaps(rpc, [
+      { swap: "depositSol", out: PICOSOL_MINT },
+    ]);
+    const amt = 1_000_000n;
+    const exactOut = quoteDepositSolExactOut(router, { amt, out: PICOSOL_MINT });
+    expect(exactOut.quote.out >= amt).toBe(true);
+
+    const exactIn = quoteDepositSol(router, {
+      amt: exactOut.quote.inp,
+      out: PICOSOL_MINT,
+    });
+    expect(exactIn).toStrictEqual(exactOut);
+
+    // 1 less input lamport should not be enough
+    const lessIn = quoteDepositSol(router, {
+      amt: exactOut.quote.inp - 1n,
+      out: PICOSOL_MINT,
+    });
+    expect(lessIn.quote.out < amt).toBe(true);
+  });
+
+  it("spl-picosol-deposit-sol-batch", async () => {
+    const rpc = localRpc();
+    const router = await routerForSwaps(rpc, [
+      { swap: "depositSol", out: PICOSOL_MINT },
+    ]);
+    const amts = [1_000_000n, 1_000_000_000n, 10_000_000_000n];
+    const batch = quoteDepositSolBatch(router, { amts, out: PICOSOL_MINT });
+    expect(batch).toStrictEqual(
+      amts.map((amt) => quoteDepositSol(router, { amt, out: PICOSOL_MINT }))
+    );
+  });
+
   // WithdrawSol
   it("spl-picosol-withdraw-sol", async () => {
     await withdrawSolFixturesTest(1000000n, {
@@ -31,6 +88,56 @@ describe("SPL Test", async () => {
     });
   });
 
+  it("spl-picosol-withdraw-sol-exact-out", async () => {
+    const rpc = localRpc();
+    const router = await routerForSwaps(rpc, [
+      { swap: "withdrawSol", inp: PICOSOL_MINT },
+    ]);
+    const amt = 1_000_000n;
+    const exactOut = quoteWithdrawSolExactOut(router, {
+      amt,
+      inp: PICOSOL_MINT,
+      out: NATIVE_MINT,
+    });
+    // router fee accounted for
+    expect(exactOut.routerFee > 0n).toBe(true);
+    expect(exactOut.quote.out >= amt).toBe(true);
+
+    const exactIn = quoteWithdrawSol(router, {
+      amt: exactOut.quote.inp,
+      inp: PICOSOL_MINT,
+    });
+    expect(exactIn).toStrictEqual(exactOut);
+  });
+
+  it("spl-picosol-withdraw-sol-router-fee-schedule-override", async () => {
+    const rpc = localRpc();
+    const swaps = [{ swap: "withdrawSol", inp: PICOSOL_MINT } as const];
+    const amt = 1_000_000n;
+    const mainnet = quoteWithdrawSol(await routerForSwaps(rpc, swaps), {
+      amt,
+      inp: PICOSOL_MINT,
+    });
+
+    const zero = quoteWithdrawSol(
+      await routerForSwaps(rpc, swaps, {
+        fees: { withdrawWrappedSol: { bps: 0n, minFee: 0n } },
+      }),
+      { amt, inp: PICOSOL_MINT }
+    );
+    expect(zero.routerFee).toStrictEqual(0n);
+    expect(zero.quote.out).toStrictEqual(mainnet.quote.out + mainnet.routerFee);
+
+    const higher = quoteWithdrawSol(
+      await routerForSwaps(rpc, swaps, {
+        fees: { withdrawWrappedSol: { bps: 100n, minFee: 1n } },
+      }),
+      { amt, inp: PICOSOL_MINT }
+    );
+    expect(higher.routerFee).toStrictEqual(zero.quote.out / 100n);
+    expect(higher.quote.out).toStrictEqual(zero.quote.out - higher.routerFee);
+  });
+
   it("spl-picosol-withdraw-sol-fails-withdrawal-too-large", async () => {
     const rpc = localRpc();
     const router = await routerForSwaps(rpc, [
@@ -122,4 +229,324 @@ describe("SPL Test", async () => {
       { useBridgeVote: true }
     );
   });
+
+  it("spl-picosol-prefund-swap-via-stake-into-spl-bsol-best-out", async () => {
+    const rpc = localRpc();
+    const router = await routerForSwaps(rpc, [
+      { swap: "prefundSwapViaStake", inp: PICOSOL_MINT, out: BSOL_MINT },
+    ]);
+    const params = {
+      amt: 1_000_000_000n,
+      inp: PICOSOL_MINT,
+      out: BSOL_MINT,
+    };
+    const first = quotePrefundSwapViaStake(router, params);
+    const best = quotePrefundSwapViaStakeBestOut(router, params);
+    expect(best.quote.inp).toStrictEqual(first.quote.inp);
+    expect(best.quote.out >= first.quote.out).toBe(true);
+  });
+
+  it("spl-picosol-prefund-swap-via-stake-into-spl-bsol-diagnosed", async () => {
+    const rpc = localRpc();
+    const router = await routerForSwaps(rpc, [
+      { swap: "prefundSwapViaStake", inp: PICOSOL_MINT, out: BSOL_MINT },
+    ]);
+    const params = {
+      amt: 1_000_000_000n,
+      inp: PICOSOL_MINT,
+      out: BSOL_MINT,
+    };
+    const { quote, rejections } = quotePrefundSwapViaStakeDiagnosed(
+      router,
+      params
+    );
+    expect(quote).toStrictEqual(quotePrefundSwapViaStake(router, params));
+    for (const r of rejections) {
+      expect(r.vote).not.toStrictEqual(quote!.quote.quote.bridge.vote);
+      expect(r.code).not.toStrictEqual("InternalErr");
+    }
+  });
+
+  it("spl-stsol-into-picosol-diagnosed-all-rejected", async () => {
+    const rpc = localRpc();
+    const router = await routerForSwaps(rpc, [
+      { swap: "prefundSwapViaStake", inp: STSOL_MINT, out: PICOSOL_MINT },
+    ]);
+    const { quote, rejections } = quotePrefundSwapViaStakeDiagnosed(router, {
+      amt: 1_000_000_000n,
+      inp: STSOL_MINT,
+      out: PICOSOL_MINT,
+    });
+    // lido's bridge validator is not on picoSOL's validator list
+    expect(quote).toBeUndefined();
+    expect(rejections.length).toStrictEqual(1);
+    const [r] = rejections;
+    expect(r.vote).toBeDefined();
+    expect(r.vote).not.toStrictEqual(PICO_VOTE_ACC);
+    expect(r.leg).toStrictEqual("DepositStake");
+    expect(r.code).toStrictEqual("UserErr");
+    expect(r.cause).toStrictEqual("SplStakePoolError::ValidatorNotFound");
+  });
+
+  it("spl-picosol-prefund-swap-via-stake-into-spl-bsol-batch", async () => {
+    const rpc = localRpc();
+    const router = await routerForSwaps(rpc, [
+      { swap: "prefundSwapViaStake", inp: PICOSOL_MINT, out: BSOL_MINT },
+    ]);
+    const amts = [1_000_000_000n, 2_000_000_000n];
+    const batch = quotePrefundSwapViaStakeBatch(router, {
+      amts,
+      inp: PICOSOL_MINT,
+      out: BSOL_MINT,
+    });
+    expect(batch).toStrictEqual(
+      amts.map((amt) =>
+        quotePrefundSwapViaStake(router, {
+          amt,
+          inp: PICOSOL_MINT,
+          out: BSOL_MINT,
+        })
+      )
+    );
+  });
+
+  it("spl-picosol-prefund-swap-via-stake-into-spl-bsol-exact-out", async () => {
+    const rpc = localRpc();
+    const router = await routerForSwaps(rpc, [
+      { swap: "prefundSwapViaStake", inp: PICOSOL_MINT, out: BSOL_MINT },
+    ]);
+    const amt = 1_000_000_000n;
+    const exactOut = quotePrefundSwapViaStakeExactOut(router, {
+      amt,
+      inp: PICOSOL_MINT,
+      out: BSOL_MINT,
+    });
+    expect(exactOut.quote.out >= amt).toBe(true);
+
+    const exactIn = quotePrefundSwapViaStake(router, {
+      amt: exactOut.quote.inp,
+      inp: PICOSOL_MINT,
+      out: BSOL_MINT,
+    });
+    expect(exactIn).toStrictEqual(exactOut);
+  });
+
+  // SwapViaStake
+
+  it("spl-picosol-swap-via-stake-into-marinade", async () => {
+    await swapViaStakeFixturesTest(1_000_000_000n, {
+      inp: PICOSOL_TOKEN_ACC_NAME,
+      out: "signer-msol-token",
+    });
+  });
+
+  it("spl-picosol-swap-via-stake-into-spl-bsol-use-bridge-vote", async () => {
+    await swapViaStakeFixturesTest(
+      1_000_000_000n,
+      {
+        inp: PICOSOL_TOKEN_ACC_NAME,
+        out: "signer-bsol-token",
+      },
+      { useBridgeVote: true }
+    );
+  });
+
+  // WithdrawSol + DepositSol
+
+  it("spl-picosol-withdraw-sol-deposit-sol-into-marinade", async () => {
+    const rpc = localRpc();
+    const router = await routerForSwaps(rpc, [
+      { swap: "withdrawSol", inp: PICOSOL_MINT },
+      { swap: "depositSol", out: MSOL_MINT },
+    ]);
+    const amt = 1_000_000_000n;
+    const { withdrawSol, depositSol } = quoteWithdrawSolDepositSol(router, {
+      amt,
+      inp: PICOSOL_MINT,
+      out: MSOL_MINT,
+    });
+    expect(withdrawSol).toStrictEqual(
+      quoteWithdrawSol(router, { amt, inp: PICOSOL_MINT })
+    );
+    expect(depositSol).toStrictEqual(
+      quoteDepositSol(router, { amt: withdrawSol.quote.out, out: MSOL_MINT })
+    );
+  });
+
+  // quoteBest
+
+  it("spl-picosol-quote-best-into-wsol", async () => {
+    const rpc = localRpc();
+    const router = await routerForSwaps(rpc, [
+      { swap: "withdrawSol", inp: PICOSOL_MINT },
+      { swap: "prefundSwapViaStake", inp: PICOSOL_MINT, out: NATIVE_MINT },
+    ]);
+    const amt = 1_000_000_000n;
+    const ranked = quoteBest(router, {
+      amt,
+      inp: PICOSOL_MINT,
+      out: NATIVE_MINT,
+    });
+    const routes = ranked.map(({ route }) => route);
+    expect(routes).toContain("WithdrawSol");
+    expect(routes).toContain("PrefundSwapViaStake");
+    for (let i = 1; i < ranked.length; i++) {
+      expect(ranked[i - 1].netOut >= ranked[i].netOut).toBe(true);
+    }
+
+    const withdrawSol = ranked.find(({ route }) => route === "WithdrawSol")!;
+    const { quote, routerFee } = quoteWithdrawSol(router, {
+      amt,
+      inp: PICOSOL_MINT,
+    });
+    expect(withdrawSol).toStrictEqual({
+      route: "WithdrawSol",
+      inp: quote.inp,
+      out: quote.out,
+      routerFee,
+      bridgeRent: 0n,
+      netOut: quote.out,
+    });
+  });
+
+  it("spl-picosol-quote-best-ranks-swap-via-stake-net-of-bridge-rent", async () => {
+    const rpc = localRpc();
+    const router = await routerForSwaps(rpc, [
+      { swap: "swapViaStake", inp: PICOSOL_MINT, out: MSOL_MINT },
+      { swap: "prefundSwapViaStake", inp: PICOSOL_MINT, out: MSOL_MINT },
+    ]);
+    const ranked = quoteBest(router, {
+      amt: 1_000_000_000n,
+      inp: PICOSOL_MINT,
+      out: MSOL_MINT,
+    });
+    for (let i = 1; i < ranked.length; i++) {
+      expect(ranked[i - 1].netOut >= ranked[i].netOut).toBe(true);
+    }
+
+    const swapViaStake = ranked.find(({ route }) => route === "SwapViaStake")!;
+    expect(swapViaStake.bridgeRent).toBe(STAKE_ACCOUNT_RENT_EXEMPT_LAMPORTS);
+    expect(swapViaStake.netOut < swapViaStake.out).toBe(true);
+  });
+
+  // Split
+
+  it("spl-picosol-split-into-wsol", async () => {
+    const rpc = localRpc();
+    const router = await routerForSwaps(rpc, [
+      { swap: "withdrawSol", inp: PICOSOL_MINT },
+      { swap: "prefundSwapViaStake", inp: PICOSOL_MINT, out: NATIVE_MINT },
+    ]);
+    const amt = 1_000_000_000n;
+    const split = quoteSplit(router, {
+      amt,
+      inp: PICOSOL_MINT,
+      out: NATIVE_MINT,
+      parts: 4n,
+    });
+    expect(split.inp).toStrictEqual(amt);
+    expect(split.legs.length > 0).toBe(true);
+
+    let legsInp = 0n;
+    let legsOut = 0n;
+    for (const leg of split.legs) {
+      const quote =
+        leg.route === "WithdrawSol" ? leg.quote.quote : leg.quote.quote.quote;
+      legsInp += quote.inp;
+      legsOut += quote.out;
+    }
+    expect(legsInp).toStrictEqual(amt);
+    expect(legsOut).toStrictEqual(split.out);
+  });
+
+  it("spl-picosol-split-into-wsol-no-capacity-reports-leg-errs", async () => {
+    const rpc = localRpc();
+    const router = await routerForSwaps(rpc, [
+      { swap: "withdrawSol", inp: PICOSOL_MINT },
+      { swap: "prefundSwapViaStake", inp: PICOSOL_MINT, out: NATIVE_MINT },
+    ]);
+    // far more than the pool's total stake and the reserve's liquidity
+    expect(() =>
+      quoteSplit(router, {
+        amt: 1_000_000_000_000_000_000n,
+        inp: PICOSOL_MINT,
+        out: NATIVE_MINT,
+        parts: 4n,
+      })
+    ).toThrowError(
+      /^PoolErr:SplitErr::NoCapacity.*; PrefundSwapViaStake \w+: \w+Err:.*; WithdrawSol: \w+Err:/
+    );
+  });
+
+  // SOL value
+
+  it("spl-picosol-sol-value", async () => {
+    const rpc = localRpc();
+    const router = await routerForSwaps(rpc, [
+      { swap: "depositSol", out: PICOSOL_MINT },
+      { swap: "withdrawSol", inp: PICOSOL_MINT },
+    ]);
+    const amt = 1_000_000_000n;
+    const [picosol, wsol] = getSolValue(router, [PICOSOL_MINT, NATIVE_MINT], amt);
+
+    expect(picosol.mint).toStrictEqual(PICOSOL_MINT);
+    expect(picosol.solValue > 0n).toBe(true);
+    expect(picosol.tokensForSol > 0n).toBe(true);
+    // fees only ever make effective rates worse
+    expect(picosol.depositSol! <= picosol.tokensForSol).toBe(true);
+    expect(picosol.withdrawSol! <= picosol.solValue).toBe(true);
+
+    expect(wsol.solValue).toStrictEqual(amt);
+    expect(wsol.tokensForSol).toStrictEqual(amt);
+  });
+
+  // Fee breakdown
+
+  it("spl-picosol-prefund-swap-via-stake-into-spl-bsol-fees", async () => {
+    const rpc = localRpc();
+    const router = await routerForSwaps(rpc, [
+      { swap: "prefundSwapViaStake", inp: PICOSOL_MINT, out: BSOL_MINT },
+    ]);
+    const { quote, prefundFee, fees } = quotePrefundSwapViaStake(router, {
+      amt: 1_000_000_000n,
+      inp: PICOSOL_MINT,
+      out: BSOL_MINT,
+    });
+    expect(fees).toBeDefined();
+    const { components, totalLamports } = fees!;
+
+    const router_ = components.find(({ kind }) => kind === "Router")!;
+    expect(router_.unit).toStrictEqual("Out");
+    expect(router_.amt).toStrictEqual(quote.routerFee);
+    const prefund = components.find(({ kind }) => kind === "Prefund")!;
+    expect(prefund.unit).toStrictEqual("Lamports");
+    expect(prefund.lamports).toStrictEqual(prefundFee);
+    expect(totalLamports).toStrictEqual(
+      components.reduce((sum, { lamports }) => sum + lamports, 0n)
+    );
+  });
+
+  it("spl-picosol-withdraw-sol-fees", async () => {
+    const rpc = localRpc();
+    const router = await routerForSwaps(rpc, [
+      { swap: "withdrawSol", inp: PICOSOL_MINT },
+    ]);
+    const { quote, routerFee, fees } = quoteWithdrawSol(router, {
+      amt: 1_000_000_000n,
+      inp: PICOSOL_MINT,
+    });
+    expect(fees).toBeDefined();
+    const { components, totalLamports } = fees!;
+    // pool fee is charged in input tokens, router fee in output wSOL.
+    // picoSOL fixture has a nonzero SOL withdrawal fee
+    const pool = components.find(({ kind }) => kind === "PoolManager")!;
+    expect(quote.fee > 0n).toBe(true);
+    expect(pool.unit).toStrictEqual("Inp");
+    expect(pool.amt).toStrictEqual(quote.fee);
+    expect(pool.lamports > 0n).toBe(true);
+    const router_ = components.find(({ kind }) => kind === "Router")!;
+    expect(router_.unit).toStrictEqual("Out");
+    expect(router_.lamports).toStrictEqual(routerFee);
+    expect(totalLamports).toStrictEqual(pool.lamports + router_.lamports);
+  });
 });
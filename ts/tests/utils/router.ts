@@ -5,6 +5,7 @@ import {
   update,
   type SanctumRouterHandle,
   type InitMint,
+  type RouterConfig,
   type SwapMints,
   initSyncEmbed,
   type SanctumRouterErr,
@@ -13,7 +14,7 @@ import {
 import type { Rpc, SolanaRpcApi } from "@solana/kit";
 import { fetchAccountMap } from "./rpc";
 import { SPL_INIT_HARDCODES } from "./spl";
-import { NATIVE_MINT } from "./token";
+import { MSOL_MINT, NATIVE_MINT } from "./token";
 
 /**
  * Initializes, updates and returns `SanctumRouterHandle` that is ready for quoting
@@ -26,14 +27,16 @@ import { NATIVE_MINT } from "./token";
  * @param spls
  * @param mints
  * @param currEpoch
+ * @param config defaults to mainnet's if omitted
  */
 export async function routerForSwaps(
   rpc: Rpc<SolanaRpcApi>,
-  swapMints: SwapMints[]
+  swapMints: SwapMints[],
+  config?: RouterConfig
 ): Promise<SanctumRouterHandle> {
   initSyncEmbed();
 
-  const sanctumRouter = newSanctumRouter();
+  const sanctumRouter = newSanctumRouter(config);
 
   const initMints: InitMint[] = swapMints
     .flatMap((swapMint) => {
@@ -48,6 +51,10 @@ export async function routerForSwaps(
           return [swapMint.inp, NATIVE_MINT];
         case "prefundSwapViaStake":
           return [swapMint.inp, swapMint.out, NATIVE_MINT];
+        case "swapViaStake":
+          return [swapMint.inp, swapMint.out];
+        case "marinadeDelayedUnstake":
+          return [MSOL_MINT];
       }
     })
     .map((mint) => {
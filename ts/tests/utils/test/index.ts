@@ -2,4 +2,5 @@ export * from "./deposit-sol";
 export * from "./deposit-stake";
 export * from "./prefund-swap-via-stake";
 export * from "./prefund-withdraw-stake";
+export * from "./swap-via-stake";
 export * from "./withdraw-sol";
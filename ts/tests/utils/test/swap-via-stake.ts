@@ -0,0 +1,64 @@
+import {
+  quoteSwapViaStake,
+  swapViaStakeIx,
+  type SwapViaStakeSwapParams,
+} from "@sanctumso/sanctum-router";
+import { expect } from "vitest";
+import { mapTup } from "../ops";
+import { routerForSwaps } from "../router";
+import { testFixturesTokenAcc } from "../token";
+import { localRpc } from "../rpc";
+import { STAKE_ACCOUNT_RENT_EXEMPT_LAMPORTS } from "../stake";
+import { simTokenSwapAssertQuoteMatches } from "./swap";
+
+// Assume bridge stake seed 0 is always unused
+const BRIDGE_STAKE_SEED = 0;
+
+interface SwapViaStakeFixturesTestOpts {
+  useBridgeVote: boolean;
+}
+
+export async function swapViaStakeFixturesTest(
+  amt: bigint,
+  tokenAccFixtures: { inp: string; out: string },
+  opts: SwapViaStakeFixturesTestOpts = { useBridgeVote: false }
+) {
+  const { useBridgeVote } = opts;
+  const { inp: inpTokenAccName, out: outTokenAccName } = tokenAccFixtures;
+  const [
+    { addr: inpTokenAcc, owner: inpTokenAccOwner, mint: inpMint },
+    { addr: outTokenAcc, mint: outMint },
+  ] = mapTup([inpTokenAccName, outTokenAccName], testFixturesTokenAcc);
+  const rpc = localRpc();
+
+  const router = await routerForSwaps(rpc, [
+    { swap: "swapViaStake", inp: inpMint, out: outMint },
+  ]);
+
+  const { quote, routerFee, bridgeRent } = quoteSwapViaStake(router, {
+    amt,
+    out: outMint,
+    inp: inpMint,
+  });
+  expect(bridgeRent).toBe(STAKE_ACCOUNT_RENT_EXEMPT_LAMPORTS);
+  const params: SwapViaStakeSwapParams = {
+    amt,
+    inp: inpMint,
+    out: outMint,
+    signerInp: inpTokenAcc,
+    signerOut: outTokenAcc,
+    signer: inpTokenAccOwner,
+    bridgeStakeSeed: BRIDGE_STAKE_SEED,
+  };
+  if (useBridgeVote) {
+    params.bridgeVote = quote.bridge.vote;
+  }
+  const ix = swapViaStakeIx(router, params);
+
+  await simTokenSwapAssertQuoteMatches(
+    rpc,
+    { quote: { ...quote, fee: quote.outFee }, routerFee },
+    params,
+    ix
+  );
+}